@@ -1,5 +1,32 @@
+use std::process::Command;
+
 // generated by `sqlx migrate build-script`
 fn main() {
     // trigger recompilation when a new migration is added
     println!("cargo:rerun-if-changed=migrations");
-}
\ No newline at end of file
+
+    // the commit is reported by `/version`; Docker builds have no `.git`
+    // and pass it in the GIT_COMMIT build argument instead
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+    let commit = match std::env::var("GIT_COMMIT") {
+        Ok(commit) if !commit.is_empty() => commit,
+        _ => git_commit().unwrap_or_else(|| "unknown".to_string()),
+    };
+    println!("cargo:rustc-env=GIT_COMMIT={}", commit);
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+}
+
+fn git_commit() -> Option<String> {
+    let git_dir = git(&["rev-parse", "--git-dir"])?;
+    // HEAD moves on checkout, the refs on commit
+    println!("cargo:rerun-if-changed={}/HEAD", git_dir);
+    println!("cargo:rerun-if-changed={}/refs/heads", git_dir);
+    git(&["rev-parse", "--short", "HEAD"])
+}
@@ -0,0 +1,4 @@
+DROP TABLE users;
+DROP TYPE user_role;
+DROP TABLE books;
+DROP TABLE authors;
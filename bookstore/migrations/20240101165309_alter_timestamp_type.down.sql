@@ -0,0 +1 @@
+ALTER TABLE users ALTER COLUMN date_registered TYPE timestamp;
\ No newline at end of file
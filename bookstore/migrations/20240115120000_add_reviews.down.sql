@@ -0,0 +1,10 @@
+DROP TRIGGER tr_reviews_update_book_rating ON reviews;
+DROP FUNCTION reviews_update_book_rating();
+
+DROP TABLE reviews;
+
+DROP INDEX ix_books_rating;
+
+ALTER TABLE books
+    DROP COLUMN rating_sum,
+    DROP COLUMN rating_count;
@@ -0,0 +1,59 @@
+ALTER TABLE books
+    ADD COLUMN rating_sum bigint NOT NULL DEFAULT 0,
+    ADD COLUMN rating_count integer NOT NULL DEFAULT 0;
+
+-- `id` breaks the ties, so that the pages neither repeat nor skip books
+CREATE INDEX ix_books_rating
+    ON books ((rating_sum::float8 / NULLIF(rating_count, 0)) DESC NULLS LAST, rating_count DESC, id);
+
+CREATE TABLE reviews (
+    id uuid NOT NULL,
+    book_id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    rating smallint NOT NULL,
+    text varchar(4096) NOT NULL,
+    date_created timestamp with time zone NOT NULL DEFAULT now(),
+    date_updated timestamp with time zone DEFAULT NULL,
+    hidden boolean NOT NULL DEFAULT FALSE,
+    CONSTRAINT pk_reviews PRIMARY KEY (id),
+    CONSTRAINT fk_reviews_book_id_books
+        FOREIGN KEY (book_id)
+            REFERENCES books(id)
+            ON DELETE CASCADE,
+    CONSTRAINT fk_reviews_user_id_users
+        FOREIGN KEY (user_id)
+            REFERENCES users(id)
+            ON DELETE CASCADE,
+    CONSTRAINT uq_reviews_book_id_user_id UNIQUE (book_id, user_id),
+    CONSTRAINT ck_reviews_rating CHECK (rating BETWEEN 1 AND 5)
+);
+
+-- Keep the per-book aggregates in sync with the visible reviews, so that
+-- reading a book never has to scan its reviews.
+CREATE FUNCTION reviews_update_book_rating() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'UPDATE' OR TG_OP = 'DELETE' THEN
+        IF NOT OLD.hidden THEN
+            UPDATE books
+            SET rating_sum = rating_sum - OLD.rating,
+                rating_count = rating_count - 1
+            WHERE id = OLD.book_id;
+        END IF;
+    END IF;
+
+    IF TG_OP = 'UPDATE' OR TG_OP = 'INSERT' THEN
+        IF NOT NEW.hidden THEN
+            UPDATE books
+            SET rating_sum = rating_sum + NEW.rating,
+                rating_count = rating_count + 1
+            WHERE id = NEW.book_id;
+        END IF;
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER tr_reviews_update_book_rating
+    AFTER INSERT OR DELETE OR UPDATE OF rating, hidden, book_id ON reviews
+    FOR EACH ROW EXECUTE FUNCTION reviews_update_book_rating();
@@ -0,0 +1,3 @@
+DROP TABLE shelf_entries;
+DROP TABLE shelves;
+DROP TYPE shelf_kind;
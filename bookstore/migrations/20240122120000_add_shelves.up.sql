@@ -0,0 +1,43 @@
+CREATE TYPE shelf_kind AS ENUM ('want_to_read', 'reading', 'read', 'custom');
+
+CREATE TABLE shelves (
+    id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    kind shelf_kind NOT NULL,
+    name varchar(64) NOT NULL,
+    public boolean NOT NULL DEFAULT FALSE,
+    date_created timestamp with time zone NOT NULL DEFAULT now(),
+    CONSTRAINT pk_shelves PRIMARY KEY (id),
+    CONSTRAINT fk_shelves_user_id_users
+        FOREIGN KEY (user_id)
+            REFERENCES users(id)
+            ON DELETE CASCADE,
+    CONSTRAINT uq_shelves_user_id_name UNIQUE (user_id, name)
+);
+
+-- every user has at most one shelf of each built-in kind
+CREATE UNIQUE INDEX ux_shelves_user_id_kind ON shelves (user_id, kind) WHERE kind <> 'custom';
+
+CREATE TABLE shelf_entries (
+    shelf_id uuid NOT NULL,
+    book_id uuid NOT NULL,
+    date_added timestamp with time zone NOT NULL DEFAULT now(),
+    date_started date DEFAULT NULL,
+    date_finished date DEFAULT NULL,
+    progress_percent smallint DEFAULT NULL,
+    progress_page integer DEFAULT NULL,
+    CONSTRAINT pk_shelf_entries PRIMARY KEY (shelf_id, book_id),
+    CONSTRAINT fk_shelf_entries_shelf_id_shelves
+        FOREIGN KEY (shelf_id)
+            REFERENCES shelves(id)
+            ON DELETE CASCADE,
+    CONSTRAINT fk_shelf_entries_book_id_books
+        FOREIGN KEY (book_id)
+            REFERENCES books(id)
+            ON DELETE CASCADE,
+    CONSTRAINT ck_shelf_entries_progress_percent CHECK (progress_percent BETWEEN 0 AND 100),
+    CONSTRAINT ck_shelf_entries_progress_page CHECK (progress_page >= 0),
+    CONSTRAINT ck_shelf_entries_dates CHECK (date_finished >= date_started)
+);
+
+CREATE INDEX ix_shelf_entries_book_id ON shelf_entries (book_id);
@@ -0,0 +1,13 @@
+DROP TRIGGER tr_books_notify_wishlists ON books;
+DROP FUNCTION books_notify_wishlists();
+
+DROP TABLE notifications;
+DROP TYPE notification_kind;
+
+DROP TABLE wishlist_items;
+
+ALTER TABLE books
+    DROP CONSTRAINT ck_books_stock,
+    DROP CONSTRAINT ck_books_price,
+    DROP COLUMN stock,
+    DROP COLUMN price;
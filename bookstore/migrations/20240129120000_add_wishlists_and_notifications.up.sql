@@ -0,0 +1,80 @@
+-- prices are stored in minor currency units (kopecks)
+ALTER TABLE books
+    ADD COLUMN price integer DEFAULT NULL,
+    ADD COLUMN stock integer NOT NULL DEFAULT 0,
+    ADD CONSTRAINT ck_books_price CHECK (price >= 0),
+    ADD CONSTRAINT ck_books_stock CHECK (stock >= 0);
+
+CREATE TABLE wishlist_items (
+    user_id uuid NOT NULL,
+    book_id uuid NOT NULL,
+    price_threshold integer DEFAULT NULL,
+    notify_restock boolean NOT NULL DEFAULT TRUE,
+    date_added timestamp with time zone NOT NULL DEFAULT now(),
+    CONSTRAINT pk_wishlist_items PRIMARY KEY (user_id, book_id),
+    CONSTRAINT fk_wishlist_items_user_id_users
+        FOREIGN KEY (user_id)
+            REFERENCES users(id)
+            ON DELETE CASCADE,
+    CONSTRAINT fk_wishlist_items_book_id_books
+        FOREIGN KEY (book_id)
+            REFERENCES books(id)
+            ON DELETE CASCADE,
+    CONSTRAINT ck_wishlist_items_price_threshold CHECK (price_threshold >= 0)
+);
+
+CREATE INDEX ix_wishlist_items_book_id ON wishlist_items (book_id);
+
+CREATE TYPE notification_kind AS ENUM ('back_in_stock', 'price_drop');
+
+CREATE TABLE notifications (
+    id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    kind notification_kind NOT NULL,
+    book_id uuid DEFAULT NULL,
+    book_title varchar(256) NOT NULL,
+    old_price integer DEFAULT NULL,
+    new_price integer DEFAULT NULL,
+    date_created timestamp with time zone NOT NULL DEFAULT now(),
+    read boolean NOT NULL DEFAULT FALSE,
+    CONSTRAINT pk_notifications PRIMARY KEY (id),
+    CONSTRAINT fk_notifications_user_id_users
+        FOREIGN KEY (user_id)
+            REFERENCES users(id)
+            ON DELETE CASCADE,
+    CONSTRAINT fk_notifications_book_id_books
+        FOREIGN KEY (book_id)
+            REFERENCES books(id)
+            ON DELETE SET NULL
+);
+
+CREATE INDEX ix_notifications_user_id_date_created ON notifications (user_id, date_created DESC);
+
+-- Notifications are generated by the same statement that changes the stock
+-- or the price, so they are committed (or rolled back) together with it.
+CREATE FUNCTION books_notify_wishlists() RETURNS trigger AS $$
+BEGIN
+    IF OLD.stock = 0 AND NEW.stock > 0 THEN
+        INSERT INTO notifications
+            (id, user_id, kind, book_id, book_title, old_price, new_price)
+        SELECT gen_random_uuid(), w.user_id, 'back_in_stock', NEW.id, NEW.title, NULL, NEW.price
+        FROM wishlist_items w
+        WHERE w.book_id = NEW.id AND w.notify_restock;
+    END IF;
+
+    -- a book that had no price yet is as good as a cheaper one
+    IF NEW.price IS NOT NULL AND (OLD.price IS NULL OR NEW.price < OLD.price) THEN
+        INSERT INTO notifications
+            (id, user_id, kind, book_id, book_title, old_price, new_price)
+        SELECT gen_random_uuid(), w.user_id, 'price_drop', NEW.id, NEW.title, OLD.price, NEW.price
+        FROM wishlist_items w
+        WHERE w.book_id = NEW.id AND NEW.price <= w.price_threshold;
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER tr_books_notify_wishlists
+    AFTER UPDATE OF stock, price ON books
+    FOR EACH ROW EXECUTE FUNCTION books_notify_wishlists();
@@ -0,0 +1,7 @@
+DROP TABLE holds;
+DROP TYPE hold_status;
+
+DROP TABLE loans;
+
+DROP TABLE copies;
+DROP TYPE copy_status;
@@ -0,0 +1,68 @@
+CREATE TYPE copy_status AS ENUM ('available', 'on_loan');
+
+CREATE TABLE copies (
+    id uuid NOT NULL,
+    book_id uuid NOT NULL,
+    status copy_status NOT NULL DEFAULT 'available',
+    date_added timestamp with time zone NOT NULL DEFAULT now(),
+    CONSTRAINT pk_copies PRIMARY KEY (id),
+    CONSTRAINT fk_copies_book_id_books
+        FOREIGN KEY (book_id)
+            REFERENCES books(id)
+            ON DELETE CASCADE
+);
+
+CREATE INDEX ix_copies_book_id ON copies (book_id);
+
+CREATE TABLE loans (
+    id uuid NOT NULL,
+    copy_id uuid NOT NULL,
+    book_id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    date_borrowed timestamp with time zone NOT NULL DEFAULT now(),
+    date_due timestamp with time zone NOT NULL,
+    date_returned timestamp with time zone DEFAULT NULL,
+    renewals integer NOT NULL DEFAULT 0,
+    CONSTRAINT pk_loans PRIMARY KEY (id),
+    CONSTRAINT fk_loans_copy_id_copies
+        FOREIGN KEY (copy_id)
+            REFERENCES copies(id)
+            ON DELETE CASCADE,
+    CONSTRAINT fk_loans_book_id_books
+        FOREIGN KEY (book_id)
+            REFERENCES books(id)
+            ON DELETE CASCADE,
+    CONSTRAINT fk_loans_user_id_users
+        FOREIGN KEY (user_id)
+            REFERENCES users(id)
+            ON DELETE CASCADE
+);
+
+-- a copy can be lent to at most one user at a time
+CREATE UNIQUE INDEX ux_loans_copy_id_active ON loans (copy_id) WHERE date_returned IS NULL;
+CREATE INDEX ix_loans_user_id ON loans (user_id);
+CREATE INDEX ix_loans_date_due_active ON loans (date_due) WHERE date_returned IS NULL;
+
+CREATE TYPE hold_status AS ENUM ('waiting', 'fulfilled', 'cancelled');
+
+CREATE TABLE holds (
+    id uuid NOT NULL,
+    book_id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    date_placed timestamp with time zone NOT NULL DEFAULT now(),
+    status hold_status NOT NULL DEFAULT 'waiting',
+    CONSTRAINT pk_holds PRIMARY KEY (id),
+    CONSTRAINT fk_holds_book_id_books
+        FOREIGN KEY (book_id)
+            REFERENCES books(id)
+            ON DELETE CASCADE,
+    CONSTRAINT fk_holds_user_id_users
+        FOREIGN KEY (user_id)
+            REFERENCES users(id)
+            ON DELETE CASCADE
+);
+
+-- a user waits in the queue for a book at most once
+CREATE UNIQUE INDEX ux_holds_book_id_user_id_waiting ON holds (book_id, user_id) WHERE status = 'waiting';
+CREATE INDEX ix_holds_book_id_date_placed_waiting ON holds (book_id, date_placed) WHERE status = 'waiting';
+CREATE INDEX ix_holds_user_id ON holds (user_id);
@@ -0,0 +1,28 @@
+DROP TABLE copy_transfers;
+
+DROP INDEX ix_copies_branch_id;
+
+ALTER TABLE copies
+    DROP CONSTRAINT uq_copies_barcode,
+    DROP CONSTRAINT fk_copies_branch_id_branches,
+    DROP COLUMN branch_id,
+    DROP COLUMN condition,
+    DROP COLUMN barcode;
+
+DROP TYPE copy_condition;
+
+-- enum values cannot be dropped, so the type is recreated; copies which are
+-- lost or withdrawn have no place in the old schema and are removed
+DELETE FROM copies WHERE status IN ('lost', 'withdrawn');
+
+ALTER TYPE copy_status RENAME TO copy_status_old;
+CREATE TYPE copy_status AS ENUM ('available', 'on_loan');
+
+ALTER TABLE copies
+    ALTER COLUMN status DROP DEFAULT,
+    ALTER COLUMN status TYPE copy_status USING status::text::copy_status,
+    ALTER COLUMN status SET DEFAULT 'available';
+
+DROP TYPE copy_status_old;
+
+DROP TABLE branches;
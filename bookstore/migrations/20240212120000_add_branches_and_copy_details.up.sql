@@ -0,0 +1,60 @@
+CREATE TABLE branches (
+    id uuid NOT NULL,
+    name varchar(128) NOT NULL,
+    address varchar(512) NOT NULL DEFAULT '',
+    date_created timestamp with time zone NOT NULL DEFAULT now(),
+    CONSTRAINT pk_branches PRIMARY KEY (id),
+    CONSTRAINT uq_branches_name UNIQUE (name)
+);
+
+-- the new values are not used within this migration, so adding them in a transaction is fine
+ALTER TYPE copy_status ADD VALUE 'lost';
+ALTER TYPE copy_status ADD VALUE 'withdrawn';
+
+CREATE TYPE copy_condition AS ENUM ('new', 'good', 'fair', 'poor', 'damaged');
+
+ALTER TABLE copies
+    ADD COLUMN barcode varchar(64),
+    ADD COLUMN condition copy_condition NOT NULL DEFAULT 'good',
+    ADD COLUMN branch_id uuid DEFAULT NULL,
+    ADD CONSTRAINT fk_copies_branch_id_branches
+        FOREIGN KEY (branch_id)
+            REFERENCES branches(id)
+            ON DELETE RESTRICT;
+
+-- copies registered before barcodes were introduced get their id as one
+UPDATE copies SET barcode = id::text;
+
+ALTER TABLE copies
+    ALTER COLUMN barcode SET NOT NULL,
+    ADD CONSTRAINT uq_copies_barcode UNIQUE (barcode);
+
+CREATE INDEX ix_copies_branch_id ON copies (branch_id);
+
+CREATE TABLE copy_transfers (
+    id uuid NOT NULL,
+    copy_id uuid NOT NULL,
+    from_branch_id uuid DEFAULT NULL,
+    to_branch_id uuid DEFAULT NULL,
+    user_id uuid DEFAULT NULL,
+    date_transferred timestamp with time zone NOT NULL DEFAULT now(),
+    CONSTRAINT pk_copy_transfers PRIMARY KEY (id),
+    CONSTRAINT fk_copy_transfers_copy_id_copies
+        FOREIGN KEY (copy_id)
+            REFERENCES copies(id)
+            ON DELETE CASCADE,
+    CONSTRAINT fk_copy_transfers_from_branch_id_branches
+        FOREIGN KEY (from_branch_id)
+            REFERENCES branches(id)
+            ON DELETE SET NULL,
+    CONSTRAINT fk_copy_transfers_to_branch_id_branches
+        FOREIGN KEY (to_branch_id)
+            REFERENCES branches(id)
+            ON DELETE SET NULL,
+    CONSTRAINT fk_copy_transfers_user_id_users
+        FOREIGN KEY (user_id)
+            REFERENCES users(id)
+            ON DELETE SET NULL
+);
+
+CREATE INDEX ix_copy_transfers_copy_id ON copy_transfers (copy_id);
@@ -0,0 +1 @@
+DROP INDEX uq_users_nickname;
@@ -0,0 +1,25 @@
+-- nicknames which differ only in case have to be renamed by hand first,
+-- the migration names them instead of failing on the index
+DO $$
+DECLARE
+    collisions text;
+BEGIN
+    SELECT string_agg(format('%s (%s)', folded, nicknames), '; ' ORDER BY folded)
+    INTO collisions
+    FROM (
+        SELECT lower(nickname) AS folded,
+               string_agg(format('%s %s', nickname, id), ', ' ORDER BY date_registered) AS nicknames
+        FROM users
+        GROUP BY lower(nickname)
+        HAVING count(*) > 1
+    ) AS c;
+
+    IF collisions IS NOT NULL THEN
+        RAISE EXCEPTION 'users share nicknames which differ only in case: %', collisions
+            USING HINT = 'Rename all but one user of each nickname, then run the migrations again.';
+    END IF;
+END
+$$;
+
+-- nicknames are ASCII, so `lower` is enough to fold the case
+CREATE UNIQUE INDEX uq_users_nickname ON users (lower(nickname));
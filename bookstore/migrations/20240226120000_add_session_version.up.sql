@@ -0,0 +1,2 @@
+-- tokens carry the version they were issued for; bumping it ends every session
+ALTER TABLE users ADD COLUMN session_version integer NOT NULL DEFAULT 0;
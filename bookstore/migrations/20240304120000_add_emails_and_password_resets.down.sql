@@ -0,0 +1,7 @@
+DROP TABLE outbox;
+DROP TABLE account_tokens;
+DROP TYPE account_token_purpose;
+DROP INDEX uq_users_email;
+ALTER TABLE users
+    DROP COLUMN email_verified,
+    DROP COLUMN email;
@@ -0,0 +1,41 @@
+-- an address is only trusted, and unique, once its owner has confirmed it
+ALTER TABLE users
+    ADD COLUMN email varchar(254) DEFAULT NULL,
+    ADD COLUMN email_verified boolean NOT NULL DEFAULT FALSE;
+
+CREATE UNIQUE INDEX uq_users_email ON users (lower(email)) WHERE email_verified;
+
+CREATE TYPE account_token_purpose AS ENUM ('verify_email', 'reset_password');
+
+-- only the SHA-256 of a token is stored, the token itself is sent by mail
+CREATE TABLE account_tokens (
+    id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    purpose account_token_purpose NOT NULL,
+    token_hash varchar(64) NOT NULL,
+    email varchar(254) NOT NULL,
+    date_created timestamp with time zone NOT NULL DEFAULT now(),
+    date_expires timestamp with time zone NOT NULL,
+    date_used timestamp with time zone DEFAULT NULL,
+    CONSTRAINT pk_account_tokens PRIMARY KEY (id),
+    CONSTRAINT uq_account_tokens_token_hash UNIQUE (token_hash),
+    CONSTRAINT fk_account_tokens_user_id_users
+        FOREIGN KEY (user_id)
+            REFERENCES users(id)
+            ON DELETE CASCADE
+);
+
+CREATE INDEX ix_account_tokens_user_id ON account_tokens (user_id);
+
+-- outgoing mail; a relay delivers the messages and sets date_sent
+CREATE TABLE outbox (
+    id uuid NOT NULL,
+    recipient varchar(254) NOT NULL,
+    subject varchar(256) NOT NULL,
+    body text NOT NULL,
+    date_created timestamp with time zone NOT NULL DEFAULT now(),
+    date_sent timestamp with time zone DEFAULT NULL,
+    CONSTRAINT pk_outbox PRIMARY KEY (id)
+);
+
+CREATE INDEX ix_outbox_unsent ON outbox (date_created) WHERE date_sent IS NULL;
@@ -0,0 +1,2 @@
+DROP TABLE login_failures;
+DROP TYPE login_key_kind;
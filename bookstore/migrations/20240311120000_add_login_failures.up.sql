@@ -0,0 +1,13 @@
+CREATE TYPE login_key_kind AS ENUM ('nickname', 'ip');
+
+-- failed logins in a row per lowercased nickname and per client address;
+-- lowercasing may make the 64 characters of a nickname longer ('İ' is 'i̇'),
+-- so there is room for them even in bytes
+CREATE TABLE login_failures (
+    kind login_key_kind NOT NULL,
+    key varchar(256) NOT NULL,
+    failures integer NOT NULL,
+    date_last_failure timestamp with time zone NOT NULL DEFAULT now(),
+    locked_until timestamp with time zone DEFAULT NULL,
+    CONSTRAINT pk_login_failures PRIMARY KEY (kind, key)
+);
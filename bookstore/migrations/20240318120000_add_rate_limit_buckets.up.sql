@@ -0,0 +1,13 @@
+-- token buckets of the rate limiter when it is shared between the instances
+CREATE TABLE rate_limit_buckets (
+    key varchar(128) NOT NULL,
+    tokens double precision NOT NULL,
+    -- whether the last request took a token
+    allowed bool NOT NULL,
+    date_updated timestamp with time zone NOT NULL DEFAULT now(),
+    -- when the bucket is full again, after which the row is of no use
+    date_full timestamp with time zone NOT NULL,
+    CONSTRAINT pk_rate_limit_buckets PRIMARY KEY (key)
+);
+
+CREATE INDEX ix_rate_limit_buckets_date_full ON rate_limit_buckets (date_full);
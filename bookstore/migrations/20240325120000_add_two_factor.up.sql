@@ -0,0 +1,22 @@
+-- RFC 6238 TOTP; the secret is set on enrollment and only used once confirmed
+ALTER TABLE users
+    ADD COLUMN totp_secret varchar(32) DEFAULT NULL,
+    ADD COLUMN totp_enabled boolean NOT NULL DEFAULT FALSE,
+    -- the time step of the last accepted code, which cannot be used again
+    ADD COLUMN totp_last_step bigint DEFAULT NULL;
+
+-- one-time codes for when the authenticator is lost; only their SHA-256 is stored
+CREATE TABLE recovery_codes (
+    id uuid NOT NULL,
+    user_id uuid NOT NULL,
+    code_hash varchar(64) NOT NULL,
+    date_created timestamp with time zone NOT NULL DEFAULT now(),
+    date_used timestamp with time zone DEFAULT NULL,
+    CONSTRAINT pk_recovery_codes PRIMARY KEY (id),
+    CONSTRAINT fk_recovery_codes_user_id_users
+        FOREIGN KEY (user_id)
+            REFERENCES users(id)
+            ON DELETE CASCADE
+);
+
+CREATE INDEX ix_recovery_codes_user_id ON recovery_codes (user_id);
@@ -0,0 +1,101 @@
+//! Outgoing mail.
+//!
+//! The application never talks to an SMTP server itself: `OutboxMailer` puts
+//! the messages into the `outbox` table, and a relay delivers them from there.
+//! In development the messages can be copied to stdout or to a file as well.
+
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use actix_web::web;
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+
+use crate::adapters::repositories::outbox::OutboxRepository;
+use crate::application::entities::mail::Mail;
+use crate::config::MailConfig;
+
+
+/// Something that accepts messages for delivery. The future is `Send`, so that
+/// the messages can be sent off the request, on a task of their own.
+pub trait Mailer: Send + Sync {
+  fn send(&self, mail: Mail) -> BoxFuture<'_, Result<(), Box<dyn Error>>>;
+}
+
+/// Where `OutboxMailer` copies the messages besides the outbox.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MailCopy {
+  None,
+  Stdout,
+  File(PathBuf),
+}
+
+impl MailCopy {
+  pub fn new(config: &MailConfig) -> Self {
+    match config.copy_to.as_str() {
+      "" => MailCopy::None,
+      "stdout" => MailCopy::Stdout,
+      path => MailCopy::File(PathBuf::from(path)),
+    }
+  }
+}
+
+/// The default `Mailer`: queues the messages in the `outbox` table.
+pub struct OutboxMailer {
+  outbox_repo: Arc<OutboxRepository>,
+  copy: MailCopy,
+}
+
+impl OutboxMailer {
+  pub fn new(outbox_repo: Arc<OutboxRepository>, config: &MailConfig) -> Self {
+    Self {
+      outbox_repo,
+      copy: MailCopy::new(config),
+    }
+  }
+
+  /// The message as text, for reading rather than delivery.
+  fn render(mail: &Mail) -> String {
+    format!(
+      "To: {}\nSubject: {}\nDate: {}\n\n{}\n\n",
+      mail.recipient,
+      mail.subject,
+      mail.date_created.to_rfc2822(),
+      mail.body,
+    )
+  }
+}
+
+impl Mailer for OutboxMailer {
+  fn send(&self, mail: Mail) -> BoxFuture<'_, Result<(), Box<dyn Error>>> {
+    async move {
+      self.outbox_repo.add_one(&mail).await?;
+
+      // the message is queued already, a failed copy is not worth failing the request
+      let text = Self::render(&mail);
+      match &self.copy {
+        MailCopy::None => {},
+        MailCopy::Stdout => {
+          let mut stdout = std::io::stdout().lock();
+          if let Err(e) = stdout.write_all(text.as_bytes()).and_then(|_| stdout.flush()) {
+            log::warn!("Failed to copy mail {} to stdout: {}", mail.id, e);
+          }
+        },
+        MailCopy::File(path) => {
+          let path = path.clone();
+          let written = web::block(move || {
+            OpenOptions::new().create(true).append(true).open(path)?.write_all(text.as_bytes())
+          }).await;
+          match written {
+            Ok(Ok(())) => {},
+            Ok(Err(e)) => log::warn!("Failed to copy mail {} to a file: {}", mail.id, e),
+            Err(e) => log::warn!("Failed to copy mail {} to a file: {}", mail.id, e),
+          }
+        },
+      }
+      Ok(())
+    }.boxed()
+  }
+}
@@ -0,0 +1,136 @@
+use std::time::{Duration, Instant};
+use prometheus::{Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry, TextEncoder};
+use sqlx::{Pool, Postgres};
+
+/// How long a scrape waits for the probe connection; an exhausted pool must not stall the scrapes.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+
+/// Prometheus metrics of the application, exported at `/metrics`.
+pub struct Metrics {
+  registry: Registry,
+  conn_pool: Pool<Postgres>,
+
+  /// Handled requests by method, matched route pattern and status.
+  pub http_requests: IntCounterVec,
+  /// Request latency by method and matched route pattern.
+  pub http_request_duration: HistogramVec,
+
+  db_pool_size: IntGauge,
+  db_pool_idle: IntGauge,
+  /// Not the wait of the requests, only of one probe per scrape.
+  db_pool_probe_duration: Histogram,
+
+  /// Requests rejected by `JwtAuth`, by reason.
+  pub auth_failures: IntCounterVec,
+  /// Requests turned away by `RateLimit`, by policy.
+  pub rate_limited: IntCounterVec,
+
+  pub registrations: IntCounter,
+  pub books_added: IntCounter,
+  pub loans_checked_out: IntCounter,
+  pub holds_placed: IntCounter,
+}
+
+impl Metrics {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    let registry = Registry::new_custom(Some("bookstore".to_string()), None)
+      .expect("the metrics prefix is valid");
+
+    let http_requests = IntCounterVec::new(
+      Opts::new("http_requests_total", "Handled HTTP requests."),
+      &["method", "route", "status"],
+    ).expect("the metric is valid");
+    let http_request_duration = HistogramVec::new(
+      HistogramOpts::new("http_request_duration_seconds", "HTTP request latency."),
+      &["method", "route"],
+    ).expect("the metric is valid");
+
+    let db_pool_size = IntGauge::new("db_pool_connections", "Open database connections.")
+      .expect("the metric is valid");
+    let db_pool_idle = IntGauge::new("db_pool_idle_connections", "Idle database connections.")
+      .expect("the metric is valid");
+    let db_pool_probe_duration = Histogram::with_opts(
+      HistogramOpts::new(
+        "db_pool_probe_acquire_duration_seconds",
+        "Time a probe on the scrape took to acquire a database connection; above 1 s it gave up.",
+      ).buckets(vec![0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0])
+    ).expect("the metric is valid");
+
+    let auth_failures = IntCounterVec::new(
+      Opts::new("auth_failures_total", "Requests rejected by the authentication middleware."),
+      &["reason"],
+    ).expect("the metric is valid");
+
+    let rate_limited = IntCounterVec::new(
+      Opts::new("rate_limited_total", "Requests rejected by the rate limiter."),
+      &["policy"],
+    ).expect("the metric is valid");
+
+    let registrations = IntCounter::new("registrations_total", "Registered users.")
+      .expect("the metric is valid");
+    let books_added = IntCounter::new("books_added_total", "Books added to the catalogue.")
+      .expect("the metric is valid");
+    let loans_checked_out = IntCounter::new("loans_checked_out_total", "Copies checked out.")
+      .expect("the metric is valid");
+    let holds_placed = IntCounter::new("holds_placed_total", "Holds placed.")
+      .expect("the metric is valid");
+
+    let metrics = Self {
+      registry,
+      conn_pool,
+      http_requests,
+      http_request_duration,
+      db_pool_size,
+      db_pool_idle,
+      db_pool_probe_duration,
+      auth_failures,
+      rate_limited,
+      registrations,
+      books_added,
+      loans_checked_out,
+      holds_placed,
+    };
+    metrics.register();
+    metrics
+  }
+
+  // every metric has a unique name, so registration never fails
+  fn register(&self) {
+    let collectors: Vec<Box<dyn prometheus::core::Collector>> = vec![
+      Box::new(self.http_requests.clone()),
+      Box::new(self.http_request_duration.clone()),
+      Box::new(self.db_pool_size.clone()),
+      Box::new(self.db_pool_idle.clone()),
+      Box::new(self.db_pool_probe_duration.clone()),
+      Box::new(self.auth_failures.clone()),
+      Box::new(self.rate_limited.clone()),
+      Box::new(self.registrations.clone()),
+      Box::new(self.books_added.clone()),
+      Box::new(self.loans_checked_out.clone()),
+      Box::new(self.holds_placed.clone()),
+    ];
+    for collector in collectors {
+      self.registry.register(collector).expect("metric names are unique");
+    }
+  }
+
+  /// Refresh the pool gauges and render all metrics in the Prometheus text format.
+  pub async fn render(&self) -> String {
+    let started = Instant::now();
+    match actix_web::rt::time::timeout(PROBE_TIMEOUT, self.conn_pool.acquire()).await {
+      Ok(Ok(_)) => self.db_pool_probe_duration.observe(started.elapsed().as_secs_f64()),
+      Ok(Err(e)) => log::error!(error:err = e; "Error acquiring a connection for the pool metrics: {}", e),
+      // counted in the +Inf bucket, so that a saturated pool shows on the graphs
+      Err(_) => self.db_pool_probe_duration.observe(started.elapsed().as_secs_f64()),
+    }
+    self.db_pool_size.set(self.conn_pool.size() as i64);
+    self.db_pool_idle.set(self.conn_pool.num_idle() as i64);
+
+    let mut buffer = vec![];
+    if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+      log::error!(error:err = e; "Error encoding metrics: {}", e);
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+  }
+}
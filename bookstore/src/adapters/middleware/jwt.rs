@@ -3,7 +3,8 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::time::SystemTime;
 use actix_web::{dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform}, Error, HttpMessage, web};
-use actix_web::error::{ErrorForbidden, ErrorUnauthorized, ErrorInternalServerError, ErrorNotFound};
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::http::StatusCode;
 use serde::{Deserialize, Serialize};
 use futures_util::future::LocalBoxFuture;
 
@@ -13,8 +14,9 @@ use hmac::{Hmac, Mac};
 use sha2::Sha256;
 use uuid::Uuid;
 
+use crate::adapters::middleware::problem::problem_response;
 use crate::application::entities::user::UserRole;
-use crate::application::services::user::UserFetchResult;
+use crate::application::services::auth::SessionCheckResult;
 use crate::application::state::app_state::AppState;
 
 const JWT_EXPIRATION_TIME: u64 = 60 * 60 * 24 * 30; // 1 month
@@ -24,39 +26,95 @@ pub struct JwtClaims {
   pub id: String,
   pub role: UserRole,
   pub exp: u64,
+  /// `users.session_version` at the time the token was issued;
+  /// the tokens issued before the column was added have none.
+  #[serde(default)]
+  pub ver: i32,
 }
 
 impl JwtClaims {
-  pub fn new(id: Uuid, role: UserRole) -> Self {
+  pub fn new(id: Uuid, role: UserRole, session_version: i32) -> Self {
     Self {
       id: id.to_string(),
       role,
-      exp: SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .expect("going back in time, huh?")
-        .as_secs() + JWT_EXPIRATION_TIME,
+      ver: session_version,
+      exp: now_secs() + JWT_EXPIRATION_TIME,
     }
   }
 
-  pub fn from_token(token: String) -> Result<Self, jwt::Error> {
-    let secret = std::env::var("APP_SECRET").unwrap();
+  /// ID of the user the token was issued to. `JwtAuth` only lets through
+  /// claims with a well-formed ID, so the nil UUID never shows up in handlers.
+  pub fn user_id(&self) -> Uuid {
+    Uuid::from_str(self.id.as_str()).unwrap_or_default()
+  }
+
+  // HMAC accepts keys of any length, so creating the key never fails
+  pub fn from_token(token: String, secret: &str) -> Result<Self, jwt::Error> {
+    let key: Hmac<Sha256> = Hmac::new_from_slice(secret.as_bytes()).unwrap();
+    token.verify_with_key(&key)
+  }
+
+  pub fn to_token(self, secret: &str) -> String {
+    let key: Hmac<Sha256> = Hmac::new_from_slice(secret.as_bytes()).unwrap();
+    self.sign_with_key(&key).unwrap()
+  }
+}
+
+/// Claims of the token `/api/auth/login` gives to the users with two-factor authentication
+/// in place of a session: it is only good for the second step of the login. It has no `role`,
+/// so it never passes for `JwtClaims`, and a session token has no `challenge`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginChallengeClaims {
+  pub id: String,
+  /// `users.session_version`, so that a password change ends the pending logins too.
+  pub ver: i32,
+  pub exp: u64,
+  pub challenge: bool,
+}
+
+impl LoginChallengeClaims {
+  pub fn new(id: Uuid, session_version: i32, ttl_secs: u64) -> Self {
+    Self {
+      id: id.to_string(),
+      ver: session_version,
+      exp: now_secs() + ttl_secs,
+      challenge: true,
+    }
+  }
+
+  /// The claims of a well-formed and unexpired challenge.
+  pub fn from_token(token: String, secret: &str) -> Option<Self> {
     let key: Hmac<Sha256> = Hmac::new_from_slice(secret.as_bytes()).unwrap();
-    return token.verify_with_key(&key);
+    let claims: Self = token.verify_with_key(&key).ok()?;
+    (claims.challenge && claims.exp > now_secs()).then_some(claims)
   }
 
-  pub fn to_token(self) -> String {
-    let secret = std::env::var("APP_SECRET").unwrap();
+  pub fn to_token(self, secret: &str) -> String {
     let key: Hmac<Sha256> = Hmac::new_from_slice(secret.as_bytes()).unwrap();
-    return self.sign_with_key(&key).unwrap();
+    self.sign_with_key(&key).unwrap()
+  }
+
+  pub fn user_id(&self) -> Option<Uuid> {
+    Uuid::from_str(self.id.as_str()).ok()
   }
 }
 
+fn now_secs() -> u64 {
+  SystemTime::now()
+    .duration_since(SystemTime::UNIX_EPOCH)
+    .expect("going back in time, huh?")
+    .as_secs()
+}
+
 pub struct JwtAuth {
   /// roles, which have access to the resource
   roles: Vec<UserRole>,
 
   /// global application state (to be able to use services)
   app_state: web::Data<AppState>,
+
+  /// let in the users who have yet to set up the required two-factor authentication
+  enrollment: bool,
 }
 
 impl JwtAuth {
@@ -64,17 +122,48 @@ impl JwtAuth {
     Self {
       roles: roles_permitted,
       app_state,
+      enrollment: false,
     }
   }
+
+  /// Let in the administrators without two-factor authentication even if it is
+  /// required, so that they can set it up.
+  pub fn allow_enrollment(mut self) -> Self {
+    self.enrollment = true;
+    self
+  }
+}
+
+/// Why a request was turned away, as reported in the `auth_failures_total` metric.
+struct Rejection {
+  reason: &'static str,
+  status: StatusCode,
+  detail: Option<&'static str>,
+}
+
+impl Rejection {
+  fn new(reason: &'static str, status: StatusCode, detail: Option<&'static str>) -> Self {
+    Self {
+      reason,
+      status,
+      detail,
+    }
+  }
+
+  fn into_response<B>(self, req: ServiceRequest, app_state: &AppState) -> ServiceResponse<EitherBody<B>> {
+    app_state.metrics.auth_failures.with_label_values(&[self.reason]).inc();
+    let res = problem_response(req.request(), self.status, self.detail);
+    req.into_response(res).map_into_right_body()
+  }
 }
 
 impl<S, B> Transform<S, ServiceRequest> for JwtAuth
   where
     S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
     S::Future: 'static,
-    B: 'static,
+    B: MessageBody + 'static,
 {
-  type Response = ServiceResponse<B>;
+  type Response = ServiceResponse<EitherBody<B>>;
   type Error = Error;
   type Transform = JwtAuthMiddleware<S>;
   type InitError = ();
@@ -85,6 +174,7 @@ impl<S, B> Transform<S, ServiceRequest> for JwtAuth
       service: Arc::new(service),
       roles: self.roles.clone(),
       app_state: self.app_state.clone(),
+      enrollment: self.enrollment,
     }))
   }
 }
@@ -93,72 +183,124 @@ pub struct JwtAuthMiddleware<S> {
   service: Arc<S>,
   roles: Vec<UserRole>,
   app_state: web::Data<AppState>,
+  enrollment: bool,
 }
 
-impl<S, B> Service<ServiceRequest> for JwtAuthMiddleware<S>
-  where
-    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
-    S::Future: 'static,
-    B: 'static,
-{
-  type Response = ServiceResponse<B>;
-  type Error = Error;
-  type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
-
-  forward_ready!(service);
-
-  fn call(&self, req: ServiceRequest) -> Self::Future {
+impl<S> JwtAuthMiddleware<S> {
+  /// Extract the claims from the `Authorization` header and check the role.
+  fn check_token(&self, req: &ServiceRequest) -> Result<JwtClaims, Rejection> {
     // try to extract a token from the `Authorization` header
     let auth_header = match req.headers().get("Authorization") {
       Some(auth_header) => match auth_header.to_str() {
         Ok(auth_header) => auth_header,
-        Err(_) => return Box::pin(async { Err(ErrorUnauthorized("")) })
+        Err(_) => return Err(Rejection::new("malformed_header", StatusCode::UNAUTHORIZED, None)),
       },
-      None => return Box::pin(async { Err(ErrorUnauthorized("")) })
+      None => return Err(Rejection::new("missing_header", StatusCode::UNAUTHORIZED, None)),
     };
     let token = match auth_header.strip_prefix("Bearer ") {
       Some(token) => token,
-      None => return Box::pin(async { Err(ErrorUnauthorized("")) })
+      None => return Err(Rejection::new("malformed_header", StatusCode::UNAUTHORIZED, None)),
     };
-    let claims = match JwtClaims::from_token(token.to_string()) {
+    let claims = match JwtClaims::from_token(token.to_string(), &self.app_state.config.auth.secret) {
       Ok(claims) => claims,
-      Err(_) => return Box::pin(async { Err(ErrorUnauthorized("")) })
+      Err(_) => return Err(Rejection::new("invalid_token", StatusCode::UNAUTHORIZED, None)),
     };
 
     if !self.roles.contains(&claims.role) {
-      return Box::pin(async { Err(ErrorForbidden("Insufficient rights for this resource.")) });
+      return Err(Rejection::new(
+        "insufficient_role",
+        StatusCode::FORBIDDEN,
+        Some("Insufficient rights for this resource."),
+      ));
     }
 
+    Ok(claims)
+  }
+}
+
+impl<S, B> Service<ServiceRequest> for JwtAuthMiddleware<S>
+  where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+  type Response = ServiceResponse<EitherBody<B>>;
+  type Error = Error;
+  type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+  forward_ready!(service);
+
+  fn call(&self, req: ServiceRequest) -> Self::Future {
+    let claims = match self.check_token(&req) {
+      Ok(claims) => claims,
+      Err(rejection) => {
+        let res = rejection.into_response(req, &self.app_state);
+        return Box::pin(async { Ok(res) })
+      }
+    };
+
     let svc = self.service.clone();
     let state = self.app_state.clone();
+    let enrollment = self.enrollment;
 
     Box::pin(async move {
       let user_id = match Uuid::from_str(claims.id.as_str()) {
         Ok(id) => id,
         Err(e) => {
-          log::error!("Failed to extract user ID from JWT claims: {}", e);
-          return Err(ErrorInternalServerError("Unexpected error. Contact the administrator."))
+          log::error!(error:err = e; "Failed to extract user ID from JWT claims: {}", e);
+          let rejection = Rejection::new(
+            "invalid_token",
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Some("Unexpected error. Contact the administrator."),
+          );
+          return Ok(rejection.into_response(req, &state))
         },
       };
 
-      // check if the account is suspended
-      let user = match state.user_service.get_by_id(&user_id).await {
-        UserFetchResult::Ok(user) => user,
-        UserFetchResult::NotFound => return Err(ErrorNotFound("The associated user account could not be found.")),
-        UserFetchResult::UnexpectedError(_) => return Err(ErrorInternalServerError("Unexpected error. Contact the administrator.")),
+      // check if the account is still there, active and the session is not revoked
+      let rejection = match state.auth_service.check_session(&user_id, claims.ver).await {
+        SessionCheckResult::Ok => None,
+        SessionCheckResult::NotFound => Some(Rejection::new(
+          "user_not_found",
+          StatusCode::NOT_FOUND,
+          Some("The associated user account could not be found."),
+        )),
+        SessionCheckResult::Suspended => Some(Rejection::new(
+          "suspended",
+          StatusCode::FORBIDDEN,
+          Some("The user account has been suspended. Contact the administrator."),
+        )),
+        SessionCheckResult::TwoFactorRequired if enrollment => None,
+        SessionCheckResult::TwoFactorRequired => Some(Rejection::new(
+          "two_factor_required",
+          StatusCode::FORBIDDEN,
+          Some("Administrators must set up two-factor authentication at /api/me/2fa first."),
+        )),
+        SessionCheckResult::Revoked => Some(Rejection::new(
+          "revoked_session",
+          StatusCode::UNAUTHORIZED,
+          Some("The session has been ended. Log in again."),
+        )),
+        SessionCheckResult::UnexpectedError(_) => Some(Rejection::new(
+          "unexpected_error",
+          StatusCode::INTERNAL_SERVER_ERROR,
+          Some("Unexpected error. Contact the administrator."),
+        )),
       };
-
-      if user.suspended {
-        return Err(ErrorForbidden("The user account has been suspended. Contact the administrator."));
+      if let Some(rejection) = rejection {
+        return Ok(rejection.into_response(req, &state))
       }
 
+      // from here on every log line of the request carries the user
+      tracing::Span::current().record("user_id", tracing::field::display(user_id));
+
       // inject the claims into responder
       req.extensions_mut().insert(claims);
 
       // continue down the middleware chain
-      let fut = svc.call(req);
+      let res = svc.call(req).await?;
 
-      Ok(fut.await?)
+      Ok(res.map_into_left_body())
     })
   }
-}
\ No newline at end of file
+}
@@ -0,0 +1,89 @@
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Instant;
+use actix_web::{dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform}, Error};
+use futures_util::future::LocalBoxFuture;
+
+use crate::adapters::metrics::Metrics;
+
+/// Route label for requests that matched no resource.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+
+/// Records the count and latency of every request by its matched route pattern.
+pub struct RequestMetrics {
+  metrics: Arc<Metrics>,
+}
+
+impl RequestMetrics {
+  pub fn new(metrics: Arc<Metrics>) -> Self {
+    Self {
+      metrics,
+    }
+  }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+  where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type Transform = RequestMetricsMiddleware<S>;
+  type InitError = ();
+  type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+  fn new_transform(&self, service: S) -> Self::Future {
+    ready(Ok(RequestMetricsMiddleware {
+      service: Rc::new(service),
+      metrics: self.metrics.clone(),
+    }))
+  }
+}
+
+pub struct RequestMetricsMiddleware<S> {
+  service: Rc<S>,
+  metrics: Arc<Metrics>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsMiddleware<S>
+  where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+  forward_ready!(service);
+
+  fn call(&self, req: ServiceRequest) -> Self::Future {
+    let started = Instant::now();
+    let method = req.method().to_string();
+    // the pattern (`/api/book/{id}`) rather than the path keeps the label set bounded
+    let route = req.match_pattern().unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+    let svc = self.service.clone();
+    let metrics = self.metrics.clone();
+
+    Box::pin(async move {
+      let res = svc.call(req).await;
+
+      // errors of inner middleware (e.g. `JwtAuth`) become responses later on
+      let status = match &res {
+        Ok(res) => res.status(),
+        Err(e) => e.as_response_error().status_code(),
+      };
+
+      metrics.http_requests.with_label_values(&[&method, &route, status.as_str()]).inc();
+      metrics.http_request_duration
+        .with_label_values(&[&method, &route])
+        .observe(started.elapsed().as_secs_f64());
+
+      res
+    })
+  }
+}
@@ -1 +1,5 @@
-pub mod jwt;
\ No newline at end of file
+pub mod jwt;
+pub mod metrics;
+pub mod request_id;
+pub mod problem;
+pub mod rate_limit;
\ No newline at end of file
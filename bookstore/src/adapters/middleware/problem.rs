@@ -0,0 +1,110 @@
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use actix_web::{dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform}, Error, HttpMessage, HttpRequest, HttpResponse};
+use actix_web::body::{BoxBody, EitherBody, MessageBody};
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::http::StatusCode;
+use futures_util::future::LocalBoxFuture;
+
+use crate::adapters::middleware::request_id::RequestId;
+use crate::application::dto::response::problem::{FieldErrorResp, Problem};
+
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+
+fn problem(req: &HttpRequest, status: StatusCode, detail: Option<String>) -> Problem {
+  let request_id = req.extensions().get::<RequestId>().map(|id| id.0.clone());
+  let title = status.canonical_reason().unwrap_or("Error");
+  Problem::new(status.as_u16(), title, detail, request_id)
+}
+
+fn problem_body(req: &HttpRequest, status: StatusCode, detail: Option<String>) -> String {
+  serde_json::to_string(&problem(req, status, detail)).unwrap_or_default()
+}
+
+/// An `application/problem+json` error response for the request.
+pub fn problem_response(req: &HttpRequest, status: StatusCode, detail: Option<&str>) -> HttpResponse {
+  HttpResponse::build(status)
+    .content_type(PROBLEM_CONTENT_TYPE)
+    .body(problem_body(req, status, detail.map(str::to_string)))
+}
+
+/// A 422 response listing every field of the request which failed the validation.
+pub fn validation_problem_response(req: &HttpRequest, errors: Vec<FieldErrorResp>) -> HttpResponse {
+  let status = StatusCode::UNPROCESSABLE_ENTITY;
+  let mut problem = problem(req, status, Some("Some fields of the request are invalid.".to_string()));
+  problem.errors = errors;
+  HttpResponse::build(status)
+    .content_type(PROBLEM_CONTENT_TYPE)
+    .body(serde_json::to_string(&problem).unwrap_or_default())
+}
+
+/// Fills the bodies of error responses which carry nothing (empty or JSON `null`)
+/// with `application/problem+json`. Error responses with a body are left as is.
+pub struct ProblemDetails;
+
+impl<S, B> Transform<S, ServiceRequest> for ProblemDetails
+  where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+  type Response = ServiceResponse<EitherBody<B>>;
+  type Error = Error;
+  type Transform = ProblemDetailsMiddleware<S>;
+  type InitError = ();
+  type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+  fn new_transform(&self, service: S) -> Self::Future {
+    ready(Ok(ProblemDetailsMiddleware {
+      service: Rc::new(service),
+    }))
+  }
+}
+
+pub struct ProblemDetailsMiddleware<S> {
+  service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for ProblemDetailsMiddleware<S>
+  where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+  type Response = ServiceResponse<EitherBody<B>>;
+  type Error = Error;
+  type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+  forward_ready!(service);
+
+  fn call(&self, req: ServiceRequest) -> Self::Future {
+    let fut = self.service.call(req);
+
+    Box::pin(async move {
+      let res = fut.await?;
+      let status = res.status();
+      if !status.is_client_error() && !status.is_server_error() {
+        return Ok(res.map_into_left_body())
+      }
+
+      let (req, res) = res.into_parts();
+      let (res, body) = res.into_parts();
+      let bytes = match body.try_into_bytes() {
+        Ok(bytes) => bytes,
+        // a streaming body is never empty
+        Err(body) => return Ok(ServiceResponse::new(req, res.set_body(body)).map_into_left_body()),
+      };
+
+      let res = if bytes.is_empty() || &bytes[..] == b"null" {
+        let mut res = res.set_body(BoxBody::new(problem_body(&req, status, None)));
+        res.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_CONTENT_TYPE));
+        res
+      } else {
+        res.set_body(BoxBody::new(bytes))
+      };
+
+      Ok(ServiceResponse::new(req, res).map_into_right_body())
+    })
+  }
+}
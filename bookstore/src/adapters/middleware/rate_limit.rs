@@ -0,0 +1,152 @@
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use actix_web::{dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform}, Error, HttpMessage, web};
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::http::header::{self, HeaderMap, HeaderName, HeaderValue};
+use actix_web::http::StatusCode;
+use futures_util::future::LocalBoxFuture;
+
+use crate::adapters::middleware::jwt::JwtClaims;
+use crate::adapters::middleware::problem::problem_response;
+use crate::adapters::rate_limit::Decision;
+use crate::adapters::util::client_ip::client_ip;
+use crate::application::state::app_state::AppState;
+use crate::config::BucketConfig;
+
+const RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+const RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+const RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+const RATELIMIT_POLICY: HeaderName = HeaderName::from_static("ratelimit-policy");
+
+
+/// Token bucket rate limiting of a scope, per user on the scopes behind `JwtAuth`
+/// and per client address on the others. Every response tells the client about
+/// its bucket in the `RateLimit-*` headers; a request finding the bucket empty gets
+/// `429` with `Retry-After`. The limiter lets everything through if the store fails.
+///
+/// Wrap `JwtAuth` around it, so that the user is known by the time it runs.
+pub struct RateLimit {
+  /// Name of the bucket set, which the keys and the metric are labelled with.
+  policy: &'static str,
+  bucket: BucketConfig,
+  app_state: web::Data<AppState>,
+}
+
+impl RateLimit {
+  pub fn new(policy: &'static str, bucket: &BucketConfig, app_state: web::Data<AppState>) -> Self {
+    Self {
+      policy,
+      bucket: *bucket,
+      app_state,
+    }
+  }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+  where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+  type Response = ServiceResponse<EitherBody<B>>;
+  type Error = Error;
+  type Transform = RateLimitMiddleware<S>;
+  type InitError = ();
+  type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+  fn new_transform(&self, service: S) -> Self::Future {
+    ready(Ok(RateLimitMiddleware {
+      service: Rc::new(service),
+      policy: self.policy,
+      bucket: self.bucket,
+      app_state: self.app_state.clone(),
+    }))
+  }
+}
+
+pub struct RateLimitMiddleware<S> {
+  service: Rc<S>,
+  policy: &'static str,
+  bucket: BucketConfig,
+  app_state: web::Data<AppState>,
+}
+
+impl<S> RateLimitMiddleware<S> {
+  /// Key of the bucket the request takes a token from.
+  fn key(&self, req: &ServiceRequest) -> String {
+    // only the claims `JwtAuth` has checked; a token the client merely sends proves nothing
+    if let Some(claims) = req.extensions().get::<JwtClaims>() {
+      return format!("{}:user:{}", self.policy, claims.id)
+    }
+
+    match client_ip(req.request()) {
+      Some(ip) => format!("{}:ip:{}", self.policy, ip),
+      None => format!("{}:ip:unknown", self.policy),
+    }
+  }
+}
+
+fn insert_headers(headers: &mut HeaderMap, bucket: &BucketConfig, decision: &Decision) {
+  // the window is how long an empty bucket takes to fill up
+  let window = (bucket.capacity as u64 * 60).div_ceil(bucket.per_minute as u64);
+  headers.insert(RATELIMIT_LIMIT, HeaderValue::from(bucket.capacity));
+  headers.insert(RATELIMIT_REMAINING, HeaderValue::from(decision.remaining));
+  headers.insert(RATELIMIT_RESET, HeaderValue::from(decision.reset_secs));
+  if let Ok(policy) = HeaderValue::from_str(&format!("{};w={}", bucket.capacity, window)) {
+    headers.insert(RATELIMIT_POLICY, policy);
+  }
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+  where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+  type Response = ServiceResponse<EitherBody<B>>;
+  type Error = Error;
+  type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+  forward_ready!(service);
+
+  fn call(&self, req: ServiceRequest) -> Self::Future {
+    let svc = self.service.clone();
+
+    if !self.app_state.config.rate_limit.enabled {
+      return Box::pin(async move {
+        Ok(svc.call(req).await?.map_into_left_body())
+      })
+    }
+
+    let key = self.key(&req);
+    let policy = self.policy;
+    let bucket = self.bucket;
+    let state = self.app_state.clone();
+
+    Box::pin(async move {
+      let decision = match state.rate_limit_store.take(&key, &bucket).await {
+        Ok(decision) => decision,
+        Err(e) => {
+          log::warn!("Rate limiting is skipped, the store failed: {}", e);
+          return Ok(svc.call(req).await?.map_into_left_body())
+        },
+      };
+
+      if !decision.allowed {
+        state.metrics.rate_limited.with_label_values(&[policy]).inc();
+        let mut res = problem_response(
+          req.request(),
+          StatusCode::TOO_MANY_REQUESTS,
+          Some("Too many requests. Try again later."),
+        );
+        insert_headers(res.headers_mut(), &bucket, &decision);
+        res.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(decision.retry_after_secs));
+        return Ok(req.into_response(res).map_into_right_body())
+      }
+
+      let mut res = svc.call(req).await?;
+      insert_headers(res.headers_mut(), &bucket, &decision);
+      Ok(res.map_into_left_body())
+    })
+  }
+}
@@ -0,0 +1,108 @@
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use actix_web::{dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform}, Error, HttpMessage};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use futures_util::future::LocalBoxFuture;
+use tracing::Instrument;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest request ID accepted from the client.
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+
+/// ID of the current request, available in the request extensions.
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+  /// Take the ID from the `X-Request-Id` header, if it is sane, or generate a new one.
+  fn from_request(req: &ServiceRequest) -> Self {
+    let header = req.headers()
+      .get(REQUEST_ID_HEADER)
+      .and_then(|value| value.to_str().ok())
+      .filter(|value| {
+        !value.is_empty()
+          && value.len() <= MAX_REQUEST_ID_LENGTH
+          && value.chars().all(|c| c.is_ascii_graphic())
+      });
+
+    match header {
+      Some(value) => Self(value.to_string()),
+      None => Self(Uuid::new_v4().to_string()),
+    }
+  }
+}
+
+/// Assigns every request an ID, runs it inside a span carrying the ID
+/// (and, once `JwtAuth` lets it through, the user ID) and echoes the ID
+/// back in the `X-Request-Id` response header.
+pub struct RequestTracing;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestTracing
+  where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type Transform = RequestTracingMiddleware<S>;
+  type InitError = ();
+  type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+  fn new_transform(&self, service: S) -> Self::Future {
+    ready(Ok(RequestTracingMiddleware {
+      service: Rc::new(service),
+    }))
+  }
+}
+
+pub struct RequestTracingMiddleware<S> {
+  service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestTracingMiddleware<S>
+  where
+    S: Service<ServiceRequest, Response=ServiceResponse<B>, Error=Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+  forward_ready!(service);
+
+  fn call(&self, req: ServiceRequest) -> Self::Future {
+    let request_id = RequestId::from_request(&req);
+    let route = req.match_pattern().unwrap_or_else(|| req.path().to_string());
+
+    let span = tracing::info_span!(
+      "http_request",
+      otel.name = %format!("{} {}", req.method(), route),
+      request_id = %request_id.0,
+      method = %req.method(),
+      path = %req.path(),
+      status = tracing::field::Empty,
+      user_id = tracing::field::Empty,
+    );
+
+    req.extensions_mut().insert(request_id.clone());
+
+    // the synchronous part of the inner middleware runs inside the span too
+    let fut = span.in_scope(|| self.service.call(req));
+
+    Box::pin(async move {
+      let mut res = fut.instrument(span.clone()).await?;
+
+      span.record("status", res.status().as_u16());
+      if let Ok(value) = HeaderValue::from_str(&request_id.0) {
+        res.headers_mut().insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+      }
+
+      Ok(res)
+    })
+  }
+}
@@ -1,4 +1,7 @@
 pub mod routes;
 pub mod repositories;
 pub mod util;
-pub mod middleware;
\ No newline at end of file
+pub mod middleware;
+pub mod metrics;pub mod mail;
+
+pub mod rate_limit;
\ No newline at end of file
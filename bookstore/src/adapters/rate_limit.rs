@@ -0,0 +1,137 @@
+//! Token buckets of the rate limiter.
+//!
+//! A bucket holds up to `capacity` tokens and gains `per_minute` of them a
+//! minute; every request takes one. `MemoryStore` keeps the buckets in the
+//! process, which is enough for a single instance; `PostgresStore` keeps them
+//! in the database, so that all instances share them.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use futures_util::future::LocalBoxFuture;
+use futures_util::FutureExt;
+
+use crate::adapters::repositories::rate_limit::RateLimitRepository;
+use crate::config::BucketConfig;
+
+/// The store forgets the full buckets every this many requests.
+const PRUNE_EVERY: u64 = 1000;
+
+
+/// What became of the token a request asked for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decision {
+  pub allowed: bool,
+  /// Whole tokens left in the bucket.
+  pub remaining: u32,
+  /// Seconds until the bucket is full again.
+  pub reset_secs: u64,
+  /// Seconds until the next token, if the request was turned away.
+  pub retry_after_secs: u64,
+}
+
+impl Decision {
+  /// The decision from the tokens left after the request took one, or failed to.
+  pub fn new(bucket: &BucketConfig, allowed: bool, tokens: f64) -> Self {
+    let per_sec = per_sec(bucket);
+    let tokens = tokens.clamp(0.0, bucket.capacity as f64);
+    Self {
+      allowed,
+      remaining: tokens.floor() as u32,
+      reset_secs: ((bucket.capacity as f64 - tokens) / per_sec).ceil() as u64,
+      retry_after_secs: if allowed { 0 } else { ((1.0 - tokens) / per_sec).ceil().max(1.0) as u64 },
+    }
+  }
+}
+
+fn per_sec(bucket: &BucketConfig) -> f64 {
+  bucket.per_minute as f64 / 60.0
+}
+
+/// Somewhere to keep the buckets.
+pub trait RateLimitStore: Send + Sync {
+  /// Take a token from the bucket under the key, creating a full one if there is none.
+  fn take<'a>(&'a self, key: &'a str, bucket: &'a BucketConfig) -> LocalBoxFuture<'a, Result<Decision, Box<dyn Error>>>;
+}
+
+struct MemoryBucket {
+  tokens: f64,
+  updated: Instant,
+  full_at: Instant,
+}
+
+/// The default `RateLimitStore`: the buckets of this process only.
+#[derive(Default)]
+pub struct MemoryStore {
+  buckets: Mutex<HashMap<String, MemoryBucket>>,
+  requests: AtomicU64,
+}
+
+impl MemoryStore {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  fn take_now(&self, key: &str, bucket: &BucketConfig, now: Instant) -> Decision {
+    let capacity = bucket.capacity as f64;
+    let per_sec = per_sec(bucket);
+    // a panic while holding the lock leaves the map consistent, so the poison is ignored
+    let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+    if self.requests.fetch_add(1, Ordering::Relaxed).is_multiple_of(PRUNE_EVERY) {
+      buckets.retain(|_, bucket| bucket.full_at > now);
+    }
+
+    let entry = buckets.entry(key.to_string()).or_insert(MemoryBucket {
+      tokens: capacity,
+      updated: now,
+      full_at: now,
+    });
+    let elapsed = now.saturating_duration_since(entry.updated).as_secs_f64();
+    let refilled = (entry.tokens + elapsed * per_sec).min(capacity);
+    let allowed = refilled >= 1.0;
+
+    entry.tokens = if allowed { refilled - 1.0 } else { refilled };
+    entry.updated = now;
+    entry.full_at = now + Duration::from_secs_f64((capacity - entry.tokens) / per_sec);
+    Decision::new(bucket, allowed, entry.tokens)
+  }
+}
+
+impl RateLimitStore for MemoryStore {
+  fn take<'a>(&'a self, key: &'a str, bucket: &'a BucketConfig) -> LocalBoxFuture<'a, Result<Decision, Box<dyn Error>>> {
+    let decision = self.take_now(key, bucket, Instant::now());
+    async move { Ok(decision) }.boxed_local()
+  }
+}
+
+/// The buckets in the `rate_limit_buckets` table, shared by all instances.
+pub struct PostgresStore {
+  rate_limit_repo: Arc<RateLimitRepository>,
+  requests: AtomicU64,
+}
+
+impl PostgresStore {
+  pub fn new(rate_limit_repo: Arc<RateLimitRepository>) -> Self {
+    Self {
+      rate_limit_repo,
+      requests: AtomicU64::new(0),
+    }
+  }
+}
+
+impl RateLimitStore for PostgresStore {
+  fn take<'a>(&'a self, key: &'a str, bucket: &'a BucketConfig) -> LocalBoxFuture<'a, Result<Decision, Box<dyn Error>>> {
+    async move {
+      if self.requests.fetch_add(1, Ordering::Relaxed).is_multiple_of(PRUNE_EVERY) {
+        // the repository has logged the error, and the buckets will be pruned next time
+        let _ = self.rate_limit_repo.delete_full().await;
+      }
+
+      let (allowed, tokens) = self.rate_limit_repo.take(key, bucket.capacity as f64, per_sec(bucket)).await?;
+      Ok(Decision::new(bucket, allowed, tokens))
+    }.boxed_local()
+  }
+}
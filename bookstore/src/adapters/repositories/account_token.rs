@@ -0,0 +1,102 @@
+use std::error::Error;
+use uuid::Uuid;
+use sqlx::{Pool, Postgres};
+
+use crate::application::entities::account_token::{AccountToken, TokenPurpose};
+
+
+pub struct AccountTokenRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl AccountTokenRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Save token into the database.
+  #[tracing::instrument(name = "AccountTokenRepository::add_one", skip_all)]
+  pub async fn add_one(&self, token: &AccountToken) -> Result<(), Box<dyn Error>> {
+    let text = concat!(
+      "INSERT INTO account_tokens\n",
+      "  (id, user_id, purpose, token_hash, email, date_created, date_expires)\n",
+      "VALUES\n",
+      "  ($1, $2, $3, $4, $5, $6, $7)"
+    );
+    let query = sqlx::query(text)
+      .bind(token.id)
+      .bind(token.user_id)
+      .bind(token.purpose)
+      .bind(&token.token_hash)
+      .bind(&token.email)
+      .bind(token.date_created)
+      .bind(token.date_expires);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!(error:err = e; "Error adding account token: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch an unused and unexpired token by its hash.
+  #[tracing::instrument(name = "AccountTokenRepository::get_valid", skip_all)]
+  pub async fn get_valid(&self, purpose: TokenPurpose, token_hash: &str) -> Result<Option<AccountToken>, Box<dyn Error>> {
+    let text = concat!(
+      "SELECT * FROM account_tokens\n",
+      "WHERE token_hash = $1 AND purpose = $2 AND date_used IS NULL AND date_expires > now()\n",
+      "LIMIT 1"
+    );
+    let query = sqlx::query_as::<_, AccountToken>(text)
+      .bind(token_hash)
+      .bind(purpose);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(token) => Ok(token),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching account token: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Mark the token used. Returns `false` if it has been used or has expired meanwhile,
+  /// so that of two concurrent requests only one gets through.
+  #[tracing::instrument(name = "AccountTokenRepository::use_one", skip_all)]
+  pub async fn use_one(&self, id: &Uuid) -> Result<bool, Box<dyn Error>> {
+    let text = concat!(
+      "UPDATE account_tokens SET date_used = now()\n",
+      "WHERE id = $1 AND date_used IS NULL AND date_expires > now()"
+    );
+    let query = sqlx::query(text).bind(id);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(result) => Ok(result.rows_affected() > 0),
+      Err(e) => {
+        log::error!(error:err = e; "Error using account token: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Mark all unused tokens of the user for the purpose as used.
+  #[tracing::instrument(name = "AccountTokenRepository::revoke_all", skip_all)]
+  pub async fn revoke_all(&self, user_id: &Uuid, purpose: TokenPurpose) -> Result<(), Box<dyn Error>> {
+    let text = "UPDATE account_tokens SET date_used = now() WHERE user_id = $1 AND purpose = $2 AND date_used IS NULL";
+    let query = sqlx::query(text)
+      .bind(user_id)
+      .bind(purpose);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!(error:err = e; "Error revoking account tokens: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
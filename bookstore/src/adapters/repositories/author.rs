@@ -17,6 +17,7 @@ impl AuthorRepository {
   }
 
   /// Fetch author from the database by ID.
+  #[tracing::instrument(name = "AuthorRepository::get_by_id", skip_all)]
   pub async fn get_by_id(&self, id: &Uuid) -> Result<Option<Author>, Box<dyn Error>> {
     let text = "SELECT * FROM authors WHERE id = $1 LIMIT 1";
     let query = sqlx::query_as::<_, Author>(text).bind(id);
@@ -24,13 +25,14 @@ impl AuthorRepository {
     match query.fetch_optional(&self.conn_pool).await {
       Ok(author) => Ok(author),
       Err(e) => {
-        log::error!("Error fetching author by id: {}", e);
+        log::error!(error:err = e; "Error fetching author by id: {}", e);
         Err(Box::new(e))
       }
     }
   }
 
   /// Fetch authors from the database.
+  #[tracing::instrument(name = "AuthorRepository::get_list", skip_all)]
   pub async fn get_list(&self, page: u32, size: u32) -> Result<Vec<Author>, Box<dyn Error>> {
     let text = "SELECT * FROM authors OFFSET $1 LIMIT $2";
     let query = sqlx::query_as::<_, Author>(text)
@@ -40,13 +42,14 @@ impl AuthorRepository {
     match query.fetch_all(&self.conn_pool).await {
       Ok(authors) => Ok(authors),
       Err(e) => {
-        log::error!("Error fetching authors: {}", e);
+        log::error!(error:err = e; "Error fetching authors: {}", e);
         Err(Box::new(e))
       }
     }
   }
 
   /// Save author into the database.
+  #[tracing::instrument(name = "AuthorRepository::add_one", skip_all)]
   pub async fn add_one(&self, author: Author) -> Result<(), Box<dyn Error>> {
     let text = concat!(
     "INSERT INTO authors\n",
@@ -63,13 +66,14 @@ impl AuthorRepository {
     match query.execute(&self.conn_pool).await {
       Ok(_) => Ok(()),
       Err(e) => {
-        log::error!("Error adding author: {}", e);
+        log::error!(error:err = e; "Error adding author: {}", e);
         Err(Box::new(e))
       }
     }
   }
 
   /// Delete author from the database by ID.
+  #[tracing::instrument(name = "AuthorRepository::delete_one", skip_all)]
   pub async fn delete_one(&self, id: &Uuid) -> Result<(), Box<dyn Error>> {
     let text = "DELETE FROM authors WHERE id = $1";
     let query = sqlx::query(text).bind(id);
@@ -77,7 +81,7 @@ impl AuthorRepository {
     match query.execute(&self.conn_pool).await {
       Ok(_) => Ok(()),
       Err(e) => {
-        log::error!("Error deleting author: {}", e);
+        log::error!(error:err = e; "Error deleting author: {}", e);
         Err(Box::new(e))
       }
     }
@@ -2,6 +2,7 @@ use std::error::Error;
 use uuid::Uuid;
 use sqlx::{Pool, Postgres};
 
+use crate::application::dto::request::book::BookListSort;
 use crate::application::entities::book::Book;
 
 
@@ -17,6 +18,7 @@ impl BookRepository {
   }
 
   /// Fetch book from the database by ID.
+  #[tracing::instrument(name = "BookRepository::get_by_id", skip_all)]
   pub async fn get_by_id(&self, id: &Uuid) -> Result<Option<Book>, Box<dyn Error>> {
     let text = "SELECT * FROM books WHERE id = $1 LIMIT 1";
     let query = sqlx::query_as::<_, Book>(text).bind(id);
@@ -24,13 +26,29 @@ impl BookRepository {
     match query.fetch_optional(&self.conn_pool).await {
       Ok(book) => Ok(book),
       Err(e) => {
-        log::error!("Error fetching book by id: {}", e);
+        log::error!(error:err = e; "Error fetching book by id: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch books from the database by their IDs.
+  #[tracing::instrument(name = "BookRepository::get_by_ids", skip_all)]
+  pub async fn get_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Book>, Box<dyn Error>> {
+    let text = "SELECT * FROM books WHERE id = ANY($1)";
+    let query = sqlx::query_as::<_, Book>(text).bind(ids);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(books) => Ok(books),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching books by ids: {}", e);
         Err(Box::new(e))
       }
     }
   }
 
   /// Fetch books from the database by `author_id`.
+  #[tracing::instrument(name = "BookRepository::get_by_author_id", skip_all)]
   pub async fn get_by_author_id(&self, author_id: &Uuid) -> Result<Vec<Book>, Box<dyn Error>> {
     let text = "SELECT * FROM books WHERE author_id = $1";
     let query = sqlx::query_as::<_, Book>(text).bind(author_id);
@@ -38,15 +56,24 @@ impl BookRepository {
     match query.fetch_all(&self.conn_pool).await {
       Ok(book) => Ok(book),
       Err(e) => {
-        log::error!("Error fetching books by author_id: {}", e);
+        log::error!(error:err = e; "Error fetching books by author_id: {}", e);
         Err(Box::new(e))
       }
     }
   }
 
   /// Fetch books from the database.
-  pub async fn get_list(&self, page: u32, size: u32) -> Result<Vec<Book>, Box<dyn Error>> {
-    let text = "SELECT * FROM books OFFSET $1 LIMIT $2";
+  #[tracing::instrument(name = "BookRepository::get_list", skip_all)]
+  pub async fn get_list(&self, page: u32, size: u32, sort: Option<BookListSort>) -> Result<Vec<Book>, Box<dyn Error>> {
+    let text = match sort {
+      None => "SELECT * FROM books OFFSET $1 LIMIT $2",
+      // the ordering expression matches the `ix_books_rating` index
+      Some(BookListSort::Rating) => concat!(
+        "SELECT * FROM books\n",
+        "ORDER BY (rating_sum::float8 / NULLIF(rating_count, 0)) DESC NULLS LAST, rating_count DESC, id\n",
+        "OFFSET $1 LIMIT $2"
+      ),
+    };
     let query = sqlx::query_as::<_, Book>(text)
       .bind((page * size) as i64)
       .bind(size as i64);
@@ -54,35 +81,64 @@ impl BookRepository {
     match query.fetch_all(&self.conn_pool).await {
       Ok(books) => Ok(books),
       Err(e) => {
-        log::error!("Error fetching books: {}", e);
+        log::error!(error:err = e; "Error fetching books: {}", e);
         Err(Box::new(e))
       }
     }
   }
 
   /// Save book into the database.
+  #[tracing::instrument(name = "BookRepository::add_one", skip_all)]
   pub async fn add_one(&self, book: Book) -> Result<(), Box<dyn Error>> {
     let text = concat!(
       "INSERT INTO books\n",
-      "  (id, title, author_id)\n",
+      "  (id, title, author_id, price, stock)\n",
       "VALUES\n",
-      "  ($1, $2, $3)"
+      "  ($1, $2, $3, $4, $5)"
     );
     let query = sqlx::query(text)
       .bind(book.id)
       .bind(book.title)
-      .bind(book.author_id);
+      .bind(book.author_id)
+      .bind(book.price)
+      .bind(book.stock);
 
     match query.execute(&self.conn_pool).await {
       Ok(_) => Ok(()),
       Err(e) => {
-        log::error!("Error adding book: {}", e);
+        log::error!(error:err = e; "Error adding book: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Update book's price and stock in the database by ID. `None` leaves
+  /// the value unchanged. Wishlist notifications are generated by the
+  /// database within the same statement.
+  #[tracing::instrument(name = "BookRepository::update_inventory", skip_all)]
+  pub async fn update_inventory(&self, id: &Uuid, price: Option<i32>, stock: Option<i32>) -> Result<Option<Book>, Box<dyn Error>> {
+    let text = concat!(
+      "UPDATE books\n",
+      "SET price = COALESCE($1, price), stock = COALESCE($2, stock)\n",
+      "WHERE id = $3\n",
+      "RETURNING *"
+    );
+    let query = sqlx::query_as::<_, Book>(text)
+      .bind(price)
+      .bind(stock)
+      .bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(book) => Ok(book),
+      Err(e) => {
+        log::error!(error:err = e; "Error updating book inventory: {}", e);
         Err(Box::new(e))
       }
     }
   }
 
   /// Delete book from the database by ID.
+  #[tracing::instrument(name = "BookRepository::delete_one", skip_all)]
   pub async fn delete_one(&self, id: &Uuid) -> Result<(), Box<dyn Error>> {
     let text = "DELETE FROM books WHERE id = $1";
     let query = sqlx::query(text).bind(id);
@@ -90,7 +146,7 @@ impl BookRepository {
     match query.execute(&self.conn_pool).await {
       Ok(_) => Ok(()),
       Err(e) => {
-        log::error!("Error deleting book: {}", e);
+        log::error!(error:err = e; "Error deleting book: {}", e);
         Err(Box::new(e))
       }
     }
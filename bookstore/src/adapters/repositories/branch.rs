@@ -0,0 +1,121 @@
+use std::error::Error;
+use uuid::Uuid;
+use sqlx::{Pool, Postgres};
+
+use crate::application::entities::branch::Branch;
+
+
+pub struct BranchRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl BranchRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Fetch branch from the database by its id.
+  #[tracing::instrument(name = "BranchRepository::get_by_id", skip_all)]
+  pub async fn get_by_id(&self, id: &Uuid) -> Result<Option<Branch>, Box<dyn Error>> {
+    let text = "SELECT * FROM branches WHERE id = $1 LIMIT 1";
+    let query = sqlx::query_as::<_, Branch>(text).bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(branch) => Ok(branch),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching branch by id: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch branch from the database by its name.
+  #[tracing::instrument(name = "BranchRepository::get_by_name", skip_all)]
+  pub async fn get_by_name(&self, name: &str) -> Result<Option<Branch>, Box<dyn Error>> {
+    let text = "SELECT * FROM branches WHERE name = $1 LIMIT 1";
+    let query = sqlx::query_as::<_, Branch>(text).bind(name);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(branch) => Ok(branch),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching branch by name: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch all branches from the database, ordered by name.
+  #[tracing::instrument(name = "BranchRepository::get_list", skip_all)]
+  pub async fn get_list(&self) -> Result<Vec<Branch>, Box<dyn Error>> {
+    let text = "SELECT * FROM branches ORDER BY name";
+    let query = sqlx::query_as::<_, Branch>(text);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(branches) => Ok(branches),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching branches: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Add branch to the database.
+  #[tracing::instrument(name = "BranchRepository::add_one", skip_all)]
+  pub async fn add_one(&self, branch: Branch) -> Result<Branch, Box<dyn Error>> {
+    let text = concat!(
+      "INSERT INTO branches\n",
+      "  (id, name, address, date_created)\n",
+      "VALUES\n",
+      "  ($1, $2, $3, $4)\n",
+      "RETURNING *"
+    );
+    let query = sqlx::query_as::<_, Branch>(text)
+      .bind(branch.id)
+      .bind(branch.name)
+      .bind(branch.address)
+      .bind(branch.date_created);
+
+    match query.fetch_one(&self.conn_pool).await {
+      Ok(branch) => Ok(branch),
+      Err(e) => {
+        log::error!(error:err = e; "Error adding branch: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Update branch name and address.
+  #[tracing::instrument(name = "BranchRepository::update_one", skip_all)]
+  pub async fn update_one(&self, id: &Uuid, name: String, address: String) -> Result<Option<Branch>, Box<dyn Error>> {
+    let text = "UPDATE branches SET name = $1, address = $2 WHERE id = $3 RETURNING *";
+    let query = sqlx::query_as::<_, Branch>(text)
+      .bind(name)
+      .bind(address)
+      .bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(branch) => Ok(branch),
+      Err(e) => {
+        log::error!(error:err = e; "Error updating branch: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Delete branch from the database by ID. Returns `false` if there was no such branch.
+  #[tracing::instrument(name = "BranchRepository::delete_one", skip_all)]
+  pub async fn delete_one(&self, id: &Uuid) -> Result<bool, Box<dyn Error>> {
+    let text = "DELETE FROM branches WHERE id = $1";
+    let query = sqlx::query(text).bind(id);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(res) => Ok(res.rows_affected() > 0),
+      Err(e) => {
+        log::error!(error:err = e; "Error deleting branch: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
@@ -0,0 +1,300 @@
+use std::error::Error;
+use uuid::Uuid;
+use sqlx::{Pool, Postgres};
+
+use crate::application::entities::branch::BranchAvailability;
+use crate::application::entities::copy::{BookCopy, CopyCondition, CopyStatus, CopyTransfer};
+
+
+pub struct CopyRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl CopyRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Fetch copy from the database by its id.
+  #[tracing::instrument(name = "CopyRepository::get_by_id", skip_all)]
+  pub async fn get_by_id(&self, id: &Uuid) -> Result<Option<BookCopy>, Box<dyn Error>> {
+    let text = "SELECT * FROM copies WHERE id = $1 LIMIT 1";
+    let query = sqlx::query_as::<_, BookCopy>(text).bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(copy) => Ok(copy),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching copy by id: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch copy from the database by its barcode.
+  #[tracing::instrument(name = "CopyRepository::get_by_barcode", skip_all)]
+  pub async fn get_by_barcode(&self, barcode: &str) -> Result<Option<BookCopy>, Box<dyn Error>> {
+    let text = "SELECT * FROM copies WHERE barcode = $1 LIMIT 1";
+    let query = sqlx::query_as::<_, BookCopy>(text).bind(barcode);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(copy) => Ok(copy),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching copy by barcode: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch copies from the database, oldest first. Filters which are `None` are not applied.
+  #[tracing::instrument(name = "CopyRepository::get_list", skip_all)]
+  pub async fn get_list(
+    &self,
+    book_id: Option<Uuid>,
+    branch_id: Option<Uuid>,
+    status: Option<CopyStatus>,
+    page: u32,
+    size: u32,
+  ) -> Result<Vec<BookCopy>, Box<dyn Error>> {
+    let text = concat!(
+      "SELECT * FROM copies\n",
+      "WHERE ($1::uuid IS NULL OR book_id = $1)\n",
+      "  AND ($2::uuid IS NULL OR branch_id = $2)\n",
+      "  AND ($3::copy_status IS NULL OR status = $3)\n",
+      "ORDER BY date_added, id\n",
+      "OFFSET $4 LIMIT $5"
+    );
+    let query = sqlx::query_as::<_, BookCopy>(text)
+      .bind(book_id)
+      .bind(branch_id)
+      .bind(status)
+      .bind((page * size) as i64)
+      .bind(size as i64);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(copies) => Ok(copies),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching copies: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Add a physical copy of a book to the database.
+  #[tracing::instrument(name = "CopyRepository::add_one", skip_all)]
+  pub async fn add_one(&self, copy: BookCopy) -> Result<BookCopy, Box<dyn Error>> {
+    let text = concat!(
+      "INSERT INTO copies\n",
+      "  (id, book_id, status, date_added, barcode, condition, branch_id)\n",
+      "VALUES\n",
+      "  ($1, $2, $3, $4, $5, $6, $7)\n",
+      "RETURNING *"
+    );
+    let query = sqlx::query_as::<_, BookCopy>(text)
+      .bind(copy.id)
+      .bind(copy.book_id)
+      .bind(copy.status)
+      .bind(copy.date_added)
+      .bind(copy.barcode)
+      .bind(copy.condition)
+      .bind(copy.branch_id);
+
+    match query.fetch_one(&self.conn_pool).await {
+      Ok(copy) => Ok(copy),
+      Err(e) => {
+        log::error!(error:err = e; "Error adding copy: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Update barcode, condition and status of a copy, provided its status is still `expected_status`.
+  /// Returns `None` if the copy is gone or its status has changed meanwhile.
+  #[tracing::instrument(name = "CopyRepository::update_one", skip_all)]
+  pub async fn update_one(
+    &self,
+    id: &Uuid,
+    barcode: String,
+    condition: CopyCondition,
+    status: CopyStatus,
+    expected_status: CopyStatus,
+  ) -> Result<Option<BookCopy>, Box<dyn Error>> {
+    let text = concat!(
+      "UPDATE copies SET barcode = $2, condition = $3, status = $4\n",
+      "WHERE id = $1 AND status = $5\n",
+      "RETURNING *"
+    );
+    let query = sqlx::query_as::<_, BookCopy>(text)
+      .bind(id)
+      .bind(barcode)
+      .bind(condition)
+      .bind(status)
+      .bind(expected_status);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(copy) => Ok(copy),
+      Err(e) => {
+        log::error!(error:err = e; "Error updating copy: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Delete copy from the database by ID.
+  #[tracing::instrument(name = "CopyRepository::delete_one", skip_all)]
+  pub async fn delete_one(&self, id: &Uuid) -> Result<bool, Box<dyn Error>> {
+    let text = "DELETE FROM copies WHERE id = $1";
+    let query = sqlx::query(text).bind(id);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(res) => Ok(res.rows_affected() > 0),
+      Err(e) => {
+        log::error!(error:err = e; "Error deleting copy: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Check whether the copy has ever been lent.
+  #[tracing::instrument(name = "CopyRepository::has_loans", skip_all)]
+  pub async fn has_loans(&self, id: &Uuid) -> Result<bool, Box<dyn Error>> {
+    let text = "SELECT EXISTS (SELECT 1 FROM loans WHERE copy_id = $1)";
+    let query = sqlx::query_scalar::<_, bool>(text).bind(id);
+
+    match query.fetch_one(&self.conn_pool).await {
+      Ok(exists) => Ok(exists),
+      Err(e) => {
+        log::error!(error:err = e; "Error checking copy loans: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Count the copies assigned to a branch.
+  #[tracing::instrument(name = "CopyRepository::count_by_branch_id", skip_all)]
+  pub async fn count_by_branch_id(&self, branch_id: &Uuid) -> Result<i64, Box<dyn Error>> {
+    let text = "SELECT count(*) FROM copies WHERE branch_id = $1";
+    let query = sqlx::query_scalar::<_, i64>(text).bind(branch_id);
+
+    match query.fetch_one(&self.conn_pool).await {
+      Ok(count) => Ok(count),
+      Err(e) => {
+        log::error!(error:err = e; "Error counting branch copies: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Count the copies of a book which are on the shelf right now.
+  #[tracing::instrument(name = "CopyRepository::count_available_by_book_id", skip_all)]
+  pub async fn count_available_by_book_id(&self, book_id: &Uuid) -> Result<i64, Box<dyn Error>> {
+    let text = "SELECT count(*) FROM copies WHERE book_id = $1 AND status = 'available'";
+    let query = sqlx::query_scalar::<_, i64>(text).bind(book_id);
+
+    match query.fetch_one(&self.conn_pool).await {
+      Ok(count) => Ok(count),
+      Err(e) => {
+        log::error!(error:err = e; "Error counting available copies: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Count the copies of several books per branch.
+  #[tracing::instrument(name = "CopyRepository::get_availability_by_book_ids", skip_all)]
+  pub async fn get_availability_by_book_ids(&self, book_ids: &[Uuid]) -> Result<Vec<BranchAvailability>, Box<dyn Error>> {
+    let text = concat!(
+      "SELECT\n",
+      "  c.book_id,\n",
+      "  c.branch_id,\n",
+      "  b.name AS branch_name,\n",
+      "  count(*) FILTER (WHERE c.status = 'available') AS available,\n",
+      "  count(*) FILTER (WHERE c.status IN ('available', 'on_loan')) AS total\n",
+      "FROM copies c\n",
+      "LEFT JOIN branches b ON b.id = c.branch_id\n",
+      "WHERE c.book_id = ANY($1)\n",
+      "GROUP BY c.book_id, c.branch_id, b.name\n",
+      "HAVING count(*) FILTER (WHERE c.status IN ('available', 'on_loan')) > 0\n",
+      "ORDER BY b.name NULLS LAST"
+    );
+    let query = sqlx::query_as::<_, BranchAvailability>(text).bind(book_ids);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(availability) => Ok(availability),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching copy availability: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Move a copy to another branch and record the transfer.
+  /// Returns `None` if the copy is gone or has been lent meanwhile.
+  #[tracing::instrument(name = "CopyRepository::transfer", skip_all)]
+  pub async fn transfer(&self, transfer: CopyTransfer) -> Result<Option<BookCopy>, Box<dyn Error>> {
+    let mut tx = match self.conn_pool.begin().await {
+      Ok(tx) => tx,
+      Err(e) => {
+        log::error!(error:err = e; "Error starting transaction: {}", e);
+        return Err(Box::new(e))
+      }
+    };
+
+    let text = "UPDATE copies SET branch_id = $2 WHERE id = $1 AND status <> 'on_loan' RETURNING *";
+    let query = sqlx::query_as::<_, BookCopy>(text)
+      .bind(transfer.copy_id)
+      .bind(transfer.to_branch_id);
+
+    let copy = match query.fetch_optional(&mut *tx).await {
+      Ok(Some(copy)) => copy,
+      Ok(None) => return Ok(None),
+      Err(e) => {
+        log::error!(error:err = e; "Error transferring copy: {}", e);
+        return Err(Box::new(e))
+      }
+    };
+
+    let text = concat!(
+      "INSERT INTO copy_transfers\n",
+      "  (id, copy_id, from_branch_id, to_branch_id, user_id, date_transferred)\n",
+      "VALUES\n",
+      "  ($1, $2, $3, $4, $5, $6)"
+    );
+    let query = sqlx::query(text)
+      .bind(transfer.id)
+      .bind(transfer.copy_id)
+      .bind(transfer.from_branch_id)
+      .bind(transfer.to_branch_id)
+      .bind(transfer.user_id)
+      .bind(transfer.date_transferred);
+
+    if let Err(e) = query.execute(&mut *tx).await {
+      log::error!(error:err = e; "Error recording copy transfer: {}", e);
+      return Err(Box::new(e))
+    }
+
+    match tx.commit().await {
+      Ok(_) => Ok(Some(copy)),
+      Err(e) => {
+        log::error!(error:err = e; "Error committing copy transfer: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch transfer history of a copy, oldest first.
+  #[tracing::instrument(name = "CopyRepository::get_transfer_list", skip_all)]
+  pub async fn get_transfer_list(&self, copy_id: &Uuid) -> Result<Vec<CopyTransfer>, Box<dyn Error>> {
+    let text = "SELECT * FROM copy_transfers WHERE copy_id = $1 ORDER BY date_transferred";
+    let query = sqlx::query_as::<_, CopyTransfer>(text).bind(copy_id);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(transfers) => Ok(transfers),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching copy transfers: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
@@ -0,0 +1,107 @@
+use std::error::Error;
+use sqlx::{Pool, Postgres};
+
+
+/// Bulk copy of whole tables as JSON arrays of rows, for backups and moving
+/// data between deployments.
+pub struct DumpRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl DumpRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Names of the tables which have at least one row.
+  #[tracing::instrument(name = "DumpRepository::get_non_empty_tables", skip_all)]
+  pub async fn get_non_empty_tables(&self, tables: &[&str]) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut non_empty = vec![];
+    for table in tables {
+      // table names come from a fixed list, never from the input
+      let text = format!("SELECT EXISTS (SELECT 1 FROM {})", table);
+      match sqlx::query_scalar::<_, bool>(&text).fetch_one(&self.conn_pool).await {
+        Ok(true) => non_empty.push(table.to_string()),
+        Ok(false) => {},
+        Err(e) => {
+          log::error!(error:err = e; "Error checking table {}: {}", table, e);
+          return Err(Box::new(e))
+        }
+      }
+    }
+    Ok(non_empty)
+  }
+
+  /// Fetch the rows of every table as a JSON array, all from the same snapshot.
+  #[tracing::instrument(name = "DumpRepository::export_tables", skip_all)]
+  pub async fn export_tables(&self, tables: &[&str]) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let export = async {
+      let mut tx = self.conn_pool.begin().await?;
+      sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *tx)
+        .await?;
+
+      let mut rows = vec![];
+      for table in tables {
+        let text = format!("SELECT COALESCE(json_agg(t), '[]')::text FROM {} t", table);
+        let json = sqlx::query_scalar::<_, String>(&text).fetch_one(&mut *tx).await?;
+        rows.push((table.to_string(), json));
+      }
+
+      tx.commit().await?;
+      Ok::<_, sqlx::Error>(rows)
+    };
+
+    match export.await {
+      Ok(rows) => Ok(rows),
+      Err(e) => {
+        log::error!(error:err = e; "Error exporting tables: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Insert the rows given as JSON arrays in one transaction, in the given
+  /// order; with `replace` every table in `all_tables` is emptied first.
+  #[tracing::instrument(name = "DumpRepository::import_tables", skip_all)]
+  pub async fn import_tables(&self, all_tables: &[&str], rows: &[(String, String)], replace: bool) -> Result<(), Box<dyn Error>> {
+    let import = async {
+      let mut tx = self.conn_pool.begin().await?;
+
+      if replace {
+        let text = format!("TRUNCATE {} CASCADE", all_tables.join(", "));
+        sqlx::query(&text).execute(&mut *tx).await?;
+      }
+
+      for (table, json) in rows {
+        let text = format!("INSERT INTO {0} SELECT * FROM json_populate_recordset(NULL::{0}, $1::json)", table);
+        sqlx::query(&text).bind(json).execute(&mut *tx).await?;
+      }
+
+      // the review trigger has added the imported reviews on top of the imported totals
+      let text = concat!(
+        "UPDATE books b\n",
+        "SET rating_sum = COALESCE(r.rating_sum, 0), rating_count = COALESCE(r.rating_count, 0)\n",
+        "FROM books b2\n",
+        "LEFT JOIN (\n",
+        "  SELECT book_id, sum(rating) AS rating_sum, count(*) AS rating_count\n",
+        "  FROM reviews WHERE NOT hidden GROUP BY book_id\n",
+        ") r ON r.book_id = b2.id\n",
+        "WHERE b.id = b2.id"
+      );
+      sqlx::query(text).execute(&mut *tx).await?;
+
+      tx.commit().await
+    };
+
+    match import.await {
+      Ok(()) => Ok(()),
+      Err(e) => {
+        log::error!(error:err = e; "Error importing tables: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
@@ -0,0 +1,71 @@
+use std::error::Error;
+use std::time::Duration;
+use sqlx::{Pool, Postgres};
+
+use crate::application::entities::health::PoolStats;
+
+
+pub struct HealthRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl HealthRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Acquire a connection from the pool and run a trivial query on it,
+  /// giving up after `timeout`.
+  #[tracing::instrument(name = "HealthRepository::ping", skip_all)]
+  pub async fn ping(&self, timeout: Duration) -> Result<(), Box<dyn Error>> {
+    let check = async {
+      let mut conn = self.conn_pool.acquire().await?;
+      sqlx::query("SELECT 1").execute(&mut *conn).await?;
+      Ok::<(), sqlx::Error>(())
+    };
+
+    match actix_web::rt::time::timeout(timeout, check).await {
+      Ok(Ok(())) => Ok(()),
+      Ok(Err(e)) => {
+        log::error!(error:err = e; "Error checking the database connection: {}", e);
+        Err(Box::new(e))
+      },
+      Err(e) => {
+        log::error!("Error checking the database connection: no response within {:?}", timeout);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch the versions of the successfully applied migrations, in ascending order;
+  /// none if migrations have never been run.
+  #[tracing::instrument(name = "HealthRepository::get_applied_migrations", skip_all)]
+  pub async fn get_applied_migrations(&self) -> Result<Vec<i64>, Box<dyn Error>> {
+    let fetch = async {
+      let text = "SELECT to_regclass('_sqlx_migrations') IS NOT NULL";
+      if !sqlx::query_scalar::<_, bool>(text).fetch_one(&self.conn_pool).await? {
+        return Ok(vec![])
+      }
+
+      let text = "SELECT version FROM _sqlx_migrations WHERE success ORDER BY version";
+      sqlx::query_scalar::<_, i64>(text).fetch_all(&self.conn_pool).await
+    };
+
+    match fetch.await {
+      Ok(versions) => Ok(versions),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching applied migrations: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  pub fn get_pool_stats(&self) -> PoolStats {
+    PoolStats {
+      size: self.conn_pool.size(),
+      idle: self.conn_pool.num_idle(),
+    }
+  }
+}
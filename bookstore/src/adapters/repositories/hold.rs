@@ -0,0 +1,142 @@
+use std::error::Error;
+use uuid::Uuid;
+use sqlx::{Pool, Postgres};
+
+use crate::application::entities::hold::Hold;
+
+
+pub struct HoldRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl HoldRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Fetch a hold from the database by its id.
+  #[tracing::instrument(name = "HoldRepository::get_by_id", skip_all)]
+  pub async fn get_by_id(&self, id: &Uuid) -> Result<Option<Hold>, Box<dyn Error>> {
+    let text = "SELECT * FROM holds WHERE id = $1 LIMIT 1";
+    let query = sqlx::query_as::<_, Hold>(text).bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(hold) => Ok(hold),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching hold by id: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch user's place in the queue for a book, if the user is waiting for it.
+  #[tracing::instrument(name = "HoldRepository::get_waiting_by_book_id_and_user_id", skip_all)]
+  pub async fn get_waiting_by_book_id_and_user_id(&self, book_id: &Uuid, user_id: &Uuid) -> Result<Option<Hold>, Box<dyn Error>> {
+    let text = "SELECT * FROM holds WHERE book_id = $1 AND user_id = $2 AND status = 'waiting' LIMIT 1";
+    let query = sqlx::query_as::<_, Hold>(text)
+      .bind(book_id)
+      .bind(user_id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(hold) => Ok(hold),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching hold: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch the queues the user is waiting in, oldest first.
+  #[tracing::instrument(name = "HoldRepository::get_waiting_list_by_user_id", skip_all)]
+  pub async fn get_waiting_list_by_user_id(&self, user_id: &Uuid) -> Result<Vec<Hold>, Box<dyn Error>> {
+    let text = "SELECT * FROM holds WHERE user_id = $1 AND status = 'waiting' ORDER BY date_placed";
+    let query = sqlx::query_as::<_, Hold>(text).bind(user_id);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(holds) => Ok(holds),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching holds: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Count the users waiting for a book.
+  #[tracing::instrument(name = "HoldRepository::count_waiting_by_book_id", skip_all)]
+  pub async fn count_waiting_by_book_id(&self, book_id: &Uuid) -> Result<i64, Box<dyn Error>> {
+    let text = "SELECT count(*) FROM holds WHERE book_id = $1 AND status = 'waiting'";
+    let query = sqlx::query_scalar::<_, i64>(text).bind(book_id);
+
+    match query.fetch_one(&self.conn_pool).await {
+      Ok(count) => Ok(count),
+      Err(e) => {
+        log::error!(error:err = e; "Error counting holds: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Position of a waiting hold in its queue, starting from 1.
+  #[tracing::instrument(name = "HoldRepository::get_position", skip_all)]
+  pub async fn get_position(&self, hold: &Hold) -> Result<i64, Box<dyn Error>> {
+    let text = concat!(
+      "SELECT count(*) + 1 FROM holds\n",
+      "WHERE book_id = $1 AND status = 'waiting' AND (date_placed, id) < ($2, $3)"
+    );
+    let query = sqlx::query_scalar::<_, i64>(text)
+      .bind(hold.book_id)
+      .bind(hold.date_placed)
+      .bind(hold.id);
+
+    match query.fetch_one(&self.conn_pool).await {
+      Ok(position) => Ok(position),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching hold position: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Add a hold to the database.
+  #[tracing::instrument(name = "HoldRepository::add_one", skip_all)]
+  pub async fn add_one(&self, hold: Hold) -> Result<Hold, Box<dyn Error>> {
+    let text = concat!(
+      "INSERT INTO holds\n",
+      "  (id, book_id, user_id, date_placed, status)\n",
+      "VALUES\n",
+      "  ($1, $2, $3, $4, $5)\n",
+      "RETURNING *"
+    );
+    let query = sqlx::query_as::<_, Hold>(text)
+      .bind(hold.id)
+      .bind(hold.book_id)
+      .bind(hold.user_id)
+      .bind(hold.date_placed)
+      .bind(hold.status);
+
+    match query.fetch_one(&self.conn_pool).await {
+      Ok(hold) => Ok(hold),
+      Err(e) => {
+        log::error!(error:err = e; "Error adding hold: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Cancel a waiting hold. Returns `false` if it is not waiting anymore.
+  #[tracing::instrument(name = "HoldRepository::cancel", skip_all)]
+  pub async fn cancel(&self, id: &Uuid) -> Result<bool, Box<dyn Error>> {
+    let text = "UPDATE holds SET status = 'cancelled' WHERE id = $1 AND status = 'waiting'";
+    let query = sqlx::query(text).bind(id);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(res) => Ok(res.rows_affected() > 0),
+      Err(e) => {
+        log::error!(error:err = e; "Error cancelling hold: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
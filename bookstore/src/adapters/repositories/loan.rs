@@ -0,0 +1,263 @@
+use std::error::Error;
+use chrono::{DateTime, Local};
+use uuid::Uuid;
+use sqlx::{Pool, Postgres};
+
+use crate::application::entities::loan::Loan;
+
+
+pub struct LoanRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl LoanRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Fetch a loan from the database by its id.
+  #[tracing::instrument(name = "LoanRepository::get_by_id", skip_all)]
+  pub async fn get_by_id(&self, id: &Uuid) -> Result<Option<Loan>, Box<dyn Error>> {
+    let text = "SELECT * FROM loans WHERE id = $1 LIMIT 1";
+    let query = sqlx::query_as::<_, Loan>(text).bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(loan) => Ok(loan),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching loan by id: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch user's loans from the database, newest first.
+  #[tracing::instrument(name = "LoanRepository::get_list_by_user_id", skip_all)]
+  pub async fn get_list_by_user_id(
+    &self,
+    user_id: &Uuid,
+    active_only: bool,
+    page: u32,
+    size: u32,
+  ) -> Result<Vec<Loan>, Box<dyn Error>> {
+    let text = concat!(
+      "SELECT * FROM loans\n",
+      "WHERE user_id = $1 AND (NOT $2 OR date_returned IS NULL)\n",
+      "ORDER BY date_borrowed DESC\n",
+      "OFFSET $3 LIMIT $4"
+    );
+    let query = sqlx::query_as::<_, Loan>(text)
+      .bind(user_id)
+      .bind(active_only)
+      .bind((page * size) as i64)
+      .bind(size as i64);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(loans) => Ok(loans),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching loans: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch the loans which are past their due date, most overdue first.
+  #[tracing::instrument(name = "LoanRepository::get_overdue_list", skip_all)]
+  pub async fn get_overdue_list(&self, page: u32, size: u32) -> Result<Vec<Loan>, Box<dyn Error>> {
+    let text = concat!(
+      "SELECT * FROM loans\n",
+      "WHERE date_returned IS NULL AND date_due < now()\n",
+      "ORDER BY date_due\n",
+      "OFFSET $1 LIMIT $2"
+    );
+    let query = sqlx::query_as::<_, Loan>(text)
+      .bind((page * size) as i64)
+      .bind(size as i64);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(loans) => Ok(loans),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching overdue loans: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Count user's loans which are past their due date.
+  #[tracing::instrument(name = "LoanRepository::count_overdue_by_user_id", skip_all)]
+  pub async fn count_overdue_by_user_id(&self, user_id: &Uuid) -> Result<i64, Box<dyn Error>> {
+    let text = "SELECT count(*) FROM loans WHERE user_id = $1 AND date_returned IS NULL AND date_due < now()";
+    let query = sqlx::query_scalar::<_, i64>(text).bind(user_id);
+
+    match query.fetch_one(&self.conn_pool).await {
+      Ok(count) => Ok(count),
+      Err(e) => {
+        log::error!(error:err = e; "Error counting overdue loans: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Lend a copy of a book to the user.
+  ///
+  /// Copies on the shelf are reserved for the users at the head of the hold queue,
+  /// so the user gets one only if there are more available copies than users waiting
+  /// ahead of them. Returns `None` if there is no copy for the user.
+  #[tracing::instrument(name = "LoanRepository::checkout", skip_all)]
+  pub async fn checkout(&self, book_id: &Uuid, user_id: &Uuid, date_due: DateTime<Local>) -> Result<Option<Loan>, Box<dyn Error>> {
+    let mut tx = match self.conn_pool.begin().await {
+      Ok(tx) => tx,
+      Err(e) => {
+        log::error!(error:err = e; "Error starting transaction: {}", e);
+        return Err(Box::new(e))
+      }
+    };
+
+    // locking the available copies serializes concurrent checkouts of the same book
+    let text = concat!(
+      "SELECT id FROM copies\n",
+      "WHERE book_id = $1 AND status = 'available'\n",
+      "ORDER BY date_added\n",
+      "FOR UPDATE"
+    );
+    let query = sqlx::query_scalar::<_, Uuid>(text).bind(book_id);
+
+    let copy_ids = match query.fetch_all(&mut *tx).await {
+      Ok(ids) => ids,
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching available copies: {}", e);
+        return Err(Box::new(e))
+      }
+    };
+
+    let text = "SELECT user_id FROM holds WHERE book_id = $1 AND status = 'waiting' ORDER BY date_placed, id";
+    let query = sqlx::query_scalar::<_, Uuid>(text).bind(book_id);
+
+    let queue = match query.fetch_all(&mut *tx).await {
+      Ok(queue) => queue,
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching hold queue: {}", e);
+        return Err(Box::new(e))
+      }
+    };
+
+    let ahead = queue.iter().position(|id| id == user_id).unwrap_or(queue.len());
+    if ahead >= copy_ids.len() {
+      return Ok(None)
+    }
+
+    let text = "UPDATE copies SET status = 'on_loan' WHERE id = $1";
+    let query = sqlx::query(text).bind(copy_ids[0]);
+
+    if let Err(e) = query.execute(&mut *tx).await {
+      log::error!(error:err = e; "Error updating copy status: {}", e);
+      return Err(Box::new(e))
+    }
+
+    let text = "UPDATE holds SET status = 'fulfilled' WHERE book_id = $1 AND user_id = $2 AND status = 'waiting'";
+    let query = sqlx::query(text)
+      .bind(book_id)
+      .bind(user_id);
+
+    if let Err(e) = query.execute(&mut *tx).await {
+      log::error!(error:err = e; "Error fulfilling hold: {}", e);
+      return Err(Box::new(e))
+    }
+
+    let text = concat!(
+      "INSERT INTO loans\n",
+      "  (id, copy_id, book_id, user_id, date_borrowed, date_due, date_returned, renewals)\n",
+      "VALUES\n",
+      "  ($1, $2, $3, $4, $5, $6, NULL, 0)\n",
+      "RETURNING *"
+    );
+    let query = sqlx::query_as::<_, Loan>(text)
+      .bind(Uuid::new_v4())
+      .bind(copy_ids[0])
+      .bind(book_id)
+      .bind(user_id)
+      .bind(Local::now())
+      .bind(date_due);
+
+    let loan = match query.fetch_one(&mut *tx).await {
+      Ok(loan) => loan,
+      Err(e) => {
+        log::error!(error:err = e; "Error adding loan: {}", e);
+        return Err(Box::new(e))
+      }
+    };
+
+    match tx.commit().await {
+      Ok(_) => Ok(Some(loan)),
+      Err(e) => {
+        log::error!(error:err = e; "Error committing loan: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Move the due date of an active loan, unless it has been renewed `max_renewals` times already.
+  /// Returns `None` if the loan cannot be renewed.
+  #[tracing::instrument(name = "LoanRepository::renew", skip_all)]
+  pub async fn renew(&self, id: &Uuid, date_due: DateTime<Local>, max_renewals: i32) -> Result<Option<Loan>, Box<dyn Error>> {
+    let text = concat!(
+      "UPDATE loans SET date_due = $2, renewals = renewals + 1\n",
+      "WHERE id = $1 AND date_returned IS NULL AND renewals < $3\n",
+      "RETURNING *"
+    );
+    let query = sqlx::query_as::<_, Loan>(text)
+      .bind(id)
+      .bind(date_due)
+      .bind(max_renewals);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(loan) => Ok(loan),
+      Err(e) => {
+        log::error!(error:err = e; "Error renewing loan: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Mark a loan as returned and put the copy back on the shelf.
+  /// Returns `None` if the loan has been returned already.
+  #[tracing::instrument(name = "LoanRepository::return_one", skip_all)]
+  pub async fn return_one(&self, id: &Uuid) -> Result<Option<Loan>, Box<dyn Error>> {
+    let mut tx = match self.conn_pool.begin().await {
+      Ok(tx) => tx,
+      Err(e) => {
+        log::error!(error:err = e; "Error starting transaction: {}", e);
+        return Err(Box::new(e))
+      }
+    };
+
+    let text = "UPDATE loans SET date_returned = now() WHERE id = $1 AND date_returned IS NULL RETURNING *";
+    let query = sqlx::query_as::<_, Loan>(text).bind(id);
+
+    let loan = match query.fetch_optional(&mut *tx).await {
+      Ok(Some(loan)) => loan,
+      Ok(None) => return Ok(None),
+      Err(e) => {
+        log::error!(error:err = e; "Error returning loan: {}", e);
+        return Err(Box::new(e))
+      }
+    };
+
+    let text = "UPDATE copies SET status = 'available' WHERE id = $1 AND status = 'on_loan'";
+    let query = sqlx::query(text).bind(loan.copy_id);
+
+    if let Err(e) = query.execute(&mut *tx).await {
+      log::error!(error:err = e; "Error updating copy status: {}", e);
+      return Err(Box::new(e))
+    }
+
+    match tx.commit().await {
+      Ok(_) => Ok(Some(loan)),
+      Err(e) => {
+        log::error!(error:err = e; "Error committing loan return: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
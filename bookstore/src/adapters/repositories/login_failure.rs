@@ -0,0 +1,125 @@
+use std::error::Error;
+use chrono::{DateTime, Duration, Local};
+use sqlx::{Pool, Postgres};
+
+use crate::application::entities::login_failure::{LoginFailure, LoginKeyKind};
+
+
+pub struct LoginFailureRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl LoginFailureRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// The latest time the nickname or the address is locked until, if it is still in the future.
+  #[tracing::instrument(name = "LoginFailureRepository::get_locked_until", skip_all)]
+  pub async fn get_locked_until(&self, nickname: &str, ip: Option<&str>) -> Result<Option<DateTime<Local>>, Box<dyn Error>> {
+    let text = concat!(
+      "SELECT max(locked_until) FROM login_failures\n",
+      "WHERE ((kind = 'nickname' AND key = $1) OR (kind = 'ip' AND key = $2)) AND locked_until > now()"
+    );
+    let query = sqlx::query_scalar::<_, Option<DateTime<Local>>>(text)
+      .bind(nickname)
+      .bind(ip);
+
+    match query.fetch_one(&self.conn_pool).await {
+      Ok(locked_until) => Ok(locked_until),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching login lockouts: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Count one more failure for the key and return the count. The count starts
+  /// over if the previous failure is older than `window` and the key is not locked.
+  #[tracing::instrument(name = "LoginFailureRepository::add_failure", skip_all)]
+  pub async fn add_failure(&self, kind: LoginKeyKind, key: &str, window: Duration) -> Result<i32, Box<dyn Error>> {
+    let text = concat!(
+      "INSERT INTO login_failures AS f (kind, key, failures, date_last_failure)\n",
+      "VALUES ($1, $2, 1, now())\n",
+      "ON CONFLICT (kind, key) DO UPDATE SET\n",
+      "  failures = CASE\n",
+      "    WHEN f.date_last_failure < now() - make_interval(secs => $3) AND (f.locked_until IS NULL OR f.locked_until <= now()) THEN 1\n",
+      "    ELSE f.failures + 1\n",
+      "  END,\n",
+      "  date_last_failure = now()\n",
+      "RETURNING failures"
+    );
+    let query = sqlx::query_scalar::<_, i32>(text)
+      .bind(kind)
+      .bind(key)
+      .bind(window.num_seconds() as f64);
+
+    match query.fetch_one(&self.conn_pool).await {
+      Ok(failures) => Ok(failures),
+      Err(e) => {
+        log::error!(error:err = e; "Error adding login failure: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Reject the logins with the key until the given time.
+  #[tracing::instrument(name = "LoginFailureRepository::lock", skip_all)]
+  pub async fn lock(&self, kind: LoginKeyKind, key: &str, until: DateTime<Local>) -> Result<(), Box<dyn Error>> {
+    let text = "UPDATE login_failures SET locked_until = $1 WHERE kind = $2 AND key = $3";
+    let query = sqlx::query(text)
+      .bind(until)
+      .bind(kind)
+      .bind(key);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!(error:err = e; "Error locking login: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Forget the failures of the key, lifting its lockout.
+  #[tracing::instrument(name = "LoginFailureRepository::delete_one", skip_all)]
+  pub async fn delete_one(&self, kind: LoginKeyKind, key: &str) -> Result<bool, Box<dyn Error>> {
+    let text = "DELETE FROM login_failures WHERE kind = $1 AND key = $2";
+    let query = sqlx::query(text)
+      .bind(kind)
+      .bind(key);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(result) => Ok(result.rows_affected() > 0),
+      Err(e) => {
+        log::error!(error:err = e; "Error deleting login failures: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch the keys with failures, the latest failure first.
+  #[tracing::instrument(name = "LoginFailureRepository::get_list", skip_all)]
+  pub async fn get_list(&self, page: u32, size: u32, locked_only: bool) -> Result<Vec<LoginFailure>, Box<dyn Error>> {
+    let text = concat!(
+      "SELECT * FROM login_failures\n",
+      "WHERE NOT $1 OR locked_until > now()\n",
+      "ORDER BY date_last_failure DESC\n",
+      "OFFSET $2 LIMIT $3"
+    );
+    let query = sqlx::query_as::<_, LoginFailure>(text)
+      .bind(locked_only)
+      .bind((page * size) as i64)
+      .bind(size as i64);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(failures) => Ok(failures),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching login failures: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
@@ -1,3 +1,37 @@
 pub mod user;
 pub mod book;
 pub mod author;
+pub mod review;
+pub mod shelf;
+pub mod wishlist;
+pub mod notification;
+pub mod copy;
+pub mod loan;
+pub mod hold;
+pub mod branch;
+pub mod health;
+pub mod dump;
+pub mod seed;
+pub mod account_token;
+pub mod outbox;
+pub mod login_failure;
+pub mod rate_limit;
+pub mod recovery_code;
+
+use std::error::Error;
+
+/// The error returned by a repository is a violation of a unique constraint,
+/// which the services report as a conflict rather than a failure.
+pub fn is_unique_violation(e: &(dyn Error + 'static)) -> bool {
+  e.downcast_ref::<sqlx::Error>()
+    .and_then(|e| e.as_database_error())
+    .is_some_and(|e| e.is_unique_violation())
+}
+
+/// The error returned by a repository is a violation of a check constraint,
+/// which the services report as a bad request rather than a failure.
+pub fn is_check_violation(e: &(dyn Error + 'static)) -> bool {
+  e.downcast_ref::<sqlx::Error>()
+    .and_then(|e| e.as_database_error())
+    .is_some_and(|e| e.is_check_violation())
+}
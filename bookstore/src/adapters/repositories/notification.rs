@@ -0,0 +1,81 @@
+use std::error::Error;
+use uuid::Uuid;
+use sqlx::{Pool, Postgres};
+
+use crate::application::entities::notification::Notification;
+
+
+pub struct NotificationRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl NotificationRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Fetch user's notifications from the database, newest first.
+  #[tracing::instrument(name = "NotificationRepository::get_list_by_user_id", skip_all)]
+  pub async fn get_list_by_user_id(
+    &self,
+    user_id: &Uuid,
+    page: u32,
+    size: u32,
+    unread_only: bool,
+  ) -> Result<Vec<Notification>, Box<dyn Error>> {
+    let text = concat!(
+      "SELECT * FROM notifications\n",
+      "WHERE user_id = $1 AND NOT (read AND $2)\n",
+      "ORDER BY date_created DESC\n",
+      "OFFSET $3 LIMIT $4"
+    );
+    let query = sqlx::query_as::<_, Notification>(text)
+      .bind(user_id)
+      .bind(unread_only)
+      .bind((page * size) as i64)
+      .bind(size as i64);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(notifications) => Ok(notifications),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching notifications: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Update the `read` column of user's notification by ID.
+  #[tracing::instrument(name = "NotificationRepository::update_read", skip_all)]
+  pub async fn update_read(&self, user_id: &Uuid, id: &Uuid, read: bool) -> Result<Option<Notification>, Box<dyn Error>> {
+    let text = "UPDATE notifications SET read = $1 WHERE id = $2 AND user_id = $3 RETURNING *";
+    let query = sqlx::query_as::<_, Notification>(text)
+      .bind(read)
+      .bind(id)
+      .bind(user_id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(notification) => Ok(notification),
+      Err(e) => {
+        log::error!(error:err = e; "Error updating notification: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Mark all of user's notifications as read.
+  #[tracing::instrument(name = "NotificationRepository::mark_all_read", skip_all)]
+  pub async fn mark_all_read(&self, user_id: &Uuid) -> Result<(), Box<dyn Error>> {
+    let text = "UPDATE notifications SET read = TRUE WHERE user_id = $1 AND NOT read";
+    let query = sqlx::query(text).bind(user_id);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!(error:err = e; "Error updating notifications: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
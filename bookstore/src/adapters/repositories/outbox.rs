@@ -0,0 +1,42 @@
+use std::error::Error;
+use sqlx::{Pool, Postgres};
+
+use crate::application::entities::mail::Mail;
+
+
+pub struct OutboxRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl OutboxRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Queue the message for delivery.
+  #[tracing::instrument(name = "OutboxRepository::add_one", skip_all)]
+  pub async fn add_one(&self, mail: &Mail) -> Result<(), Box<dyn Error>> {
+    let text = concat!(
+      "INSERT INTO outbox\n",
+      "  (id, recipient, subject, body, date_created)\n",
+      "VALUES\n",
+      "  ($1, $2, $3, $4, $5)"
+    );
+    let query = sqlx::query(text)
+      .bind(mail.id)
+      .bind(&mail.recipient)
+      .bind(&mail.subject)
+      .bind(&mail.body)
+      .bind(mail.date_created);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!(error:err = e; "Error adding mail to the outbox: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
@@ -0,0 +1,68 @@
+use std::error::Error;
+use sqlx::{Pool, Postgres};
+
+
+pub struct RateLimitRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl RateLimitRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Refill the bucket for the time since the last request and take a token
+  /// from it, if there is a whole one. Returns whether the token was taken
+  /// and how many are left, in one statement, so that concurrent requests
+  /// to several instances cannot take the same token.
+  #[tracing::instrument(name = "RateLimitRepository::take", skip_all)]
+  pub async fn take(&self, key: &str, capacity: f64, per_sec: f64) -> Result<(bool, f64), Box<dyn Error>> {
+    // the row lock makes a concurrent request wait and then see the tokens left by this one
+    let text = concat!(
+      "WITH refilled AS (\n",
+      "  SELECT coalesce((\n",
+      "    SELECT least($2, tokens + extract(epoch FROM now() - date_updated)::float8 * $3)\n",
+      "    FROM rate_limit_buckets WHERE key = $1 FOR UPDATE\n",
+      "  ), $2) AS tokens\n",
+      "), taken AS (\n",
+      "  SELECT tokens >= 1 AS allowed, CASE WHEN tokens >= 1 THEN tokens - 1 ELSE tokens END AS tokens FROM refilled\n",
+      ")\n",
+      "INSERT INTO rate_limit_buckets (key, tokens, allowed, date_updated, date_full)\n",
+      "SELECT $1, tokens, allowed, now(), now() + make_interval(secs => ($2 - tokens) / $3) FROM taken\n",
+      "ON CONFLICT (key) DO UPDATE SET\n",
+      "  tokens = EXCLUDED.tokens,\n",
+      "  allowed = EXCLUDED.allowed,\n",
+      "  date_updated = EXCLUDED.date_updated,\n",
+      "  date_full = EXCLUDED.date_full\n",
+      "RETURNING allowed, tokens"
+    );
+    let query = sqlx::query_as::<_, (bool, f64)>(text)
+      .bind(key)
+      .bind(capacity)
+      .bind(per_sec);
+
+    match query.fetch_one(&self.conn_pool).await {
+      Ok(taken) => Ok(taken),
+      Err(e) => {
+        log::error!(error:err = e; "Error taking a rate limit token: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Forget the buckets which have filled up since their last request.
+  #[tracing::instrument(name = "RateLimitRepository::delete_full", skip_all)]
+  pub async fn delete_full(&self) -> Result<u64, Box<dyn Error>> {
+    let text = "DELETE FROM rate_limit_buckets WHERE date_full <= now()";
+
+    match sqlx::query(text).execute(&self.conn_pool).await {
+      Ok(result) => Ok(result.rows_affected()),
+      Err(e) => {
+        log::error!(error:err = e; "Error deleting full rate limit buckets: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
@@ -0,0 +1,96 @@
+use std::error::Error;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+
+pub struct RecoveryCodeRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl RecoveryCodeRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Replace the recovery codes of the user with the new ones, given as hashes.
+  #[tracing::instrument(name = "RecoveryCodeRepository::replace_all", skip_all)]
+  pub async fn replace_all(&self, user_id: &Uuid, code_hashes: &[String]) -> Result<(), Box<dyn Error>> {
+    let replace = async {
+      let mut tx = self.conn_pool.begin().await?;
+      sqlx::query("DELETE FROM recovery_codes WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+      for code_hash in code_hashes {
+        sqlx::query("INSERT INTO recovery_codes (id, user_id, code_hash) VALUES ($1, $2, $3)")
+          .bind(Uuid::new_v4())
+          .bind(user_id)
+          .bind(code_hash)
+          .execute(&mut *tx)
+          .await?;
+      }
+
+      tx.commit().await
+    };
+
+    match replace.await {
+      Ok(()) => Ok(()),
+      Err(e) => {
+        log::error!(error:err = e; "Error replacing recovery codes: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Use up the user's recovery code with the hash; `false` if there is no such unused code.
+  #[tracing::instrument(name = "RecoveryCodeRepository::use_one", skip_all)]
+  pub async fn use_one(&self, user_id: &Uuid, code_hash: &str) -> Result<bool, Box<dyn Error>> {
+    let text = "UPDATE recovery_codes SET date_used = now() WHERE user_id = $1 AND code_hash = $2 AND date_used IS NULL";
+    let query = sqlx::query(text)
+      .bind(user_id)
+      .bind(code_hash);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(result) => Ok(result.rows_affected() > 0),
+      Err(e) => {
+        log::error!(error:err = e; "Error using recovery code: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Recovery codes of the user not used yet.
+  #[tracing::instrument(name = "RecoveryCodeRepository::count_unused", skip_all)]
+  pub async fn count_unused(&self, user_id: &Uuid) -> Result<i64, Box<dyn Error>> {
+    let text = "SELECT count(*) FROM recovery_codes WHERE user_id = $1 AND date_used IS NULL";
+    let query = sqlx::query_scalar::<_, i64>(text)
+      .bind(user_id);
+
+    match query.fetch_one(&self.conn_pool).await {
+      Ok(count) => Ok(count),
+      Err(e) => {
+        log::error!(error:err = e; "Error counting recovery codes: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Forget all recovery codes of the user.
+  #[tracing::instrument(name = "RecoveryCodeRepository::delete_all", skip_all)]
+  pub async fn delete_all(&self, user_id: &Uuid) -> Result<(), Box<dyn Error>> {
+    let text = "DELETE FROM recovery_codes WHERE user_id = $1";
+    let query = sqlx::query(text)
+      .bind(user_id);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!(error:err = e; "Error deleting recovery codes: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
@@ -0,0 +1,168 @@
+use std::error::Error;
+use chrono::Local;
+use uuid::Uuid;
+use sqlx::{Pool, Postgres};
+
+use crate::adapters::repositories::is_unique_violation;
+use crate::application::entities::review::Review;
+
+
+pub struct ReviewRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl ReviewRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Fetch review from the database by ID.
+  #[tracing::instrument(name = "ReviewRepository::get_by_id", skip_all)]
+  pub async fn get_by_id(&self, id: &Uuid) -> Result<Option<Review>, Box<dyn Error>> {
+    let text = "SELECT * FROM reviews WHERE id = $1 LIMIT 1";
+    let query = sqlx::query_as::<_, Review>(text).bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(review) => Ok(review),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching review by id: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch the review left by `user_id` on `book_id`.
+  #[tracing::instrument(name = "ReviewRepository::get_by_book_and_user", skip_all)]
+  pub async fn get_by_book_and_user(&self, book_id: &Uuid, user_id: &Uuid) -> Result<Option<Review>, Box<dyn Error>> {
+    let text = "SELECT * FROM reviews WHERE book_id = $1 AND user_id = $2 LIMIT 1";
+    let query = sqlx::query_as::<_, Review>(text)
+      .bind(book_id)
+      .bind(user_id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(review) => Ok(review),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching review by book_id and user_id: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch reviews of a book from the database, newest first.
+  #[tracing::instrument(name = "ReviewRepository::get_list_by_book_id", skip_all)]
+  pub async fn get_list_by_book_id(
+    &self,
+    book_id: &Uuid,
+    page: u32,
+    size: u32,
+    include_hidden: bool,
+  ) -> Result<Vec<Review>, Box<dyn Error>> {
+    let text = concat!(
+      "SELECT * FROM reviews\n",
+      "WHERE book_id = $1 AND ($2 OR NOT hidden)\n",
+      "ORDER BY date_created DESC\n",
+      "OFFSET $3 LIMIT $4"
+    );
+    let query = sqlx::query_as::<_, Review>(text)
+      .bind(book_id)
+      .bind(include_hidden)
+      .bind((page * size) as i64)
+      .bind(size as i64);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(reviews) => Ok(reviews),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching reviews: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Save review into the database.
+  #[tracing::instrument(name = "ReviewRepository::add_one", skip_all)]
+  pub async fn add_one(&self, review: Review) -> Result<(), Box<dyn Error>> {
+    let text = concat!(
+      "INSERT INTO reviews\n",
+      "  (id, book_id, user_id, rating, text, date_created, date_updated, hidden)\n",
+      "VALUES\n",
+      "  ($1, $2, $3, $4, $5, $6, $7, $8)"
+    );
+    let query = sqlx::query(text)
+      .bind(review.id)
+      .bind(review.book_id)
+      .bind(review.user_id)
+      .bind(review.rating)
+      .bind(review.text)
+      .bind(review.date_created)
+      .bind(review.date_updated)
+      .bind(review.hidden);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        if !is_unique_violation(&e) {
+          log::error!(error:err = e; "Error adding review: {}", e);
+        }
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Update review's rating and text in the database by ID.
+  #[tracing::instrument(name = "ReviewRepository::update_one", skip_all)]
+  pub async fn update_one(&self, id: &Uuid, rating: i16, text: String) -> Result<Option<Review>, Box<dyn Error>> {
+    let update_text = concat!(
+      "UPDATE reviews\n",
+      "SET rating = $1, text = $2, date_updated = $3\n",
+      "WHERE id = $4\n",
+      "RETURNING *"
+    );
+    let query = sqlx::query_as::<_, Review>(update_text)
+      .bind(rating)
+      .bind(text)
+      .bind(Local::now())
+      .bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(review) => Ok(review),
+      Err(e) => {
+        log::error!(error:err = e; "Error updating review: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Update review's `hidden` column in the database by ID.
+  #[tracing::instrument(name = "ReviewRepository::update_hidden", skip_all)]
+  pub async fn update_hidden(&self, id: &Uuid, hidden: bool) -> Result<Option<Review>, Box<dyn Error>> {
+    let text = "UPDATE reviews SET hidden = $1 WHERE id = $2 RETURNING *";
+    let query = sqlx::query_as::<_, Review>(text)
+      .bind(hidden)
+      .bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(review) => Ok(review),
+      Err(e) => {
+        log::error!(error:err = e; "Error updating review visibility: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Delete review from the database by ID.
+  #[tracing::instrument(name = "ReviewRepository::delete_one", skip_all)]
+  pub async fn delete_one(&self, id: &Uuid) -> Result<(), Box<dyn Error>> {
+    let text = "DELETE FROM reviews WHERE id = $1";
+    let query = sqlx::query(text).bind(id);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!(error:err = e; "Error deleting review: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
@@ -0,0 +1,106 @@
+use std::error::Error;
+use sqlx::{Pool, Postgres, QueryBuilder};
+
+use crate::application::entities::author::Author;
+use crate::application::entities::book::Book;
+use crate::application::entities::user::User;
+
+/// Rows per `INSERT`; keeps the statements well below the bind parameter limit.
+const BATCH_SIZE: usize = 1000;
+
+
+/// Rows actually inserted by [`SeedRepository::add_all`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InsertedRows {
+  pub authors: u64,
+  pub books: u64,
+  pub users: u64,
+}
+
+/// Bulk inserts of generated data.
+pub struct SeedRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl SeedRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Save authors, books and users in one transaction, with multi-row inserts;
+  /// rows which are already there are skipped.
+  #[tracing::instrument(name = "SeedRepository::add_all", skip_all)]
+  pub async fn add_all(&self, authors: &[Author], books: &[Book], users: &[User]) -> Result<InsertedRows, Box<dyn Error>> {
+    let insert = async {
+      let mut tx = self.conn_pool.begin().await?;
+      let mut inserted = InsertedRows::default();
+
+      for batch in authors.chunks(BATCH_SIZE) {
+        inserted.authors += QueryBuilder::<Postgres>::new("INSERT INTO authors (id, first_name, last_name, middle_name) ")
+          .push_values(batch, |mut row, author| {
+            row.push_bind(author.id)
+              .push_bind(&author.first_name)
+              .push_bind(&author.last_name)
+              .push_bind(&author.middle_name);
+          })
+          .push(" ON CONFLICT DO NOTHING")
+          .build()
+          .execute(&mut *tx)
+          .await?
+          .rows_affected();
+      }
+
+      for batch in books.chunks(BATCH_SIZE) {
+        inserted.books += QueryBuilder::<Postgres>::new("INSERT INTO books (id, title, author_id, price, stock) ")
+          .push_values(batch, |mut row, book| {
+            row.push_bind(book.id)
+              .push_bind(&book.title)
+              .push_bind(book.author_id)
+              .push_bind(book.price)
+              .push_bind(book.stock);
+          })
+          .push(" ON CONFLICT DO NOTHING")
+          .build()
+          .execute(&mut *tx)
+          .await?
+          .rows_affected();
+      }
+
+      for batch in users.chunks(BATCH_SIZE) {
+        inserted.users += QueryBuilder::<Postgres>::new(concat!(
+          "INSERT INTO users ",
+          "(id, first_name, last_name, middle_name, nickname, hashed_password, date_registered, role, suspended) ",
+        ))
+          .push_values(batch, |mut row, user| {
+            row.push_bind(user.id)
+              .push_bind(&user.first_name)
+              .push_bind(&user.last_name)
+              .push_bind(&user.middle_name)
+              .push_bind(&user.nickname)
+              .push_bind(&user.hashed_password)
+              .push_bind(user.date_registered)
+              .push_bind(user.role.clone())
+              .push_bind(user.suspended);
+          })
+          .push(" ON CONFLICT DO NOTHING")
+          .build()
+          .execute(&mut *tx)
+          .await?
+          .rows_affected();
+      }
+
+      tx.commit().await?;
+      Ok::<_, sqlx::Error>(inserted)
+    };
+
+    match insert.await {
+      Ok(inserted) => Ok(inserted),
+      Err(e) => {
+        log::error!(error:err = e; "Error adding seed data: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
@@ -0,0 +1,311 @@
+use std::error::Error;
+use chrono::{Local, NaiveDate};
+use uuid::Uuid;
+use sqlx::{Pool, Postgres};
+
+use crate::adapters::repositories::{is_check_violation, is_unique_violation};
+use crate::application::entities::shelf::{Shelf, ShelfEntry, ShelfKind, YearReadingStats};
+
+
+pub struct ShelfRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl ShelfRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Create the built-in shelves of a user, unless they already exist.
+  #[tracing::instrument(name = "ShelfRepository::ensure_built_in", skip_all)]
+  pub async fn ensure_built_in(&self, user_id: &Uuid) -> Result<(), Box<dyn Error>> {
+    let text = concat!(
+      "INSERT INTO shelves\n",
+      "  (id, user_id, kind, name, public, date_created)\n",
+      "VALUES\n",
+      "  ($1, $2, $3, $4, FALSE, $5)\n",
+      "ON CONFLICT DO NOTHING"
+    );
+
+    for (kind, name) in ShelfKind::BUILT_IN {
+      let query = sqlx::query(text)
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(kind)
+        .bind(name)
+        .bind(Local::now());
+
+      if let Err(e) = query.execute(&self.conn_pool).await {
+        log::error!(error:err = e; "Error adding built-in shelf: {}", e);
+        return Err(Box::new(e))
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Fetch shelf from the database by ID.
+  #[tracing::instrument(name = "ShelfRepository::get_by_id", skip_all)]
+  pub async fn get_by_id(&self, id: &Uuid) -> Result<Option<Shelf>, Box<dyn Error>> {
+    let text = "SELECT * FROM shelves WHERE id = $1 LIMIT 1";
+    let query = sqlx::query_as::<_, Shelf>(text).bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(shelf) => Ok(shelf),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching shelf by id: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch user's shelf from the database by name.
+  #[tracing::instrument(name = "ShelfRepository::get_by_user_and_name", skip_all)]
+  pub async fn get_by_user_and_name(&self, user_id: &Uuid, name: &str) -> Result<Option<Shelf>, Box<dyn Error>> {
+    let text = "SELECT * FROM shelves WHERE user_id = $1 AND name = $2 LIMIT 1";
+    let query = sqlx::query_as::<_, Shelf>(text)
+      .bind(user_id)
+      .bind(name);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(shelf) => Ok(shelf),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching shelf by name: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch user's shelves from the database, built-in ones first.
+  #[tracing::instrument(name = "ShelfRepository::get_list_by_user_id", skip_all)]
+  pub async fn get_list_by_user_id(&self, user_id: &Uuid, only_public: bool) -> Result<Vec<Shelf>, Box<dyn Error>> {
+    let text = concat!(
+      "SELECT * FROM shelves\n",
+      "WHERE user_id = $1 AND (public OR NOT $2)\n",
+      "ORDER BY kind, date_created"
+    );
+    let query = sqlx::query_as::<_, Shelf>(text)
+      .bind(user_id)
+      .bind(only_public);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(shelves) => Ok(shelves),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching shelves: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Save shelf into the database.
+  #[tracing::instrument(name = "ShelfRepository::add_one", skip_all)]
+  pub async fn add_one(&self, shelf: Shelf) -> Result<(), Box<dyn Error>> {
+    let text = concat!(
+      "INSERT INTO shelves\n",
+      "  (id, user_id, kind, name, public, date_created)\n",
+      "VALUES\n",
+      "  ($1, $2, $3, $4, $5, $6)"
+    );
+    let query = sqlx::query(text)
+      .bind(shelf.id)
+      .bind(shelf.user_id)
+      .bind(shelf.kind)
+      .bind(shelf.name)
+      .bind(shelf.public)
+      .bind(shelf.date_created);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        if !is_unique_violation(&e) {
+          log::error!(error:err = e; "Error adding shelf: {}", e);
+        }
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Update shelf's name and visibility in the database by ID.
+  #[tracing::instrument(name = "ShelfRepository::update_one", skip_all)]
+  pub async fn update_one(&self, id: &Uuid, name: String, public: bool) -> Result<Option<Shelf>, Box<dyn Error>> {
+    let text = "UPDATE shelves SET name = $1, public = $2 WHERE id = $3 RETURNING *";
+    let query = sqlx::query_as::<_, Shelf>(text)
+      .bind(name)
+      .bind(public)
+      .bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(shelf) => Ok(shelf),
+      Err(e) => {
+        if !is_unique_violation(&e) {
+          log::error!(error:err = e; "Error updating shelf: {}", e);
+        }
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Delete shelf with all its entries from the database by ID.
+  #[tracing::instrument(name = "ShelfRepository::delete_one", skip_all)]
+  pub async fn delete_one(&self, id: &Uuid) -> Result<(), Box<dyn Error>> {
+    let text = "DELETE FROM shelves WHERE id = $1";
+    let query = sqlx::query(text).bind(id);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(_) => Ok(()),
+      Err(e) => {
+        log::error!(error:err = e; "Error deleting shelf: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch entries of several shelves from the database, oldest first.
+  #[tracing::instrument(name = "ShelfRepository::get_entries_by_shelf_ids", skip_all)]
+  pub async fn get_entries_by_shelf_ids(&self, shelf_ids: &[Uuid]) -> Result<Vec<ShelfEntry>, Box<dyn Error>> {
+    let text = "SELECT * FROM shelf_entries WHERE shelf_id = ANY($1) ORDER BY date_added";
+    let query = sqlx::query_as::<_, ShelfEntry>(text).bind(shelf_ids);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(entries) => Ok(entries),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching shelf entries: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Insert or update a shelf entry.
+  ///
+  /// A book lives on at most one built-in shelf of a user, so putting it
+  /// onto a built-in shelf removes it from the other ones. The start date
+  /// is carried over from the removed entry, unless a new one is given;
+  /// if it turns out later than the finish date, `ck_shelf_entries_dates` fails.
+  #[tracing::instrument(name = "ShelfRepository::put_entry", skip_all)]
+  pub async fn put_entry(&self, shelf: &Shelf, mut entry: ShelfEntry) -> Result<ShelfEntry, Box<dyn Error>> {
+    let mut tx = match self.conn_pool.begin().await {
+      Ok(tx) => tx,
+      Err(e) => {
+        log::error!(error:err = e; "Error starting transaction: {}", e);
+        return Err(Box::new(e))
+      }
+    };
+
+    if shelf.kind.is_built_in() {
+      let text = concat!(
+        "DELETE FROM shelf_entries\n",
+        "WHERE book_id = $1 AND shelf_id IN (\n",
+        "  SELECT id FROM shelves WHERE user_id = $2 AND kind <> 'custom' AND id <> $3\n",
+        ")\n",
+        "RETURNING date_started"
+      );
+      let query = sqlx::query_scalar::<_, Option<NaiveDate>>(text)
+        .bind(entry.book_id)
+        .bind(shelf.user_id)
+        .bind(shelf.id);
+
+      match query.fetch_all(&mut *tx).await {
+        Ok(dates) => if entry.date_started.is_none() {
+          entry.date_started = dates.into_iter().flatten().min();
+        },
+        Err(e) => {
+          log::error!(error:err = e; "Error moving shelf entry: {}", e);
+          return Err(Box::new(e))
+        }
+      }
+    }
+
+    let text = concat!(
+      "INSERT INTO shelf_entries\n",
+      "  (shelf_id, book_id, date_added, date_started, date_finished, progress_percent, progress_page)\n",
+      "VALUES\n",
+      "  ($1, $2, $3, $4, $5, $6, $7)\n",
+      "ON CONFLICT (shelf_id, book_id) DO UPDATE SET\n",
+      "  date_started = EXCLUDED.date_started,\n",
+      "  date_finished = EXCLUDED.date_finished,\n",
+      "  progress_percent = EXCLUDED.progress_percent,\n",
+      "  progress_page = EXCLUDED.progress_page\n",
+      "RETURNING *"
+    );
+    let query = sqlx::query_as::<_, ShelfEntry>(text)
+      .bind(entry.shelf_id)
+      .bind(entry.book_id)
+      .bind(entry.date_added)
+      .bind(entry.date_started)
+      .bind(entry.date_finished)
+      .bind(entry.progress_percent)
+      .bind(entry.progress_page);
+
+    let entry = match query.fetch_one(&mut *tx).await {
+      Ok(entry) => entry,
+      Err(e) => {
+        // the carried over start date may come after the finish date
+        if !is_check_violation(&e) {
+          log::error!(error:err = e; "Error saving shelf entry: {}", e);
+        }
+        return Err(Box::new(e))
+      }
+    };
+
+    match tx.commit().await {
+      Ok(_) => Ok(entry),
+      Err(e) => {
+        log::error!(error:err = e; "Error committing shelf entry: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Remove a book from a shelf. Returns `false` if it was not there.
+  #[tracing::instrument(name = "ShelfRepository::delete_entry", skip_all)]
+  pub async fn delete_entry(&self, shelf_id: &Uuid, book_id: &Uuid) -> Result<bool, Box<dyn Error>> {
+    let text = "DELETE FROM shelf_entries WHERE shelf_id = $1 AND book_id = $2";
+    let query = sqlx::query(text)
+      .bind(shelf_id)
+      .bind(book_id);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(res) => Ok(res.rows_affected() > 0),
+      Err(e) => {
+        log::error!(error:err = e; "Error deleting shelf entry: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Compute per-year reading statistics of a user from the start and
+  /// finish dates of the books on all of their shelves.
+  #[tracing::instrument(name = "ShelfRepository::get_reading_stats", skip_all)]
+  pub async fn get_reading_stats(&self, user_id: &Uuid) -> Result<Vec<YearReadingStats>, Box<dyn Error>> {
+    let text = concat!(
+      "WITH entries AS (\n",
+      "  SELECT e.book_id, e.date_started, e.date_finished\n",
+      "  FROM shelf_entries e JOIN shelves s ON s.id = e.shelf_id\n",
+      "  WHERE s.user_id = $1\n",
+      "), started AS (\n",
+      "  SELECT EXTRACT(YEAR FROM date_started)::int AS year, COUNT(DISTINCT book_id) AS books\n",
+      "  FROM entries WHERE date_started IS NOT NULL GROUP BY 1\n",
+      "), finished AS (\n",
+      "  SELECT EXTRACT(YEAR FROM date_finished)::int AS year, COUNT(DISTINCT book_id) AS books\n",
+      "  FROM entries WHERE date_finished IS NOT NULL GROUP BY 1\n",
+      ")\n",
+      "SELECT\n",
+      "  year,\n",
+      "  COALESCE(started.books, 0) AS books_started,\n",
+      "  COALESCE(finished.books, 0) AS books_finished\n",
+      "FROM started FULL JOIN finished USING (year)\n",
+      "ORDER BY year"
+    );
+    let query = sqlx::query_as::<_, YearReadingStats>(text).bind(user_id);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(stats) => Ok(stats),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching reading statistics: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
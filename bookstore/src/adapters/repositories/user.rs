@@ -5,6 +5,7 @@ use sqlx;
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
+use crate::adapters::repositories::is_unique_violation;
 use crate::application::dto::request::user::UpdateSuspendedReq;
 use crate::application::entities::user::User;
 
@@ -21,6 +22,7 @@ impl UserRepository {
   }
 
   /// Fetch user from the database by ID.
+  #[tracing::instrument(name = "UserRepository::get_by_id", skip_all)]
   pub async fn get_by_id(&self, id: &Uuid) -> Result<Option<User>, Box<dyn Error>> {
     let text = "SELECT * FROM users WHERE id = $1 LIMIT 1";
     let query = sqlx::query_as::<_, User>(text).bind(id);
@@ -28,27 +30,44 @@ impl UserRepository {
     match query.fetch_optional(&self.conn_pool).await {
       Ok(user) => Ok(user),
       Err(e) => {
-        log::error!("Error fetching user by id: {}", e);
+        log::error!(error:err = e; "Error fetching user by id: {}", e);
         Err(Box::new(e))
       }
     }
   }
 
-  /// Fetch user from the database by nickname.
+  /// Fetch user from the database by nickname, ignoring the case.
+  #[tracing::instrument(name = "UserRepository::get_by_nickname", skip_all)]
   pub async fn get_by_nickname(&self, nickname: &String) -> Result<Option<User>, Box<dyn Error>> {
-    let text = "SELECT * FROM users WHERE nickname = $1 LIMIT 1";
+    let text = "SELECT * FROM users WHERE lower(nickname) = lower($1) LIMIT 1";
     let query = sqlx::query_as::<_, User>(text).bind(nickname);
 
     match query.fetch_optional(&self.conn_pool).await {
       Ok(user) => Ok(user),
       Err(e) => {
-        log::error!("Error fetching user by nickname: {}", e);
+        log::error!(error:err = e; "Error fetching user by nickname: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Fetch the user who has confirmed the address, ignoring the case.
+  #[tracing::instrument(name = "UserRepository::get_by_verified_email", skip_all)]
+  pub async fn get_by_verified_email(&self, email: &str) -> Result<Option<User>, Box<dyn Error>> {
+    let text = "SELECT * FROM users WHERE lower(email) = lower($1) AND email_verified LIMIT 1";
+    let query = sqlx::query_as::<_, User>(text).bind(email);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(user) => Ok(user),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching user by email: {}", e);
         Err(Box::new(e))
       }
     }
   }
 
   /// Fetch users from the database.
+  #[tracing::instrument(name = "UserRepository::get_list", skip_all)]
   pub async fn get_list(&self, page: u32, size: u32) -> Result<Vec<User>, Box<dyn Error>> {
     let text = "SELECT * FROM users OFFSET $1 LIMIT $2";
     let query = sqlx::query_as::<_, User>(text)
@@ -58,13 +77,14 @@ impl UserRepository {
     match query.fetch_all(&self.conn_pool).await {
       Ok(users) => Ok(users),
       Err(e) => {
-        log::error!("Error fetching users: {}", e);
+        log::error!(error:err = e; "Error fetching users: {}", e);
         Err(Box::new(e))
       }
     }
   }
 
-  /// Save user into the database.
+  /// Save user into the database. A taken nickname, in any case, is a unique violation.
+  #[tracing::instrument(name = "UserRepository::add_one", skip_all)]
   pub async fn add_one(&self, user: User) -> Result<(), Box<dyn Error>> {
     let text = concat!(
       "INSERT INTO users\n",
@@ -86,13 +106,16 @@ impl UserRepository {
     match query.execute(&self.conn_pool).await {
       Ok(_) => Ok(()),
       Err(e) => {
-        log::error!("Error adding user: {}", e);
+        if !is_unique_violation(&e) {
+          log::error!(error:err = e; "Error adding user: {}", e);
+        }
         Err(Box::new(e))
       }
     }
   }
 
   /// Update user's `suspended` column in the database by ID.
+  #[tracing::instrument(name = "UserRepository::update_suspended", skip_all)]
   pub async fn update_suspended(&self, id: &Uuid, data: UpdateSuspendedReq) -> Result<Option<User>, Box<dyn Error>> {
     let update_text = "UPDATE users SET suspended = $1 WHERE id = $2";
     let update_query = sqlx::query(update_text)
@@ -106,7 +129,7 @@ impl UserRepository {
     match update_query.execute(&self.conn_pool).await {
       Ok(_) => {},
       Err(e) => {
-        log::error!("Error updating user: {}", e);
+        log::error!(error:err = e; "Error updating user: {}", e);
         return Err(Box::new(e))
       }
     };
@@ -114,9 +137,207 @@ impl UserRepository {
     match fetch_query.fetch_optional(&self.conn_pool).await {
       Ok(user) => Ok(user),
       Err(e) => {
-        log::error!("Error fetching user after update: {}", e);
+        log::error!(error:err = e; "Error fetching user after update: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Update user's names by ID.
+  #[tracing::instrument(name = "UserRepository::update_names", skip_all)]
+  pub async fn update_names(
+    &self,
+    id: &Uuid,
+    first_name: String,
+    last_name: String,
+    middle_name: Option<String>,
+  ) -> Result<Option<User>, Box<dyn Error>> {
+    let text = "UPDATE users SET first_name = $1, last_name = $2, middle_name = $3 WHERE id = $4 RETURNING *";
+    let query = sqlx::query_as::<_, User>(text)
+      .bind(first_name)
+      .bind(last_name)
+      .bind(middle_name)
+      .bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(user) => Ok(user),
+      Err(e) => {
+        log::error!(error:err = e; "Error updating user names: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Replace user's email address by ID; the new one is not verified yet.
+  #[tracing::instrument(name = "UserRepository::update_email", skip_all)]
+  pub async fn update_email(&self, id: &Uuid, email: Option<&str>) -> Result<Option<User>, Box<dyn Error>> {
+    let text = "UPDATE users SET email = $1, email_verified = FALSE WHERE id = $2 RETURNING *";
+    let query = sqlx::query_as::<_, User>(text)
+      .bind(email)
+      .bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(user) => Ok(user),
+      Err(e) => {
+        log::error!(error:err = e; "Error updating user email: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Mark user's address as verified, unless it has been changed meanwhile.
+  /// An address verified by another user, in any case, is a unique violation.
+  #[tracing::instrument(name = "UserRepository::verify_email", skip_all)]
+  pub async fn verify_email(&self, id: &Uuid, email: &str) -> Result<bool, Box<dyn Error>> {
+    let text = "UPDATE users SET email_verified = TRUE WHERE id = $1 AND email = $2";
+    let query = sqlx::query(text)
+      .bind(id)
+      .bind(email);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(result) => Ok(result.rows_affected() > 0),
+      Err(e) => {
+        if !is_unique_violation(&e) {
+          log::error!(error:err = e; "Error verifying user email: {}", e);
+        }
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Replace user's password hash by ID and end all of their sessions.
+  /// Returns the new session version, `None` if there is no such user.
+  #[tracing::instrument(name = "UserRepository::update_password", skip_all)]
+  pub async fn update_password(&self, id: &Uuid, hashed_password: &str) -> Result<Option<i32>, Box<dyn Error>> {
+    let text = concat!(
+      "UPDATE users SET hashed_password = $1, session_version = session_version + 1\n",
+      "WHERE id = $2\n",
+      "RETURNING session_version"
+    );
+    let query = sqlx::query_scalar::<_, i32>(text)
+      .bind(hashed_password)
+      .bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(version) => Ok(version),
+      Err(e) => {
+        log::error!(error:err = e; "Error updating user password: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Start over the TOTP enrollment of the user with a new secret, unless it is confirmed already.
+  #[tracing::instrument(name = "UserRepository::set_totp_secret", skip_all)]
+  pub async fn set_totp_secret(&self, id: &Uuid, secret: &str) -> Result<bool, Box<dyn Error>> {
+    let text = "UPDATE users SET totp_secret = $1, totp_last_step = NULL WHERE id = $2 AND NOT totp_enabled";
+    let query = sqlx::query(text)
+      .bind(secret)
+      .bind(id);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(result) => Ok(result.rows_affected() > 0),
+      Err(e) => {
+        log::error!(error:err = e; "Error setting user TOTP secret: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Confirm the TOTP enrollment with the code of the step and end all sessions of the user.
+  /// Returns the new session version, `None` if there is no enrollment to confirm.
+  #[tracing::instrument(name = "UserRepository::enable_totp", skip_all)]
+  pub async fn enable_totp(&self, id: &Uuid, step: i64) -> Result<Option<i32>, Box<dyn Error>> {
+    let text = concat!(
+      "UPDATE users SET totp_enabled = TRUE, totp_last_step = $1, session_version = session_version + 1\n",
+      "WHERE id = $2 AND totp_secret IS NOT NULL AND NOT totp_enabled\n",
+      "RETURNING session_version"
+    );
+    let query = sqlx::query_scalar::<_, i32>(text)
+      .bind(step)
+      .bind(id);
+
+    match query.fetch_optional(&self.conn_pool).await {
+      Ok(version) => Ok(version),
+      Err(e) => {
+        log::error!(error:err = e; "Error enabling user TOTP: {}", e);
         Err(Box::new(e))
       }
     }
   }
-}
\ No newline at end of file
+
+  /// Forget the TOTP secret of the user.
+  #[tracing::instrument(name = "UserRepository::disable_totp", skip_all)]
+  pub async fn disable_totp(&self, id: &Uuid) -> Result<bool, Box<dyn Error>> {
+    let text = "UPDATE users SET totp_secret = NULL, totp_enabled = FALSE, totp_last_step = NULL WHERE id = $1";
+    let query = sqlx::query(text)
+      .bind(id);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(result) => Ok(result.rows_affected() > 0),
+      Err(e) => {
+        log::error!(error:err = e; "Error disabling user TOTP: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Accept a code of the step, unless a code of the same or a later step has been accepted.
+  #[tracing::instrument(name = "UserRepository::use_totp_step", skip_all)]
+  pub async fn use_totp_step(&self, id: &Uuid, step: i64) -> Result<bool, Box<dyn Error>> {
+    let text = concat!(
+      "UPDATE users SET totp_last_step = $1\n",
+      "WHERE id = $2 AND totp_enabled AND (totp_last_step IS NULL OR totp_last_step < $1)"
+    );
+    let query = sqlx::query(text)
+      .bind(step)
+      .bind(id);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(result) => Ok(result.rows_affected() > 0),
+      Err(e) => {
+        log::error!(error:err = e; "Error using user TOTP step: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Replace the hash of an unchanged password with a stronger one.
+  /// Does nothing if the password has been changed meanwhile.
+  #[tracing::instrument(name = "UserRepository::upgrade_password_hash", skip_all)]
+  pub async fn upgrade_password_hash(&self, id: &Uuid, old_hash: &str, new_hash: &str) -> Result<bool, Box<dyn Error>> {
+    let text = "UPDATE users SET hashed_password = $1 WHERE id = $2 AND hashed_password = $3";
+    let query = sqlx::query(text)
+      .bind(new_hash)
+      .bind(id)
+      .bind(old_hash);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(result) => Ok(result.rows_affected() > 0),
+      Err(e) => {
+        log::error!(error:err = e; "Error upgrading user password hash: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Delete user by ID along with their shelves, reviews and the rest.
+  /// Does nothing and returns `false` while the user has books on loan.
+  #[tracing::instrument(name = "UserRepository::delete_one", skip_all)]
+  pub async fn delete_one(&self, id: &Uuid) -> Result<bool, Box<dyn Error>> {
+    let text = concat!(
+      "DELETE FROM users\n",
+      "WHERE id = $1\n",
+      "  AND NOT EXISTS (SELECT 1 FROM loans WHERE user_id = $1 AND date_returned IS NULL)"
+    );
+    let query = sqlx::query(text).bind(id);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(result) => Ok(result.rows_affected() > 0),
+      Err(e) => {
+        log::error!(error:err = e; "Error deleting user: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
@@ -0,0 +1,79 @@
+use std::error::Error;
+use uuid::Uuid;
+use sqlx::{Pool, Postgres};
+
+use crate::application::entities::wishlist::WishlistItem;
+
+
+pub struct WishlistRepository {
+  conn_pool: Pool<Postgres>,
+}
+
+impl WishlistRepository {
+  pub fn new(conn_pool: Pool<Postgres>) -> Self {
+    Self {
+      conn_pool,
+    }
+  }
+
+  /// Fetch user's wishlist from the database, newest first.
+  #[tracing::instrument(name = "WishlistRepository::get_list_by_user_id", skip_all)]
+  pub async fn get_list_by_user_id(&self, user_id: &Uuid) -> Result<Vec<WishlistItem>, Box<dyn Error>> {
+    let text = "SELECT * FROM wishlist_items WHERE user_id = $1 ORDER BY date_added DESC";
+    let query = sqlx::query_as::<_, WishlistItem>(text).bind(user_id);
+
+    match query.fetch_all(&self.conn_pool).await {
+      Ok(items) => Ok(items),
+      Err(e) => {
+        log::error!(error:err = e; "Error fetching wishlist: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Insert a wishlist item or update its notification settings.
+  #[tracing::instrument(name = "WishlistRepository::put_one", skip_all)]
+  pub async fn put_one(&self, item: WishlistItem) -> Result<WishlistItem, Box<dyn Error>> {
+    let text = concat!(
+      "INSERT INTO wishlist_items\n",
+      "  (user_id, book_id, price_threshold, notify_restock, date_added)\n",
+      "VALUES\n",
+      "  ($1, $2, $3, $4, $5)\n",
+      "ON CONFLICT (user_id, book_id) DO UPDATE SET\n",
+      "  price_threshold = EXCLUDED.price_threshold,\n",
+      "  notify_restock = EXCLUDED.notify_restock\n",
+      "RETURNING *"
+    );
+    let query = sqlx::query_as::<_, WishlistItem>(text)
+      .bind(item.user_id)
+      .bind(item.book_id)
+      .bind(item.price_threshold)
+      .bind(item.notify_restock)
+      .bind(item.date_added);
+
+    match query.fetch_one(&self.conn_pool).await {
+      Ok(item) => Ok(item),
+      Err(e) => {
+        log::error!(error:err = e; "Error saving wishlist item: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+
+  /// Remove a book from user's wishlist. Returns `false` if it was not there.
+  #[tracing::instrument(name = "WishlistRepository::delete_one", skip_all)]
+  pub async fn delete_one(&self, user_id: &Uuid, book_id: &Uuid) -> Result<bool, Box<dyn Error>> {
+    let text = "DELETE FROM wishlist_items WHERE user_id = $1 AND book_id = $2";
+    let query = sqlx::query(text)
+      .bind(user_id)
+      .bind(book_id);
+
+    match query.execute(&self.conn_pool).await {
+      Ok(res) => Ok(res.rows_affected() > 0),
+      Err(e) => {
+        log::error!(error:err = e; "Error deleting wishlist item: {}", e);
+        Err(Box::new(e))
+      }
+    }
+  }
+}
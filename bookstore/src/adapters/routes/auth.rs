@@ -1,9 +1,14 @@
-use actix_web::{http, web, Responder};
+use actix_web::{http, web, HttpRequest, HttpResponse, Responder};
 
+use crate::adapters::util::client_ip::client_ip;
+use crate::adapters::util::validation::{validation_error, ValidJson};
 use crate::application::state::app_state::AppState;
-use crate::application::dto::request::user::{LoginReq, RegisterReq};
+use crate::application::dto::request::user::{ForgotPasswordReq, LoginReq, RegisterReq, ResetPasswordReq, VerifyEmailReq};
+use crate::application::dto::request::two_factor::TwoFactorLoginReq;
+use crate::application::dto::response::two_factor::LoginChallengeResp;
 use crate::application::dto::response::user::TokenResp;
-use crate::application::services::auth::RegistrationError;
+use crate::application::services::account::{EmailVerifyResult, PasswordRecoveryResult};
+use crate::application::services::auth::{LoginResult, RegistrationError};
 
 #[utoipa::path(
   post,
@@ -12,24 +17,29 @@ use crate::application::services::auth::RegistrationError;
   request_body = RegisterReq,
   responses(
     (status = CREATED, body = TokenResp),
-    (status = BAD_REQUEST, description = "Неверный формат полей."),
     (status = CONFLICT, description = "Пользователь с такми псевдонимом уже существует."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку или пароль не соответствует политике."),
   )
 )]
 #[post("/register")]
 pub async fn register(
+  req: HttpRequest,
   state: web::Data<AppState>,
-  data: web::Json<RegisterReq>,
-) -> impl Responder
+  data: ValidJson<RegisterReq>,
+) -> Result<impl Responder, actix_web::Error>
 {
-  match state.auth_service.register(data.0).await {
-    Ok(token) => (web::Json(Some(TokenResp { token })), http::StatusCode::CREATED),
+  Ok(match state.auth_service.register(data.0).await {
+    Ok(token) => {
+      state.metrics.registrations.inc();
+      (web::Json(Some(TokenResp { token })), http::StatusCode::CREATED)
+    },
     Err(e) => match e {
       RegistrationError::AlreadyExists => (web::Json(None), http::StatusCode::CONFLICT),
       RegistrationError::BadRequest => (web::Json(None), http::StatusCode::BAD_REQUEST),
+      RegistrationError::WeakPassword(errors) => return Err(validation_error(&req, errors)),
       RegistrationError::UnexpectedError => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR)
     }
-  }
+  })
 }
 
 #[utoipa::path(
@@ -39,20 +49,138 @@ pub async fn register(
   request_body = LoginReq,
   responses(
     (status = OK, body = TokenResp),
+    (status = ACCEPTED, body = LoginChallengeResp, description = "У пользователя включена двухфакторная аутентификация: вход завершается через `/api/auth/login/2fa`."),
     (status = UNAUTHORIZED, description = "Не удалось авторизоваться."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+    (
+      status = TOO_MANY_REQUESTS,
+      description = "Слишком много неудачных попыток с этим псевдонимом или с этого адреса.",
+      headers(("Retry-After" = i64, description = "Через сколько секунд можно попробовать снова.")),
+    ),
   )
 )]
 #[post("/login")]
 pub async fn login(
+  req: HttpRequest,
   state: web::Data<AppState>,
-  data: web::Json<LoginReq>,
+  data: ValidJson<LoginReq>,
 ) -> impl Responder
 {
-  match state.auth_service.login(data.0).await {
-    Ok(token) => match token {
-      None => (web::Json(None), http::StatusCode::UNAUTHORIZED),
-      Some(token) => (web::Json(Some(TokenResp { token })), http::StatusCode::OK)
-    },
-    Err(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  let result = state.auth_service.login(data.0, client_ip(&req)).await;
+  login_response(&state, result)
+}
+
+#[utoipa::path(
+  post,
+  tag = "Аутентификация",
+  context_path = "/api/auth",
+  request_body = TwoFactorLoginReq,
+  responses(
+    (status = OK, body = TokenResp),
+    (status = UNAUTHORIZED, description = "Код неверен или уже использован, либо `challenge` недействителен."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+    (
+      status = TOO_MANY_REQUESTS,
+      description = "Слишком много неудачных попыток с этим псевдонимом или с этого адреса.",
+      headers(("Retry-After" = i64, description = "Через сколько секунд можно попробовать снова.")),
+    ),
+  )
+)]
+#[post("/login/2fa")]
+pub async fn login_2fa(
+  req: HttpRequest,
+  state: web::Data<AppState>,
+  data: ValidJson<TwoFactorLoginReq>,
+) -> impl Responder
+{
+  let result = state.auth_service.login_2fa(data.0, client_ip(&req)).await;
+  login_response(&state, result)
+}
+
+fn login_response(state: &AppState, result: LoginResult) -> HttpResponse {
+  match result {
+    LoginResult::Ok(token) => HttpResponse::Ok().json(TokenResp { token }),
+    LoginResult::Challenge(challenge) => HttpResponse::Accepted().json(LoginChallengeResp {
+      challenge,
+      expires_in: state.config.auth.login_challenge_ttl_mins * 60,
+    }),
+    LoginResult::WrongCredentials => HttpResponse::new(http::StatusCode::UNAUTHORIZED),
+    LoginResult::Throttled(secs) => HttpResponse::TooManyRequests()
+      .insert_header((http::header::RETRY_AFTER, secs.to_string()))
+      .finish(),
+    LoginResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Аутентификация",
+  context_path = "/api/auth",
+  request_body = ForgotPasswordReq,
+  responses(
+    (status = ACCEPTED, description = "Если адрес подтвержден кем-то из пользователей, на него отправлен код для восстановления пароля. Ответ не зависит от того, есть ли такой пользователь."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  )
+)]
+#[post("/forgot")]
+pub async fn forgot_password(
+  state: web::Data<AppState>,
+  data: ValidJson<ForgotPasswordReq>,
+) -> impl Responder
+{
+  // the same answer whatever happened, errors included
+  state.account_service.clone().forgot_password(data.0).await;
+  HttpResponse::new(http::StatusCode::ACCEPTED)
+}
+
+#[utoipa::path(
+  post,
+  tag = "Аутентификация",
+  context_path = "/api/auth",
+  request_body = ResetPasswordReq,
+  responses(
+    (status = OK, description = "Пароль изменен, все сеансы пользователя завершены."),
+    (status = BAD_REQUEST, description = "Код неверен, уже использован или истек."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку или пароль не соответствует политике."),
+  )
+)]
+#[post("/reset")]
+pub async fn reset_password(
+  req: HttpRequest,
+  state: web::Data<AppState>,
+  data: ValidJson<ResetPasswordReq>,
+) -> Result<impl Responder, actix_web::Error>
+{
+  Ok(match state.account_service.reset_password(data.0).await {
+    PasswordRecoveryResult::Ok => HttpResponse::new(http::StatusCode::OK),
+    PasswordRecoveryResult::InvalidToken => HttpResponse::new(http::StatusCode::BAD_REQUEST),
+    PasswordRecoveryResult::WeakPassword(errors) => return Err(validation_error(&req, errors)),
+    PasswordRecoveryResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
+  })
+}
+
+#[utoipa::path(
+  post,
+  tag = "Аутентификация",
+  context_path = "/api/auth",
+  request_body = VerifyEmailReq,
+  responses(
+    (status = OK, description = "Адрес подтвержден."),
+    (status = BAD_REQUEST, description = "Код неверен, уже использован или истек, или адрес с тех пор изменен."),
+    (status = CONFLICT, description = "Адрес уже подтвержден другим пользователем."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  )
+)]
+#[post("/verify-email")]
+pub async fn verify_email(
+  state: web::Data<AppState>,
+  data: ValidJson<VerifyEmailReq>,
+) -> impl Responder
+{
+  match state.account_service.verify_email(data.0).await {
+    EmailVerifyResult::Ok => HttpResponse::new(http::StatusCode::OK),
+    EmailVerifyResult::InvalidToken => HttpResponse::new(http::StatusCode::BAD_REQUEST),
+    EmailVerifyResult::AlreadyTaken => HttpResponse::new(http::StatusCode::CONFLICT),
+    EmailVerifyResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
   }
-}
\ No newline at end of file
+}
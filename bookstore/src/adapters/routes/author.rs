@@ -1,7 +1,8 @@
-use actix_web::{http, HttpResponse, Responder, web};
+use actix_web::{http, HttpRequest, HttpResponse, Responder, web};
 use uuid::Uuid;
 
 use crate::adapters::middleware::jwt::JwtClaims;
+use crate::adapters::util::validation::{validation_error, ValidJson, ValidQuery};
 use crate::application::dto::request::author::{AddAuthorReq, GetAuthorListReq};
 use crate::application::entities::user::UserRole;
 use crate::application::services::author::{AuthorAddResult, AuthorDeleteResult, AuthorFetchResult, AuthorListFetchResult};
@@ -14,10 +15,11 @@ use crate::application::state::app_state::AppState;
   context_path = "/api/author",
   params(
     ("page" = u32, Query, description = "Индекс страницы.", example = 0),
-    ("size" = u32, Query, description = "Размер одной страницы.", minimum = 1, example = 20),
+    ("size" = u32, Query, description = "Размер одной страницы.", minimum = 1, maximum = 100, example = 20),
   ),
   responses(
     (status = OK, body = AuthorListResp),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
   ),
   security(
     ("jwt_auth" = [])
@@ -26,7 +28,7 @@ use crate::application::state::app_state::AppState;
 #[get("")]
 pub async fn get_list(
   state: web::Data<AppState>,
-  query: web::Query<GetAuthorListReq>,
+  query: ValidQuery<GetAuthorListReq>,
 ) -> impl Responder
 {
   match state.author_service.get_list(query.0).await {
@@ -103,6 +105,7 @@ pub async fn delete_one(
   responses(
     (status = CREATED, description = "Автор добавлен."),
     (status = FORBIDDEN, description = "Недостаточно прав для выполнения действия."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
   ),
   security(
     ("jwt_auth" = [])
@@ -110,17 +113,19 @@ pub async fn delete_one(
 )]
 #[post("")]
 pub async fn add_one(
+  req: HttpRequest,
   state: web::Data<AppState>,
-  data: web::Json<AddAuthorReq>,
+  data: ValidJson<AddAuthorReq>,
   auth_claims: web::ReqData<JwtClaims>,
-) -> impl Responder
+) -> Result<impl Responder, actix_web::Error>
 {
   if auth_claims.role != UserRole::Admin {
-    return HttpResponse::new(http::StatusCode::FORBIDDEN)
+    return Ok(HttpResponse::new(http::StatusCode::FORBIDDEN))
   }
 
-  match state.author_service.add_one(data.0).await {
+  Ok(match state.author_service.add_one(data.0).await {
     AuthorAddResult::Created => HttpResponse::new(http::StatusCode::CREATED),
+    AuthorAddResult::Invalid(errors) => return Err(validation_error(&req, errors)),
     AuthorAddResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
-  }
+  })
 }
\ No newline at end of file
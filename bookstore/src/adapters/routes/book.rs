@@ -2,10 +2,11 @@ use actix_web::{http, HttpResponse, Responder, web};
 use uuid::Uuid;
 
 use crate::adapters::middleware::jwt::JwtClaims;
-use crate::application::dto::request::book::{GetBookListReq, AddBookReq};
+use crate::adapters::util::validation::{ValidJson, ValidQuery};
+use crate::application::dto::request::book::{GetBookListReq, AddBookReq, UpdateInventoryReq};
 use crate::application::entities::user::UserRole;
 use crate::application::state::app_state::AppState;
-use crate::application::services::book::{BookAddResult, BookDeleteResult, BookFetchResult, BookListFetchResult};
+use crate::application::services::book::{BookAddResult, BookDeleteResult, BookFetchResult, BookListFetchResult, BookUpdateResult};
 
 
 #[utoipa::path(
@@ -14,10 +15,12 @@ use crate::application::services::book::{BookAddResult, BookDeleteResult, BookFe
   context_path = "/api/book",
   params(
     ("page" = u32, Query, description = "Индекс страницы.", example = 0),
-    ("size" = u32, Query, description = "Размер одной страницы.", minimum = 1, example = 20),
+    ("size" = u32, Query, description = "Размер одной страницы.", minimum = 1, maximum = 100, example = 20),
+    ("sort" = Option<BookListSort>, Query, description = "Порядок сортировки."),
   ),
   responses(
     (status = OK, body = BookListResp),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
   ),
   security(
     ("jwt_auth" = [])
@@ -26,7 +29,7 @@ use crate::application::services::book::{BookAddResult, BookDeleteResult, BookFe
 #[get("")]
 pub async fn get_list(
   state: web::Data<AppState>,
-  query: web::Query<GetBookListReq>,
+  query: ValidQuery<GetBookListReq>,
 ) -> impl Responder
 {
   match state.book_service.get_list(query.0).await {
@@ -104,6 +107,7 @@ pub async fn delete_one(
     (status = CREATED, description = "Книга добавлена."),
     (status = NOT_FOUND, description = "Автор с таким ID не найден."),
     (status = FORBIDDEN, description = "Недостаточно прав для выполнения действия."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
   ),
   security(
     ("jwt_auth" = [])
@@ -112,7 +116,7 @@ pub async fn delete_one(
 #[post("")]
 pub async fn add_one(
   state: web::Data<AppState>,
-  data: web::Json<AddBookReq>,
+  data: ValidJson<AddBookReq>,
   auth_claims: web::ReqData<JwtClaims>,
 ) -> impl Responder
 {
@@ -121,8 +125,50 @@ pub async fn add_one(
   }
 
   match state.book_service.add_one(data.0).await {
-    BookAddResult::Created => HttpResponse::new(http::StatusCode::CREATED),
+    BookAddResult::Created => {
+      state.metrics.books_added.inc();
+      HttpResponse::new(http::StatusCode::CREATED)
+    },
     BookAddResult::AuthorNotFound => HttpResponse::new(http::StatusCode::NOT_FOUND),
+    BookAddResult::BadRequest => HttpResponse::new(http::StatusCode::BAD_REQUEST),
     BookAddResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
   }
+}
+
+#[utoipa::path(
+  put,
+  tag = "Книги",
+  context_path = "/api/book",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор книги."),
+  ),
+  request_body = UpdateInventoryReq,
+  responses(
+    (status = OK, body = FullBookResp),
+    (status = FORBIDDEN, description = "Недостаточно прав для выполнения действия."),
+    (status = NOT_FOUND, description = "Книга с таким идентификатором не найдена."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[put("/{id}/inventory")]
+pub async fn update_inventory(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  data: ValidJson<UpdateInventoryReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  if auth_claims.role != UserRole::Admin {
+    return (web::Json(None), http::StatusCode::FORBIDDEN)
+  }
+
+  match state.book_service.update_inventory(&path.0, data.0).await {
+    BookUpdateResult::Ok(book) => (web::Json(Some(book)), http::StatusCode::OK),
+    BookUpdateResult::BadRequest => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    BookUpdateResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    BookUpdateResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
 }
\ No newline at end of file
@@ -0,0 +1,170 @@
+use actix_web::{http, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::adapters::middleware::jwt::JwtClaims;
+use crate::adapters::util::validation::ValidJson;
+use crate::application::dto::request::branch::{AddBranchReq, UpdateBranchReq};
+use crate::application::entities::user::UserRole;
+use crate::application::services::branch::{BranchAddResult, BranchDeleteResult, BranchFetchResult, BranchListFetchResult, BranchUpdateResult};
+use crate::application::state::app_state::AppState;
+
+
+#[utoipa::path(
+  get,
+  tag = "Филиалы",
+  context_path = "/api/branch",
+  responses(
+    (status = OK, body = BranchListResp),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("")]
+pub async fn get_list(
+  state: web::Data<AppState>,
+) -> impl Responder
+{
+  match state.branch_service.get_list().await {
+    BranchListFetchResult::Ok(branches) => (web::Json(Some(branches)), http::StatusCode::OK),
+    BranchListFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  get,
+  tag = "Филиалы",
+  context_path = "/api/branch",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор филиала."),
+  ),
+  responses(
+    (status = OK, body = FullBranchResp),
+    (status = NOT_FOUND, description = "Филиал с таким идентификатором не найден."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("/{id}")]
+pub async fn get_by_id(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+) -> impl Responder
+{
+  match state.branch_service.get_by_id(&path.0).await {
+    BranchFetchResult::Ok(branch) => (web::Json(Some(branch)), http::StatusCode::OK),
+    BranchFetchResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    BranchFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Филиалы",
+  context_path = "/api/branch",
+  request_body = AddBranchReq,
+  responses(
+    (status = CREATED, body = FullBranchResp),
+    (status = FORBIDDEN, description = "Добавлять филиалы могут только администраторы."),
+    (status = CONFLICT, description = "Филиал с таким названием уже существует."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[post("")]
+pub async fn add_one(
+  state: web::Data<AppState>,
+  data: ValidJson<AddBranchReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  if auth_claims.role != UserRole::Admin {
+    return (web::Json(None), http::StatusCode::FORBIDDEN)
+  }
+
+  match state.branch_service.add_one(data.0).await {
+    BranchAddResult::Ok(branch) => (web::Json(Some(branch)), http::StatusCode::CREATED),
+    BranchAddResult::BadRequest => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    BranchAddResult::AlreadyExists => (web::Json(None), http::StatusCode::CONFLICT),
+    BranchAddResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  patch,
+  tag = "Филиалы",
+  context_path = "/api/branch",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор филиала."),
+  ),
+  request_body = UpdateBranchReq,
+  responses(
+    (status = OK, body = FullBranchResp),
+    (status = FORBIDDEN, description = "Изменять филиалы могут только администраторы."),
+    (status = NOT_FOUND, description = "Филиал с таким идентификатором не найден."),
+    (status = CONFLICT, description = "Филиал с таким названием уже существует."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[patch("/{id}")]
+pub async fn update_one(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  data: ValidJson<UpdateBranchReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  if auth_claims.role != UserRole::Admin {
+    return (web::Json(None), http::StatusCode::FORBIDDEN)
+  }
+
+  match state.branch_service.update_one(&path.0, data.0).await {
+    BranchUpdateResult::Ok(branch) => (web::Json(Some(branch)), http::StatusCode::OK),
+    BranchUpdateResult::BadRequest => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    BranchUpdateResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    BranchUpdateResult::AlreadyExists => (web::Json(None), http::StatusCode::CONFLICT),
+    BranchUpdateResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  delete,
+  tag = "Филиалы",
+  context_path = "/api/branch",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор филиала."),
+  ),
+  responses(
+    (status = OK, description = "Филиал удалён."),
+    (status = FORBIDDEN, description = "Удалять филиалы могут только администраторы."),
+    (status = NOT_FOUND, description = "Филиал с таким идентификатором не найден."),
+    (status = CONFLICT, description = "В филиале есть экземпляры: их нужно сначала переместить."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[delete("/{id}")]
+pub async fn delete_one(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  if auth_claims.role != UserRole::Admin {
+    return HttpResponse::new(http::StatusCode::FORBIDDEN)
+  }
+
+  match state.branch_service.delete_one(&path.0).await {
+    BranchDeleteResult::Ok => HttpResponse::new(http::StatusCode::OK),
+    BranchDeleteResult::NotFound => HttpResponse::new(http::StatusCode::NOT_FOUND),
+    BranchDeleteResult::NotEmpty => HttpResponse::new(http::StatusCode::CONFLICT),
+    BranchDeleteResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
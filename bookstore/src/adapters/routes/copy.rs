@@ -0,0 +1,259 @@
+use actix_web::{http, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::adapters::middleware::jwt::JwtClaims;
+use crate::adapters::util::validation::{ValidJson, ValidQuery};
+use crate::application::dto::request::copy::{AddCopyReq, GetCopyListReq, TransferCopyReq, UpdateCopyReq};
+use crate::application::entities::user::UserRole;
+use crate::application::services::copy::{
+  CopyAddResult,
+  CopyDeleteResult,
+  CopyFetchResult,
+  CopyListFetchResult,
+  CopyTransferListFetchResult,
+  CopyTransferResult,
+  CopyUpdateResult,
+};
+use crate::application::state::app_state::AppState;
+
+
+#[utoipa::path(
+  get,
+  tag = "Экземпляры",
+  context_path = "/api/copy",
+  params(
+    ("page" = u32, Query, description = "Индекс страницы.", example = 0),
+    ("size" = u32, Query, description = "Размер одной страницы.", minimum = 1, maximum = 100, example = 20),
+    ("book_id" = Option<Uuid>, Query, description = "Только экземпляры этой книги."),
+    ("branch_id" = Option<Uuid>, Query, description = "Только экземпляры этого филиала."),
+    ("status" = Option<CopyStatus>, Query, description = "Только экземпляры с этим статусом."),
+  ),
+  responses(
+    (status = OK, body = CopyListResp),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("")]
+pub async fn get_list(
+  state: web::Data<AppState>,
+  query: ValidQuery<GetCopyListReq>,
+) -> impl Responder
+{
+  match state.copy_service.get_list(query.0).await {
+    CopyListFetchResult::Ok(copies) => (web::Json(Some(copies)), http::StatusCode::OK),
+    CopyListFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  get,
+  tag = "Экземпляры",
+  context_path = "/api/copy",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор экземпляра."),
+  ),
+  responses(
+    (status = OK, body = FullCopyResp),
+    (status = NOT_FOUND, description = "Экземпляр с таким идентификатором не найден."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("/{id}")]
+pub async fn get_by_id(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+) -> impl Responder
+{
+  match state.copy_service.get_by_id(&path.0).await {
+    CopyFetchResult::Ok(copy) => (web::Json(Some(copy)), http::StatusCode::OK),
+    CopyFetchResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    CopyFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Экземпляры",
+  context_path = "/api/copy",
+  request_body = AddCopyReq,
+  responses(
+    (status = CREATED, body = FullCopyResp),
+    (status = FORBIDDEN, description = "Добавлять экземпляры могут только администраторы."),
+    (status = NOT_FOUND, description = "Книга или филиал не найдены."),
+    (status = CONFLICT, description = "Экземпляр с таким штрихкодом уже существует."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[post("")]
+pub async fn add_one(
+  state: web::Data<AppState>,
+  data: ValidJson<AddCopyReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  if auth_claims.role != UserRole::Admin {
+    return (web::Json(None), http::StatusCode::FORBIDDEN)
+  }
+
+  match state.copy_service.add_one(data.0).await {
+    CopyAddResult::Ok(copy) => (web::Json(Some(copy)), http::StatusCode::CREATED),
+    CopyAddResult::BadRequest => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    CopyAddResult::BookNotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    CopyAddResult::BranchNotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    CopyAddResult::AlreadyExists => (web::Json(None), http::StatusCode::CONFLICT),
+    CopyAddResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  patch,
+  tag = "Экземпляры",
+  context_path = "/api/copy",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор экземпляра."),
+  ),
+  request_body = UpdateCopyReq,
+  responses(
+    (status = OK, body = FullCopyResp),
+    (status = FORBIDDEN, description = "Изменять экземпляры могут только администраторы."),
+    (status = NOT_FOUND, description = "Экземпляр с таким идентификатором не найден."),
+    (status = CONFLICT, description = "Штрихкод занят либо статус выданного экземпляра нельзя изменить."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[patch("/{id}")]
+pub async fn update_one(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  data: ValidJson<UpdateCopyReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  if auth_claims.role != UserRole::Admin {
+    return (web::Json(None), http::StatusCode::FORBIDDEN)
+  }
+
+  match state.copy_service.update_one(&path.0, data.0).await {
+    CopyUpdateResult::Ok(copy) => (web::Json(Some(copy)), http::StatusCode::OK),
+    CopyUpdateResult::BadRequest => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    CopyUpdateResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    CopyUpdateResult::AlreadyExists => (web::Json(None), http::StatusCode::CONFLICT),
+    CopyUpdateResult::Conflict => (web::Json(None), http::StatusCode::CONFLICT),
+    CopyUpdateResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  delete,
+  tag = "Экземпляры",
+  context_path = "/api/copy",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор экземпляра."),
+  ),
+  responses(
+    (status = OK, description = "Экземпляр удалён."),
+    (status = FORBIDDEN, description = "Удалять экземпляры могут только администраторы."),
+    (status = NOT_FOUND, description = "Экземпляр с таким идентификатором не найден."),
+    (status = CONFLICT, description = "Экземпляр выдавался читателям: его можно только списать."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[delete("/{id}")]
+pub async fn delete_one(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  if auth_claims.role != UserRole::Admin {
+    return HttpResponse::new(http::StatusCode::FORBIDDEN)
+  }
+
+  match state.copy_service.delete_one(&path.0).await {
+    CopyDeleteResult::Ok => HttpResponse::new(http::StatusCode::OK),
+    CopyDeleteResult::NotFound => HttpResponse::new(http::StatusCode::NOT_FOUND),
+    CopyDeleteResult::HasLoans => HttpResponse::new(http::StatusCode::CONFLICT),
+    CopyDeleteResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Экземпляры",
+  context_path = "/api/copy",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор экземпляра."),
+  ),
+  request_body = TransferCopyReq,
+  responses(
+    (status = OK, body = FullCopyResp),
+    (status = FORBIDDEN, description = "Перемещать экземпляры могут только администраторы."),
+    (status = NOT_FOUND, description = "Экземпляр или филиал не найдены."),
+    (status = CONFLICT, description = "Экземпляр выдан либо уже находится в этом филиале."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[post("/{id}/transfer")]
+pub async fn transfer(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  data: ValidJson<TransferCopyReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  if auth_claims.role != UserRole::Admin {
+    return (web::Json(None), http::StatusCode::FORBIDDEN)
+  }
+
+  match state.copy_service.transfer(&path.0, &auth_claims.user_id(), data.0).await {
+    CopyTransferResult::Ok(copy) => (web::Json(Some(copy)), http::StatusCode::OK),
+    CopyTransferResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    CopyTransferResult::BranchNotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    CopyTransferResult::Conflict => (web::Json(None), http::StatusCode::CONFLICT),
+    CopyTransferResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  get,
+  tag = "Экземпляры",
+  context_path = "/api/copy",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор экземпляра."),
+  ),
+  responses(
+    (status = OK, body = CopyTransferListResp),
+    (status = NOT_FOUND, description = "Экземпляр с таким идентификатором не найден."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("/{id}/transfers")]
+pub async fn get_transfer_list(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+) -> impl Responder
+{
+  match state.copy_service.get_transfer_list(&path.0).await {
+    CopyTransferListFetchResult::Ok(transfers) => (web::Json(Some(transfers)), http::StatusCode::OK),
+    CopyTransferListFetchResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    CopyTransferListFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
@@ -0,0 +1,145 @@
+use actix_web::{http, Responder, web};
+
+use crate::adapters::util::validation::ValidJson;
+use crate::application::dto::request::diagnostics::SetLogLevelReq;
+use crate::application::services::diagnostics::{LogLevelRemoveResult, LogLevelSetResult};
+use crate::application::state::app_state::AppState;
+
+
+#[utoipa::path(
+  get,
+  tag = "Диагностика",
+  context_path = "/api/admin/diagnostics",
+  responses(
+    (status = OK, body = LogLevelsResp),
+    (status = FORBIDDEN, description = "Доступно только администраторам."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("/log-levels")]
+pub async fn get_log_levels(
+  state: web::Data<AppState>,
+) -> impl Responder
+{
+  (web::Json(state.diagnostics_service.get_log_levels()), http::StatusCode::OK)
+}
+
+#[utoipa::path(
+  put,
+  tag = "Диагностика",
+  context_path = "/api/admin/diagnostics",
+  request_body = SetLogLevelReq,
+  responses(
+    (status = OK, body = LogLevelsResp, description = "Уровень изменён до истечения срока."),
+    (status = FORBIDDEN, description = "Доступно только администраторам."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[put("/log-levels")]
+pub async fn set_log_level(
+  state: web::Data<AppState>,
+  data: ValidJson<SetLogLevelReq>,
+) -> impl Responder
+{
+  match state.diagnostics_service.set_log_level(data.0) {
+    LogLevelSetResult::Ok(levels) => (web::Json(Some(levels)), http::StatusCode::OK),
+    LogLevelSetResult::InvalidModule => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    LogLevelSetResult::InvalidLevel => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    LogLevelSetResult::InvalidTtl => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    LogLevelSetResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  delete,
+  tag = "Диагностика",
+  context_path = "/api/admin/diagnostics",
+  params(
+    ("module" = String, Path, description = "Модуль, для которого возвращается уровень из конфигурации."),
+  ),
+  responses(
+    (status = OK, body = LogLevelsResp),
+    (status = NOT_FOUND, description = "Уровень этого модуля не изменялся."),
+    (status = FORBIDDEN, description = "Доступно только администраторам."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[delete("/log-levels/{module}")]
+pub async fn remove_log_level(
+  state: web::Data<AppState>,
+  path: web::Path<(String, )>,
+) -> impl Responder
+{
+  match state.diagnostics_service.remove_log_level(&path.into_inner().0) {
+    LogLevelRemoveResult::Ok(levels) => (web::Json(Some(levels)), http::StatusCode::OK),
+    LogLevelRemoveResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    LogLevelRemoveResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  get,
+  tag = "Диагностика",
+  context_path = "/api/admin/diagnostics",
+  responses(
+    (status = OK, body = PoolStatsResp),
+    (status = FORBIDDEN, description = "Доступно только администраторам."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("/pool")]
+pub async fn get_pool_stats(
+  state: web::Data<AppState>,
+) -> impl Responder
+{
+  (web::Json(state.diagnostics_service.get_pool_stats()), http::StatusCode::OK)
+}
+
+#[utoipa::path(
+  get,
+  tag = "Диагностика",
+  context_path = "/api/admin/diagnostics",
+  responses(
+    (status = OK, description = "Действующая конфигурация; пароли и секреты скрыты."),
+    (status = FORBIDDEN, description = "Доступно только администраторам."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("/config")]
+pub async fn get_config(
+  state: web::Data<AppState>,
+) -> impl Responder
+{
+  (web::Json(state.diagnostics_service.get_config()), http::StatusCode::OK)
+}
+
+#[utoipa::path(
+  get,
+  tag = "Диагностика",
+  context_path = "/api/admin/diagnostics",
+  responses(
+    (status = OK, body = ErrorSummaryListResp, description = "Ошибки с момента запуска по модулям."),
+    (status = FORBIDDEN, description = "Доступно только администраторам."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("/errors")]
+pub async fn get_error_summaries(
+  state: web::Data<AppState>,
+) -> impl Responder
+{
+  (web::Json(state.diagnostics_service.get_error_summaries()), http::StatusCode::OK)
+}
@@ -0,0 +1,56 @@
+use actix_web::{http, Responder, web};
+
+use crate::application::services::health::ReadinessCheckResult;
+use crate::application::state::app_state::AppState;
+
+
+#[utoipa::path(
+  get,
+  tag = "Служебное",
+  context_path = "/health",
+  responses(
+    (status = OK, body = LivenessResp, description = "Процесс запущен."),
+  ),
+)]
+#[get("/live")]
+pub async fn live(
+  state: web::Data<AppState>,
+) -> impl Responder
+{
+  (web::Json(state.health_service.check_live()), http::StatusCode::OK)
+}
+
+#[utoipa::path(
+  get,
+  tag = "Служебное",
+  context_path = "/health",
+  responses(
+    (status = OK, body = ReadinessResp, description = "Сервис готов принимать запросы."),
+    (status = SERVICE_UNAVAILABLE, body = ReadinessResp, description = "База данных недоступна или не все миграции применены."),
+  ),
+)]
+#[get("/ready")]
+pub async fn ready(
+  state: web::Data<AppState>,
+) -> impl Responder
+{
+  match state.health_service.check_ready().await {
+    ReadinessCheckResult::Ready(resp) => (web::Json(resp), http::StatusCode::OK),
+    ReadinessCheckResult::NotReady(resp) => (web::Json(resp), http::StatusCode::SERVICE_UNAVAILABLE),
+  }
+}
+
+#[utoipa::path(
+  get,
+  tag = "Служебное",
+  responses(
+    (status = OK, body = VersionResp),
+  ),
+)]
+#[get("/version")]
+pub async fn version(
+  state: web::Data<AppState>,
+) -> impl Responder
+{
+  (web::Json(state.health_service.get_version().await), http::StatusCode::OK)
+}
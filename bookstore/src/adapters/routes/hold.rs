@@ -0,0 +1,97 @@
+use actix_web::{http, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::adapters::middleware::jwt::JwtClaims;
+use crate::adapters::util::validation::ValidJson;
+use crate::application::dto::request::hold::PlaceHoldReq;
+use crate::application::services::hold::{HoldCancelResult, HoldListFetchResult, HoldPlaceResult};
+use crate::application::state::app_state::AppState;
+
+
+#[utoipa::path(
+  get,
+  tag = "Очередь за книгами",
+  context_path = "/api/hold",
+  responses(
+    (status = OK, body = HoldListResp),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("")]
+pub async fn get_own_list(
+  state: web::Data<AppState>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.hold_service.get_own_list(&auth_claims.user_id()).await {
+    HoldListFetchResult::Ok(holds) => (web::Json(Some(holds)), http::StatusCode::OK),
+    HoldListFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Очередь за книгами",
+  context_path = "/api/hold",
+  request_body = PlaceHoldReq,
+  responses(
+    (status = CREATED, body = FullHoldResp),
+    (status = NOT_FOUND, description = "Книга с таким идентификатором не найдена."),
+    (status = CONFLICT, description = "Пользователь уже в очереди либо есть свободный экземпляр."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[post("")]
+pub async fn place(
+  state: web::Data<AppState>,
+  data: ValidJson<PlaceHoldReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.hold_service.place(&auth_claims.user_id(), data.0).await {
+    HoldPlaceResult::Ok(hold) => {
+      state.metrics.holds_placed.inc();
+      (web::Json(Some(hold)), http::StatusCode::CREATED)
+    },
+    HoldPlaceResult::BookNotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    HoldPlaceResult::AlreadyWaiting => (web::Json(None), http::StatusCode::CONFLICT),
+    HoldPlaceResult::CopyAvailable => (web::Json(None), http::StatusCode::CONFLICT),
+    HoldPlaceResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  delete,
+  tag = "Очередь за книгами",
+  context_path = "/api/hold",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор места в очереди."),
+  ),
+  responses(
+    (status = OK, description = "Пользователь покинул очередь."),
+    (status = FORBIDDEN, description = "Место в очереди принадлежит другому пользователю."),
+    (status = NOT_FOUND, description = "Ожидающее место в очереди с таким идентификатором не найдено."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[delete("/{id}")]
+pub async fn cancel(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.hold_service.cancel(&path.0, &auth_claims.user_id()).await {
+    HoldCancelResult::Ok => HttpResponse::new(http::StatusCode::OK),
+    HoldCancelResult::NotFound => HttpResponse::new(http::StatusCode::NOT_FOUND),
+    HoldCancelResult::Forbidden => HttpResponse::new(http::StatusCode::FORBIDDEN),
+    HoldCancelResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
@@ -0,0 +1,221 @@
+use actix_web::{http, Responder, web};
+use uuid::Uuid;
+
+use crate::adapters::middleware::jwt::JwtClaims;
+use crate::adapters::util::validation::{ValidJson, ValidQuery};
+use crate::application::dto::request::loan::{CheckoutReq, GetLoanListReq, GetOverdueListReq};
+use crate::application::entities::user::UserRole;
+use crate::application::services::loan::{LoanCheckoutResult, LoanFetchResult, LoanListFetchResult, LoanRenewResult, LoanReturnResult};
+use crate::application::state::app_state::AppState;
+
+
+#[utoipa::path(
+  get,
+  tag = "Выдачи",
+  context_path = "/api/loan",
+  params(
+    ("page" = u32, Query, description = "Индекс страницы.", example = 0),
+    ("size" = u32, Query, description = "Размер одной страницы.", minimum = 1, maximum = 100, example = 20),
+    ("active_only" = Option<bool>, Query, description = "Только невозвращённые.", example = false),
+  ),
+  responses(
+    (status = OK, body = LoanListResp),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("")]
+pub async fn get_own_list(
+  state: web::Data<AppState>,
+  query: ValidQuery<GetLoanListReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.loan_service.get_own_list(&auth_claims.user_id(), query.0).await {
+    LoanListFetchResult::Ok(loans) => (web::Json(Some(loans)), http::StatusCode::OK),
+    LoanListFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  get,
+  tag = "Выдачи",
+  context_path = "/api/loan",
+  params(
+    ("page" = u32, Query, description = "Индекс страницы.", example = 0),
+    ("size" = u32, Query, description = "Размер одной страницы.", minimum = 1, maximum = 100, example = 20),
+  ),
+  responses(
+    (status = OK, body = LoanListResp),
+    (status = FORBIDDEN, description = "Просматривать просроченные выдачи могут только администраторы."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("/overdue")]
+pub async fn get_overdue_list(
+  state: web::Data<AppState>,
+  query: ValidQuery<GetOverdueListReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  if auth_claims.role != UserRole::Admin {
+    return (web::Json(None), http::StatusCode::FORBIDDEN)
+  }
+
+  match state.loan_service.get_overdue_list(query.0).await {
+    LoanListFetchResult::Ok(loans) => (web::Json(Some(loans)), http::StatusCode::OK),
+    LoanListFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  get,
+  tag = "Выдачи",
+  context_path = "/api/loan",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор выдачи."),
+  ),
+  responses(
+    (status = OK, body = FullLoanResp),
+    (status = FORBIDDEN, description = "Выдача принадлежит другому пользователю."),
+    (status = NOT_FOUND, description = "Выдача с таким идентификатором не найдена."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("/{id}")]
+pub async fn get_by_id(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  let is_admin = auth_claims.role == UserRole::Admin;
+  match state.loan_service.get_by_id(&path.0, &auth_claims.user_id(), is_admin).await {
+    LoanFetchResult::Ok(loan) => (web::Json(Some(loan)), http::StatusCode::OK),
+    LoanFetchResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    LoanFetchResult::Forbidden => (web::Json(None), http::StatusCode::FORBIDDEN),
+    LoanFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Выдачи",
+  context_path = "/api/loan",
+  request_body = CheckoutReq,
+  responses(
+    (status = CREATED, body = FullLoanResp),
+    (status = FORBIDDEN, description = "Выдавать книги другим пользователям могут только администраторы \
+      либо у читателя есть просроченные выдачи."),
+    (status = NOT_FOUND, description = "Книга или пользователь не найдены."),
+    (status = CONFLICT, description = "Свободных экземпляров нет: можно встать в очередь."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[post("")]
+pub async fn checkout(
+  state: web::Data<AppState>,
+  data: ValidJson<CheckoutReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  let borrower_id = data.user_id.unwrap_or(auth_claims.user_id());
+  if borrower_id != auth_claims.user_id() && auth_claims.role != UserRole::Admin {
+    return (web::Json(None), http::StatusCode::FORBIDDEN)
+  }
+
+  match state.loan_service.checkout(&borrower_id, data.0).await {
+    LoanCheckoutResult::Ok(loan) => {
+      state.metrics.loans_checked_out.inc();
+      (web::Json(Some(loan)), http::StatusCode::CREATED)
+    },
+    LoanCheckoutResult::BookNotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    LoanCheckoutResult::UserNotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    LoanCheckoutResult::Blocked => (web::Json(None), http::StatusCode::FORBIDDEN),
+    LoanCheckoutResult::NoCopyAvailable => (web::Json(None), http::StatusCode::CONFLICT),
+    LoanCheckoutResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Выдачи",
+  context_path = "/api/loan",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор выдачи."),
+  ),
+  responses(
+    (status = OK, body = FullLoanResp),
+    (status = FORBIDDEN, description = "Выдача принадлежит другому пользователю \
+      либо у читателя есть просроченные выдачи."),
+    (status = NOT_FOUND, description = "Выдача с таким идентификатором не найдена."),
+    (status = CONFLICT, description = "Книга возвращена, продлевалась слишком много раз \
+      или её ждут другие читатели."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[post("/{id}/renew")]
+pub async fn renew(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  let is_admin = auth_claims.role == UserRole::Admin;
+  match state.loan_service.renew(&path.0, &auth_claims.user_id(), is_admin).await {
+    LoanRenewResult::Ok(loan) => (web::Json(Some(loan)), http::StatusCode::OK),
+    LoanRenewResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    LoanRenewResult::Forbidden => (web::Json(None), http::StatusCode::FORBIDDEN),
+    LoanRenewResult::Blocked => (web::Json(None), http::StatusCode::FORBIDDEN),
+    LoanRenewResult::Conflict => (web::Json(None), http::StatusCode::CONFLICT),
+    LoanRenewResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Выдачи",
+  context_path = "/api/loan",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор выдачи."),
+  ),
+  responses(
+    (status = OK, body = FullLoanResp),
+    (status = FORBIDDEN, description = "Отмечать возврат могут только администраторы."),
+    (status = NOT_FOUND, description = "Выдача с таким идентификатором не найдена."),
+    (status = CONFLICT, description = "Книга уже возвращена."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[post("/{id}/return")]
+pub async fn return_one(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  if auth_claims.role != UserRole::Admin {
+    return (web::Json(None), http::StatusCode::FORBIDDEN)
+  }
+
+  match state.loan_service.return_one(&path.0).await {
+    LoanReturnResult::Ok(loan) => (web::Json(Some(loan)), http::StatusCode::OK),
+    LoanReturnResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    LoanReturnResult::AlreadyReturned => (web::Json(None), http::StatusCode::CONFLICT),
+    LoanReturnResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
@@ -0,0 +1,69 @@
+use actix_web::{http, HttpResponse, Responder, web};
+
+use crate::adapters::util::validation::ValidQuery;
+use crate::application::dto::request::login_failure::GetLoginFailureListReq;
+use crate::application::entities::login_failure::LoginKeyKind;
+use crate::application::services::auth::{LoginFailureListFetchResult, LoginUnlockResult};
+use crate::application::state::app_state::AppState;
+
+
+#[utoipa::path(
+  get,
+  tag = "Блокировки входа",
+  context_path = "/api/admin/lockouts",
+  params(
+    ("page" = u32, Query, description = "Индекс страницы.", example = 0),
+    ("size" = u32, Query, description = "Размер одной страницы.", minimum = 1, maximum = 100, example = 20),
+    ("locked_only" = Option<bool>, Query, description = "Только заблокированные сейчас.", example = false),
+  ),
+  responses(
+    (status = OK, body = LoginFailureListResp),
+    (status = FORBIDDEN, description = "Доступно только администраторам."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("")]
+pub async fn get_list(
+  state: web::Data<AppState>,
+  query: ValidQuery<GetLoginFailureListReq>,
+) -> impl Responder
+{
+  match state.auth_service.get_login_failures(query.0).await {
+    LoginFailureListFetchResult::Ok(failures) => (web::Json(Some(failures)), http::StatusCode::OK),
+    LoginFailureListFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  delete,
+  tag = "Блокировки входа",
+  context_path = "/api/admin/lockouts",
+  params(
+    ("kind" = LoginKeyKind, Path, description = "Псевдоним или адрес клиента.", example = "nickname"),
+    ("key" = String, Path, description = "Псевдоним без учета регистра или адрес.", example = "aboba_x69"),
+  ),
+  responses(
+    (status = OK, description = "Неудачные попытки забыты, вход снова разрешен."),
+    (status = NOT_FOUND, description = "Неудачных попыток не было."),
+    (status = FORBIDDEN, description = "Доступно только администраторам."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[delete("/{kind}/{key}")]
+pub async fn unlock(
+  state: web::Data<AppState>,
+  path: web::Path<(LoginKeyKind, String)>,
+) -> impl Responder
+{
+  let (kind, key) = path.into_inner();
+  match state.auth_service.unlock_login(kind, &key).await {
+    LoginUnlockResult::Ok => HttpResponse::new(http::StatusCode::OK),
+    LoginUnlockResult::NotFound => HttpResponse::new(http::StatusCode::NOT_FOUND),
+    LoginUnlockResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
@@ -0,0 +1,177 @@
+use actix_web::{http, HttpRequest, HttpResponse, Responder, web};
+
+use crate::adapters::middleware::jwt::JwtClaims;
+use crate::adapters::util::validation::{validation_error, ValidJson};
+use crate::application::dto::request::user::{ChangePasswordReq, DeleteMeReq, SetEmailReq, UpdateMeReq};
+use crate::application::dto::response::user::TokenResp;
+use crate::application::services::account::EmailSetResult;
+use crate::application::services::auth::{AccountDeleteResult, PasswordChangeResult};
+use crate::application::services::user::{UserFetchResult, UserUpdateResult};
+use crate::application::state::app_state::AppState;
+
+
+#[utoipa::path(
+  get,
+  tag = "Профиль",
+  context_path = "/api/me",
+  responses(
+    (status = OK, body = FullUserResp),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("")]
+pub async fn get_own(
+  state: web::Data<AppState>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.user_service.get_by_id(&auth_claims.user_id()).await {
+    UserFetchResult::Ok(user) => (web::Json(Some(user)), http::StatusCode::OK),
+    UserFetchResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    UserFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  patch,
+  tag = "Профиль",
+  context_path = "/api/me",
+  request_body = UpdateMeReq,
+  responses(
+    (status = OK, body = FullUserResp),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[patch("")]
+pub async fn update_own(
+  state: web::Data<AppState>,
+  data: ValidJson<UpdateMeReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.user_service.update_names(&auth_claims.user_id(), data.0).await {
+    UserUpdateResult::Ok(user) => (web::Json(Some(user)), http::StatusCode::OK),
+    UserUpdateResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    UserUpdateResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Профиль",
+  context_path = "/api/me",
+  request_body = ChangePasswordReq,
+  responses(
+    (status = OK, body = TokenResp, description = "Пароль изменен. Остальные сеансы завершены, токен нового сеанса в ответе."),
+    (status = FORBIDDEN, description = "Текущий пароль указан неверно."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку или пароль не соответствует политике."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[post("/password")]
+pub async fn change_password(
+  req: HttpRequest,
+  state: web::Data<AppState>,
+  data: ValidJson<ChangePasswordReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> Result<impl Responder, actix_web::Error>
+{
+  Ok(match state.auth_service.change_password(&auth_claims.user_id(), data.0).await {
+    PasswordChangeResult::Ok(token) => (web::Json(Some(TokenResp { token })), http::StatusCode::OK),
+    PasswordChangeResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    PasswordChangeResult::WrongPassword => (web::Json(None), http::StatusCode::FORBIDDEN),
+    PasswordChangeResult::WeakPassword(errors) => return Err(validation_error(&req, errors)),
+    PasswordChangeResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  })
+}
+
+#[utoipa::path(
+  delete,
+  tag = "Профиль",
+  context_path = "/api/me",
+  request_body = DeleteMeReq,
+  responses(
+    (status = OK, description = "Аккаунт удален вместе с полками, отзывами, бронированиями и уведомлениями."),
+    (status = FORBIDDEN, description = "Пароль указан неверно."),
+    (status = CONFLICT, description = "У пользователя есть невозвращенные книги."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[delete("")]
+pub async fn delete_own(
+  state: web::Data<AppState>,
+  data: ValidJson<DeleteMeReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.auth_service.delete_account(&auth_claims.user_id(), data.0).await {
+    AccountDeleteResult::Ok => HttpResponse::new(http::StatusCode::OK),
+    AccountDeleteResult::NotFound => HttpResponse::new(http::StatusCode::NOT_FOUND),
+    AccountDeleteResult::WrongPassword => HttpResponse::new(http::StatusCode::FORBIDDEN),
+    AccountDeleteResult::HasLoans => HttpResponse::new(http::StatusCode::CONFLICT),
+    AccountDeleteResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  put,
+  tag = "Профиль",
+  context_path = "/api/me",
+  request_body = SetEmailReq,
+  responses(
+    (status = OK, body = FullUserResp, description = "Адрес сохранен, на него отправлен код для подтверждения."),
+    (status = CONFLICT, description = "Адрес уже подтвержден другим пользователем."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[put("/email")]
+pub async fn set_email(
+  state: web::Data<AppState>,
+  data: ValidJson<SetEmailReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.account_service.set_email(&auth_claims.user_id(), data.0).await {
+    EmailSetResult::Ok(user) => (web::Json(Some(user)), http::StatusCode::OK),
+    EmailSetResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    EmailSetResult::AlreadyTaken => (web::Json(None), http::StatusCode::CONFLICT),
+    EmailSetResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  delete,
+  tag = "Профиль",
+  context_path = "/api/me",
+  responses(
+    (status = OK, body = FullUserResp, description = "Адрес удален, восстановить пароль по почте больше нельзя."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[delete("/email")]
+pub async fn remove_email(
+  state: web::Data<AppState>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.account_service.remove_email(&auth_claims.user_id()).await {
+    UserUpdateResult::Ok(user) => (web::Json(Some(user)), http::StatusCode::OK),
+    UserUpdateResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    UserUpdateResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
@@ -0,0 +1,21 @@
+use actix_web::{HttpResponse, Responder, web};
+
+use crate::application::state::app_state::AppState;
+
+
+#[utoipa::path(
+  get,
+  tag = "Служебное",
+  responses(
+    (status = OK, content_type = "text/plain", description = "Метрики в текстовом формате Prometheus."),
+  ),
+)]
+#[get("/metrics")]
+pub async fn export(
+  state: web::Data<AppState>,
+) -> impl Responder
+{
+  HttpResponse::Ok()
+    .content_type(prometheus::TEXT_FORMAT)
+    .body(state.metrics.render().await)
+}
@@ -3,3 +3,197 @@ pub mod auth;
 pub mod user;
 pub mod book;
 pub mod author;
+pub mod review;
+pub mod shelf;
+pub mod wishlist;
+pub mod notification;
+pub mod copy;
+pub mod loan;
+pub mod hold;
+pub mod branch;
+pub mod health;
+pub mod metrics;
+pub mod diagnostics;
+pub mod me;
+pub mod lockout;
+pub mod two_factor;
+
+use actix_web::web;
+
+use crate::adapters::middleware::jwt::JwtAuth;
+use crate::adapters::middleware::rate_limit::RateLimit;
+use crate::adapters::util::payload;
+use crate::application::entities::user::UserRole;
+use crate::application::state::app_state::AppState;
+
+
+/// Register the health checks and the API, protected by `JwtAuth` where needed and
+/// rate limited by `RateLimit`, along with the extractor configs. The app-wide
+/// middleware and the docs are up to the caller.
+pub fn configure(cfg: &mut web::ServiceConfig, app_state: &web::Data<AppState>) {
+  payload::configure(cfg, &app_state.config.server);
+  let limits = &app_state.config.rate_limit;
+  cfg
+    .service(
+      web::scope("/health")
+        .service(health::live)
+        .service(health::ready)
+    )
+    .service(health::version)
+    .service(
+      web::scope("/api")
+        .service(
+          web::scope("/ping")
+            .service(ping::say_pong)
+            .wrap(RateLimit::new("default", &limits.default, app_state.clone()))
+        )
+        .service(
+          web::scope("/auth")
+            .service(auth::login)
+            .service(auth::login_2fa)
+            .service(auth::register)
+            .service(auth::forgot_password)
+            .service(auth::reset_password)
+            .service(auth::verify_email)
+            .wrap(RateLimit::new("auth", &limits.auth, app_state.clone()))
+        )
+        .service(
+          web::scope("/user")
+            .service(user::get_by_id)
+            .service(user::get_list)
+            .service(user::update_suspended)
+            .service(shelf::get_public_list)
+            .wrap(RateLimit::new("default", &limits.default, app_state.clone()))
+            // scope-wide middleware: protect the scope with JwtAuth
+            .wrap(JwtAuth::new(vec![UserRole::Admin, UserRole::User], app_state.clone()))
+        )
+        .service(
+          web::scope("/book")
+            .service(book::get_list)
+            .service(book::get_by_id)
+            .service(book::add_one)
+            .service(book::delete_one)
+            .service(book::update_inventory)
+            .service(review::get_list)
+            .service(review::add_one)
+            .service(review::update_own)
+            .service(review::delete_own)
+            .service(review::update_hidden)
+            .wrap(RateLimit::new("catalogue", &limits.catalogue, app_state.clone()))
+            .wrap(JwtAuth::new(vec![UserRole::Admin, UserRole::User], app_state.clone()))
+        )
+        .service(
+          web::scope("/author")
+            .service(author::get_list)
+            .service(author::get_by_id)
+            .service(author::add_one)
+            .service(author::delete_one)
+            .wrap(RateLimit::new("catalogue", &limits.catalogue, app_state.clone()))
+            .wrap(JwtAuth::new(vec![UserRole::Admin, UserRole::User], app_state.clone()))
+        )
+        .service(
+          web::scope("/copy")
+            .service(copy::get_list)
+            .service(copy::get_by_id)
+            .service(copy::add_one)
+            .service(copy::update_one)
+            .service(copy::delete_one)
+            .service(copy::transfer)
+            .service(copy::get_transfer_list)
+            .wrap(RateLimit::new("default", &limits.default, app_state.clone()))
+            .wrap(JwtAuth::new(vec![UserRole::Admin, UserRole::User], app_state.clone()))
+        )
+        .service(
+          web::scope("/branch")
+            .service(branch::get_list)
+            .service(branch::get_by_id)
+            .service(branch::add_one)
+            .service(branch::update_one)
+            .service(branch::delete_one)
+            .wrap(RateLimit::new("default", &limits.default, app_state.clone()))
+            .wrap(JwtAuth::new(vec![UserRole::Admin, UserRole::User], app_state.clone()))
+        )
+        .service(
+          web::scope("/loan")
+            .service(loan::get_own_list)
+            .service(loan::get_overdue_list)
+            .service(loan::get_by_id)
+            .service(loan::checkout)
+            .service(loan::renew)
+            .service(loan::return_one)
+            .wrap(RateLimit::new("default", &limits.default, app_state.clone()))
+            .wrap(JwtAuth::new(vec![UserRole::Admin, UserRole::User], app_state.clone()))
+        )
+        .service(
+          web::scope("/hold")
+            .service(hold::get_own_list)
+            .service(hold::place)
+            .service(hold::cancel)
+            .wrap(RateLimit::new("default", &limits.default, app_state.clone()))
+            .wrap(JwtAuth::new(vec![UserRole::Admin, UserRole::User], app_state.clone()))
+        )
+        // ahead of `/me`, which would take these requests otherwise
+        .service(
+          web::scope("/me/2fa")
+            .service(two_factor::get_status)
+            .service(two_factor::start)
+            .service(two_factor::confirm)
+            .service(two_factor::disable)
+            .service(two_factor::regenerate_recovery_codes)
+            .wrap(RateLimit::new("default", &limits.default, app_state.clone()))
+            // the administrators who must set up 2FA can do it here, and only here
+            .wrap(JwtAuth::new(vec![UserRole::Admin, UserRole::User], app_state.clone()).allow_enrollment())
+        )
+        .service(
+          web::scope("/me")
+            .service(me::get_own)
+            .service(me::update_own)
+            .service(me::change_password)
+            .service(me::delete_own)
+            .service(me::set_email)
+            .service(me::remove_email)
+            .service(
+              web::scope("/shelves")
+                .service(shelf::get_own_list)
+                .service(shelf::add_one)
+                .service(shelf::get_reading_stats)
+                .service(shelf::update_one)
+                .service(shelf::delete_one)
+                .service(shelf::put_entry)
+                .service(shelf::delete_entry)
+            )
+            .service(
+              web::scope("/wishlist")
+                .service(wishlist::get_list)
+                .service(wishlist::put_one)
+                .service(wishlist::delete_one)
+            )
+            .service(
+              web::scope("/notifications")
+                .service(notification::get_list)
+                .service(notification::mark_all_read)
+                .service(notification::update_read)
+            )
+            .wrap(RateLimit::new("default", &limits.default, app_state.clone()))
+            .wrap(JwtAuth::new(vec![UserRole::Admin, UserRole::User], app_state.clone()))
+        )
+        .service(
+          web::scope("/admin/diagnostics")
+            .service(diagnostics::get_log_levels)
+            .service(diagnostics::set_log_level)
+            .service(diagnostics::remove_log_level)
+            .service(diagnostics::get_pool_stats)
+            .service(diagnostics::get_config)
+            .service(diagnostics::get_error_summaries)
+            .wrap(RateLimit::new("default", &limits.default, app_state.clone()))
+            .wrap(JwtAuth::new(vec![UserRole::Admin], app_state.clone()))
+        )
+        .service(
+          web::scope("/admin/lockouts")
+            .service(lockout::get_list)
+            .service(lockout::unlock)
+            .wrap(RateLimit::new("default", &limits.default, app_state.clone()))
+            .wrap(JwtAuth::new(vec![UserRole::Admin], app_state.clone()))
+        )
+    );
+}
@@ -0,0 +1,94 @@
+use actix_web::{http, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::adapters::middleware::jwt::JwtClaims;
+use crate::adapters::util::validation::{ValidJson, ValidQuery};
+use crate::application::dto::request::notification::{GetNotificationListReq, UpdateReadReq};
+use crate::application::services::notification::{NotificationListFetchResult, NotificationMarkAllResult, NotificationUpdateResult};
+use crate::application::state::app_state::AppState;
+
+
+#[utoipa::path(
+  get,
+  tag = "Уведомления",
+  context_path = "/api/me/notifications",
+  params(
+    ("page" = u32, Query, description = "Индекс страницы.", example = 0),
+    ("size" = u32, Query, description = "Размер одной страницы.", minimum = 1, maximum = 100, example = 20),
+    ("unread_only" = Option<bool>, Query, description = "Только непрочитанные.", example = false),
+  ),
+  responses(
+    (status = OK, body = NotificationListResp),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("")]
+pub async fn get_list(
+  state: web::Data<AppState>,
+  query: ValidQuery<GetNotificationListReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.notification_service.get_list(&auth_claims.user_id(), query.0).await {
+    NotificationListFetchResult::Ok(notifications) => (web::Json(Some(notifications)), http::StatusCode::OK),
+    NotificationListFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  put,
+  tag = "Уведомления",
+  context_path = "/api/me/notifications",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор уведомления."),
+  ),
+  request_body = UpdateReadReq,
+  responses(
+    (status = OK, body = FullNotificationResp),
+    (status = NOT_FOUND, description = "Уведомление с таким идентификатором не найдено."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[put("/{id}/read")]
+pub async fn update_read(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  data: ValidJson<UpdateReadReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.notification_service.update_read(&auth_claims.user_id(), &path.0, data.0).await {
+    NotificationUpdateResult::Ok(notification) => (web::Json(Some(notification)), http::StatusCode::OK),
+    NotificationUpdateResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    NotificationUpdateResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Уведомления",
+  context_path = "/api/me/notifications",
+  responses(
+    (status = OK, description = "Все уведомления отмечены как прочитанные."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[post("/read")]
+pub async fn mark_all_read(
+  state: web::Data<AppState>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.notification_service.mark_all_read(&auth_claims.user_id()).await {
+    NotificationMarkAllResult::Ok => HttpResponse::new(http::StatusCode::OK),
+    NotificationMarkAllResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
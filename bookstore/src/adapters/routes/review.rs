@@ -0,0 +1,183 @@
+use actix_web::{http, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::adapters::middleware::jwt::JwtClaims;
+use crate::adapters::util::validation::{ValidJson, ValidQuery};
+use crate::application::dto::request::review::{AddReviewReq, GetReviewListReq, UpdateHiddenReq, UpdateReviewReq};
+use crate::application::entities::user::UserRole;
+use crate::application::services::review::{ReviewAddResult, ReviewDeleteResult, ReviewListFetchResult, ReviewUpdateResult};
+use crate::application::state::app_state::AppState;
+
+
+#[utoipa::path(
+  get,
+  tag = "Отзывы",
+  context_path = "/api/book",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор книги."),
+    ("page" = u32, Query, description = "Индекс страницы.", example = 0),
+    ("size" = u32, Query, description = "Размер одной страницы.", minimum = 1, maximum = 100, example = 20),
+  ),
+  responses(
+    (status = OK, body = ReviewListResp, description = "Скрытые отзывы видны только администраторам."),
+    (status = NOT_FOUND, description = "Книга с таким идентификатором не найдена."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("/{id}/review")]
+pub async fn get_list(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  query: ValidQuery<GetReviewListReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  let include_hidden = auth_claims.role == UserRole::Admin;
+
+  match state.review_service.get_list(&path.0, query.0, include_hidden).await {
+    ReviewListFetchResult::Ok(reviews) => (web::Json(Some(reviews)), http::StatusCode::OK),
+    ReviewListFetchResult::BookNotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    ReviewListFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Отзывы",
+  context_path = "/api/book",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор книги."),
+  ),
+  request_body = AddReviewReq,
+  responses(
+    (status = CREATED, body = FullReviewResp),
+    (status = FORBIDDEN, description = "Аккаунт пользователя приостановлен."),
+    (status = NOT_FOUND, description = "Книга с таким идентификатором не найдена."),
+    (status = CONFLICT, description = "Пользователь уже оставил отзыв об этой книге."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[post("/{id}/review")]
+pub async fn add_one(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  data: ValidJson<AddReviewReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.review_service.add_one(&path.0, &auth_claims.user_id(), data.0).await {
+    ReviewAddResult::Created(review) => (web::Json(Some(review)), http::StatusCode::CREATED),
+    ReviewAddResult::BadRequest => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    ReviewAddResult::Suspended => (web::Json(None), http::StatusCode::FORBIDDEN),
+    ReviewAddResult::BookNotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    ReviewAddResult::AlreadyExists => (web::Json(None), http::StatusCode::CONFLICT),
+    ReviewAddResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  put,
+  tag = "Отзывы",
+  context_path = "/api/book",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор книги."),
+  ),
+  request_body = UpdateReviewReq,
+  responses(
+    (status = OK, body = FullReviewResp),
+    (status = NOT_FOUND, description = "Пользователь не оставлял отзыв об этой книге."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[put("/{id}/review")]
+pub async fn update_own(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  data: ValidJson<UpdateReviewReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.review_service.update_own(&path.0, &auth_claims.user_id(), data.0).await {
+    ReviewUpdateResult::Ok(review) => (web::Json(Some(review)), http::StatusCode::OK),
+    ReviewUpdateResult::BadRequest => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    ReviewUpdateResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    ReviewUpdateResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  delete,
+  tag = "Отзывы",
+  context_path = "/api/book",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор книги."),
+  ),
+  responses(
+    (status = OK, description = "Отзыв удален."),
+    (status = NOT_FOUND, description = "Пользователь не оставлял отзыв об этой книге."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[delete("/{id}/review")]
+pub async fn delete_own(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.review_service.delete_own(&path.0, &auth_claims.user_id()).await {
+    ReviewDeleteResult::Ok => HttpResponse::new(http::StatusCode::OK),
+    ReviewDeleteResult::NotFound => HttpResponse::new(http::StatusCode::NOT_FOUND),
+    ReviewDeleteResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  put,
+  tag = "Отзывы",
+  context_path = "/api/book",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор книги."),
+    ("review_id" = Uuid, Path, description = "Идентификатор отзыва."),
+  ),
+  request_body = UpdateHiddenReq,
+  responses(
+    (status = OK, body = FullReviewResp),
+    (status = FORBIDDEN, description = "Недостаточно прав для выполнения действия."),
+    (status = NOT_FOUND, description = "Отзыв с таким идентификатором не найден."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[put("/{id}/review/{review_id}/hidden")]
+pub async fn update_hidden(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, Uuid)>,
+  data: ValidJson<UpdateHiddenReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  if auth_claims.role != UserRole::Admin {
+    return (web::Json(None), http::StatusCode::FORBIDDEN)
+  }
+
+  match state.review_service.update_hidden(&path.0, &path.1, data.0).await {
+    ReviewUpdateResult::Ok(review) => (web::Json(Some(review)), http::StatusCode::OK),
+    ReviewUpdateResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    ReviewUpdateResult::BadRequest => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    ReviewUpdateResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
@@ -0,0 +1,249 @@
+use actix_web::{http, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::adapters::middleware::jwt::JwtClaims;
+use crate::adapters::util::validation::ValidJson;
+use crate::application::dto::request::shelf::{AddShelfReq, PutShelfEntryReq, UpdateShelfReq};
+use crate::application::services::shelf::{
+  ReadingStatsFetchResult, ShelfAddResult, ShelfDeleteResult, ShelfEntryDeleteResult, ShelfEntryPutResult,
+  ShelfListFetchResult, ShelfUpdateResult,
+};
+use crate::application::state::app_state::AppState;
+
+
+#[utoipa::path(
+  get,
+  tag = "Полки",
+  context_path = "/api/me/shelves",
+  responses(
+    (status = OK, body = ShelfListResp, description = "Все полки пользователя, включая встроенные."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("")]
+pub async fn get_own_list(
+  state: web::Data<AppState>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.shelf_service.get_own_list(&auth_claims.user_id()).await {
+    ShelfListFetchResult::Ok(shelves) => (web::Json(Some(shelves)), http::StatusCode::OK),
+    ShelfListFetchResult::UserNotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    ShelfListFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Полки",
+  context_path = "/api/me/shelves",
+  request_body = AddShelfReq,
+  responses(
+    (status = CREATED, body = FullShelfResp),
+    (status = CONFLICT, description = "Полка с таким названием уже существует."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[post("")]
+pub async fn add_one(
+  state: web::Data<AppState>,
+  data: ValidJson<AddShelfReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.shelf_service.add_one(&auth_claims.user_id(), data.0).await {
+    ShelfAddResult::Created(shelf) => (web::Json(Some(shelf)), http::StatusCode::CREATED),
+    ShelfAddResult::BadRequest => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    ShelfAddResult::AlreadyExists => (web::Json(None), http::StatusCode::CONFLICT),
+    ShelfAddResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  get,
+  tag = "Полки",
+  context_path = "/api/me/shelves",
+  responses(
+    (status = OK, body = ReadingStatsResp, description = "Количество начатых и прочитанных книг по годам."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("/stats")]
+pub async fn get_reading_stats(
+  state: web::Data<AppState>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.shelf_service.get_reading_stats(&auth_claims.user_id()).await {
+    ReadingStatsFetchResult::Ok(stats) => (web::Json(Some(stats)), http::StatusCode::OK),
+    ReadingStatsFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  patch,
+  tag = "Полки",
+  context_path = "/api/me/shelves",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор полки."),
+  ),
+  request_body = UpdateShelfReq,
+  responses(
+    (status = OK, body = FullShelfResp),
+    (status = BAD_REQUEST, description = "Попытка переименовать встроенную полку."),
+    (status = NOT_FOUND, description = "Полка с таким идентификатором не найдена."),
+    (status = CONFLICT, description = "Полка с таким названием уже существует."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[patch("/{id}")]
+pub async fn update_one(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  data: ValidJson<UpdateShelfReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.shelf_service.update_one(&auth_claims.user_id(), &path.0, data.0).await {
+    ShelfUpdateResult::Ok(shelf) => (web::Json(Some(shelf)), http::StatusCode::OK),
+    ShelfUpdateResult::BadRequest => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    ShelfUpdateResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    ShelfUpdateResult::AlreadyExists => (web::Json(None), http::StatusCode::CONFLICT),
+    ShelfUpdateResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  delete,
+  tag = "Полки",
+  context_path = "/api/me/shelves",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор полки."),
+  ),
+  responses(
+    (status = OK, description = "Полка удалена."),
+    (status = BAD_REQUEST, description = "Встроенные полки удалить нельзя."),
+    (status = NOT_FOUND, description = "Полка с таким идентификатором не найдена."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[delete("/{id}")]
+pub async fn delete_one(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.shelf_service.delete_one(&auth_claims.user_id(), &path.0).await {
+    ShelfDeleteResult::Ok => HttpResponse::new(http::StatusCode::OK),
+    ShelfDeleteResult::BuiltIn => HttpResponse::new(http::StatusCode::BAD_REQUEST),
+    ShelfDeleteResult::NotFound => HttpResponse::new(http::StatusCode::NOT_FOUND),
+    ShelfDeleteResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  put,
+  tag = "Полки",
+  context_path = "/api/me/shelves",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор полки."),
+    ("book_id" = Uuid, Path, description = "Идентификатор книги."),
+  ),
+  request_body = PutShelfEntryReq,
+  responses(
+    (status = OK, body = ShelfEntryResp),
+    (status = BAD_REQUEST, description = "Дата окончания раньше даты начала, подставленной по умолчанию."),
+    (status = NOT_FOUND, description = "Полка или книга не найдена."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[put("/{id}/book/{book_id}")]
+pub async fn put_entry(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, Uuid)>,
+  data: ValidJson<PutShelfEntryReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.shelf_service.put_entry(&auth_claims.user_id(), &path.0, &path.1, data.0).await {
+    ShelfEntryPutResult::Ok(entry) => (web::Json(Some(entry)), http::StatusCode::OK),
+    ShelfEntryPutResult::BadRequest => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    ShelfEntryPutResult::ShelfNotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    ShelfEntryPutResult::BookNotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    ShelfEntryPutResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  delete,
+  tag = "Полки",
+  context_path = "/api/me/shelves",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор полки."),
+    ("book_id" = Uuid, Path, description = "Идентификатор книги."),
+  ),
+  responses(
+    (status = OK, description = "Книга убрана с полки."),
+    (status = NOT_FOUND, description = "Полка не найдена или книги на ней нет."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[delete("/{id}/book/{book_id}")]
+pub async fn delete_entry(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, Uuid)>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.shelf_service.delete_entry(&auth_claims.user_id(), &path.0, &path.1).await {
+    ShelfEntryDeleteResult::Ok => HttpResponse::new(http::StatusCode::OK),
+    ShelfEntryDeleteResult::NotFound => HttpResponse::new(http::StatusCode::NOT_FOUND),
+    ShelfEntryDeleteResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  get,
+  tag = "Полки",
+  context_path = "/api/user",
+  params(
+    ("id" = Uuid, Path, description = "Идентификатор пользователя."),
+  ),
+  responses(
+    (status = OK, body = ShelfListResp, description = "Публичные полки пользователя."),
+    (status = NOT_FOUND, description = "Пользователь с таким идентификатором не найден."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("/{id}/shelves")]
+pub async fn get_public_list(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+) -> impl Responder
+{
+  match state.shelf_service.get_public_list(&path.0).await {
+    ShelfListFetchResult::Ok(shelves) => (web::Json(Some(shelves)), http::StatusCode::OK),
+    ShelfListFetchResult::UserNotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    ShelfListFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
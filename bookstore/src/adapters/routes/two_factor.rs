@@ -0,0 +1,162 @@
+use actix_web::{http, HttpResponse, Responder, web};
+
+use crate::adapters::middleware::jwt::JwtClaims;
+use crate::adapters::util::validation::ValidJson;
+use crate::application::dto::request::two_factor::{DisableTwoFactorReq, StartTwoFactorReq, TwoFactorCodeReq};
+use crate::application::services::two_factor::{
+  RecoveryCodesResult,
+  TwoFactorConfirmResult,
+  TwoFactorDisableResult,
+  TwoFactorSetupResult,
+  TwoFactorStatusResult,
+};
+use crate::application::state::app_state::AppState;
+
+
+#[utoipa::path(
+  get,
+  tag = "Двухфакторная аутентификация",
+  context_path = "/api/me/2fa",
+  responses(
+    (status = OK, body = TwoFactorStatusResp),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("")]
+pub async fn get_status(
+  state: web::Data<AppState>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.two_factor_service.get_status(&auth_claims.user_id()).await {
+    TwoFactorStatusResult::Ok(status) => (web::Json(Some(status)), http::StatusCode::OK),
+    TwoFactorStatusResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    TwoFactorStatusResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Двухфакторная аутентификация",
+  context_path = "/api/me/2fa",
+  request_body = StartTwoFactorReq,
+  responses(
+    (status = OK, body = TwoFactorSetupResp, description = "Секрет для приложения-аутентификатора. Вход не изменится, пока подключение не подтверждено кодом; повторный запрос выдает новый секрет."),
+    (status = FORBIDDEN, description = "Пароль указан неверно."),
+    (status = CONFLICT, description = "Двухфакторная аутентификация уже подключена."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[post("")]
+pub async fn start(
+  state: web::Data<AppState>,
+  data: ValidJson<StartTwoFactorReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.two_factor_service.start(&auth_claims.user_id(), data.0).await {
+    TwoFactorSetupResult::Ok(setup) => (web::Json(Some(setup)), http::StatusCode::OK),
+    TwoFactorSetupResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    TwoFactorSetupResult::WrongPassword => (web::Json(None), http::StatusCode::FORBIDDEN),
+    TwoFactorSetupResult::AlreadyEnabled => (web::Json(None), http::StatusCode::CONFLICT),
+    TwoFactorSetupResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Двухфакторная аутентификация",
+  context_path = "/api/me/2fa",
+  request_body = TwoFactorCodeReq,
+  responses(
+    (status = OK, body = TwoFactorEnabledResp, description = "Подключено. Остальные сеансы завершены, токен нового сеанса и коды восстановления в ответе."),
+    (status = FORBIDDEN, description = "Код из приложения неверен."),
+    (status = CONFLICT, description = "Подключение не начато или уже подтверждено."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[post("/confirm")]
+pub async fn confirm(
+  state: web::Data<AppState>,
+  data: ValidJson<TwoFactorCodeReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.two_factor_service.confirm(&auth_claims.user_id(), data.0).await {
+    TwoFactorConfirmResult::Ok(enabled) => (web::Json(Some(enabled)), http::StatusCode::OK),
+    TwoFactorConfirmResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    TwoFactorConfirmResult::NotPending => (web::Json(None), http::StatusCode::CONFLICT),
+    TwoFactorConfirmResult::WrongCode => (web::Json(None), http::StatusCode::FORBIDDEN),
+    TwoFactorConfirmResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  delete,
+  tag = "Двухфакторная аутентификация",
+  context_path = "/api/me/2fa",
+  request_body = DisableTwoFactorReq,
+  responses(
+    (status = OK, description = "Отключено, коды восстановления удалены."),
+    (status = FORBIDDEN, description = "Пароль или код указан неверно."),
+    (status = CONFLICT, description = "Двухфакторная аутентификация не подключена или обязательна для администраторов."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[delete("")]
+pub async fn disable(
+  state: web::Data<AppState>,
+  data: ValidJson<DisableTwoFactorReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.two_factor_service.disable(&auth_claims.user_id(), data.0).await {
+    TwoFactorDisableResult::Ok => HttpResponse::new(http::StatusCode::OK),
+    TwoFactorDisableResult::NotFound => HttpResponse::new(http::StatusCode::NOT_FOUND),
+    TwoFactorDisableResult::WrongPassword | TwoFactorDisableResult::WrongCode => HttpResponse::new(http::StatusCode::FORBIDDEN),
+    TwoFactorDisableResult::NotEnabled | TwoFactorDisableResult::Required => HttpResponse::new(http::StatusCode::CONFLICT),
+    TwoFactorDisableResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  post,
+  tag = "Двухфакторная аутентификация",
+  context_path = "/api/me/2fa",
+  request_body = TwoFactorCodeReq,
+  responses(
+    (status = OK, body = RecoveryCodesResp),
+    (status = FORBIDDEN, description = "Код указан неверно."),
+    (status = CONFLICT, description = "Двухфакторная аутентификация не подключена."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[post("/recovery-codes")]
+pub async fn regenerate_recovery_codes(
+  state: web::Data<AppState>,
+  data: ValidJson<TwoFactorCodeReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.two_factor_service.regenerate_recovery_codes(&auth_claims.user_id(), data.0).await {
+    RecoveryCodesResult::Ok(codes) => (web::Json(Some(codes)), http::StatusCode::OK),
+    RecoveryCodesResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    RecoveryCodesResult::WrongCode => (web::Json(None), http::StatusCode::FORBIDDEN),
+    RecoveryCodesResult::NotEnabled => (web::Json(None), http::StatusCode::CONFLICT),
+    RecoveryCodesResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
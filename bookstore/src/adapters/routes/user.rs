@@ -1,6 +1,7 @@
 use actix_web::{http, Responder, web};
 use uuid::Uuid;
 use crate::adapters::middleware::jwt::JwtClaims;
+use crate::adapters::util::validation::{ValidJson, ValidQuery};
 
 use crate::application::dto::request::user::{GetUserListReq, UpdateSuspendedReq};
 use crate::application::entities::user::UserRole;
@@ -14,10 +15,12 @@ use crate::application::state::app_state::AppState;
   context_path = "/api/user",
   params(
     ("page" = u32, Query, description = "Индекс страницы.", example = 0),
-    ("size" = u32, Query, description = "Размер одной страницы.", minimum = 1, example = 20),
+    ("size" = u32, Query, description = "Размер одной страницы.", minimum = 1, maximum = 100, example = 20),
   ),
   responses(
     (status = OK, body = UserListResp),
+    (status = FORBIDDEN, description = "Недостаточно прав для выполнения действия."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
   ),
   security(
     ("jwt_auth" = [])
@@ -26,9 +29,15 @@ use crate::application::state::app_state::AppState;
 #[get("")]
 pub async fn get_list(
   state: web::Data<AppState>,
-  query: web::Query<GetUserListReq>,
+  query: ValidQuery<GetUserListReq>,
+  auth_claims: web::ReqData<JwtClaims>,
 ) -> impl Responder
 {
+  // users see their own profile at /api/me
+  if auth_claims.role != UserRole::Admin {
+    return (web::Json(None), http::StatusCode::FORBIDDEN)
+  }
+
   match state.user_service.get_list(query.0).await {
     UserListFetchResult::Ok(users) => (web::Json(Some(users)), http::StatusCode::OK),
     UserListFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
@@ -44,6 +53,7 @@ pub async fn get_list(
   ),
   responses(
     (status = OK, body = FullUserResp),
+    (status = FORBIDDEN, description = "Недостаточно прав для выполнения действия."),
     (status = NOT_FOUND, description = "Пользователь с таким идентификатором не найден."),
   ),
   security(
@@ -54,8 +64,13 @@ pub async fn get_list(
 pub async fn get_by_id(
   state: web::Data<AppState>,
   query: web::Path<(Uuid, )>,
+  auth_claims: web::ReqData<JwtClaims>,
 ) -> impl Responder
 {
+  if auth_claims.role != UserRole::Admin {
+    return (web::Json(None), http::StatusCode::FORBIDDEN)
+  }
+
   match state.user_service.get_by_id(&query.into_inner().0).await {
     UserFetchResult::Ok(user) => (web::Json(Some(user)), http::StatusCode::OK),
     UserFetchResult::NotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
@@ -75,6 +90,7 @@ pub async fn get_by_id(
     (status = OK, body = FullUserResp),
     (status = FORBIDDEN, description = "Недостаточно прав для выполнения действия."),
     (status = NOT_FOUND, description = "Пользователь с таким идентификатором не найден."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
   ),
   security(
     ("jwt_auth" = [])
@@ -84,7 +100,7 @@ pub async fn get_by_id(
 pub async fn update_suspended(
   state: web::Data<AppState>,
   path: web::Path<(Uuid, )>,
-  data: web::Json<UpdateSuspendedReq>,
+  data: ValidJson<UpdateSuspendedReq>,
   auth_claims: web::ReqData<JwtClaims>,
 ) -> impl Responder
 {
@@ -0,0 +1,94 @@
+use actix_web::{http, HttpResponse, Responder, web};
+use uuid::Uuid;
+
+use crate::adapters::middleware::jwt::JwtClaims;
+use crate::adapters::util::validation::ValidJson;
+use crate::application::dto::request::wishlist::PutWishlistItemReq;
+use crate::application::services::wishlist::{WishlistDeleteResult, WishlistFetchResult, WishlistPutResult};
+use crate::application::state::app_state::AppState;
+
+
+#[utoipa::path(
+  get,
+  tag = "Список желаемого",
+  context_path = "/api/me/wishlist",
+  responses(
+    (status = OK, body = WishlistResp),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[get("")]
+pub async fn get_list(
+  state: web::Data<AppState>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.wishlist_service.get_list(&auth_claims.user_id()).await {
+    WishlistFetchResult::Ok(wishlist) => (web::Json(Some(wishlist)), http::StatusCode::OK),
+    WishlistFetchResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  put,
+  tag = "Список желаемого",
+  context_path = "/api/me/wishlist",
+  params(
+    ("book_id" = Uuid, Path, description = "Идентификатор книги."),
+  ),
+  request_body = PutWishlistItemReq,
+  responses(
+    (status = OK, body = WishlistItemResp),
+    (status = NOT_FOUND, description = "Книга с таким идентификатором не найдена."),
+    (status = UNPROCESSABLE_ENTITY, body = Problem, content_type = "application/problem+json", description = "Поля запроса не прошли проверку."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[put("/{book_id}")]
+pub async fn put_one(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  data: ValidJson<PutWishlistItemReq>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.wishlist_service.put_one(&auth_claims.user_id(), &path.0, data.0).await {
+    WishlistPutResult::Ok(item) => (web::Json(Some(item)), http::StatusCode::OK),
+    WishlistPutResult::BadRequest => (web::Json(None), http::StatusCode::BAD_REQUEST),
+    WishlistPutResult::BookNotFound => (web::Json(None), http::StatusCode::NOT_FOUND),
+    WishlistPutResult::UnexpectedError(_) => (web::Json(None), http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
+
+#[utoipa::path(
+  delete,
+  tag = "Список желаемого",
+  context_path = "/api/me/wishlist",
+  params(
+    ("book_id" = Uuid, Path, description = "Идентификатор книги."),
+  ),
+  responses(
+    (status = OK, description = "Книга убрана из списка желаемого."),
+    (status = NOT_FOUND, description = "Книги нет в списке желаемого."),
+  ),
+  security(
+    ("jwt_auth" = [])
+  )
+)]
+#[delete("/{book_id}")]
+pub async fn delete_one(
+  state: web::Data<AppState>,
+  path: web::Path<(Uuid, )>,
+  auth_claims: web::ReqData<JwtClaims>,
+) -> impl Responder
+{
+  match state.wishlist_service.delete_one(&auth_claims.user_id(), &path.0).await {
+    WishlistDeleteResult::Ok => HttpResponse::new(http::StatusCode::OK),
+    WishlistDeleteResult::NotFound => HttpResponse::new(http::StatusCode::NOT_FOUND),
+    WishlistDeleteResult::UnexpectedError(_) => HttpResponse::new(http::StatusCode::INTERNAL_SERVER_ERROR),
+  }
+}
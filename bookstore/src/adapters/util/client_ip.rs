@@ -0,0 +1,49 @@
+//! The address of the client behind the trusted reverse proxies.
+
+use std::net::IpAddr;
+use actix_web::{web, HttpRequest};
+
+use crate::application::state::app_state::AppState;
+use crate::config::ProxyList;
+
+
+/// The address the request came from. `X-Forwarded-For` is only read when the
+/// peer is a trusted proxy, and then from the right, skipping the other trusted
+/// proxies: the entries to the left of them are whatever the client sent.
+pub fn client_ip(req: &HttpRequest) -> Option<IpAddr> {
+  let peer = req.peer_addr()?.ip();
+  match req.app_data::<web::Data<AppState>>() {
+    Some(state) => Some(resolve(peer, forwarded_for(req), &state.config.server.trusted_proxies)),
+    None => Some(peer),
+  }
+}
+
+fn forwarded_for(req: &HttpRequest) -> Vec<String> {
+  req.headers()
+    .get_all("x-forwarded-for")
+    .filter_map(|value| value.to_str().ok())
+    .flat_map(|value| value.split(','))
+    .map(|item| item.trim().to_string())
+    .collect()
+}
+
+fn resolve(peer: IpAddr, forwarded: Vec<String>, proxies: &ProxyList) -> IpAddr {
+  if !proxies.contains(&peer) {
+    return peer
+  }
+
+  let mut client = peer;
+  for item in forwarded.iter().rev() {
+    match item.parse::<IpAddr>() {
+      Ok(ip) => {
+        client = ip;
+        if !proxies.contains(&ip) {
+          break
+        }
+      },
+      // garbage means the proxy did not write this entry, so the last good one stands
+      Err(_) => break,
+    }
+  }
+  client
+}
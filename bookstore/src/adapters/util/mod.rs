@@ -0,0 +1,3 @@
+pub mod validation;
+pub mod payload;
+pub mod client_ip;
@@ -0,0 +1,50 @@
+//! Error handlers of the JSON, query and path extractors, so that malformed
+//! requests get the same `application/problem+json` answer as everything else.
+
+use actix_web::error::{InternalError, JsonPayloadError, PathError, QueryPayloadError};
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpRequest, ResponseError};
+
+use crate::adapters::middleware::problem::problem_response;
+use crate::config::ServerConfig;
+
+
+/// Register the extractor configs; `web::Json`, `web::Query` and `web::Path` pick them up.
+pub fn configure(cfg: &mut web::ServiceConfig, config: &ServerConfig) {
+  cfg
+    .app_data(web::JsonConfig::default().limit(config.max_body_bytes).error_handler(json_error))
+    .app_data(web::QueryConfig::default().error_handler(query_error))
+    .app_data(web::PathConfig::default().error_handler(path_error));
+}
+
+pub fn json_error(err: JsonPayloadError, req: &HttpRequest) -> actix_web::Error {
+  let status = match err {
+    JsonPayloadError::ContentType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+    _ => err.status_code(),
+  };
+  let detail = match &err {
+    JsonPayloadError::ContentType => "Expected a body of type `application/json`.".to_string(),
+    JsonPayloadError::Deserialize(e) => format!("Malformed JSON body: {}.", e),
+    _ => format!("{}.", err.to_string().trim_end_matches('.')),
+  };
+  let res = problem_response(req, status, Some(&detail));
+  InternalError::from_response(err, res).into()
+}
+
+pub fn query_error(err: QueryPayloadError, req: &HttpRequest) -> actix_web::Error {
+  let detail = match &err {
+    QueryPayloadError::Deserialize(e) => format!("Malformed query string: {}.", e),
+    _ => err.to_string(),
+  };
+  let res = problem_response(req, StatusCode::BAD_REQUEST, Some(&detail));
+  InternalError::from_response(err, res).into()
+}
+
+pub fn path_error(err: PathError, req: &HttpRequest) -> actix_web::Error {
+  let detail = match &err {
+    PathError::Deserialize(e) => format!("Malformed path: {}.", e),
+    _ => err.to_string(),
+  };
+  let res = problem_response(req, StatusCode::BAD_REQUEST, Some(&detail));
+  InternalError::from_response(err, res).into()
+}
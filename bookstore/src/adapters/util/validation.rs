@@ -0,0 +1,228 @@
+//! Extractors which run the `#[validate(...)]` rules of the request DTOs and
+//! turn the failures into a 422 `application/problem+json` response.
+//! With `server.strict_requests` the fields unknown to the DTO are failures too.
+
+use std::future::{ready, Ready};
+use std::ops::Deref;
+use actix_web::dev::Payload;
+use actix_web::error::{InternalError, JsonPayloadError, QueryPayloadError};
+use actix_web::http::header;
+use actix_web::{web, FromRequest, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
+
+use crate::adapters::middleware::problem::validation_problem_response;
+use crate::adapters::util::payload::{json_error, query_error};
+use crate::application::dto::response::problem::FieldErrorResp;
+use crate::application::state::app_state::AppState;
+
+
+/// JSON body which has passed the validation.
+pub struct ValidJson<T>(pub T);
+
+/// Query parameters which have passed the validation.
+pub struct ValidQuery<T>(pub T);
+
+impl<T> Deref for ValidJson<T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    &self.0
+  }
+}
+
+impl<T> Deref for ValidQuery<T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    &self.0
+  }
+}
+
+impl<T: DeserializeOwned + Validate + 'static> FromRequest for ValidJson<T> {
+  type Error = actix_web::Error;
+  type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+  fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+    let req = req.clone();
+    // the body goes through `web::Json` so that the size limit and the content type check apply
+    let json = web::Json::<Value>::from_request(&req, payload);
+    Box::pin(async move {
+      let value = json.await?.into_inner();
+      let mut unknown = vec![];
+      let data = serde_ignored::deserialize(value, |path| unknown.push(path.to_string()))
+        .map_err(|e| json_error(JsonPayloadError::Deserialize(e), &req))?;
+      validate(&req, data, unknown).map(ValidJson)
+    })
+  }
+}
+
+impl<T: DeserializeOwned + Validate> FromRequest for ValidQuery<T> {
+  type Error = actix_web::Error;
+  type Future = Ready<Result<Self, Self::Error>>;
+
+  fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+    let mut unknown = vec![];
+    let query = form_urlencoded::parse(req.query_string().as_bytes());
+    let result = serde_ignored::deserialize(serde_urlencoded::Deserializer::new(query), |path| unknown.push(path.to_string()))
+      .map_err(|e| query_error(QueryPayloadError::Deserialize(e), req))
+      .and_then(|data| validate(req, data, unknown))
+      .map(ValidQuery);
+    ready(result)
+  }
+}
+
+/// Run the validation rules; `unknown` are the fields the DTO has ignored.
+fn validate<T: Validate>(req: &HttpRequest, data: T, unknown: Vec<String>) -> Result<T, actix_web::Error> {
+  let strict = req.app_data::<web::Data<AppState>>().is_some_and(|state| state.config.server.strict_requests);
+  let unknown = if strict { unknown } else { vec![] };
+
+  let errors = match data.validate() {
+    Ok(_) if unknown.is_empty() => return Ok(data),
+    Ok(_) => ValidationErrors::new(),
+    Err(errors) => errors,
+  };
+  Err(failure(req, errors, unknown))
+}
+
+/// The 422 response for the errors of the rules checked by the services,
+/// such as the password policy, in the format of the extractors.
+pub fn validation_error(req: &HttpRequest, errors: ValidationErrors) -> actix_web::Error {
+  failure(req, errors, vec![])
+}
+
+fn failure(req: &HttpRequest, errors: ValidationErrors, unknown: Vec<String>) -> actix_web::Error {
+  let lang = Lang::of(req);
+  let mut fields = vec![];
+  collect(&errors, "", lang, &mut fields);
+  for field in unknown {
+    fields.push(FieldErrorResp {
+      field,
+      code: "unknown_field".to_string(),
+      message: message(&ValidationError::new("unknown_field"), lang),
+    });
+  }
+  fields.sort_by(|a, b| (&a.field, &a.code).cmp(&(&b.field, &b.code)));
+  let res = validation_problem_response(req, fields);
+  InternalError::from_response(errors, res).into()
+}
+
+/// Flatten the errors of nested structures into dotted field paths.
+fn collect(errors: &ValidationErrors, prefix: &str, lang: Lang, out: &mut Vec<FieldErrorResp>) {
+  for (field, kind) in errors.errors() {
+    let path = |name: &str| if prefix.is_empty() { name.to_string() } else { format!("{}.{}", prefix, name) };
+    match kind {
+      ValidationErrorsKind::Field(field_errors) => {
+        for error in field_errors {
+          // errors of the rules on several fields name the field themselves
+          let field = match (*field, error.params.get("field")) {
+            ("__all__", Some(Value::String(name))) => name.as_str(),
+            _ => field,
+          };
+          out.push(FieldErrorResp {
+            field: path(field),
+            code: error.code.to_string(),
+            message: message(error, lang),
+          });
+        }
+      },
+      ValidationErrorsKind::Struct(errors) => collect(errors, &path(field), lang, out),
+      ValidationErrorsKind::List(items) => {
+        for (index, errors) in items {
+          collect(errors, &format!("{}[{}]", path(field), index), lang, out);
+        }
+      },
+    }
+  }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Lang {
+  Ru,
+  En,
+}
+
+impl Lang {
+  /// The preferred of the supported languages in `Accept-Language`, Russian by default.
+  fn of(req: &HttpRequest) -> Self {
+    let Some(accepted) = req.headers().get(header::ACCEPT_LANGUAGE).and_then(|h| h.to_str().ok()) else {
+      return Lang::Ru
+    };
+
+    let mut best = (Lang::Ru, 0.0);
+    for item in accepted.split(',') {
+      let mut parts = item.trim().split(';');
+      let tag = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+      let quality = parts
+        .find_map(|p| p.trim().strip_prefix("q="))
+        .and_then(|q| q.parse::<f32>().ok())
+        .unwrap_or(1.0);
+      let lang = match tag.split('-').next() {
+        Some("ru") => Lang::Ru,
+        Some("en") => Lang::En,
+        _ => continue,
+      };
+      if quality > best.1 {
+        best = (lang, quality);
+      }
+    }
+    best.0
+  }
+}
+
+fn message(error: &ValidationError, lang: Lang) -> String {
+  let param = |name: &str| error.params.get(name).filter(|v| !v.is_null()).map(|v| v.to_string());
+  let (min, max) = (param("min"), param("max"));
+
+  match (error.code.as_ref(), lang) {
+    ("length", Lang::Ru) => match (min, max) {
+      (Some(min), Some(max)) => format!("Длина должна быть от {} до {} символов.", min, max),
+      (Some(min), None) => format!("Длина должна быть не меньше {} символов.", min),
+      (None, Some(max)) => format!("Длина должна быть не больше {} символов.", max),
+      (None, None) => "Недопустимая длина.".to_string(),
+    },
+    ("length", Lang::En) => match (min, max) {
+      (Some(min), Some(max)) => format!("Must be {} to {} characters long.", min, max),
+      (Some(min), None) => format!("Must be at least {} characters long.", min),
+      (None, Some(max)) => format!("Must be at most {} characters long.", max),
+      (None, None) => "Invalid length.".to_string(),
+    },
+    ("range", Lang::Ru) => match (min, max) {
+      (Some(min), Some(max)) => format!("Значение должно быть от {} до {}.", min, max),
+      (Some(min), None) => format!("Значение должно быть не меньше {}.", min),
+      (None, Some(max)) => format!("Значение должно быть не больше {}.", max),
+      (None, None) => "Значение вне допустимого диапазона.".to_string(),
+    },
+    ("range", Lang::En) => match (min, max) {
+      (Some(min), Some(max)) => format!("Must be from {} to {}.", min, max),
+      (Some(min), None) => format!("Must be at least {}.", min),
+      (None, Some(max)) => format!("Must be at most {}.", max),
+      (None, None) => "Out of range.".to_string(),
+    },
+    ("blank", Lang::Ru) => "Значение не должно быть пустым.".to_string(),
+    ("blank", Lang::En) => "Must not be blank.".to_string(),
+    ("name", Lang::Ru) => "Допустимы только буквы, разделённые одиночными пробелами, дефисами или апострофами.".to_string(),
+    ("name", Lang::En) => "Only letters separated by single spaces, hyphens or apostrophes are allowed.".to_string(),
+    ("nickname", Lang::Ru) => "Допустимы только английские буквы, цифры и символы `.`, `-`, `_`.".to_string(),
+    ("nickname", Lang::En) => "Only English letters, digits and `.`, `-`, `_` are allowed.".to_string(),
+    ("email", Lang::Ru) => "Ожидается адрес электронной почты.".to_string(),
+    ("email", Lang::En) => "Expected an email address.".to_string(),
+    ("log_module", Lang::Ru) => "Ожидается путь модуля, например `bookstore::adapters`.".to_string(),
+    ("log_module", Lang::En) => "Expected a module path such as `bookstore::adapters`.".to_string(),
+    ("log_level", Lang::Ru) => "Ожидается `off`, `error`, `warn`, `info`, `debug` или `trace`.".to_string(),
+    ("log_level", Lang::En) => "Expected `off`, `error`, `warn`, `info`, `debug` or `trace`.".to_string(),
+    ("date_order", Lang::Ru) => "Дата окончания не может быть раньше даты начала.".to_string(),
+    ("date_order", Lang::En) => "Must not be earlier than the start date.".to_string(),
+    ("common_password", Lang::Ru) => "Пароль слишком распространён, выберите другой.".to_string(),
+    ("common_password", Lang::En) => "The password is too common, choose another one.".to_string(),
+    ("contains_nickname", Lang::Ru) => "Пароль не должен содержать псевдоним.".to_string(),
+    ("contains_nickname", Lang::En) => "The password must not contain the nickname.".to_string(),
+    ("unknown_field", Lang::Ru) => "Неизвестное поле.".to_string(),
+    ("unknown_field", Lang::En) => "Unknown field.".to_string(),
+    (code, Lang::Ru) => format!("Значение не прошло проверку `{}`.", code),
+    (code, Lang::En) => format!("Failed the `{}` check.", code),
+  }
+}
@@ -5,28 +5,119 @@ use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
 #[derive(OpenApi)]
 #[openapi(
   paths(
+    bookstore::adapters::routes::health::live,
+    bookstore::adapters::routes::health::ready,
+    bookstore::adapters::routes::health::version,
+    bookstore::adapters::routes::metrics::export,
+
     bookstore::adapters::routes::auth::register,
     bookstore::adapters::routes::auth::login,
+    bookstore::adapters::routes::auth::login_2fa,
+    bookstore::adapters::routes::auth::forgot_password,
+    bookstore::adapters::routes::auth::reset_password,
+    bookstore::adapters::routes::auth::verify_email,
 
     bookstore::adapters::routes::user::get_list,
     bookstore::adapters::routes::user::get_by_id,
     bookstore::adapters::routes::user::update_suspended,
+    bookstore::adapters::routes::me::get_own,
+    bookstore::adapters::routes::me::update_own,
+    bookstore::adapters::routes::me::change_password,
+    bookstore::adapters::routes::me::delete_own,
+    bookstore::adapters::routes::me::set_email,
+    bookstore::adapters::routes::me::remove_email,
+    bookstore::adapters::routes::two_factor::get_status,
+    bookstore::adapters::routes::two_factor::start,
+    bookstore::adapters::routes::two_factor::confirm,
+    bookstore::adapters::routes::two_factor::disable,
+    bookstore::adapters::routes::two_factor::regenerate_recovery_codes,
 
     bookstore::adapters::routes::book::get_list,
     bookstore::adapters::routes::book::get_by_id,
     bookstore::adapters::routes::book::delete_one,
     bookstore::adapters::routes::book::add_one,
+    bookstore::adapters::routes::book::update_inventory,
 
     bookstore::adapters::routes::author::get_list,
     bookstore::adapters::routes::author::get_by_id,
     bookstore::adapters::routes::author::delete_one,
     bookstore::adapters::routes::author::add_one,
+
+    bookstore::adapters::routes::review::get_list,
+    bookstore::adapters::routes::review::add_one,
+    bookstore::adapters::routes::review::update_own,
+    bookstore::adapters::routes::review::delete_own,
+    bookstore::adapters::routes::review::update_hidden,
+
+    bookstore::adapters::routes::shelf::get_own_list,
+    bookstore::adapters::routes::shelf::add_one,
+    bookstore::adapters::routes::shelf::get_reading_stats,
+    bookstore::adapters::routes::shelf::update_one,
+    bookstore::adapters::routes::shelf::delete_one,
+    bookstore::adapters::routes::shelf::put_entry,
+    bookstore::adapters::routes::shelf::delete_entry,
+    bookstore::adapters::routes::shelf::get_public_list,
+
+    bookstore::adapters::routes::wishlist::get_list,
+    bookstore::adapters::routes::wishlist::put_one,
+    bookstore::adapters::routes::wishlist::delete_one,
+
+    bookstore::adapters::routes::notification::get_list,
+    bookstore::adapters::routes::notification::update_read,
+    bookstore::adapters::routes::notification::mark_all_read,
+
+    bookstore::adapters::routes::copy::get_list,
+    bookstore::adapters::routes::copy::get_by_id,
+    bookstore::adapters::routes::copy::add_one,
+    bookstore::adapters::routes::copy::update_one,
+    bookstore::adapters::routes::copy::delete_one,
+    bookstore::adapters::routes::copy::transfer,
+    bookstore::adapters::routes::copy::get_transfer_list,
+
+    bookstore::adapters::routes::branch::get_list,
+    bookstore::adapters::routes::branch::get_by_id,
+    bookstore::adapters::routes::branch::add_one,
+    bookstore::adapters::routes::branch::update_one,
+    bookstore::adapters::routes::branch::delete_one,
+
+    bookstore::adapters::routes::loan::get_own_list,
+    bookstore::adapters::routes::loan::get_overdue_list,
+    bookstore::adapters::routes::loan::get_by_id,
+    bookstore::adapters::routes::loan::checkout,
+    bookstore::adapters::routes::loan::renew,
+    bookstore::adapters::routes::loan::return_one,
+
+    bookstore::adapters::routes::hold::get_own_list,
+    bookstore::adapters::routes::hold::place,
+    bookstore::adapters::routes::hold::cancel,
+
+    bookstore::adapters::routes::diagnostics::get_log_levels,
+    bookstore::adapters::routes::diagnostics::set_log_level,
+    bookstore::adapters::routes::diagnostics::remove_log_level,
+    bookstore::adapters::routes::diagnostics::get_pool_stats,
+    bookstore::adapters::routes::diagnostics::get_config,
+    bookstore::adapters::routes::diagnostics::get_error_summaries,
+
+    bookstore::adapters::routes::lockout::get_list,
+    bookstore::adapters::routes::lockout::unlock,
   ),
   components(
     schemas(
+      bookstore::application::dto::response::health::HealthStatus,
+      bookstore::application::dto::response::health::LivenessResp,
+      bookstore::application::dto::response::health::ReadinessResp,
+      bookstore::application::dto::response::health::VersionResp,
+      bookstore::application::dto::response::problem::Problem,
+      bookstore::application::dto::response::problem::FieldErrorResp,
+
       bookstore::application::dto::response::user::TokenResp,
       bookstore::application::dto::response::user::UserListResp,
       bookstore::application::dto::response::user::FullUserResp,
+      bookstore::application::dto::response::two_factor::LoginChallengeResp,
+      bookstore::application::dto::response::two_factor::TwoFactorSetupResp,
+      bookstore::application::dto::response::two_factor::TwoFactorEnabledResp,
+      bookstore::application::dto::response::two_factor::RecoveryCodesResp,
+      bookstore::application::dto::response::two_factor::TwoFactorStatusResp,
 
       bookstore::application::dto::response::author::FullAuthorResp,
       bookstore::application::dto::response::author::MinAuthorResp,
@@ -36,14 +127,93 @@ use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
       bookstore::application::dto::response::book::MinBookResp,
       bookstore::application::dto::response::book::BookListResp,
 
+      bookstore::application::dto::response::review::FullReviewResp,
+      bookstore::application::dto::response::review::ReviewListResp,
+
+      bookstore::application::dto::response::shelf::FullShelfResp,
+      bookstore::application::dto::response::shelf::ShelfEntryResp,
+      bookstore::application::dto::response::shelf::ShelfListResp,
+      bookstore::application::dto::response::shelf::YearReadingStatsResp,
+      bookstore::application::dto::response::shelf::ReadingStatsResp,
+
+      bookstore::application::dto::response::wishlist::WishlistItemResp,
+      bookstore::application::dto::response::wishlist::WishlistResp,
+
+      bookstore::application::dto::response::notification::FullNotificationResp,
+      bookstore::application::dto::response::notification::NotificationListResp,
+
+      bookstore::application::dto::response::copy::FullCopyResp,
+      bookstore::application::dto::response::copy::CopyListResp,
+      bookstore::application::dto::response::copy::CopyTransferResp,
+      bookstore::application::dto::response::copy::CopyTransferListResp,
+
+      bookstore::application::dto::response::branch::FullBranchResp,
+      bookstore::application::dto::response::branch::BranchListResp,
+      bookstore::application::dto::response::branch::BranchAvailabilityResp,
+
+      bookstore::application::dto::response::loan::FullLoanResp,
+      bookstore::application::dto::response::loan::LoanListResp,
+
+      bookstore::application::dto::response::hold::FullHoldResp,
+      bookstore::application::dto::response::hold::HoldListResp,
+
+      bookstore::application::dto::response::diagnostics::LoggerLevelResp,
+      bookstore::application::dto::response::diagnostics::LogLevelOverrideResp,
+      bookstore::application::dto::response::diagnostics::LogLevelsResp,
+      bookstore::application::dto::response::diagnostics::PoolStatsResp,
+      bookstore::application::dto::response::diagnostics::ErrorSummaryResp,
+      bookstore::application::dto::response::diagnostics::ErrorSummaryListResp,
+      bookstore::application::dto::response::login_failure::FullLoginFailureResp,
+      bookstore::application::dto::response::login_failure::LoginFailureListResp,
+
       bookstore::application::dto::request::user::RegisterReq,
       bookstore::application::dto::request::user::LoginReq,
       bookstore::application::dto::request::user::UpdateSuspendedReq,
+      bookstore::application::dto::request::user::UpdateMeReq,
+      bookstore::application::dto::request::user::ChangePasswordReq,
+      bookstore::application::dto::request::user::DeleteMeReq,
+      bookstore::application::dto::request::user::SetEmailReq,
+      bookstore::application::dto::request::user::VerifyEmailReq,
+      bookstore::application::dto::request::user::ForgotPasswordReq,
+      bookstore::application::dto::request::user::ResetPasswordReq,
+      bookstore::application::dto::request::two_factor::StartTwoFactorReq,
+      bookstore::application::dto::request::two_factor::TwoFactorCodeReq,
+      bookstore::application::dto::request::two_factor::DisableTwoFactorReq,
+      bookstore::application::dto::request::two_factor::TwoFactorLoginReq,
 
       bookstore::application::dto::request::author::AddAuthorReq,
       bookstore::application::dto::request::book::AddBookReq,
+      bookstore::application::dto::request::book::BookListSort,
+      bookstore::application::dto::request::book::UpdateInventoryReq,
+
+      bookstore::application::dto::request::review::AddReviewReq,
+      bookstore::application::dto::request::review::UpdateReviewReq,
+      bookstore::application::dto::request::review::UpdateHiddenReq,
+
+      bookstore::application::dto::request::shelf::AddShelfReq,
+      bookstore::application::dto::request::shelf::UpdateShelfReq,
+      bookstore::application::dto::request::shelf::PutShelfEntryReq,
+
+      bookstore::application::dto::request::wishlist::PutWishlistItemReq,
+
+      bookstore::application::dto::request::notification::UpdateReadReq,
+
+      bookstore::application::dto::request::copy::AddCopyReq,
+      bookstore::application::dto::request::copy::UpdateCopyReq,
+      bookstore::application::dto::request::copy::TransferCopyReq,
+      bookstore::application::dto::request::branch::AddBranchReq,
+      bookstore::application::dto::request::branch::UpdateBranchReq,
+      bookstore::application::dto::request::loan::CheckoutReq,
+      bookstore::application::dto::request::hold::PlaceHoldReq,
+      bookstore::application::dto::request::diagnostics::SetLogLevelReq,
 
       bookstore::application::entities::user::UserRole,
+      bookstore::application::entities::shelf::ShelfKind,
+      bookstore::application::entities::notification::NotificationKind,
+      bookstore::application::entities::login_failure::LoginKeyKind,
+      bookstore::application::entities::copy::CopyStatus,
+      bookstore::application::entities::copy::CopyCondition,
+      bookstore::application::entities::hold::HoldStatus,
     )
   ),
   modifiers(&SecurityAddon)
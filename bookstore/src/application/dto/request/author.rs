@@ -1,27 +1,35 @@
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::application::util::validation::NAME_RE;
 
 
 /// Запрос на получение информации о нескольких авторах.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct GetAuthorListReq {
   pub page: u32,
+
+  #[validate(range(min = 1, max = 100))]
   pub size: u32,
 }
 
 
 /// Запрос на добавление автора.
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
 pub struct AddAuthorReq {
   /// Имя.
-  #[schema(example = "Вася")]
+  #[validate(length(min = 1, max = 64), regex(path = *NAME_RE, code = "name"))]
+  #[schema(example = "Вася", min_length = 1, max_length = 64, pattern = r"^\p{L}\p{M}*(?:[ '’-]?\p{L}\p{M}*)*$")]
   pub first_name: String,
 
   /// Фамилия.
-  #[schema(example = "Васин")]
+  #[validate(length(min = 1, max = 64), regex(path = *NAME_RE, code = "name"))]
+  #[schema(example = "Васин", min_length = 1, max_length = 64, pattern = r"^\p{L}\p{M}*(?:[ '’-]?\p{L}\p{M}*)*$")]
   pub last_name: String,
 
   /// Отчество.
-  #[schema(example = "Васильевич")]
+  #[validate(length(min = 1, max = 64), regex(path = *NAME_RE, code = "name"))]
+  #[schema(example = "Васильевич", min_length = 1, max_length = 64, pattern = r"^\p{L}\p{M}*(?:[ '’-]?\p{L}\p{M}*)*$")]
   pub middle_name: Option<String>,
 }
@@ -1,23 +1,67 @@
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::application::util::validation::not_blank;
 use uuid::Uuid;
 
 
+/// Порядок сортировки списка книг.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BookListSort {
+  /// По убыванию средней оценки; книги без отзывов идут последними.
+  Rating,
+}
+
 /// Запрос на получение информации о нескольких книгах.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct GetBookListReq {
   pub page: u32,
+
+  #[validate(range(min = 1, max = 100))]
   pub size: u32,
+  pub sort: Option<BookListSort>,
 }
 
 /// Запрос на добавление книги.
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
 pub struct AddBookReq {
   /// Название.
-  #[schema(example = "Книга", min_length = 1)]
+  #[validate(length(max = 256), custom(function = not_blank))]
+  #[schema(example = "Книга", min_length = 1, max_length = 256)]
   pub title: String,
 
   /// Идентификатор автора книги.
   #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
   pub author_id: Option<Uuid>,
+
+  /// Цена в копейках.
+  #[validate(range(min = 0))]
+  #[schema(example = 49900, minimum = 0)]
+  pub price: Option<i32>,
+
+  /// Количество экземпляров в наличии.
+  #[serde(default)]
+  #[validate(range(min = 0))]
+  #[schema(example = 10, minimum = 0)]
+  pub stock: i32,
+}
+
+/// Запрос на изменение цены и наличия книги.
+///
+/// Отсутствующие поля не изменяются. Пользователи, добавившие книгу
+/// в список желаемого, получают уведомления о поступлении в продажу
+/// и о снижении цены.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateInventoryReq {
+  /// Цена в копейках.
+  #[validate(range(min = 0))]
+  #[schema(example = 39900, minimum = 0)]
+  pub price: Option<i32>,
+
+  /// Количество экземпляров в наличии.
+  #[validate(range(min = 0))]
+  #[schema(example = 5, minimum = 0)]
+  pub stock: Option<i32>,
 }
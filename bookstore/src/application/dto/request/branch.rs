@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::application::util::validation::not_blank;
+
+
+/// Запрос на добавление филиала.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct AddBranchReq {
+  /// Название.
+  #[validate(length(max = 128), custom(function = not_blank))]
+  #[schema(example = "Центральная библиотека", min_length = 1, max_length = 128)]
+  pub name: String,
+
+  /// Адрес.
+  #[validate(length(max = 512))]
+  #[schema(example = "ул. Ленина, 1", max_length = 512)]
+  #[serde(default)]
+  pub address: String,
+}
+
+/// Запрос на изменение филиала.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateBranchReq {
+  /// Новое название.
+  #[validate(length(max = 128), custom(function = not_blank))]
+  #[schema(example = "Центральная библиотека", min_length = 1, max_length = 128)]
+  pub name: Option<String>,
+
+  /// Новый адрес.
+  #[validate(length(max = 512))]
+  #[schema(example = "ул. Ленина, 1", max_length = 512)]
+  pub address: Option<String>,
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::application::util::validation::not_blank;
+use uuid::Uuid;
+
+use crate::application::entities::copy::{CopyCondition, CopyStatus};
+
+
+/// Запрос на получение экземпляров.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct GetCopyListReq {
+  pub page: u32,
+
+  #[validate(range(min = 1, max = 100))]
+  pub size: u32,
+  pub book_id: Option<Uuid>,
+  pub branch_id: Option<Uuid>,
+  pub status: Option<CopyStatus>,
+}
+
+/// Запрос на добавление физического экземпляра книги.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct AddCopyReq {
+  /// Идентификатор книги.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub book_id: Uuid,
+
+  /// Штрихкод.
+  #[validate(length(max = 64), custom(function = not_blank))]
+  #[schema(example = "4600000000017", min_length = 1, max_length = 64)]
+  pub barcode: String,
+
+  /// Состояние.
+  #[schema(example = CopyCondition::Good)]
+  #[serde(default)]
+  pub condition: CopyCondition,
+
+  /// Идентификатор филиала.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub branch_id: Option<Uuid>,
+}
+
+/// Запрос на изменение экземпляра.
+///
+/// Статус «выдан» устанавливается и снимается только выдачей и возвратом.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateCopyReq {
+  /// Новый штрихкод.
+  #[validate(length(max = 64), custom(function = not_blank))]
+  #[schema(example = "4600000000017", min_length = 1, max_length = 64)]
+  pub barcode: Option<String>,
+
+  /// Новое состояние.
+  #[schema(example = CopyCondition::Fair)]
+  pub condition: Option<CopyCondition>,
+
+  /// Новый статус.
+  #[schema(example = CopyStatus::Lost)]
+  pub status: Option<CopyStatus>,
+}
+
+/// Запрос на перемещение экземпляра в другой филиал.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct TransferCopyReq {
+  /// Идентификатор филиала назначения.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub branch_id: Uuid,
+}
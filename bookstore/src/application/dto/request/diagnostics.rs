@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::application::util::validation::{LOG_LEVEL_RE, LOG_MODULE_RE};
+
+
+/// Запрос на временное изменение уровня логирования модуля.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct SetLogLevelReq {
+  /// Модуль (цель логирования); уровень действует и на его подмодули.
+  #[validate(length(min = 1, max = 128), regex(path = *LOG_MODULE_RE, code = "log_module"))]
+  #[schema(example = "bookstore::adapters::repositories::book", min_length = 1, max_length = 128)]
+  pub module: String,
+
+  /// Уровень: `off`, `error`, `warn`, `info`, `debug` или `trace`.
+  #[validate(regex(path = *LOG_LEVEL_RE, code = "log_level"))]
+  #[schema(example = "debug", pattern = r"^(?i)(off|error|warn|info|debug|trace)$")]
+  pub level: String,
+
+  /// Через сколько секунд вернуть прежний уровень; по умолчанию 15 минут, не больше суток.
+  #[validate(range(min = 1, max = 86400))]
+  #[schema(example = 600, minimum = 1, maximum = 86400)]
+  pub ttl_secs: Option<u64>,
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+use uuid::Uuid;
+
+
+/// Запрос на постановку в очередь за книгой.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct PlaceHoldReq {
+  /// Идентификатор книги.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub book_id: Uuid,
+}
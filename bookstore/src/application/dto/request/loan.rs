@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+use uuid::Uuid;
+
+
+/// Запрос на получение своих выдач.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct GetLoanListReq {
+  pub page: u32,
+
+  #[validate(range(min = 1, max = 100))]
+  pub size: u32,
+  #[serde(default)]
+  pub active_only: bool,
+}
+
+/// Запрос на получение просроченных выдач.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct GetOverdueListReq {
+  pub page: u32,
+
+  #[validate(range(min = 1, max = 100))]
+  pub size: u32,
+}
+
+/// Запрос на выдачу экземпляра книги.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct CheckoutReq {
+  /// Идентификатор книги.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub book_id: Uuid,
+
+  /// Кому выдать книгу. Выдавать книги другим пользователям
+  /// могут только администраторы; по умолчанию — себе.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub user_id: Option<Uuid>,
+}
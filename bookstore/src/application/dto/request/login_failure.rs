@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+
+/// Запрос на получение неудачных попыток входа.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct GetLoginFailureListReq {
+  pub page: u32,
+
+  #[validate(range(min = 1, max = 100))]
+  pub size: u32,
+  #[serde(default)]
+  pub locked_only: bool,
+}
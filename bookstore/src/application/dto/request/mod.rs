@@ -1,3 +1,15 @@
 pub mod user;
 pub mod book;
 pub mod author;
+pub mod review;
+pub mod shelf;
+pub mod wishlist;
+pub mod notification;
+pub mod copy;
+pub mod loan;
+pub mod hold;
+pub mod branch;
+pub mod diagnostics;
+pub mod login_failure;
+
+pub mod two_factor;
\ No newline at end of file
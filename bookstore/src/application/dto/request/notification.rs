@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+
+/// Запрос на получение уведомлений.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct GetNotificationListReq {
+  pub page: u32,
+
+  #[validate(range(min = 1, max = 100))]
+  pub size: u32,
+  #[serde(default)]
+  pub unread_only: bool,
+}
+
+/// Запрос на изменение статуса прочтения уведомления.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateReadReq {
+  pub read: bool,
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+
+/// Запрос на получение отзывов о книге.
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct GetReviewListReq {
+  pub page: u32,
+
+  #[validate(range(min = 1, max = 100))]
+  pub size: u32,
+}
+
+/// Запрос на добавление отзыва о книге.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct AddReviewReq {
+  /// Оценка от 1 до 5.
+  #[validate(range(min = 1, max = 5))]
+  #[schema(example = 5, minimum = 1, maximum = 5)]
+  pub rating: i16,
+
+  /// Текст отзыва.
+  #[validate(length(max = 4096))]
+  #[schema(example = "Отличная книга!", max_length = 4096)]
+  pub text: String,
+}
+
+/// Запрос на изменение своего отзыва о книге.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateReviewReq {
+  /// Оценка от 1 до 5.
+  #[validate(range(min = 1, max = 5))]
+  #[schema(example = 4, minimum = 1, maximum = 5)]
+  pub rating: i16,
+
+  /// Текст отзыва.
+  #[validate(length(max = 4096))]
+  #[schema(example = "Хорошая книга.", max_length = 4096)]
+  pub text: String,
+}
+
+/// Запрос на скрытие или показ отзыва.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateHiddenReq {
+  pub hidden: bool,
+}
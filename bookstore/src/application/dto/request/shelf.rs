@@ -0,0 +1,70 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::{Validate, ValidationError};
+
+use crate::application::util::validation::{cross_field_error, not_blank};
+
+
+/// Запрос на создание своей полки.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct AddShelfReq {
+  /// Название.
+  #[validate(length(max = 64), custom(function = not_blank))]
+  #[schema(example = "Фантастика", min_length = 1, max_length = 64)]
+  pub name: String,
+
+  /// Видна ли полка другим пользователям.
+  #[serde(default)]
+  pub public: bool,
+}
+
+/// Запрос на изменение полки.
+///
+/// Встроенные полки нельзя переименовать, но можно сделать публичными.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateShelfReq {
+  /// Новое название.
+  #[validate(length(max = 64), custom(function = not_blank))]
+  #[schema(example = "Фантастика", min_length = 1, max_length = 64)]
+  pub name: Option<String>,
+
+  /// Видна ли полка другим пользователям.
+  pub public: Option<bool>,
+}
+
+/// Запрос на добавление книги на полку или изменение прогресса чтения.
+///
+/// Книга может находиться только на одной из встроенных полок: при
+/// добавлении на встроенную полку она убирается с остальных встроенных,
+/// сохраняя дату начала чтения. На полке «Читаю» дата начала, а на полке
+/// «Прочитано» дата окончания по умолчанию равны сегодняшней.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema, Validate)]
+#[validate(schema(function = check_reading_dates))]
+pub struct PutShelfEntryReq {
+  /// Дата начала чтения.
+  #[schema(example = "2024-01-01")]
+  pub date_started: Option<NaiveDate>,
+
+  /// Дата окончания чтения.
+  #[schema(example = "2024-01-15")]
+  pub date_finished: Option<NaiveDate>,
+
+  /// Прогресс в процентах.
+  #[validate(range(min = 0, max = 100))]
+  #[schema(example = 42, minimum = 0, maximum = 100)]
+  pub progress_percent: Option<i16>,
+
+  /// Текущая страница.
+  #[validate(range(min = 0))]
+  #[schema(example = 120, minimum = 0)]
+  pub progress_page: Option<i32>,
+}
+
+/// A book cannot be finished before it is started.
+fn check_reading_dates(data: &PutShelfEntryReq) -> Result<(), ValidationError> {
+  match (data.date_started, data.date_finished) {
+    (Some(started), Some(finished)) if finished < started => Err(cross_field_error("date_order", "date_finished")),
+    _ => Ok(()),
+  }
+}
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+
+/// Запрос на подключение двухфакторной аутентификации.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct StartTwoFactorReq {
+  /// Текущий пароль для подтверждения.
+  #[validate(length(min = 1, max = 128))]
+  #[schema(example = "password", min_length = 1, max_length = 128)]
+  pub password: String,
+}
+
+/// Запрос с кодом двухфакторной аутентификации.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct TwoFactorCodeReq {
+  /// Код из приложения-аутентификатора или, где это допустимо, код восстановления.
+  #[validate(length(min = 1, max = 32))]
+  #[schema(example = "123456", min_length = 1, max_length = 32)]
+  pub code: String,
+}
+
+/// Запрос на отключение двухфакторной аутентификации.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct DisableTwoFactorReq {
+  /// Текущий пароль для подтверждения.
+  #[validate(length(min = 1, max = 128))]
+  #[schema(example = "password", min_length = 1, max_length = 128)]
+  pub password: String,
+
+  /// Код из приложения-аутентификатора или код восстановления.
+  #[validate(length(min = 1, max = 32))]
+  #[schema(example = "123456", min_length = 1, max_length = 32)]
+  pub code: String,
+}
+
+/// Второй шаг входа для пользователей с двухфакторной аутентификацией.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct TwoFactorLoginReq {
+  /// Значение `challenge` из ответа `/api/auth/login`.
+  #[validate(length(min = 1, max = 1024))]
+  #[schema(example = "jwt", min_length = 1, max_length = 1024)]
+  pub challenge: String,
+
+  /// Код из приложения-аутентификатора или код восстановления.
+  #[validate(length(min = 1, max = 32))]
+  #[schema(example = "123456", min_length = 1, max_length = 32)]
+  pub code: String,
+}
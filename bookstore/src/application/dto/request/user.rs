@@ -1,55 +1,161 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::application::util::validation::{check_password, NAME_RE, NICKNAME_RE};
 
 
 /// Запрос на регистрацию пользователя.
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
 pub struct RegisterReq {
   /// Имя.
-  #[schema(example = "Вася", min_length = 1)]
+  #[validate(length(min = 1, max = 64), regex(path = *NAME_RE, code = "name"))]
+  #[schema(example = "Вася", min_length = 1, max_length = 64, pattern = r"^\p{L}\p{M}*(?:[ '’-]?\p{L}\p{M}*)*$")]
   pub first_name: String,
 
   /// Фамилия.
-  #[schema(example = "Васин", min_length = 1)]
+  #[validate(length(min = 1, max = 64), regex(path = *NAME_RE, code = "name"))]
+  #[schema(example = "Васин", min_length = 1, max_length = 64, pattern = r"^\p{L}\p{M}*(?:[ '’-]?\p{L}\p{M}*)*$")]
   pub last_name: String,
 
   /// Отчество.
-  #[schema(example = "Васильевич", min_length = 1)]
+  #[validate(length(min = 1, max = 64), regex(path = *NAME_RE, code = "name"))]
+  #[schema(example = "Васильевич", min_length = 1, max_length = 64, pattern = r"^\p{L}\p{M}*(?:[ '’-]?\p{L}\p{M}*)*$")]
   pub middle_name: Option<String>,
 
   /// Псведоним.
   ///
   /// Непустая строка, состоящая как минимум из трех
   /// английских букв, цифр и символов `.`, `-`, `_`.
-  #[schema(example = "Aboba_x69", min_length = 3)]
+  /// Псевдонимы, отличающиеся только регистром, считаются одинаковыми.
+  #[validate(length(min = 3, max = 64), regex(path = *NICKNAME_RE, code = "nickname"))]
+  #[schema(example = "Aboba_x69", min_length = 3, max_length = 64, pattern = r"^[a-zA-Z0-9.\-_]+$")]
   pub nickname: String,
 
   /// Пароль.
-  #[schema(example = "password", min_length = 1)]
+  #[validate(custom(function = check_password))]
+  #[schema(example = "password", min_length = 1, max_length = 128)]
   pub password: String,
 }
 
 /// Запрос на авторизацию пользователя.
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
 pub struct LoginReq {
-  /// Псведоним.
-  #[schema(example = "Aboba_x69")]
+  /// Псведоним, без учета регистра.
+  #[validate(length(min = 1, max = 64))]
+  #[schema(example = "Aboba_x69", min_length = 1, max_length = 64)]
   pub nickname: String,
 
   /// Пароль.
-  #[schema(example = "password")]
+  #[validate(length(min = 1, max = 128))]
+  #[schema(example = "password", min_length = 1, max_length = 128)]
   pub password: String,
 }
 
 /// Запрос на получение информации о нескольких пользователях.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct GetUserListReq {
   pub page: u32,
+
+  #[validate(range(min = 1, max = 100))]
   pub size: u32,
 }
 
 /// Запрос на обновление статуса действия аккаунта пользователя.
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
 pub struct UpdateSuspendedReq {
   pub suspended: bool,
-}
\ No newline at end of file
+}
+/// Отличает `null` от отсутствующего поля: `Some(None)` и `None` соответственно.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+  where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+  T::deserialize(deserializer).map(Some)
+}
+
+/// Запрос на изменение своего профиля. Отсутствующие поля не меняются.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct UpdateMeReq {
+  /// Новое имя.
+  #[validate(length(min = 1, max = 64), regex(path = *NAME_RE, code = "name"))]
+  #[schema(example = "Вася", min_length = 1, max_length = 64, pattern = r"^\p{L}\p{M}*(?:[ '’-]?\p{L}\p{M}*)*$")]
+  pub first_name: Option<String>,
+
+  /// Новая фамилия.
+  #[validate(length(min = 1, max = 64), regex(path = *NAME_RE, code = "name"))]
+  #[schema(example = "Васин", min_length = 1, max_length = 64, pattern = r"^\p{L}\p{M}*(?:[ '’-]?\p{L}\p{M}*)*$")]
+  pub last_name: Option<String>,
+
+  /// Новое отчество; `null` удаляет его.
+  #[serde(default, deserialize_with = "present")]
+  #[validate(length(min = 1, max = 64), regex(path = *NAME_RE, code = "name"))]
+  #[schema(value_type = Option<String>, example = "Васильевич", min_length = 1, max_length = 64, pattern = r"^\p{L}\p{M}*(?:[ '’-]?\p{L}\p{M}*)*$")]
+  pub middle_name: Option<Option<String>>,
+}
+
+/// Запрос на смену своего пароля.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct ChangePasswordReq {
+  /// Текущий пароль.
+  #[validate(length(min = 1, max = 128))]
+  #[schema(example = "password", min_length = 1, max_length = 128)]
+  pub current_password: String,
+
+  /// Новый пароль.
+  #[validate(custom(function = check_password))]
+  #[schema(example = "correct horse battery", min_length = 1, max_length = 128)]
+  pub new_password: String,
+}
+
+/// Запрос на удаление своего аккаунта.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct DeleteMeReq {
+  /// Текущий пароль для подтверждения.
+  #[validate(length(min = 1, max = 128))]
+  #[schema(example = "password", min_length = 1, max_length = 128)]
+  pub password: String,
+}
+
+/// Запрос на смену адреса электронной почты.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct SetEmailReq {
+  /// Новый адрес. Он используется для восстановления пароля
+  /// только после подтверждения по ссылке из письма.
+  #[validate(email, length(max = 254))]
+  #[schema(example = "vasya@example.com", max_length = 254)]
+  pub email: String,
+}
+
+/// Запрос на подтверждение адреса электронной почты.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct VerifyEmailReq {
+  /// Код из письма.
+  #[validate(length(min = 1, max = 128))]
+  #[schema(example = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08", min_length = 1, max_length = 128)]
+  pub token: String,
+}
+
+/// Запрос на восстановление пароля.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct ForgotPasswordReq {
+  /// Подтвержденный адрес электронной почты.
+  #[validate(email, length(max = 254))]
+  #[schema(example = "vasya@example.com", max_length = 254)]
+  pub email: String,
+}
+
+/// Запрос на установку нового пароля по коду из письма.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct ResetPasswordReq {
+  /// Код из письма.
+  #[validate(length(min = 1, max = 128))]
+  #[schema(example = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08", min_length = 1, max_length = 128)]
+  pub token: String,
+
+  /// Новый пароль.
+  #[validate(custom(function = check_password))]
+  #[schema(example = "correct horse battery", min_length = 1, max_length = 128)]
+  pub new_password: String,
+}
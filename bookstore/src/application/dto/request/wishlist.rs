@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+
+fn default_notify_restock() -> bool {
+  true
+}
+
+/// Запрос на добавление книги в список желаемого или изменение настроек уведомлений.
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct PutWishlistItemReq {
+  /// Уведомить, когда цена опустится до этого значения (в копейках).
+  #[validate(range(min = 0))]
+  #[schema(example = 39900, minimum = 0)]
+  pub price_threshold: Option<i32>,
+
+  /// Уведомить, когда книга снова появится в наличии.
+  #[serde(default = "default_notify_restock")]
+  #[schema(example = true)]
+  pub notify_restock: bool,
+}
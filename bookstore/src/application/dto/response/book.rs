@@ -3,8 +3,10 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::application::dto::response::author::MinAuthorResp;
+use crate::application::dto::response::branch::BranchAvailabilityResp;
 use crate::application::entities::author::Author;
 use crate::application::entities::book::Book;
+use crate::application::entities::branch::BranchAvailability;
 
 
 /// Информация об одной книге.
@@ -24,11 +26,35 @@ pub struct FullBookResp {
 
   /// Информация об авторе книги.
   pub author: Option<MinAuthorResp>,
+
+  /// Средняя оценка по видимым отзывам.
+  #[schema(example = 4.5)]
+  pub rating: Option<f64>,
+
+  /// Количество видимых отзывов.
+  #[schema(example = 2)]
+  pub review_count: i32,
+
+  /// Цена в копейках.
+  #[schema(example = 49900)]
+  pub price: Option<i32>,
+
+  /// Количество экземпляров в наличии.
+  #[schema(example = 10)]
+  pub stock: i32,
+
+  /// Наличие физических экземпляров по филиалам.
+  pub availability: Vec<BranchAvailabilityResp>,
 }
 
 impl FullBookResp {
-  pub fn new(db_book: Book, db_author: Option<Author>) -> Self {
+  pub fn new(db_book: Book, db_author: Option<Author>, db_availability: Vec<BranchAvailability>) -> Self {
     Self {
+      availability: db_availability.into_iter().map(BranchAvailabilityResp::new).collect(),
+      rating: db_book.rating(),
+      review_count: db_book.rating_count,
+      price: db_book.price,
+      stock: db_book.stock,
       id: db_book.id,
       title: db_book.title,
       author_id: db_book.author_id,
@@ -73,10 +99,14 @@ impl MinBookResp {
 pub struct BookListResp(pub Vec<FullBookResp>);
 
 impl BookListResp {
-  pub fn new(db_books: Vec<Book>, db_authors: Vec<Option<Author>>) -> Self {
+  pub fn new(
+    db_books: Vec<Book>,
+    db_authors: Vec<Option<Author>>,
+    db_availability: Vec<Vec<BranchAvailability>>,
+  ) -> Self {
     let mut res = vec![];
-    for (db_book, db_author) in db_books.into_iter().zip(db_authors) {
-      res.push(FullBookResp::new(db_book, db_author));
+    for ((db_book, db_author), db_availability) in db_books.into_iter().zip(db_authors).zip(db_availability) {
+      res.push(FullBookResp::new(db_book, db_author, db_availability));
     }
     Self(res)
   }
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::application::entities::branch::{Branch, BranchAvailability};
+
+
+/// Информация об одном филиале.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FullBranchResp {
+  /// Уникальный идентификатор.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub id: Uuid,
+
+  /// Название.
+  #[schema(example = "Центральная библиотека")]
+  pub name: String,
+
+  /// Адрес.
+  #[schema(example = "ул. Ленина, 1")]
+  pub address: String,
+
+  /// Время создания.
+  #[schema(example = "2024-01-01T10:00:00+0400")]
+  pub date_created: DateTime<Local>,
+}
+
+impl FullBranchResp {
+  pub fn new(value: Branch) -> Self {
+    Self {
+      id: value.id,
+      name: value.name,
+      address: value.address,
+      date_created: value.date_created,
+    }
+  }
+}
+
+
+/// Информация о нескольких филиалах.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BranchListResp(pub Vec<FullBranchResp>);
+
+impl BranchListResp {
+  pub fn new(value: Vec<Branch>) -> Self {
+    Self(value.into_iter().map(FullBranchResp::new).collect())
+  }
+}
+
+
+/// Наличие экземпляров книги в одном филиале.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BranchAvailabilityResp {
+  /// Идентификатор филиала; пуст для экземпляров, не приписанных к филиалу.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub branch_id: Option<Uuid>,
+
+  /// Название филиала.
+  #[schema(example = "Центральная библиотека")]
+  pub branch_name: Option<String>,
+
+  /// Количество экземпляров на полке.
+  #[schema(example = 2)]
+  pub available: i64,
+
+  /// Количество экземпляров в фонде, включая выданные.
+  #[schema(example = 3)]
+  pub total: i64,
+}
+
+impl BranchAvailabilityResp {
+  pub fn new(value: BranchAvailability) -> Self {
+    Self {
+      branch_id: value.branch_id,
+      branch_name: value.branch_name,
+      available: value.available,
+      total: value.total,
+    }
+  }
+}
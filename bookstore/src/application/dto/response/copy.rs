@@ -0,0 +1,117 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::application::entities::copy::{BookCopy, CopyCondition, CopyStatus, CopyTransfer};
+
+
+/// Информация об одном физическом экземпляре книги.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FullCopyResp {
+  /// Уникальный идентификатор.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub id: Uuid,
+
+  /// Идентификатор книги.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub book_id: Uuid,
+
+  /// Штрихкод.
+  #[schema(example = "4600000000017")]
+  pub barcode: String,
+
+  /// Состояние.
+  #[schema(example = CopyCondition::Good)]
+  pub condition: CopyCondition,
+
+  /// Статус.
+  #[schema(example = CopyStatus::Available)]
+  pub status: CopyStatus,
+
+  /// Идентификатор филиала.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub branch_id: Option<Uuid>,
+
+  /// Время поступления.
+  #[schema(example = "2024-01-01T10:00:00+0400")]
+  pub date_added: DateTime<Local>,
+}
+
+impl FullCopyResp {
+  pub fn new(value: BookCopy) -> Self {
+    Self {
+      id: value.id,
+      book_id: value.book_id,
+      barcode: value.barcode,
+      condition: value.condition,
+      status: value.status,
+      branch_id: value.branch_id,
+      date_added: value.date_added,
+    }
+  }
+}
+
+
+/// Информация о нескольких экземплярах.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CopyListResp(pub Vec<FullCopyResp>);
+
+impl CopyListResp {
+  pub fn new(value: Vec<BookCopy>) -> Self {
+    Self(value.into_iter().map(FullCopyResp::new).collect())
+  }
+}
+
+
+/// Информация об одном перемещении экземпляра.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CopyTransferResp {
+  /// Уникальный идентификатор.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub id: Uuid,
+
+  /// Идентификатор экземпляра.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub copy_id: Uuid,
+
+  /// Откуда перемещён экземпляр.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub from_branch_id: Option<Uuid>,
+
+  /// Куда перемещён экземпляр.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub to_branch_id: Option<Uuid>,
+
+  /// Кто переместил экземпляр.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub user_id: Option<Uuid>,
+
+  /// Время перемещения.
+  #[schema(example = "2024-01-01T10:00:00+0400")]
+  pub date_transferred: DateTime<Local>,
+}
+
+impl CopyTransferResp {
+  pub fn new(value: CopyTransfer) -> Self {
+    Self {
+      id: value.id,
+      copy_id: value.copy_id,
+      from_branch_id: value.from_branch_id,
+      to_branch_id: value.to_branch_id,
+      user_id: value.user_id,
+      date_transferred: value.date_transferred,
+    }
+  }
+}
+
+
+/// История перемещений экземпляра.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CopyTransferListResp(pub Vec<CopyTransferResp>);
+
+impl CopyTransferListResp {
+  pub fn new(value: Vec<CopyTransfer>) -> Self {
+    Self(value.into_iter().map(CopyTransferResp::new).collect())
+  }
+}
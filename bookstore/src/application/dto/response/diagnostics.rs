@@ -0,0 +1,158 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+
+use crate::application::entities::health::PoolStats;
+use crate::config::DatabaseConfig;
+use crate::logging::{ErrorSummary, LevelOverride, LogLevels};
+
+
+/// Уровень логирования модуля из конфигурации.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LoggerLevelResp {
+  /// Модуль.
+  #[schema(example = "sqlx")]
+  pub module: String,
+
+  /// Уровень.
+  #[schema(example = "warn")]
+  pub level: String,
+}
+
+
+/// Временно изменённый уровень логирования модуля.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LogLevelOverrideResp {
+  /// Модуль.
+  #[schema(example = "bookstore::adapters::repositories::book")]
+  pub module: String,
+
+  /// Уровень.
+  #[schema(example = "debug")]
+  pub level: String,
+
+  /// Время, когда будет возвращён прежний уровень.
+  #[schema(example = "2024-01-01T10:00:00+0400")]
+  pub expires_at: DateTime<Local>,
+}
+
+impl From<LevelOverride> for LogLevelOverrideResp {
+  fn from(value: LevelOverride) -> Self {
+    Self {
+      module: value.module,
+      level: value.level.to_string().to_lowercase(),
+      expires_at: value.expires_at,
+    }
+  }
+}
+
+
+/// Действующие уровни логирования.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LogLevelsResp {
+  /// Уровень по умолчанию.
+  #[schema(example = "info")]
+  pub root: String,
+
+  /// Уровни модулей из конфигурации логирования.
+  pub configured: Vec<LoggerLevelResp>,
+
+  /// Временные изменения; перекрывают уровни из конфигурации.
+  pub overrides: Vec<LogLevelOverrideResp>,
+}
+
+impl From<LogLevels> for LogLevelsResp {
+  fn from(value: LogLevels) -> Self {
+    Self {
+      root: value.root.to_string().to_lowercase(),
+      configured: value.configured.into_iter()
+        .map(|(module, level)| LoggerLevelResp { module, level: level.to_string().to_lowercase() })
+        .collect(),
+      overrides: value.overrides.into_iter().map(LogLevelOverrideResp::from).collect(),
+    }
+  }
+}
+
+
+/// Состояние пула соединений с базой данных.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PoolStatsResp {
+  /// Открытые соединения.
+  #[schema(example = 4)]
+  pub size: u32,
+
+  /// Свободные соединения.
+  #[schema(example = 3)]
+  pub idle: usize,
+
+  /// Занятые соединения.
+  #[schema(example = 1)]
+  pub in_use: usize,
+
+  /// Наибольшее число соединений.
+  #[schema(example = 10)]
+  pub max_connections: u32,
+
+  /// Наименьшее число соединений.
+  #[schema(example = 0)]
+  pub min_connections: u32,
+
+  /// Сколько запрос может ждать свободное соединение, в секундах.
+  #[schema(example = 30)]
+  pub acquire_timeout_secs: u64,
+}
+
+impl PoolStatsResp {
+  pub fn new(stats: PoolStats, config: &DatabaseConfig) -> Self {
+    Self {
+      size: stats.size,
+      idle: stats.idle,
+      in_use: (stats.size as usize).saturating_sub(stats.idle),
+      max_connections: config.max_connections,
+      min_connections: config.min_connections,
+      acquire_timeout_secs: config.acquire_timeout_secs,
+    }
+  }
+}
+
+
+/// Сводка ошибок одного модуля с момента запуска.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ErrorSummaryResp {
+  /// Модуль, записавший ошибку.
+  #[schema(example = "bookstore::adapters::repositories::book")]
+  pub target: String,
+
+  /// Количество ошибок.
+  #[schema(example = 2)]
+  pub count: u64,
+
+  /// Время последней ошибки.
+  #[schema(example = "2024-01-01T10:00:00+0400")]
+  pub last_seen: DateTime<Local>,
+
+  /// Текст последней ошибки.
+  #[schema(example = "Error fetching books: pool timed out while waiting for an open connection")]
+  pub last_message: String,
+
+  /// Идентификатор запроса, при обработке которого произошла последняя ошибка.
+  #[schema(example = "0b6e2a4c-5a0f-4f0c-9d59-4f7f3f0e8c1a")]
+  pub last_request_id: Option<String>,
+}
+
+impl From<ErrorSummary> for ErrorSummaryResp {
+  fn from(value: ErrorSummary) -> Self {
+    Self {
+      target: value.target,
+      count: value.count,
+      last_seen: value.last_seen,
+      last_message: value.last_message,
+      last_request_id: value.last_request_id,
+    }
+  }
+}
+
+
+/// Сводки ошибок, начиная с самой свежей.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ErrorSummaryListResp(pub Vec<ErrorSummaryResp>);
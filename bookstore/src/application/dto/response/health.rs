@@ -0,0 +1,58 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+
+
+/// Состояние сервиса.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+  Ok,
+  Unavailable,
+}
+
+
+/// Результат проверки работоспособности процесса.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LivenessResp {
+  /// Процесс запущен и отвечает на запросы.
+  #[schema(example = "ok")]
+  pub status: HealthStatus,
+}
+
+
+/// Результат проверки готовности принимать запросы.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadinessResp {
+  /// Итоговое состояние: `ok`, только если все проверки пройдены.
+  #[schema(example = "ok")]
+  pub status: HealthStatus,
+
+  /// Из пула удалось получить соединение и выполнить на нём запрос.
+  #[schema(example = true)]
+  pub database: bool,
+
+  /// Время проверки базы данных в миллисекундах.
+  #[schema(example = 3)]
+  pub database_latency_ms: u64,
+
+  /// Версии миграций, которые ещё не применены к базе данных.
+  #[schema(example = json!([]))]
+  pub pending_migrations: Vec<i64>,
+}
+
+
+/// Сведения о сборке.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct VersionResp {
+  /// Версия приложения.
+  #[schema(example = "0.2.0")]
+  pub version: String,
+
+  /// Коммит, из которого собрано приложение.
+  #[schema(example = "638a5be")]
+  pub git_commit: String,
+
+  /// Версия последней применённой миграции; пуста, если база данных недоступна.
+  #[schema(example = 20240212120000_i64)]
+  pub migration_version: Option<i64>,
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::application::entities::hold::{Hold, HoldStatus};
+
+
+/// Информация об одном месте в очереди за книгой.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FullHoldResp {
+  /// Уникальный идентификатор.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub id: Uuid,
+
+  /// Идентификатор книги.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub book_id: Uuid,
+
+  /// Идентификатор читателя.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub user_id: Uuid,
+
+  /// Время постановки в очередь.
+  #[schema(example = "2024-01-01T10:00:00+0400")]
+  pub date_placed: DateTime<Local>,
+
+  /// Состояние.
+  #[schema(example = HoldStatus::Waiting)]
+  pub status: HoldStatus,
+
+  /// Позиция в очереди, начиная с 1, для ожидающих.
+  #[schema(example = 1)]
+  pub position: Option<i64>,
+}
+
+impl FullHoldResp {
+  pub fn new(value: Hold, position: Option<i64>) -> Self {
+    Self {
+      id: value.id,
+      book_id: value.book_id,
+      user_id: value.user_id,
+      date_placed: value.date_placed,
+      status: value.status,
+      position,
+    }
+  }
+}
+
+
+/// Информация о нескольких местах в очередях.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HoldListResp(pub Vec<FullHoldResp>);
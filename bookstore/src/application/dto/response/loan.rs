@@ -0,0 +1,73 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::application::entities::loan::Loan;
+
+
+/// Информация об одной выдаче.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FullLoanResp {
+  /// Уникальный идентификатор.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub id: Uuid,
+
+  /// Идентификатор выданного экземпляра.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub copy_id: Uuid,
+
+  /// Идентификатор книги.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub book_id: Uuid,
+
+  /// Идентификатор читателя.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub user_id: Uuid,
+
+  /// Время выдачи.
+  #[schema(example = "2024-01-01T10:00:00+0400")]
+  pub date_borrowed: DateTime<Local>,
+
+  /// Срок возврата.
+  #[schema(example = "2024-01-15T10:00:00+0400")]
+  pub date_due: DateTime<Local>,
+
+  /// Время возврата.
+  #[schema(example = "2024-01-10T10:00:00+0400")]
+  pub date_returned: Option<DateTime<Local>>,
+
+  /// Сколько раз выдача продлевалась.
+  #[schema(example = 1)]
+  pub renewals: i32,
+
+  /// Просрочен ли возврат.
+  pub overdue: bool,
+}
+
+impl FullLoanResp {
+  pub fn new(value: Loan) -> Self {
+    Self {
+      overdue: value.is_overdue(),
+      id: value.id,
+      copy_id: value.copy_id,
+      book_id: value.book_id,
+      user_id: value.user_id,
+      date_borrowed: value.date_borrowed,
+      date_due: value.date_due,
+      date_returned: value.date_returned,
+      renewals: value.renewals,
+    }
+  }
+}
+
+
+/// Информация о нескольких выдачах.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LoanListResp(pub Vec<FullLoanResp>);
+
+impl LoanListResp {
+  pub fn new(value: Vec<Loan>) -> Self {
+    Self(value.into_iter().map(FullLoanResp::new).collect())
+  }
+}
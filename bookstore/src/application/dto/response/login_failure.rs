@@ -0,0 +1,53 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+
+use crate::application::entities::login_failure::{LoginFailure, LoginKeyKind};
+
+
+/// Неудачные попытки входа с одним псевдонимом или с одного адреса.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FullLoginFailureResp {
+  /// Что считается: псевдоним или адрес клиента.
+  #[schema(example = LoginKeyKind::Nickname)]
+  pub kind: LoginKeyKind,
+
+  /// Псевдоним в нижнем регистре или адрес.
+  #[schema(example = "aboba_x69")]
+  pub key: String,
+
+  /// Неудачных попыток подряд.
+  #[schema(example = 10)]
+  pub failures: i32,
+
+  /// Время последней неудачной попытки.
+  #[schema(example = "2024-01-01T10:00:00+0400")]
+  pub date_last_failure: DateTime<Local>,
+
+  /// До какого времени вход отклоняется, если это время еще не прошло.
+  #[schema(example = "2024-01-01T10:15:00+0400")]
+  pub locked_until: Option<DateTime<Local>>,
+}
+
+impl FullLoginFailureResp {
+  pub fn new(value: LoginFailure) -> Self {
+    Self {
+      kind: value.kind,
+      key: value.key,
+      failures: value.failures,
+      date_last_failure: value.date_last_failure,
+      locked_until: value.locked_until.filter(|until| *until > Local::now()),
+    }
+  }
+}
+
+
+/// Неудачные попытки входа.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LoginFailureListResp(pub Vec<FullLoginFailureResp>);
+
+impl LoginFailureListResp {
+  pub fn new(value: Vec<LoginFailure>) -> Self {
+    Self(value.into_iter().map(FullLoginFailureResp::new).collect())
+  }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::application::entities::notification::{Notification, NotificationKind};
+
+
+fn format_price(price: Option<i32>) -> String {
+  match price {
+    Some(price) => format!("{}.{:02} ₽", price / 100, price % 100),
+    None => "—".to_string(),
+  }
+}
+
+
+/// Информация об одном уведомлении.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FullNotificationResp {
+  /// Уникальный идентификатор.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub id: Uuid,
+
+  /// Тип уведомления.
+  #[schema(example = NotificationKind::PriceDrop)]
+  pub kind: NotificationKind,
+
+  /// Идентификатор книги, если она еще существует.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub book_id: Option<Uuid>,
+
+  /// Название книги.
+  #[schema(example = "Книга")]
+  pub book_title: String,
+
+  /// Прежняя цена в копейках.
+  #[schema(example = 49900)]
+  pub old_price: Option<i32>,
+
+  /// Новая цена в копейках.
+  #[schema(example = 39900)]
+  pub new_price: Option<i32>,
+
+  /// Текст уведомления.
+  #[schema(example = "Цена на книгу «Книга» снизилась с 499.00 ₽ до 399.00 ₽.")]
+  pub message: String,
+
+  /// Время создания.
+  #[schema(example = "2024-01-01T10:00:00+0400")]
+  pub date_created: DateTime<Local>,
+
+  /// Прочитано ли уведомление.
+  pub read: bool,
+}
+
+impl FullNotificationResp {
+  pub fn new(value: Notification) -> Self {
+    let message = match value.kind {
+      NotificationKind::BackInStock => format!(
+        "Книга «{}» снова в наличии.",
+        value.book_title,
+      ),
+      NotificationKind::PriceDrop if value.old_price.is_none() => format!(
+        "Для книги «{}» назначена цена {}.",
+        value.book_title,
+        format_price(value.new_price),
+      ),
+      NotificationKind::PriceDrop => format!(
+        "Цена на книгу «{}» снизилась с {} до {}.",
+        value.book_title,
+        format_price(value.old_price),
+        format_price(value.new_price),
+      ),
+    };
+
+    Self {
+      id: value.id,
+      kind: value.kind,
+      book_id: value.book_id,
+      book_title: value.book_title,
+      old_price: value.old_price,
+      new_price: value.new_price,
+      message,
+      date_created: value.date_created,
+      read: value.read,
+    }
+  }
+}
+
+
+/// Информация о нескольких уведомлениях.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NotificationListResp(pub Vec<FullNotificationResp>);
+
+impl NotificationListResp {
+  pub fn new(value: Vec<Notification>) -> Self {
+    Self(value.into_iter().map(FullNotificationResp::new).collect())
+  }
+}
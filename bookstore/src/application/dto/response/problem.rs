@@ -0,0 +1,65 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+
+
+/// Описание ошибки в формате RFC 7807 (`application/problem+json`).
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Problem {
+  /// Тип ошибки; `about:blank` означает, что достаточно HTTP-статуса.
+  #[serde(rename = "type")]
+  #[schema(example = "about:blank")]
+  pub kind: String,
+
+  /// Краткое описание статуса.
+  #[schema(example = "Not Found")]
+  pub title: String,
+
+  /// HTTP-статус ответа.
+  #[schema(example = 404)]
+  pub status: u16,
+
+  /// Подробности, если они есть.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  #[schema(example = "The associated user account could not be found.")]
+  pub detail: Option<String>,
+
+  /// Идентификатор запроса, тот же, что в заголовке `X-Request-Id`.
+  #[schema(example = "1f0e4b5c-7c35-4b4f-9a57-2d6b7a0f0e61")]
+  pub request_id: Option<String>,
+
+  /// Поля, не прошедшие проверку; только в ответах со статусом 422.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub errors: Vec<FieldErrorResp>,
+}
+
+impl Problem {
+  pub fn new(status: u16, title: &str, detail: Option<String>, request_id: Option<String>) -> Self {
+    Self {
+      kind: "about:blank".to_string(),
+      title: title.to_string(),
+      status,
+      detail,
+      request_id,
+      errors: vec![],
+    }
+  }
+}
+
+
+/// Ошибка проверки одного поля запроса.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FieldErrorResp {
+  /// Имя поля в теле или в параметрах запроса.
+  #[schema(example = "first_name")]
+  pub field: String,
+
+  /// Код нарушенного правила: `length`, `range`, `blank`, `name`, `nickname`,
+  /// `log_module`, `log_level`, `date_order`, `common_password`, `contains_nickname`
+  /// или `unknown_field` (лишнее поле в строгом режиме).
+  #[schema(example = "length")]
+  pub code: String,
+
+  /// Описание ошибки на языке из заголовка `Accept-Language` (русский или английский).
+  #[schema(example = "Длина должна быть от 1 до 64 символов.")]
+  pub message: String,
+}
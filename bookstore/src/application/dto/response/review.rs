@@ -0,0 +1,68 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::application::entities::review::Review;
+
+
+/// Информация об одном отзыве.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FullReviewResp {
+  /// Уникальный идентификатор.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub id: Uuid,
+
+  /// Идентификатор книги.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub book_id: Uuid,
+
+  /// Идентификатор автора отзыва.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub user_id: Uuid,
+
+  /// Оценка от 1 до 5.
+  #[schema(example = 5)]
+  pub rating: i16,
+
+  /// Текст отзыва.
+  #[schema(example = "Отличная книга!")]
+  pub text: String,
+
+  /// Время создания.
+  #[schema(example = "2024-01-01T10:00:00+0400")]
+  pub date_created: DateTime<Local>,
+
+  /// Время последнего изменения.
+  #[schema(example = "2024-01-02T10:00:00+0400")]
+  pub date_updated: Option<DateTime<Local>>,
+
+  /// Скрыт ли отзыв администратором.
+  pub hidden: bool,
+}
+
+impl FullReviewResp {
+  pub fn new(value: Review) -> Self {
+    Self {
+      id: value.id,
+      book_id: value.book_id,
+      user_id: value.user_id,
+      rating: value.rating,
+      text: value.text,
+      date_created: value.date_created,
+      date_updated: value.date_updated,
+      hidden: value.hidden,
+    }
+  }
+}
+
+
+/// Информация о нескольких отзывах.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReviewListResp(pub Vec<FullReviewResp>);
+
+impl ReviewListResp {
+  pub fn new(value: Vec<Review>) -> Self {
+    Self(value.into_iter().map(FullReviewResp::new).collect())
+  }
+}
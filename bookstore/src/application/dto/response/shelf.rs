@@ -0,0 +1,127 @@
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::application::dto::response::book::MinBookResp;
+use crate::application::entities::book::Book;
+use crate::application::entities::shelf::{Shelf, ShelfEntry, ShelfKind, YearReadingStats};
+
+
+/// Книга на полке.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ShelfEntryResp {
+  /// Информация о книге.
+  pub book: MinBookResp,
+
+  /// Время добавления на полку.
+  #[schema(example = "2024-01-01T10:00:00+0400")]
+  pub date_added: DateTime<Local>,
+
+  /// Дата начала чтения.
+  #[schema(example = "2024-01-01")]
+  pub date_started: Option<NaiveDate>,
+
+  /// Дата окончания чтения.
+  #[schema(example = "2024-01-15")]
+  pub date_finished: Option<NaiveDate>,
+
+  /// Прогресс в процентах.
+  #[schema(example = 42)]
+  pub progress_percent: Option<i16>,
+
+  /// Текущая страница.
+  #[schema(example = 120)]
+  pub progress_page: Option<i32>,
+}
+
+impl ShelfEntryResp {
+  pub fn new(db_entry: ShelfEntry, db_book: Book) -> Self {
+    Self {
+      book: MinBookResp::new(db_book),
+      date_added: db_entry.date_added,
+      date_started: db_entry.date_started,
+      date_finished: db_entry.date_finished,
+      progress_percent: db_entry.progress_percent,
+      progress_page: db_entry.progress_page,
+    }
+  }
+}
+
+
+/// Информация об одной полке.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FullShelfResp {
+  /// Уникальный идентификатор.
+  #[schema(example = "6d786a4c-7262-439d-bfa3-7d8e6327bfd1")]
+  pub id: Uuid,
+
+  /// Тип полки.
+  #[schema(example = ShelfKind::Reading)]
+  pub kind: ShelfKind,
+
+  /// Название.
+  #[schema(example = "Читаю")]
+  pub name: String,
+
+  /// Видна ли полка другим пользователям.
+  pub public: bool,
+
+  /// Книги на полке.
+  pub entries: Vec<ShelfEntryResp>,
+}
+
+impl FullShelfResp {
+  pub fn new(db_shelf: Shelf, entries: Vec<ShelfEntryResp>) -> Self {
+    Self {
+      id: db_shelf.id,
+      kind: db_shelf.kind,
+      name: db_shelf.name,
+      public: db_shelf.public,
+      entries,
+    }
+  }
+}
+
+
+/// Информация о нескольких полках.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ShelfListResp(pub Vec<FullShelfResp>);
+
+
+/// Статистика чтения за один год.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct YearReadingStatsResp {
+  /// Год.
+  #[schema(example = 2024)]
+  pub year: i32,
+
+  /// Количество книг, начатых в этом году.
+  #[schema(example = 14)]
+  pub books_started: i64,
+
+  /// Количество книг, прочитанных в этом году.
+  #[schema(example = 12)]
+  pub books_finished: i64,
+}
+
+impl YearReadingStatsResp {
+  pub fn new(value: YearReadingStats) -> Self {
+    Self {
+      year: value.year,
+      books_started: value.books_started,
+      books_finished: value.books_finished,
+    }
+  }
+}
+
+
+/// Статистика чтения по годам.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReadingStatsResp(pub Vec<YearReadingStatsResp>);
+
+impl ReadingStatsResp {
+  pub fn new(value: Vec<YearReadingStats>) -> Self {
+    Self(value.into_iter().map(YearReadingStatsResp::new).collect())
+  }
+}
@@ -0,0 +1,58 @@
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+
+
+/// Данные для добавления аккаунта в приложение-аутентификатор.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorSetupResp {
+  /// Секрет в base32, для ввода вручную.
+  #[schema(example = "JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP")]
+  pub secret: String,
+
+  /// URI для QR-кода.
+  #[schema(example = "otpauth://totp/Bookstore:username?secret=JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP&issuer=Bookstore&algorithm=SHA1&digits=6&period=30")]
+  pub otpauth_uri: String,
+}
+
+/// Двухфакторная аутентификация подключена.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorEnabledResp {
+  /// Токен нового сеанса; остальные сеансы завершены.
+  #[schema(example = "jwt")]
+  pub token: String,
+
+  /// Одноразовые коды восстановления. Показываются только один раз.
+  #[schema(example = json!(["k3xq-7fpa", "m2nb-x4rt"]))]
+  pub recovery_codes: Vec<String>,
+}
+
+/// Новые коды восстановления; прежние больше не действуют.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RecoveryCodesResp {
+  /// Одноразовые коды восстановления. Показываются только один раз.
+  #[schema(example = json!(["k3xq-7fpa", "m2nb-x4rt"]))]
+  pub recovery_codes: Vec<String>,
+}
+
+/// Состояние двухфакторной аутентификации пользователя.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorStatusResp {
+  /// Для входа нужен код.
+  pub enabled: bool,
+
+  /// Сколько кодов восстановления еще не использовано.
+  #[schema(example = 10)]
+  pub recovery_codes_left: i64,
+}
+
+/// Пароль верен, для входа нужен код двухфакторной аутентификации.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LoginChallengeResp {
+  /// Передается в `/api/auth/login/2fa` вместе с кодом.
+  #[schema(example = "jwt")]
+  pub challenge: String,
+
+  /// Через сколько секунд `challenge` перестанет действовать.
+  #[schema(example = 300)]
+  pub expires_in: u64,
+}
@@ -39,6 +39,16 @@ pub struct FullUserResp {
 
   /// Запись с информацией о приостановке аккаунта.
   pub suspended: bool,
+
+  /// Адрес электронной почты.
+  #[schema(example = "vasya@example.com")]
+  pub email: Option<String>,
+
+  /// Адрес подтвержден владельцем.
+  pub email_verified: bool,
+
+  /// Для входа нужен код двухфакторной аутентификации.
+  pub two_factor_enabled: bool,
 }
 
 impl FullUserResp {
@@ -52,6 +62,9 @@ impl FullUserResp {
       date_registered: value.date_registered,
       role: value.role,
       suspended: value.suspended,
+      email: value.email,
+      email_verified: value.email_verified,
+      two_factor_enabled: value.totp_enabled,
     }
   }
 }
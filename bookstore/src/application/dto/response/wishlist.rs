@@ -0,0 +1,52 @@
+use chrono::{DateTime, Local};
+use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+
+use crate::application::dto::response::book::MinBookResp;
+use crate::application::entities::book::Book;
+use crate::application::entities::wishlist::WishlistItem;
+
+
+/// Книга в списке желаемого.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WishlistItemResp {
+  /// Информация о книге.
+  pub book: MinBookResp,
+
+  /// Текущая цена в копейках.
+  #[schema(example = 49900)]
+  pub price: Option<i32>,
+
+  /// Количество экземпляров в наличии.
+  #[schema(example = 0)]
+  pub stock: i32,
+
+  /// Порог цены для уведомления (в копейках).
+  #[schema(example = 39900)]
+  pub price_threshold: Option<i32>,
+
+  /// Уведомить о поступлении в продажу.
+  pub notify_restock: bool,
+
+  /// Время добавления.
+  #[schema(example = "2024-01-01T10:00:00+0400")]
+  pub date_added: DateTime<Local>,
+}
+
+impl WishlistItemResp {
+  pub fn new(db_item: WishlistItem, db_book: Book) -> Self {
+    Self {
+      price: db_book.price,
+      stock: db_book.stock,
+      book: MinBookResp::new(db_book),
+      price_threshold: db_item.price_threshold,
+      notify_restock: db_item.notify_restock,
+      date_added: db_item.date_added,
+    }
+  }
+}
+
+
+/// Список желаемого.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WishlistResp(pub Vec<WishlistItemResp>);
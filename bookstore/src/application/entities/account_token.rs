@@ -0,0 +1,43 @@
+use chrono::{DateTime, Local};
+use sqlx::{FromRow, Type};
+use uuid::Uuid;
+
+
+#[derive(Debug, PartialEq, Clone, Copy, Type)]
+#[sqlx(type_name = "account_token_purpose", rename_all = "snake_case")]
+pub enum TokenPurpose {
+  /// Confirms that the user owns the address in `email`.
+  VerifyEmail,
+
+  /// Lets the owner of the verified address in `email` set a new password.
+  ResetPassword,
+}
+
+// FromRow macro is a convenience trait for a row-to-object conversion by SQLx.
+// The token itself is only ever mailed, the database keeps its SHA-256.
+#[derive(Debug, Clone, FromRow)]
+pub struct AccountToken {
+  pub id: Uuid,
+  pub user_id: Uuid,
+  pub purpose: TokenPurpose,
+  pub token_hash: String,
+  pub email: String,
+  pub date_created: DateTime<Local>,
+  pub date_expires: DateTime<Local>,
+  pub date_used: Option<DateTime<Local>>,
+}
+
+impl AccountToken {
+  pub fn new(user_id: Uuid, purpose: TokenPurpose, token_hash: String, email: String, date_expires: DateTime<Local>) -> Self {
+    Self {
+      id: Uuid::new_v4(),
+      user_id,
+      purpose,
+      token_hash,
+      email,
+      date_created: Local::now(),
+      date_expires,
+      date_used: None,
+    }
+  }
+}
@@ -2,6 +2,7 @@ use sqlx::FromRow;
 use uuid::Uuid;
 
 use crate::application::dto::request::author::AddAuthorReq;
+use crate::application::util::validation::normalize_name;
 
 
 // FromRow macro is a convenience trait for a row-to-object conversion by SQLx.
@@ -18,9 +19,9 @@ impl Author {
   pub fn new(value: AddAuthorReq) -> Self {
     Self {
       id: Uuid::new_v4(),
-      first_name: value.first_name,
-      last_name: value.last_name,
-      middle_name: value.middle_name,
+      first_name: normalize_name(value.first_name),
+      last_name: normalize_name(value.last_name),
+      middle_name: value.middle_name.map(normalize_name),
     }
   }
 }
\ No newline at end of file
@@ -9,6 +9,10 @@ pub struct Book {
   pub id: Uuid,
   pub title: String,
   pub author_id: Option<Uuid>,
+  pub rating_sum: i64,
+  pub rating_count: i32,
+  pub price: Option<i32>,
+  pub stock: i32,
 }
 
 impl Book {
@@ -17,6 +21,19 @@ impl Book {
       id: Uuid::new_v4(),
       title: value.title,
       author_id: value.author_id,
+      rating_sum: 0,
+      rating_count: 0,
+      price: value.price,
+      stock: value.stock,
+    }
+  }
+
+  /// Average rating of the visible reviews, if there are any.
+  pub fn rating(&self) -> Option<f64> {
+    if self.rating_count > 0 {
+      Some(self.rating_sum as f64 / self.rating_count as f64)
+    } else {
+      None
     }
   }
 }
\ No newline at end of file
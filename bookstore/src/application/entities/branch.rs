@@ -0,0 +1,41 @@
+use chrono::{DateTime, Local};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::application::dto::request::branch::AddBranchReq;
+
+
+// FromRow macro is a convenience trait for a row-to-object conversion by SQLx.
+#[derive(Debug, Clone, FromRow)]
+pub struct Branch {
+  pub id: Uuid,
+  pub name: String,
+  pub address: String,
+  pub date_created: DateTime<Local>,
+}
+
+impl Branch {
+  pub fn new(value: AddBranchReq) -> Self {
+    Self {
+      id: Uuid::new_v4(),
+      name: value.name.trim().to_string(),
+      address: value.address.trim().to_string(),
+      date_created: Local::now(),
+    }
+  }
+}
+
+/// How many copies of a book a branch holds.
+/// Copies without a branch are counted under `branch_id = None`.
+#[derive(Debug, Clone, FromRow)]
+pub struct BranchAvailability {
+  pub book_id: Uuid,
+  pub branch_id: Option<Uuid>,
+  pub branch_name: Option<String>,
+
+  /// Copies on the shelf.
+  pub available: i64,
+
+  /// Copies on the shelf or on loan, i.e. not lost or withdrawn.
+  pub total: i64,
+}
@@ -0,0 +1,88 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::application::dto::request::copy::AddCopyReq;
+
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, ToSchema, Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "copy_status", rename_all = "snake_case")]
+pub enum CopyStatus {
+  /// On the shelf, can be borrowed.
+  Available,
+
+  /// Lent to a user.
+  OnLoan,
+
+  /// Missing from the library.
+  Lost,
+
+  /// Taken out of circulation.
+  Withdrawn,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone, Copy, ToSchema, Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "copy_condition", rename_all = "snake_case")]
+pub enum CopyCondition {
+  New,
+  #[default]
+  Good,
+  Fair,
+  Poor,
+  Damaged,
+}
+
+// FromRow macro is a convenience trait for a row-to-object conversion by SQLx.
+// A physical copy of a book (the book itself is the work).
+#[derive(Debug, Clone, FromRow)]
+pub struct BookCopy {
+  pub id: Uuid,
+  pub book_id: Uuid,
+  pub status: CopyStatus,
+  pub date_added: DateTime<Local>,
+  pub barcode: String,
+  pub condition: CopyCondition,
+  pub branch_id: Option<Uuid>,
+}
+
+impl BookCopy {
+  pub fn new(value: AddCopyReq) -> Self {
+    Self {
+      id: Uuid::new_v4(),
+      book_id: value.book_id,
+      status: CopyStatus::Available,
+      date_added: Local::now(),
+      barcode: value.barcode.trim().to_string(),
+      condition: value.condition,
+      branch_id: value.branch_id,
+    }
+  }
+}
+
+// FromRow macro is a convenience trait for a row-to-object conversion by SQLx.
+#[derive(Debug, Clone, FromRow)]
+pub struct CopyTransfer {
+  pub id: Uuid,
+  pub copy_id: Uuid,
+  pub from_branch_id: Option<Uuid>,
+  pub to_branch_id: Option<Uuid>,
+  pub user_id: Option<Uuid>,
+  pub date_transferred: DateTime<Local>,
+}
+
+impl CopyTransfer {
+  pub fn new(copy: &BookCopy, to_branch_id: Uuid, user_id: Uuid) -> Self {
+    Self {
+      id: Uuid::new_v4(),
+      copy_id: copy.id,
+      from_branch_id: copy.branch_id,
+      to_branch_id: Some(to_branch_id),
+      user_id: Some(user_id),
+      date_transferred: Local::now(),
+    }
+  }
+}
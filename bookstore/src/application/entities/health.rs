@@ -0,0 +1,15 @@
+/// Snapshot of the database connection pool.
+#[derive(Debug, Clone, Copy)]
+pub struct PoolStats {
+  /// Open connections, idle or in use.
+  pub size: u32,
+  pub idle: usize,
+}
+
+/// A migration embedded into the binary and whether the database has it.
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+  pub version: i64,
+  pub description: String,
+  pub applied: bool,
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, ToSchema, Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "hold_status", rename_all = "snake_case")]
+pub enum HoldStatus {
+  /// Waiting in the queue for a copy.
+  Waiting,
+
+  /// The user has borrowed a copy.
+  Fulfilled,
+
+  /// Cancelled by the user.
+  Cancelled,
+}
+
+// FromRow macro is a convenience trait for a row-to-object conversion by SQLx.
+#[derive(Debug, Clone, FromRow)]
+pub struct Hold {
+  pub id: Uuid,
+  pub book_id: Uuid,
+  pub user_id: Uuid,
+  pub date_placed: DateTime<Local>,
+  pub status: HoldStatus,
+}
+
+impl Hold {
+  pub fn new(book_id: Uuid, user_id: Uuid) -> Self {
+    Self {
+      id: Uuid::new_v4(),
+      book_id,
+      user_id,
+      date_placed: Local::now(),
+      status: HoldStatus::Waiting,
+    }
+  }
+}
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Local};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+
+// FromRow macro is a convenience trait for a row-to-object conversion by SQLx.
+#[derive(Debug, Clone, FromRow)]
+pub struct Loan {
+  pub id: Uuid,
+  pub copy_id: Uuid,
+  pub book_id: Uuid,
+  pub user_id: Uuid,
+  pub date_borrowed: DateTime<Local>,
+  pub date_due: DateTime<Local>,
+  pub date_returned: Option<DateTime<Local>>,
+  pub renewals: i32,
+}
+
+impl Loan {
+  pub fn is_overdue(&self) -> bool {
+    self.date_returned.is_none() && self.date_due < Local::now()
+  }
+}
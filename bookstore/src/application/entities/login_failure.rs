@@ -0,0 +1,26 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use utoipa::ToSchema;
+
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, ToSchema, Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "login_key_kind", rename_all = "snake_case")]
+pub enum LoginKeyKind {
+  /// Lowercased nickname, whether such a user exists or not.
+  Nickname,
+
+  /// Address of the client.
+  Ip,
+}
+
+// FromRow macro is a convenience trait for a row-to-object conversion by SQLx.
+#[derive(Debug, Clone, FromRow)]
+pub struct LoginFailure {
+  pub kind: LoginKeyKind,
+  pub key: String,
+  pub failures: i32,
+  pub date_last_failure: DateTime<Local>,
+  pub locked_until: Option<DateTime<Local>>,
+}
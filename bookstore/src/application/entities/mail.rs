@@ -0,0 +1,29 @@
+use chrono::{DateTime, Local};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+
+// FromRow macro is a convenience trait for a row-to-object conversion by SQLx.
+#[derive(Debug, Clone, FromRow)]
+pub struct Mail {
+  pub id: Uuid,
+  pub recipient: String,
+  pub subject: String,
+  pub body: String,
+  pub date_created: DateTime<Local>,
+  /// Set by the relay which delivers the message from the outbox.
+  pub date_sent: Option<DateTime<Local>>,
+}
+
+impl Mail {
+  pub fn new(recipient: String, subject: String, body: String) -> Self {
+    Self {
+      id: Uuid::new_v4(),
+      recipient,
+      subject,
+      body,
+      date_created: Local::now(),
+      date_sent: None,
+    }
+  }
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, ToSchema, Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "notification_kind", rename_all = "snake_case")]
+pub enum NotificationKind {
+  /// A wishlisted book is back in stock.
+  BackInStock,
+
+  /// The price of a wishlisted book dropped below the user's threshold.
+  PriceDrop,
+}
+
+// FromRow macro is a convenience trait for a row-to-object conversion by SQLx.
+// Notifications are created by the database, see `books_notify_wishlists()`.
+#[derive(Debug, Clone, FromRow)]
+pub struct Notification {
+  pub id: Uuid,
+  pub user_id: Uuid,
+  pub kind: NotificationKind,
+  pub book_id: Option<Uuid>,
+  pub book_title: String,
+  pub old_price: Option<i32>,
+  pub new_price: Option<i32>,
+  pub date_created: DateTime<Local>,
+  pub read: bool,
+}
@@ -0,0 +1,34 @@
+use chrono::{DateTime, Local};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::application::dto::request::review::AddReviewReq;
+
+
+// FromRow macro is a convenience trait for a row-to-object conversion by SQLx.
+#[derive(Debug, Clone, FromRow)]
+pub struct Review {
+  pub id: Uuid,
+  pub book_id: Uuid,
+  pub user_id: Uuid,
+  pub rating: i16,
+  pub text: String,
+  pub date_created: DateTime<Local>,
+  pub date_updated: Option<DateTime<Local>>,
+  pub hidden: bool,
+}
+
+impl Review {
+  pub fn new(book_id: Uuid, user_id: Uuid, value: AddReviewReq) -> Self {
+    Self {
+      id: Uuid::new_v4(),
+      book_id,
+      user_id,
+      rating: value.rating,
+      text: value.text,
+      date_created: Local::now(),
+      date_updated: None,
+      hidden: false,
+    }
+  }
+}
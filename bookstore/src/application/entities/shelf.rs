@@ -0,0 +1,95 @@
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Type};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::application::dto::request::shelf::{AddShelfReq, PutShelfEntryReq};
+
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, ToSchema, Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "shelf_kind", rename_all = "snake_case")]
+pub enum ShelfKind {
+  /// Built-in "want to read" shelf.
+  WantToRead,
+
+  /// Built-in "currently reading" shelf.
+  Reading,
+
+  /// Built-in "read" shelf.
+  Read,
+
+  /// Shelf created by the user.
+  Custom,
+}
+
+impl ShelfKind {
+  /// Built-in shelves every user has, along with their names.
+  pub const BUILT_IN: [(ShelfKind, &'static str); 3] = [
+    (ShelfKind::WantToRead, "Хочу прочитать"),
+    (ShelfKind::Reading, "Читаю"),
+    (ShelfKind::Read, "Прочитано"),
+  ];
+
+  pub fn is_built_in(&self) -> bool {
+    *self != ShelfKind::Custom
+  }
+}
+
+// FromRow macro is a convenience trait for a row-to-object conversion by SQLx.
+#[derive(Debug, Clone, FromRow)]
+pub struct Shelf {
+  pub id: Uuid,
+  pub user_id: Uuid,
+  pub kind: ShelfKind,
+  pub name: String,
+  pub public: bool,
+  pub date_created: DateTime<Local>,
+}
+
+impl Shelf {
+  pub fn new(user_id: Uuid, value: AddShelfReq) -> Self {
+    Self {
+      id: Uuid::new_v4(),
+      user_id,
+      kind: ShelfKind::Custom,
+      name: value.name,
+      public: value.public,
+      date_created: Local::now(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ShelfEntry {
+  pub shelf_id: Uuid,
+  pub book_id: Uuid,
+  pub date_added: DateTime<Local>,
+  pub date_started: Option<NaiveDate>,
+  pub date_finished: Option<NaiveDate>,
+  pub progress_percent: Option<i16>,
+  pub progress_page: Option<i32>,
+}
+
+impl ShelfEntry {
+  pub fn new(shelf_id: Uuid, book_id: Uuid, value: PutShelfEntryReq) -> Self {
+    Self {
+      shelf_id,
+      book_id,
+      date_added: Local::now(),
+      date_started: value.date_started,
+      date_finished: value.date_finished,
+      progress_percent: value.progress_percent,
+      progress_page: value.progress_page,
+    }
+  }
+}
+
+/// Reading statistics of a user for one calendar year.
+#[derive(Debug, Clone, FromRow)]
+pub struct YearReadingStats {
+  pub year: i32,
+  pub books_started: i64,
+  pub books_finished: i64,
+}
@@ -5,6 +5,7 @@ use utoipa::ToSchema;
 use sqlx::{FromRow, Type};
 
 use crate::application::dto::request::user::RegisterReq;
+use crate::application::util::validation::normalize_name;
 
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, ToSchema, Type)]
@@ -29,20 +30,37 @@ pub struct User {
   pub date_registered: DateTime<Local>,
   pub role: UserRole,
   pub suspended: bool,
+  /// Tokens issued for another version are no longer accepted.
+  pub session_version: i32,
+  pub email: Option<String>,
+  /// The owner has confirmed the address; only then is it used for recovery.
+  pub email_verified: bool,
+  /// Base32 TOTP secret, set on enrollment.
+  pub totp_secret: Option<String>,
+  /// The enrollment has been confirmed; from then on logins need a code.
+  pub totp_enabled: bool,
+  /// Time step of the last accepted code; codes up to it are no good anymore.
+  pub totp_last_step: Option<i64>,
 }
 
 impl User {
   pub fn new(value: RegisterReq) -> Self {
     Self {
       id: Uuid::new_v4(),
-      first_name: value.first_name,
-      last_name: value.last_name,
-      middle_name: value.middle_name,
+      first_name: normalize_name(value.first_name),
+      last_name: normalize_name(value.last_name),
+      middle_name: value.middle_name.map(normalize_name),
       nickname: value.nickname,
       hashed_password: "".to_string(),
       date_registered: Local::now(),
       role: UserRole::User,
       suspended: false,
+      session_version: 0,
+      email: None,
+      email_verified: false,
+      totp_secret: None,
+      totp_enabled: false,
+      totp_last_step: None,
     }
   }
 }
\ No newline at end of file
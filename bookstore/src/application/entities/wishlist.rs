@@ -0,0 +1,28 @@
+use chrono::{DateTime, Local};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::application::dto::request::wishlist::PutWishlistItemReq;
+
+
+// FromRow macro is a convenience trait for a row-to-object conversion by SQLx.
+#[derive(Debug, Clone, FromRow)]
+pub struct WishlistItem {
+  pub user_id: Uuid,
+  pub book_id: Uuid,
+  pub price_threshold: Option<i32>,
+  pub notify_restock: bool,
+  pub date_added: DateTime<Local>,
+}
+
+impl WishlistItem {
+  pub fn new(user_id: Uuid, book_id: Uuid, value: PutWishlistItemReq) -> Self {
+    Self {
+      user_id,
+      book_id,
+      price_threshold: value.price_threshold,
+      notify_restock: value.notify_restock,
+      date_added: Local::now(),
+    }
+  }
+}
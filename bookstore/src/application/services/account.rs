@@ -0,0 +1,241 @@
+use std::error::Error;
+use std::sync::Arc;
+use chrono::{Duration, Local};
+use uuid::Uuid;
+use validator::ValidationErrors;
+
+use crate::adapters::mail::Mailer;
+use crate::adapters::repositories::account_token::AccountTokenRepository;
+use crate::adapters::repositories::is_unique_violation;
+use crate::adapters::repositories::user::UserRepository;
+use crate::application::dto::request::user::{ForgotPasswordReq, ResetPasswordReq, SetEmailReq, VerifyEmailReq};
+use crate::application::dto::response::user::FullUserResp;
+use crate::application::entities::account_token::{AccountToken, TokenPurpose};
+use crate::application::entities::mail::Mail;
+use crate::application::entities::user::User;
+use crate::application::services::user::UserUpdateResult;
+use crate::application::util::password::{PasswordHashing, PasswordPolicy};
+use crate::application::util::token;
+use crate::config::AuthConfig;
+
+
+pub enum EmailSetResult {
+  /// The verification mail has been queued.
+  Ok(FullUserResp),
+  NotFound,
+  /// Another user has verified the address.
+  AlreadyTaken,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum EmailVerifyResult {
+  Ok,
+  InvalidToken,
+  /// Another user has verified the address in the meantime.
+  AlreadyTaken,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum PasswordRecoveryResult {
+  Ok,
+  InvalidToken,
+  WeakPassword(ValidationErrors),
+  UnexpectedError(Box<dyn Error>),
+}
+
+
+/// Email addresses of the users and the password recovery through them.
+pub struct AccountService
+{
+  user_repo: Arc<UserRepository>,
+  token_repo: Arc<AccountTokenRepository>,
+  mailer: Arc<dyn Mailer>,
+  policy: PasswordPolicy,
+  hashing: PasswordHashing,
+  reset_token_ttl: Duration,
+  email_token_ttl: Duration,
+}
+
+impl AccountService
+{
+  pub fn new(
+    user_repo: Arc<UserRepository>,
+    token_repo: Arc<AccountTokenRepository>,
+    mailer: Arc<dyn Mailer>,
+    config: &AuthConfig,
+  ) -> Self {
+    Self {
+      user_repo,
+      token_repo,
+      mailer,
+      policy: PasswordPolicy::new(config),
+      hashing: PasswordHashing::new(config),
+      reset_token_ttl: Duration::minutes(config.reset_token_ttl_mins),
+      email_token_ttl: Duration::hours(config.email_token_ttl_hours),
+    }
+  }
+
+  /// Replace the user's address with an unverified one and mail it a verification token.
+  #[tracing::instrument(name = "AccountService::set_email", skip_all)]
+  pub async fn set_email(&self, user_id: &Uuid, data: SetEmailReq) -> EmailSetResult {
+    match self.user_repo.get_by_verified_email(&data.email).await {
+      Ok(Some(owner)) if owner.id == *user_id => return EmailSetResult::Ok(FullUserResp::new(owner)),
+      Ok(Some(_)) => return EmailSetResult::AlreadyTaken,
+      Ok(None) => {},
+      Err(e) => return EmailSetResult::UnexpectedError(e),
+    };
+
+    let user = match self.user_repo.update_email(user_id, Some(&data.email)).await {
+      Ok(Some(user)) => user,
+      Ok(None) => return EmailSetResult::NotFound,
+      Err(e) => return EmailSetResult::UnexpectedError(e),
+    };
+
+    // the tokens mailed to the previous address are no good anymore
+    if let Err(e) = self.revoke_tokens(&user.id).await {
+      return EmailSetResult::UnexpectedError(e)
+    }
+
+    match self.send_token(&user, TokenPurpose::VerifyEmail, data.email).await {
+      Ok(_) => EmailSetResult::Ok(FullUserResp::new(user)),
+      Err(e) => EmailSetResult::UnexpectedError(e),
+    }
+  }
+
+  /// Forget the user's address.
+  #[tracing::instrument(name = "AccountService::remove_email", skip_all)]
+  pub async fn remove_email(&self, user_id: &Uuid) -> UserUpdateResult {
+    let user = match self.user_repo.update_email(user_id, None).await {
+      Ok(Some(user)) => user,
+      Ok(None) => return UserUpdateResult::NotFound,
+      Err(e) => return UserUpdateResult::UnexpectedError(e),
+    };
+
+    match self.revoke_tokens(&user.id).await {
+      Ok(_) => UserUpdateResult::Ok(FullUserResp::new(user)),
+      Err(e) => UserUpdateResult::UnexpectedError(e),
+    }
+  }
+
+  /// Mark the address the token was mailed to as verified.
+  #[tracing::instrument(name = "AccountService::verify_email", skip_all)]
+  pub async fn verify_email(&self, data: VerifyEmailReq) -> EmailVerifyResult {
+    let token = match self.token_repo.get_valid(TokenPurpose::VerifyEmail, &token::hash(&data.token)).await {
+      Ok(Some(token)) => token,
+      Ok(None) => return EmailVerifyResult::InvalidToken,
+      Err(e) => return EmailVerifyResult::UnexpectedError(e),
+    };
+
+    match self.token_repo.use_one(&token.id).await {
+      Ok(true) => {},
+      Ok(false) => return EmailVerifyResult::InvalidToken,
+      Err(e) => return EmailVerifyResult::UnexpectedError(e),
+    };
+
+    // nothing is updated if the user has changed the address since
+    match self.user_repo.verify_email(&token.user_id, &token.email).await {
+      Ok(true) => EmailVerifyResult::Ok,
+      Ok(false) => EmailVerifyResult::InvalidToken,
+      Err(e) if is_unique_violation(e.as_ref()) => EmailVerifyResult::AlreadyTaken,
+      Err(e) => EmailVerifyResult::UnexpectedError(e),
+    }
+  }
+
+  /// Mail a password reset token to the owner of the verified address, if there is one.
+  /// The caller learns nothing either way, so that the addresses cannot be probed: the token
+  /// is mailed on a task of its own, or the time it takes would give the owner away.
+  #[tracing::instrument(name = "AccountService::forgot_password", skip_all)]
+  pub async fn forgot_password(self: Arc<Self>, data: ForgotPasswordReq) {
+    let user = match self.user_repo.get_by_verified_email(&data.email).await {
+      Ok(Some(user)) => user,
+      // the repository has logged the error
+      Ok(None) | Err(_) => return,
+    };
+
+    tokio::spawn(async move {
+      let email = user.email.clone().unwrap_or(data.email);
+      if let Err(e) = self.send_token(&user, TokenPurpose::ResetPassword, email).await {
+        log::error!("Failed to mail a password reset token to {}: {}", user.id, e);
+      }
+    });
+  }
+
+  /// Set a new password with a mailed token, ending all sessions of the user.
+  #[tracing::instrument(name = "AccountService::reset_password", skip_all)]
+  pub async fn reset_password(&self, data: ResetPasswordReq) -> PasswordRecoveryResult {
+    let token = match self.token_repo.get_valid(TokenPurpose::ResetPassword, &token::hash(&data.token)).await {
+      Ok(Some(token)) => token,
+      Ok(None) => return PasswordRecoveryResult::InvalidToken,
+      Err(e) => return PasswordRecoveryResult::UnexpectedError(e),
+    };
+
+    let user = match self.user_repo.get_by_id(&token.user_id).await {
+      Ok(Some(user)) => user,
+      Ok(None) => return PasswordRecoveryResult::InvalidToken,
+      Err(e) => return PasswordRecoveryResult::UnexpectedError(e),
+    };
+
+    // a weak password does not use the token up, the user may try another one
+    if let Err(errors) = self.policy.check(&data.new_password, &user.nickname) {
+      return PasswordRecoveryResult::WeakPassword(errors)
+    }
+
+    match self.token_repo.use_one(&token.id).await {
+      Ok(true) => {},
+      Ok(false) => return PasswordRecoveryResult::InvalidToken,
+      Err(e) => return PasswordRecoveryResult::UnexpectedError(e),
+    };
+
+    let hashed_password = match self.hashing.hash(&data.new_password).await {
+      Ok(hashed_password) => hashed_password,
+      Err(e) => return PasswordRecoveryResult::UnexpectedError(e),
+    };
+
+    match self.user_repo.update_password(&user.id, &hashed_password).await {
+      Ok(Some(_)) => {},
+      Ok(None) => return PasswordRecoveryResult::InvalidToken,
+      Err(e) => return PasswordRecoveryResult::UnexpectedError(e),
+    };
+
+    // the other tokens which might have been mailed meanwhile
+    match self.token_repo.revoke_all(&user.id, TokenPurpose::ResetPassword).await {
+      Ok(_) => PasswordRecoveryResult::Ok,
+      Err(e) => PasswordRecoveryResult::UnexpectedError(e),
+    }
+  }
+
+  async fn revoke_tokens(&self, user_id: &Uuid) -> Result<(), Box<dyn Error>> {
+    self.token_repo.revoke_all(user_id, TokenPurpose::VerifyEmail).await?;
+    self.token_repo.revoke_all(user_id, TokenPurpose::ResetPassword).await
+  }
+
+  async fn send_token(&self, user: &User, purpose: TokenPurpose, email: String) -> Result<(), Box<dyn Error>> {
+    let value = token::generate();
+    let ttl = match purpose {
+      TokenPurpose::VerifyEmail => self.email_token_ttl,
+      TokenPurpose::ResetPassword => self.reset_token_ttl,
+    };
+    let record = AccountToken::new(user.id, purpose, token::hash(&value), email.clone(), Local::now() + ttl);
+    self.token_repo.add_one(&record).await?;
+
+    let (subject, body) = match purpose {
+      TokenPurpose::VerifyEmail => (
+        "Подтверждение адреса",
+        format!(
+          "Здравствуйте, {}!\n\nЧтобы подтвердить этот адрес, отправьте код на /api/auth/verify-email:\n\n{}\n\n\
+          Код действует {} ч. Если вы не указывали этот адрес, просто удалите письмо.",
+          user.first_name, value, ttl.num_hours(),
+        ),
+      ),
+      TokenPurpose::ResetPassword => (
+        "Восстановление пароля",
+        format!(
+          "Здравствуйте, {}!\n\nЧтобы задать новый пароль, отправьте код на /api/auth/reset:\n\n{}\n\n\
+          Код действует {} мин. Если вы не запрашивали восстановление, просто удалите письмо.",
+          user.first_name, value, ttl.num_minutes(),
+        ),
+      ),
+    };
+    self.mailer.send(Mail::new(email, subject.to_string(), body)).await
+  }
+}
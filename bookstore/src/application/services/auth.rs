@@ -1,14 +1,25 @@
 use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
 use std::sync::Arc;
 use std::error::Error;
+use chrono::{DateTime, Duration, Local};
 use derive_more::Error;
-use bcrypt;
-use regex::Regex;
+use uuid::Uuid;
+use validator::{Validate, ValidationErrors};
 
-use crate::application::dto::request::user::{LoginReq, RegisterReq};
-use crate::application::entities::user::User;
-use crate::adapters::middleware::jwt::JwtClaims;
+use crate::application::dto::request::login_failure::GetLoginFailureListReq;
+use crate::application::dto::request::two_factor::TwoFactorLoginReq;
+use crate::application::dto::request::user::{ChangePasswordReq, DeleteMeReq, LoginReq, RegisterReq};
+use crate::application::dto::response::login_failure::LoginFailureListResp;
+use crate::application::entities::login_failure::LoginKeyKind;
+use crate::application::entities::user::{User, UserRole};
+use crate::adapters::middleware::jwt::{JwtClaims, LoginChallengeClaims};
+use crate::adapters::repositories::is_unique_violation;
+use crate::adapters::repositories::login_failure::LoginFailureRepository;
 use crate::adapters::repositories::user::UserRepository;
+use crate::application::services::two_factor::{PasswordCheckResult, TwoFactorService};
+use crate::application::util::password::{PasswordHashing, PasswordPolicy, Verification};
+use crate::config::{AuthConfig, LoginConfig};
 
 
 #[derive(Debug, Clone, Error)]
@@ -16,6 +27,7 @@ pub enum RegistrationError {
   AlreadyExists,
   UnexpectedError,
   BadRequest,
+  WeakPassword(#[error(not(source))] ValidationErrors),
 }
 
 impl Display for RegistrationError {
@@ -24,64 +36,127 @@ impl Display for RegistrationError {
       RegistrationError::AlreadyExists => write!(f, "User with this nickname already exists"),
       RegistrationError::UnexpectedError => write!(f, "Internal error"),
       RegistrationError::BadRequest => write!(f, "Bad request format"),
+      RegistrationError::WeakPassword(_) => write!(f, "The password does not follow the policy"),
     }
   }
 }
 
-#[derive(Debug, Clone, Error)]
-pub enum LoginError {
+pub enum PasswordResetResult {
+  Ok,
   UserNotFound,
-  UnexpectedError,
+  WeakPassword(ValidationErrors),
+  UnexpectedError(Box<dyn Error>),
 }
 
-impl Display for LoginError {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    match self {
-      LoginError::UserNotFound => write!(f, "User not found"),
-      LoginError::UnexpectedError => write!(f, "Internal error"),
-    }
-  }
+pub enum SessionCheckResult {
+  Ok,
+  NotFound,
+  Suspended,
+  Revoked,
+  /// An administrator without two-factor authentication, which the configuration requires.
+  TwoFactorRequired,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum PasswordChangeResult {
+  /// The token of the new session; the others are ended.
+  Ok(String),
+  NotFound,
+  WrongPassword,
+  WeakPassword(ValidationErrors),
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum AccountDeleteResult {
+  Ok,
+  NotFound,
+  WrongPassword,
+  HasLoans,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum LoginResult {
+  Ok(String),
+  /// The password is right, but the user has to send a code with this challenge too.
+  Challenge(String),
+  WrongCredentials,
+  /// Too many failed attempts; the next one is accepted in that many seconds.
+  Throttled(i64),
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum LoginFailureListFetchResult {
+  Ok(LoginFailureListResp),
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum LoginUnlockResult {
+  Ok,
+  NotFound,
+  UnexpectedError(Box<dyn Error>),
 }
 
 
 pub struct AuthService
 {
   user_repo: Arc<UserRepository>,
+  failure_repo: Arc<LoginFailureRepository>,
+  two_factor: Arc<TwoFactorService>,
+  jwt_secret: String,
+  challenge_ttl_secs: u64,
+  policy: PasswordPolicy,
+  hashing: PasswordHashing,
+  throttling: LoginConfig,
 }
 
 impl AuthService
 {
-  pub fn new(user_repo: Arc<UserRepository>) -> Self {
+  pub fn new(
+    user_repo: Arc<UserRepository>,
+    failure_repo: Arc<LoginFailureRepository>,
+    two_factor: Arc<TwoFactorService>,
+    config: &AuthConfig,
+    throttling: &LoginConfig,
+  ) -> Self {
     Self {
-      user_repo
+      user_repo,
+      failure_repo,
+      two_factor,
+      jwt_secret: config.secret.clone(),
+      challenge_ttl_secs: config.login_challenge_ttl_mins * 60,
+      policy: PasswordPolicy::new(config),
+      hashing: PasswordHashing::new(config),
+      throttling: throttling.clone(),
     }
   }
 
-  fn check_nickname(nickname: &String) -> bool {
-    let re = Regex::new(r"^[a-zA-Z0-9.\-_]+$").unwrap();
-    re.is_match(nickname)
-  }
-
-  fn check_password(password: &String) -> bool {
-    let re = Regex::new(r"^.+$").unwrap();
-    re.is_match(password)
+  #[tracing::instrument(name = "AuthService::register", skip_all)]
+  pub async fn register(&self, data: RegisterReq) -> Result<String, RegistrationError>
+  {
+    let user = self.add_user(data, UserRole::User).await?;
+    let claims = JwtClaims::new(user.id, user.role, user.session_version);
+    Ok(claims.to_token(&self.jwt_secret))
   }
 
-  fn check_name(name: &String) -> bool {
-    let re = Regex::new(r"^[a-zA-Zа-яА-Я]+$").unwrap();
-    re.is_match(name)
+  /// Register a user with the `Admin` role; used by the command line tools.
+  #[tracing::instrument(name = "AuthService::register_admin", skip_all)]
+  pub async fn register_admin(&self, data: RegisterReq) -> Result<User, RegistrationError>
+  {
+    self.add_user(data, UserRole::Admin).await
   }
 
-  pub async fn register(&self, data: RegisterReq) -> Result<String, RegistrationError>
+  async fn add_user(&self, data: RegisterReq, role: UserRole) -> Result<User, RegistrationError>
   {
-    if !Self::check_nickname(&data.nickname)
-      || !Self::check_password(&data.password)
-      || !Self::check_name(&data.first_name)
-      || !Self::check_name(&data.last_name)
-    {
+    // the routes have checked it already, the command line tools have not
+    if data.validate().is_err() {
       return Err(RegistrationError::BadRequest);
     }
 
+    if let Err(errors) = self.policy.check(&data.password, &data.nickname) {
+      return Err(RegistrationError::WeakPassword(errors));
+    }
+
+    #[allow(clippy::single_match)]
     match self.user_repo.get_by_nickname(&data.nickname).await {
       Ok(user) => match user {
         Some(_) => return Err(RegistrationError::AlreadyExists),
@@ -90,44 +165,287 @@ impl AuthService
       Err(_) => return Err(RegistrationError::UnexpectedError),
     };
 
-    let hashed_password = match bcrypt::hash(data.password.clone(), 5) {
+    let hashed_password = match self.hashing.hash(&data.password).await {
       Ok(hashed_password) => hashed_password,
       Err(_) => return Err(RegistrationError::UnexpectedError),
     };
 
     let mut new_user = User::new(data);
     new_user.hashed_password = hashed_password;
+    new_user.role = role;
 
+    // the check above is only a shortcut, a concurrent registration
+    // of the same nickname is caught by the unique index
     match self.user_repo.add_one(new_user.clone()).await {
-      Ok(_) => {
-        let claims = JwtClaims::new(new_user.id, new_user.role);
-        Ok(claims.to_token())
-      },
+      Ok(_) => Ok(new_user),
+      Err(e) if is_unique_violation(e.as_ref()) => Err(RegistrationError::AlreadyExists),
       Err(_) => {
         Err(RegistrationError::UnexpectedError)
       }
     }
   }
 
-  pub async fn login(&self, data: LoginReq) -> Result<Option<String>, Box<dyn Error>> {
+  /// Set a new password for the user and end their sessions; used by the command line tools.
+  #[tracing::instrument(name = "AuthService::reset_password", skip_all)]
+  pub async fn reset_password(&self, nickname: &String, password: &str) -> PasswordResetResult {
+    let user = match self.user_repo.get_by_nickname(nickname).await {
+      Ok(Some(user)) => user,
+      Ok(None) => return PasswordResetResult::UserNotFound,
+      Err(e) => return PasswordResetResult::UnexpectedError(e),
+    };
+
+    if let Err(errors) = self.policy.check(password, &user.nickname) {
+      return PasswordResetResult::WeakPassword(errors)
+    }
+
+    let hashed_password = match self.hashing.hash(password).await {
+      Ok(hashed_password) => hashed_password,
+      Err(e) => return PasswordResetResult::UnexpectedError(e),
+    };
+
+    match self.user_repo.update_password(&user.id, &hashed_password).await {
+      Ok(Some(_)) => PasswordResetResult::Ok,
+      Ok(None) => PasswordResetResult::UserNotFound,
+      Err(e) => PasswordResetResult::UnexpectedError(e),
+    }
+  }
+
+  /// Log the user in, unless there have been too many failed attempts
+  /// with the nickname or from the client's address lately.
+  #[tracing::instrument(name = "AuthService::login", skip_all)]
+  pub async fn login(&self, data: LoginReq, client_ip: Option<IpAddr>) -> LoginResult {
+    let nickname = data.nickname.to_lowercase();
+    let ip = client_ip.map(|ip| ip.to_string());
+
+    // a locked nickname is not even checked, so that the lockout tells nothing about the password
+    match self.failure_repo.get_locked_until(&nickname, ip.as_deref()).await {
+      Ok(Some(until)) => return LoginResult::Throttled(retry_after(until)),
+      Ok(None) => {},
+      Err(e) => return LoginResult::UnexpectedError(e),
+    };
+
+    let mut keys = vec![(LoginKeyKind::Nickname, nickname)];
+    if let Some(ip) = ip {
+      keys.push((LoginKeyKind::Ip, ip));
+    }
+
     let user = match self.user_repo.get_by_nickname(&data.nickname).await {
-      Ok(user) => match user {
-        Some(user) => user,
-        None => return Ok(None),
+      Ok(Some(user)) => user,
+      Ok(None) => {
+        if let Err(e) = self.hashing.verify_dummy(&data.password).await {
+          return LoginResult::UnexpectedError(e)
+        }
+        return self.add_failure(&keys).await
       },
-      Err(e) => return Err(e),
+      Err(e) => return LoginResult::UnexpectedError(e),
+    };
+
+    let verification = match self.hashing.verify(&data.password, &user.hashed_password).await {
+      Ok(verification) => verification,
+      Err(e) => return LoginResult::UnexpectedError(e),
+    };
+    if verification == Verification::Invalid {
+      return self.add_failure(&keys).await
+    }
+
+    // the password is at hand only now, so this is the time to upgrade its hash
+    if verification == Verification::Outdated {
+      match self.hashing.hash(&data.password).await {
+        Ok(new_hash) => {
+          if let Err(e) = self.user_repo.upgrade_password_hash(&user.id, &user.hashed_password, &new_hash).await {
+            log::warn!("Failed to upgrade the password hash of {}: {}", user.id, e);
+          }
+        },
+        Err(e) => log::warn!("Failed to upgrade the password hash of {}: {}", user.id, e),
+      }
+    }
+
+    // the failures are kept until the code is right too, or the codes could be guessed
+    // in between the logins with a stolen password
+    if user.totp_enabled {
+      let claims = LoginChallengeClaims::new(user.id, user.session_version, self.challenge_ttl_secs);
+      return LoginResult::Challenge(claims.to_token(&self.jwt_secret))
+    }
+
+    self.start_session(&user, &keys).await
+  }
+
+  /// The second step of the login of a user with two-factor authentication.
+  /// The wrong codes are throttled the same way as the wrong passwords.
+  #[tracing::instrument(name = "AuthService::login_2fa", skip_all)]
+  pub async fn login_2fa(&self, data: TwoFactorLoginReq, client_ip: Option<IpAddr>) -> LoginResult {
+    let claims = match LoginChallengeClaims::from_token(data.challenge, &self.jwt_secret) {
+      Some(claims) => claims,
+      None => return LoginResult::WrongCredentials,
+    };
+    let user_id = match claims.user_id() {
+      Some(user_id) => user_id,
+      None => return LoginResult::WrongCredentials,
+    };
+    let user = match self.user_repo.get_by_id(&user_id).await {
+      Ok(Some(user)) if user.session_version == claims.ver => user,
+      // the password has been changed or the sessions ended since the challenge was issued
+      Ok(_) => return LoginResult::WrongCredentials,
+      Err(e) => return LoginResult::UnexpectedError(e),
+    };
+
+    let nickname = user.nickname.to_lowercase();
+    let ip = client_ip.map(|ip| ip.to_string());
+    match self.failure_repo.get_locked_until(&nickname, ip.as_deref()).await {
+      Ok(Some(until)) => return LoginResult::Throttled(retry_after(until)),
+      Ok(None) => {},
+      Err(e) => return LoginResult::UnexpectedError(e),
     };
 
-    match bcrypt::verify(&data.password, &user.hashed_password) {
-      Err(e) => Err(Box::new(e)),
-      Ok(b) => {
-        if b {
-          let claims = JwtClaims::new(user.id, user.role);
-          Ok(Some(claims.to_token()))
-        } else {
-          Ok(None)
+    let mut keys = vec![(LoginKeyKind::Nickname, nickname)];
+    if let Some(ip) = ip {
+      keys.push((LoginKeyKind::Ip, ip));
+    }
+
+    match self.two_factor.check_code(&user, &data.code).await {
+      Ok(true) => self.start_session(&user, &keys).await,
+      Ok(false) => self.add_failure(&keys).await,
+      Err(e) => LoginResult::UnexpectedError(e),
+    }
+  }
+
+  /// The token of a new session after a successful login.
+  async fn start_session(&self, user: &User, keys: &[(LoginKeyKind, String)]) -> LoginResult {
+    // the address may be shared with someone still guessing, so only the nickname is forgiven
+    if let Err(e) = self.failure_repo.delete_one(LoginKeyKind::Nickname, &keys[0].1).await {
+      return LoginResult::UnexpectedError(e)
+    }
+
+    let claims = JwtClaims::new(user.id, user.role.clone(), user.session_version);
+    LoginResult::Ok(claims.to_token(&self.jwt_secret))
+  }
+
+  /// Count a failed login for every key, delaying the next attempt as configured.
+  async fn add_failure(&self, keys: &[(LoginKeyKind, String)]) -> LoginResult {
+    let window = Duration::minutes(self.throttling.lockout_mins);
+    for (kind, key) in keys {
+      let failures = match self.failure_repo.add_failure(*kind, key, window).await {
+        Ok(failures) => failures,
+        Err(e) => return LoginResult::UnexpectedError(e),
+      };
+
+      let (free_attempts, threshold) = match kind {
+        LoginKeyKind::Nickname => (self.throttling.free_attempts, self.throttling.lockout_threshold),
+        LoginKeyKind::Ip => (self.throttling.ip_free_attempts, self.throttling.ip_lockout_threshold),
+      };
+      if let Some(delay) = self.backoff(failures, free_attempts, threshold) {
+        if failures == threshold {
+          log::warn!("Logins with {:?} {} are locked out after {} failures", kind, key, failures);
+        }
+        if let Err(e) = self.failure_repo.lock(*kind, key, Local::now() + delay).await {
+          return LoginResult::UnexpectedError(e)
         }
       }
     }
+    LoginResult::WrongCredentials
   }
-}
\ No newline at end of file
+
+  /// The delay after the failure number `failures` in a row: none for the free attempts,
+  /// then doubling from the base one, and the lockout from the threshold on.
+  fn backoff(&self, failures: i32, free_attempts: i32, threshold: i32) -> Option<Duration> {
+    let lockout = Duration::minutes(self.throttling.lockout_mins);
+    if failures >= threshold {
+      return Some(lockout)
+    }
+    if failures <= free_attempts {
+      return None
+    }
+    let doublings = (failures - free_attempts - 1).min(30) as u32;
+    let delay = Duration::seconds(self.throttling.backoff_base_secs.saturating_mul(1 << doublings));
+    Some(delay.min(lockout))
+  }
+
+  /// The keys with failed logins, for the administrators.
+  #[tracing::instrument(name = "AuthService::get_login_failures", skip_all)]
+  pub async fn get_login_failures(&self, query: GetLoginFailureListReq) -> LoginFailureListFetchResult {
+    match self.failure_repo.get_list(query.page, query.size, query.locked_only).await {
+      Ok(failures) => LoginFailureListFetchResult::Ok(LoginFailureListResp::new(failures)),
+      Err(e) => LoginFailureListFetchResult::UnexpectedError(e),
+    }
+  }
+
+  /// Forget the failed logins with the key, lifting its lockout.
+  #[tracing::instrument(name = "AuthService::unlock_login", skip_all)]
+  pub async fn unlock_login(&self, kind: LoginKeyKind, key: &str) -> LoginUnlockResult {
+    let key = match kind {
+      LoginKeyKind::Nickname => key.to_lowercase(),
+      LoginKeyKind::Ip => key.to_string(),
+    };
+    match self.failure_repo.delete_one(kind, &key).await {
+      Ok(true) => LoginUnlockResult::Ok,
+      Ok(false) => LoginUnlockResult::NotFound,
+      Err(e) => LoginUnlockResult::UnexpectedError(e),
+    }
+  }
+
+  /// Check that the token's user may still use it.
+  #[tracing::instrument(name = "AuthService::check_session", skip_all)]
+  pub async fn check_session(&self, user_id: &Uuid, session_version: i32) -> SessionCheckResult {
+    match self.user_repo.get_by_id(user_id).await {
+      Ok(Some(user)) if user.suspended => SessionCheckResult::Suspended,
+      Ok(Some(user)) if user.session_version != session_version => SessionCheckResult::Revoked,
+      Ok(Some(user)) if self.two_factor.is_required(&user) && !user.totp_enabled => SessionCheckResult::TwoFactorRequired,
+      Ok(Some(_)) => SessionCheckResult::Ok,
+      Ok(None) => SessionCheckResult::NotFound,
+      Err(e) => SessionCheckResult::UnexpectedError(e),
+    }
+  }
+
+  /// Change the user's own password, ending all of their other sessions.
+  #[tracing::instrument(name = "AuthService::change_password", skip_all)]
+  pub async fn change_password(&self, user_id: &Uuid, data: ChangePasswordReq) -> PasswordChangeResult {
+    let user = match self.two_factor.verified_user(user_id, &data.current_password).await {
+      PasswordCheckResult::Ok(user) => *user,
+      PasswordCheckResult::WrongPassword => return PasswordChangeResult::WrongPassword,
+      PasswordCheckResult::NotFound => return PasswordChangeResult::NotFound,
+      PasswordCheckResult::UnexpectedError(e) => return PasswordChangeResult::UnexpectedError(e),
+    };
+
+    if let Err(errors) = self.policy.check(&data.new_password, &user.nickname) {
+      return PasswordChangeResult::WeakPassword(errors)
+    }
+
+    let hashed_password = match self.hashing.hash(&data.new_password).await {
+      Ok(hashed_password) => hashed_password,
+      Err(e) => return PasswordChangeResult::UnexpectedError(e),
+    };
+
+    match self.user_repo.update_password(&user.id, &hashed_password).await {
+      Ok(Some(session_version)) => {
+        let claims = JwtClaims::new(user.id, user.role, session_version);
+        PasswordChangeResult::Ok(claims.to_token(&self.jwt_secret))
+      },
+      Ok(None) => PasswordChangeResult::NotFound,
+      Err(e) => PasswordChangeResult::UnexpectedError(e),
+    }
+  }
+
+  /// Delete the user's own account, confirmed with the password.
+  #[tracing::instrument(name = "AuthService::delete_account", skip_all)]
+  pub async fn delete_account(&self, user_id: &Uuid, data: DeleteMeReq) -> AccountDeleteResult {
+    let user = match self.two_factor.verified_user(user_id, &data.password).await {
+      PasswordCheckResult::Ok(user) => *user,
+      PasswordCheckResult::WrongPassword => return AccountDeleteResult::WrongPassword,
+      PasswordCheckResult::NotFound => return AccountDeleteResult::NotFound,
+      PasswordCheckResult::UnexpectedError(e) => return AccountDeleteResult::UnexpectedError(e),
+    };
+
+    // the user exists, so nothing is deleted only because of the loans
+    match self.user_repo.delete_one(&user.id).await {
+      Ok(true) => AccountDeleteResult::Ok,
+      Ok(false) => AccountDeleteResult::HasLoans,
+      Err(e) => AccountDeleteResult::UnexpectedError(e),
+    }
+  }
+}
+
+/// Whole seconds until the time, rounded up so that a retry is never early.
+fn retry_after(until: DateTime<Local>) -> i64 {
+  let millis = (until - Local::now()).num_milliseconds();
+  ((millis + 999) / 1000).max(1)
+}
@@ -1,6 +1,7 @@
 use std::error::Error;
 use std::sync::Arc;
 use uuid::Uuid;
+use validator::{Validate, ValidationErrors};
 
 use crate::adapters::repositories::book::BookRepository;
 use crate::adapters::repositories::author::AuthorRepository;
@@ -23,6 +24,7 @@ pub enum AuthorFetchResult {
 
 pub enum AuthorAddResult {
   Created,
+  Invalid(ValidationErrors),
   UnexpectedError(Box<dyn Error>),
 }
 
@@ -45,6 +47,7 @@ impl AuthorService
     }
   }
 
+  #[tracing::instrument(name = "AuthorService::get_by_id", skip_all)]
   pub async fn get_by_id(&self, id: &Uuid) -> AuthorFetchResult {
     match self.author_repo.get_by_id(id).await {
       Ok(author) => match author {
@@ -60,13 +63,20 @@ impl AuthorService
     }
   }
 
+  #[tracing::instrument(name = "AuthorService::add_one", skip_all)]
   pub async fn add_one(&self, data: AddAuthorReq) -> AuthorAddResult {
+    // the same rules as for the names of the users, whoever the caller is
+    if let Err(errors) = data.validate() {
+      return AuthorAddResult::Invalid(errors)
+    }
+
     match self.author_repo.add_one(Author::new(data)).await {
       Ok(_) => AuthorAddResult::Created,
       Err(e) => AuthorAddResult::UnexpectedError(e),
     }
   }
 
+  #[tracing::instrument(name = "AuthorService::get_list", skip_all)]
   pub async fn get_list(&self, params: GetAuthorListReq) -> AuthorListFetchResult {
     match self.author_repo.get_list(params.page, params.size).await {
       Ok(authors) => {
@@ -84,6 +94,7 @@ impl AuthorService
     }
   }
 
+  #[tracing::instrument(name = "AuthorService::delete_one", skip_all)]
   pub async fn delete_one(&self, id: &Uuid) -> AuthorDeleteResult {
     match self.author_repo.delete_one(id).await {
       Ok(_) => AuthorDeleteResult::Ok,
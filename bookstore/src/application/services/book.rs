@@ -1,18 +1,22 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::adapters::repositories::author::AuthorRepository;
 use crate::adapters::repositories::book::BookRepository;
-use crate::application::dto::request::book::{AddBookReq, GetBookListReq};
+use crate::adapters::repositories::copy::CopyRepository;
+use crate::application::dto::request::book::{AddBookReq, GetBookListReq, UpdateInventoryReq};
 use crate::application::dto::response::book::FullBookResp;
 use crate::application::entities::book::Book;
+use crate::application::entities::branch::BranchAvailability;
 
 
 pub struct BookService
 {
   book_repo: Arc<BookRepository>,
   author_repo: Arc<AuthorRepository>,
+  copy_repo: Arc<CopyRepository>,
 }
 
 pub enum BookFetchResult {
@@ -24,6 +28,7 @@ pub enum BookFetchResult {
 pub enum BookAddResult {
   Created,
   AuthorNotFound,
+  BadRequest,
   UnexpectedError(Box<dyn Error>),
 }
 
@@ -32,6 +37,13 @@ pub enum BookListFetchResult {
   UnexpectedError(Box<dyn Error>),
 }
 
+pub enum BookUpdateResult {
+  Ok(FullBookResp),
+  NotFound,
+  BadRequest,
+  UnexpectedError(Box<dyn Error>),
+}
+
 pub enum BookDeleteResult {
   Ok,
   UnexpectedError(Box<dyn Error>),
@@ -39,22 +51,42 @@ pub enum BookDeleteResult {
 
 impl BookService
 {
-  pub fn new(book_repo: Arc<BookRepository>, author_repo: Arc<AuthorRepository>) -> Self {
+  pub fn new(book_repo: Arc<BookRepository>, author_repo: Arc<AuthorRepository>, copy_repo: Arc<CopyRepository>) -> Self {
     Self {
       book_repo,
       author_repo,
+      copy_repo,
     }
   }
 
+  /// Per-branch availability of copies of several books, keyed by book id.
+  async fn get_availability(&self, book_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<BranchAvailability>>, Box<dyn Error>> {
+    let mut res: HashMap<Uuid, Vec<BranchAvailability>> = HashMap::new();
+    for availability in self.copy_repo.get_availability_by_book_ids(book_ids).await? {
+      res.entry(availability.book_id).or_default().push(availability);
+    }
+    Ok(res)
+  }
+
+  /// Build the full response for a single book.
+  async fn to_full_resp(&self, book: Book) -> Result<FullBookResp, Box<dyn Error>> {
+    let author = match book.author_id {
+      Some(author_id) => self.author_repo.get_by_id(&author_id).await?,
+      None => None,
+    };
+    let availability = self.get_availability(&[book.id]).await?
+      .remove(&book.id)
+      .unwrap_or_default();
+    Ok(FullBookResp::new(book, author, availability))
+  }
+
+  #[tracing::instrument(name = "BookService::get_by_id", skip_all)]
   pub async fn get_by_id(&self, id: &Uuid) -> BookFetchResult {
     match self.book_repo.get_by_id(id).await {
       Ok(book) => match book {
-        Some(book) => match book.author_id {
-          Some(author_id) => match self.author_repo.get_by_id(&author_id).await {
-            Ok(author) => BookFetchResult::Ok(FullBookResp::new(book, author)),
-            Err(e) => BookFetchResult::UnexpectedError(e),
-          },
-          None => BookFetchResult::Ok(FullBookResp::new(book, None)),
+        Some(book) => match self.to_full_resp(book).await {
+          Ok(book) => BookFetchResult::Ok(book),
+          Err(e) => BookFetchResult::UnexpectedError(e),
         },
         None => BookFetchResult::NotFound,
       },
@@ -62,9 +94,15 @@ impl BookService
     }
   }
 
+  #[tracing::instrument(name = "BookService::add_one", skip_all)]
   pub async fn add_one(&self, data: AddBookReq) -> BookAddResult {
+    if data.price.is_some_and(|p| p < 0) || data.stock < 0 {
+      return BookAddResult::BadRequest
+    }
+
     if let Some(author_id) = data.author_id {
       match self.author_repo.get_by_id(&author_id).await {
+        #[allow(clippy::redundant_pattern_matching)]
         Ok(author) => {
           if let None = author {
             return BookAddResult::AuthorNotFound
@@ -80,8 +118,9 @@ impl BookService
     }
   }
 
+  #[tracing::instrument(name = "BookService::get_list", skip_all)]
   pub async fn get_list(&self, params: GetBookListReq) -> BookListFetchResult {
-    match self.book_repo.get_list(params.page, params.size).await {
+    match self.book_repo.get_list(params.page, params.size, params.sort).await {
       Ok(books) => {
         let mut authors = vec![];
         for book in books.iter() {
@@ -95,13 +134,45 @@ impl BookService
             None => authors.push(None),
           }
         }
-        let res = books.into_iter().zip(authors).map(|(b, a)| FullBookResp::new(b, a)).collect();
+        let book_ids: Vec<Uuid> = books.iter().map(|b| b.id).collect();
+        let mut availability = match self.get_availability(&book_ids).await {
+          Ok(availability) => availability,
+          Err(e) => return BookListFetchResult::UnexpectedError(e),
+        };
+        let res = books.into_iter()
+          .zip(authors)
+          .map(|(b, a)| {
+            let av = availability.remove(&b.id).unwrap_or_default();
+            FullBookResp::new(b, a, av)
+          })
+          .collect();
         BookListFetchResult::Ok(res)
       },
       Err(e) => BookListFetchResult::UnexpectedError(e),
     }
   }
 
+  #[tracing::instrument(name = "BookService::update_inventory", skip_all)]
+  pub async fn update_inventory(&self, id: &Uuid, data: UpdateInventoryReq) -> BookUpdateResult {
+    if data.price.is_some_and(|p| p < 0) || data.stock.is_some_and(|s| s < 0) {
+      return BookUpdateResult::BadRequest
+    }
+
+    let book = match self.book_repo.update_inventory(id, data.price, data.stock).await {
+      Ok(book) => match book {
+        Some(book) => book,
+        None => return BookUpdateResult::NotFound,
+      },
+      Err(e) => return BookUpdateResult::UnexpectedError(e),
+    };
+
+    match self.to_full_resp(book).await {
+      Ok(book) => BookUpdateResult::Ok(book),
+      Err(e) => BookUpdateResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "BookService::delete_one", skip_all)]
   pub async fn delete_one(&self, id: &Uuid) -> BookDeleteResult {
     match self.book_repo.delete_one(id).await {
       Ok(_) => BookDeleteResult::Ok,
@@ -0,0 +1,170 @@
+use std::error::Error;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::adapters::repositories::branch::BranchRepository;
+use crate::adapters::repositories::copy::CopyRepository;
+use crate::application::dto::request::branch::{AddBranchReq, UpdateBranchReq};
+use crate::application::dto::response::branch::{BranchListResp, FullBranchResp};
+use crate::application::entities::branch::Branch;
+
+
+const MAX_BRANCH_NAME_LENGTH: usize = 128;
+const MAX_BRANCH_ADDRESS_LENGTH: usize = 512;
+
+pub struct BranchService
+{
+  branch_repo: Arc<BranchRepository>,
+  copy_repo: Arc<CopyRepository>,
+}
+
+pub enum BranchFetchResult {
+  Ok(FullBranchResp),
+  NotFound,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum BranchListFetchResult {
+  Ok(BranchListResp),
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum BranchAddResult {
+  Ok(FullBranchResp),
+  BadRequest,
+  AlreadyExists,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum BranchUpdateResult {
+  Ok(FullBranchResp),
+  BadRequest,
+  NotFound,
+  AlreadyExists,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum BranchDeleteResult {
+  Ok,
+  NotFound,
+  /// The branch still holds copies.
+  NotEmpty,
+  UnexpectedError(Box<dyn Error>),
+}
+
+impl BranchService
+{
+  pub fn new(branch_repo: Arc<BranchRepository>, copy_repo: Arc<CopyRepository>) -> Self {
+    Self {
+      branch_repo,
+      copy_repo,
+    }
+  }
+
+  fn check_name(name: &str) -> bool {
+    !name.trim().is_empty() && name.trim().chars().count() <= MAX_BRANCH_NAME_LENGTH
+  }
+
+  fn check_address(address: &str) -> bool {
+    address.trim().chars().count() <= MAX_BRANCH_ADDRESS_LENGTH
+  }
+
+  #[tracing::instrument(name = "BranchService::get_by_id", skip_all)]
+  pub async fn get_by_id(&self, id: &Uuid) -> BranchFetchResult {
+    match self.branch_repo.get_by_id(id).await {
+      Ok(branch) => match branch {
+        Some(branch) => BranchFetchResult::Ok(FullBranchResp::new(branch)),
+        None => BranchFetchResult::NotFound,
+      },
+      Err(e) => BranchFetchResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "BranchService::get_list", skip_all)]
+  pub async fn get_list(&self) -> BranchListFetchResult {
+    match self.branch_repo.get_list().await {
+      Ok(branches) => BranchListFetchResult::Ok(BranchListResp::new(branches)),
+      Err(e) => BranchListFetchResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "BranchService::add_one", skip_all)]
+  pub async fn add_one(&self, data: AddBranchReq) -> BranchAddResult {
+    if !Self::check_name(&data.name) || !Self::check_address(&data.address) {
+      return BranchAddResult::BadRequest
+    }
+
+    match self.branch_repo.get_by_name(data.name.trim()).await {
+      Ok(branch) => if branch.is_some() {
+        return BranchAddResult::AlreadyExists
+      },
+      Err(e) => return BranchAddResult::UnexpectedError(e),
+    }
+
+    match self.branch_repo.add_one(Branch::new(data)).await {
+      Ok(branch) => BranchAddResult::Ok(FullBranchResp::new(branch)),
+      Err(e) => BranchAddResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "BranchService::update_one", skip_all)]
+  pub async fn update_one(&self, id: &Uuid, data: UpdateBranchReq) -> BranchUpdateResult {
+    let branch = match self.branch_repo.get_by_id(id).await {
+      Ok(branch) => match branch {
+        Some(branch) => branch,
+        None => return BranchUpdateResult::NotFound,
+      },
+      Err(e) => return BranchUpdateResult::UnexpectedError(e),
+    };
+
+    let name = match data.name {
+      Some(name) if name.trim() != branch.name => {
+        if !Self::check_name(&name) {
+          return BranchUpdateResult::BadRequest
+        }
+        match self.branch_repo.get_by_name(name.trim()).await {
+          Ok(other) => if other.is_some() {
+            return BranchUpdateResult::AlreadyExists
+          },
+          Err(e) => return BranchUpdateResult::UnexpectedError(e),
+        }
+        name.trim().to_string()
+      },
+      _ => branch.name,
+    };
+
+    let address = match data.address {
+      Some(address) => {
+        if !Self::check_address(&address) {
+          return BranchUpdateResult::BadRequest
+        }
+        address.trim().to_string()
+      },
+      None => branch.address,
+    };
+
+    match self.branch_repo.update_one(id, name, address).await {
+      Ok(branch) => match branch {
+        Some(branch) => BranchUpdateResult::Ok(FullBranchResp::new(branch)),
+        None => BranchUpdateResult::NotFound,
+      },
+      Err(e) => BranchUpdateResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "BranchService::delete_one", skip_all)]
+  pub async fn delete_one(&self, id: &Uuid) -> BranchDeleteResult {
+    match self.copy_repo.count_by_branch_id(id).await {
+      Ok(count) => if count > 0 {
+        return BranchDeleteResult::NotEmpty
+      },
+      Err(e) => return BranchDeleteResult::UnexpectedError(e),
+    }
+
+    match self.branch_repo.delete_one(id).await {
+      Ok(true) => BranchDeleteResult::Ok,
+      Ok(false) => BranchDeleteResult::NotFound,
+      Err(e) => BranchDeleteResult::UnexpectedError(e),
+    }
+  }
+}
@@ -0,0 +1,251 @@
+use std::error::Error;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::adapters::repositories::book::BookRepository;
+use crate::adapters::repositories::branch::BranchRepository;
+use crate::adapters::repositories::copy::CopyRepository;
+use crate::application::dto::request::copy::{AddCopyReq, GetCopyListReq, TransferCopyReq, UpdateCopyReq};
+use crate::application::dto::response::copy::{CopyListResp, CopyTransferListResp, FullCopyResp};
+use crate::application::entities::copy::{BookCopy, CopyStatus, CopyTransfer};
+
+
+const MAX_BARCODE_LENGTH: usize = 64;
+
+pub struct CopyService
+{
+  copy_repo: Arc<CopyRepository>,
+  book_repo: Arc<BookRepository>,
+  branch_repo: Arc<BranchRepository>,
+}
+
+pub enum CopyFetchResult {
+  Ok(FullCopyResp),
+  NotFound,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum CopyListFetchResult {
+  Ok(CopyListResp),
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum CopyAddResult {
+  Ok(FullCopyResp),
+  BadRequest,
+  BookNotFound,
+  BranchNotFound,
+  AlreadyExists,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum CopyUpdateResult {
+  Ok(FullCopyResp),
+  BadRequest,
+  NotFound,
+  AlreadyExists,
+  /// The status cannot be changed while the copy is on loan.
+  Conflict,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum CopyDeleteResult {
+  Ok,
+  NotFound,
+  /// The copy has loan history; it should be withdrawn instead.
+  HasLoans,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum CopyTransferResult {
+  Ok(FullCopyResp),
+  NotFound,
+  BranchNotFound,
+  /// The copy is on loan or already in that branch.
+  Conflict,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum CopyTransferListFetchResult {
+  Ok(CopyTransferListResp),
+  NotFound,
+  UnexpectedError(Box<dyn Error>),
+}
+
+impl CopyService
+{
+  pub fn new(copy_repo: Arc<CopyRepository>, book_repo: Arc<BookRepository>, branch_repo: Arc<BranchRepository>) -> Self {
+    Self {
+      copy_repo,
+      book_repo,
+      branch_repo,
+    }
+  }
+
+  fn check_barcode(barcode: &str) -> bool {
+    !barcode.trim().is_empty() && barcode.trim().chars().count() <= MAX_BARCODE_LENGTH
+  }
+
+  #[tracing::instrument(name = "CopyService::get_by_id", skip_all)]
+  pub async fn get_by_id(&self, id: &Uuid) -> CopyFetchResult {
+    match self.copy_repo.get_by_id(id).await {
+      Ok(copy) => match copy {
+        Some(copy) => CopyFetchResult::Ok(FullCopyResp::new(copy)),
+        None => CopyFetchResult::NotFound,
+      },
+      Err(e) => CopyFetchResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "CopyService::get_list", skip_all)]
+  pub async fn get_list(&self, params: GetCopyListReq) -> CopyListFetchResult {
+    match self.copy_repo.get_list(params.book_id, params.branch_id, params.status, params.page, params.size).await {
+      Ok(copies) => CopyListFetchResult::Ok(CopyListResp::new(copies)),
+      Err(e) => CopyListFetchResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "CopyService::add_one", skip_all)]
+  pub async fn add_one(&self, data: AddCopyReq) -> CopyAddResult {
+    if !Self::check_barcode(&data.barcode) {
+      return CopyAddResult::BadRequest
+    }
+
+    match self.book_repo.get_by_id(&data.book_id).await {
+      Ok(book) => if book.is_none() {
+        return CopyAddResult::BookNotFound
+      },
+      Err(e) => return CopyAddResult::UnexpectedError(e),
+    }
+
+    if let Some(branch_id) = data.branch_id {
+      match self.branch_repo.get_by_id(&branch_id).await {
+        Ok(branch) => if branch.is_none() {
+          return CopyAddResult::BranchNotFound
+        },
+        Err(e) => return CopyAddResult::UnexpectedError(e),
+      }
+    }
+
+    match self.copy_repo.get_by_barcode(data.barcode.trim()).await {
+      Ok(copy) => if copy.is_some() {
+        return CopyAddResult::AlreadyExists
+      },
+      Err(e) => return CopyAddResult::UnexpectedError(e),
+    }
+
+    match self.copy_repo.add_one(BookCopy::new(data)).await {
+      Ok(copy) => CopyAddResult::Ok(FullCopyResp::new(copy)),
+      Err(e) => CopyAddResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "CopyService::update_one", skip_all)]
+  pub async fn update_one(&self, id: &Uuid, data: UpdateCopyReq) -> CopyUpdateResult {
+    let copy = match self.copy_repo.get_by_id(id).await {
+      Ok(copy) => match copy {
+        Some(copy) => copy,
+        None => return CopyUpdateResult::NotFound,
+      },
+      Err(e) => return CopyUpdateResult::UnexpectedError(e),
+    };
+
+    let barcode = match data.barcode {
+      Some(barcode) if barcode.trim() != copy.barcode => {
+        if !Self::check_barcode(&barcode) {
+          return CopyUpdateResult::BadRequest
+        }
+        match self.copy_repo.get_by_barcode(barcode.trim()).await {
+          Ok(other) => if other.is_some() {
+            return CopyUpdateResult::AlreadyExists
+          },
+          Err(e) => return CopyUpdateResult::UnexpectedError(e),
+        }
+        barcode.trim().to_string()
+      },
+      _ => copy.barcode,
+    };
+
+    // lending and returning are the only ways in and out of `on_loan`
+    let status = match data.status {
+      Some(status) if status != copy.status => {
+        if status == CopyStatus::OnLoan || copy.status == CopyStatus::OnLoan {
+          return CopyUpdateResult::Conflict
+        }
+        status
+      },
+      _ => copy.status,
+    };
+
+    let condition = data.condition.unwrap_or(copy.condition);
+
+    match self.copy_repo.update_one(id, barcode, condition, status, copy.status).await {
+      Ok(copy) => match copy {
+        Some(copy) => CopyUpdateResult::Ok(FullCopyResp::new(copy)),
+        None => CopyUpdateResult::Conflict,
+      },
+      Err(e) => CopyUpdateResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "CopyService::delete_one", skip_all)]
+  pub async fn delete_one(&self, id: &Uuid) -> CopyDeleteResult {
+    match self.copy_repo.has_loans(id).await {
+      Ok(has_loans) => if has_loans {
+        return CopyDeleteResult::HasLoans
+      },
+      Err(e) => return CopyDeleteResult::UnexpectedError(e),
+    }
+
+    match self.copy_repo.delete_one(id).await {
+      Ok(true) => CopyDeleteResult::Ok,
+      Ok(false) => CopyDeleteResult::NotFound,
+      Err(e) => CopyDeleteResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "CopyService::transfer", skip_all)]
+  pub async fn transfer(&self, id: &Uuid, user_id: &Uuid, data: TransferCopyReq) -> CopyTransferResult {
+    let copy = match self.copy_repo.get_by_id(id).await {
+      Ok(copy) => match copy {
+        Some(copy) => copy,
+        None => return CopyTransferResult::NotFound,
+      },
+      Err(e) => return CopyTransferResult::UnexpectedError(e),
+    };
+
+    if copy.status == CopyStatus::OnLoan || copy.branch_id == Some(data.branch_id) {
+      return CopyTransferResult::Conflict
+    }
+
+    match self.branch_repo.get_by_id(&data.branch_id).await {
+      Ok(branch) => if branch.is_none() {
+        return CopyTransferResult::BranchNotFound
+      },
+      Err(e) => return CopyTransferResult::UnexpectedError(e),
+    }
+
+    match self.copy_repo.transfer(CopyTransfer::new(&copy, data.branch_id, *user_id)).await {
+      Ok(copy) => match copy {
+        Some(copy) => CopyTransferResult::Ok(FullCopyResp::new(copy)),
+        None => CopyTransferResult::Conflict,
+      },
+      Err(e) => CopyTransferResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "CopyService::get_transfer_list", skip_all)]
+  pub async fn get_transfer_list(&self, id: &Uuid) -> CopyTransferListFetchResult {
+    match self.copy_repo.get_by_id(id).await {
+      Ok(copy) => if copy.is_none() {
+        return CopyTransferListFetchResult::NotFound
+      },
+      Err(e) => return CopyTransferListFetchResult::UnexpectedError(e),
+    }
+
+    match self.copy_repo.get_transfer_list(id).await {
+      Ok(transfers) => CopyTransferListFetchResult::Ok(CopyTransferListResp::new(transfers)),
+      Err(e) => CopyTransferListFetchResult::UnexpectedError(e),
+    }
+  }
+}
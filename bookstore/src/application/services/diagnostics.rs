@@ -0,0 +1,109 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use log::LevelFilter;
+
+use crate::adapters::repositories::health::HealthRepository;
+use crate::application::dto::request::diagnostics::SetLogLevelReq;
+use crate::application::dto::response::diagnostics::{ErrorSummaryListResp, ErrorSummaryResp, LogLevelsResp, PoolStatsResp};
+use crate::config::AppConfig;
+use crate::logging::LogControl;
+
+/// How long a log level override lasts unless the request says otherwise.
+const DEFAULT_OVERRIDE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Overrides are meant for investigations, not as a permanent configuration.
+const MAX_OVERRIDE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+
+pub struct DiagnosticsService
+{
+  log_control: Arc<LogControl>,
+  health_repo: Arc<HealthRepository>,
+  config: Arc<AppConfig>,
+}
+
+pub enum LogLevelSetResult {
+  Ok(LogLevelsResp),
+  InvalidModule,
+  InvalidLevel,
+  InvalidTtl,
+  UnexpectedError(String),
+}
+
+pub enum LogLevelRemoveResult {
+  Ok(LogLevelsResp),
+  NotFound,
+  UnexpectedError(String),
+}
+
+impl DiagnosticsService
+{
+  pub fn new(log_control: Arc<LogControl>, health_repo: Arc<HealthRepository>, config: Arc<AppConfig>) -> Self {
+    Self {
+      log_control,
+      health_repo,
+      config,
+    }
+  }
+
+  pub fn get_log_levels(&self) -> LogLevelsResp {
+    self.log_control.get_levels().into()
+  }
+
+  pub fn set_log_level(&self, data: SetLogLevelReq) -> LogLevelSetResult {
+    if !is_valid_module(&data.module) {
+      return LogLevelSetResult::InvalidModule
+    }
+    let Ok(level) = LevelFilter::from_str(&data.level) else {
+      return LogLevelSetResult::InvalidLevel
+    };
+    let ttl = data.ttl_secs.map_or(DEFAULT_OVERRIDE_TTL, Duration::from_secs);
+    if ttl.is_zero() || ttl > MAX_OVERRIDE_TTL {
+      return LogLevelSetResult::InvalidTtl
+    }
+
+    match self.log_control.set_override(&data.module, level, ttl) {
+      Ok(_) => LogLevelSetResult::Ok(self.get_log_levels()),
+      Err(e) => {
+        log::error!("Error setting the log level of {}: {}", data.module, e);
+        LogLevelSetResult::UnexpectedError(e)
+      }
+    }
+  }
+
+  pub fn remove_log_level(&self, module: &str) -> LogLevelRemoveResult {
+    match self.log_control.remove_override(module) {
+      Ok(true) => LogLevelRemoveResult::Ok(self.get_log_levels()),
+      Ok(false) => LogLevelRemoveResult::NotFound,
+      Err(e) => {
+        log::error!("Error removing the log level override of {}: {}", module, e);
+        LogLevelRemoveResult::UnexpectedError(e)
+      }
+    }
+  }
+
+  pub fn get_pool_stats(&self) -> PoolStatsResp {
+    PoolStatsResp::new(self.health_repo.get_pool_stats(), &self.config.database)
+  }
+
+  /// Effective configuration with the secrets redacted.
+  pub fn get_config(&self) -> AppConfig {
+    self.config.redacted()
+  }
+
+  pub fn get_error_summaries(&self) -> ErrorSummaryListResp {
+    ErrorSummaryListResp(
+      self.log_control.get_error_summaries().into_iter().map(ErrorSummaryResp::from).collect()
+    )
+  }
+}
+
+/// A log target such as `bookstore::adapters` or `actix_web`.
+fn is_valid_module(module: &str) -> bool {
+  !module.is_empty()
+    && module.len() <= 128
+    && module.split("::").all(|part| {
+      !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    })
+}
@@ -0,0 +1,149 @@
+use std::error::Error;
+use std::sync::Arc;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+
+use crate::adapters::repositories::dump::DumpRepository;
+use crate::adapters::repositories::health::HealthRepository;
+
+/// Version of the dump file layout.
+const DUMP_FORMAT: u32 = 1;
+
+/// Every table with data, parents before children.
+const TABLES: [&str; 14] = [
+  "authors",
+  "books",
+  "users",
+  "reviews",
+  "shelves",
+  "shelf_entries",
+  "wishlist_items",
+  "notifications",
+  "branches",
+  "copies",
+  "loans",
+  "holds",
+  "copy_transfers",
+  "recovery_codes",
+];
+
+
+/// Rows of one table, as exported by the database.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DumpTable {
+  pub name: String,
+  pub rows: Box<RawValue>,
+}
+
+/// Contents of the database at some point in time; only loads into a
+/// database with the same schema version.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DataDump {
+  pub format: u32,
+  pub migration_version: Option<i64>,
+  pub exported_at: DateTime<Local>,
+  pub tables: Vec<DumpTable>,
+}
+
+pub struct DumpService
+{
+  dump_repo: Arc<DumpRepository>,
+  health_repo: Arc<HealthRepository>,
+}
+
+pub enum DumpExportResult {
+  Ok(DataDump),
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum DumpImportResult {
+  Ok,
+  UnsupportedFormat(u32),
+  UnknownTable(String),
+  VersionMismatch {
+    dump: Option<i64>,
+    database: Option<i64>,
+  },
+  NotEmpty(Vec<String>),
+  UnexpectedError(Box<dyn Error>),
+}
+
+impl DumpService
+{
+  pub fn new(dump_repo: Arc<DumpRepository>, health_repo: Arc<HealthRepository>) -> Self {
+    Self {
+      dump_repo,
+      health_repo,
+    }
+  }
+
+  async fn get_migration_version(&self) -> Result<Option<i64>, Box<dyn Error>> {
+    Ok(self.health_repo.get_applied_migrations().await?.last().copied())
+  }
+
+  #[tracing::instrument(name = "DumpService::export", skip_all)]
+  pub async fn export(&self) -> DumpExportResult {
+    let migration_version = match self.get_migration_version().await {
+      Ok(version) => version,
+      Err(e) => return DumpExportResult::UnexpectedError(e),
+    };
+
+    let rows = match self.dump_repo.export_tables(&TABLES).await {
+      Ok(rows) => rows,
+      Err(e) => return DumpExportResult::UnexpectedError(e),
+    };
+
+    let mut tables = vec![];
+    for (name, json) in rows {
+      match RawValue::from_string(json) {
+        Ok(rows) => tables.push(DumpTable { name, rows }),
+        Err(e) => return DumpExportResult::UnexpectedError(Box::new(e)),
+      }
+    }
+
+    DumpExportResult::Ok(DataDump {
+      format: DUMP_FORMAT,
+      migration_version,
+      exported_at: Local::now(),
+      tables,
+    })
+  }
+
+  /// Load the dump into an empty database, or into any database with `replace`,
+  /// which deletes everything that was there.
+  #[tracing::instrument(name = "DumpService::import", skip_all)]
+  pub async fn import(&self, dump: DataDump, replace: bool) -> DumpImportResult {
+    if dump.format != DUMP_FORMAT {
+      return DumpImportResult::UnsupportedFormat(dump.format)
+    }
+    if let Some(table) = dump.tables.iter().find(|t| !TABLES.contains(&t.name.as_str())) {
+      return DumpImportResult::UnknownTable(table.name.clone())
+    }
+
+    match self.get_migration_version().await {
+      Ok(version) if version == dump.migration_version => {},
+      Ok(version) => return DumpImportResult::VersionMismatch { dump: dump.migration_version, database: version },
+      Err(e) => return DumpImportResult::UnexpectedError(e),
+    }
+
+    if !replace {
+      match self.dump_repo.get_non_empty_tables(&TABLES).await {
+        Ok(tables) if tables.is_empty() => {},
+        Ok(tables) => return DumpImportResult::NotEmpty(tables),
+        Err(e) => return DumpImportResult::UnexpectedError(e),
+      }
+    }
+
+    // insert in the order of `TABLES`, whatever the order in the file
+    let rows: Vec<(String, String)> = TABLES.iter()
+      .filter_map(|name| dump.tables.iter().find(|t| t.name == *name))
+      .map(|t| (t.name.clone(), t.rows.get().to_string()))
+      .collect();
+
+    match self.dump_repo.import_tables(&TABLES, &rows, replace).await {
+      Ok(()) => DumpImportResult::Ok,
+      Err(e) => DumpImportResult::UnexpectedError(e),
+    }
+  }
+}
@@ -0,0 +1,112 @@
+use std::error::Error;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::adapters::repositories::health::HealthRepository;
+use crate::application::dto::response::health::{HealthStatus, LivenessResp, ReadinessResp, VersionResp};
+use crate::application::entities::health::MigrationStatus;
+use crate::MIGRATOR;
+
+/// How long the readiness probe may wait for the database.
+const READINESS_TIMEOUT: Duration = Duration::from_secs(2);
+
+
+pub struct HealthService
+{
+  health_repo: Arc<HealthRepository>,
+}
+
+pub enum ReadinessCheckResult {
+  Ready(ReadinessResp),
+  NotReady(ReadinessResp),
+}
+
+pub enum MigrationStatusFetchResult {
+  Ok(Vec<MigrationStatus>),
+  UnexpectedError(Box<dyn Error>),
+}
+
+impl HealthService
+{
+  pub fn new(health_repo: Arc<HealthRepository>) -> Self {
+    Self {
+      health_repo,
+    }
+  }
+
+  pub fn check_live(&self) -> LivenessResp {
+    LivenessResp {
+      status: HealthStatus::Ok,
+    }
+  }
+
+  #[tracing::instrument(name = "HealthService::check_ready", skip_all)]
+  pub async fn check_ready(&self) -> ReadinessCheckResult {
+    let started = Instant::now();
+    let database = self.health_repo.ping(READINESS_TIMEOUT).await.is_ok();
+    let database_latency_ms = started.elapsed().as_millis() as u64;
+
+    let pending_migrations = if database {
+      match self.health_repo.get_applied_migrations().await {
+        Ok(applied) => MIGRATOR.iter()
+          .filter(|m| !m.migration_type.is_down_migration() && !applied.contains(&m.version))
+          .map(|m| m.version)
+          .collect(),
+        // without the migrations table the schema cannot be trusted
+        Err(_) => MIGRATOR.iter()
+          .filter(|m| !m.migration_type.is_down_migration())
+          .map(|m| m.version)
+          .collect(),
+      }
+    } else {
+      vec![]
+    };
+
+    let ready = database && pending_migrations.is_empty();
+    let resp = ReadinessResp {
+      status: if ready { HealthStatus::Ok } else { HealthStatus::Unavailable },
+      database,
+      database_latency_ms,
+      pending_migrations,
+    };
+
+    if ready {
+      ReadinessCheckResult::Ready(resp)
+    } else {
+      ReadinessCheckResult::NotReady(resp)
+    }
+  }
+
+  #[tracing::instrument(name = "HealthService::get_version", skip_all)]
+  pub async fn get_version(&self) -> VersionResp {
+    let migration_version = match self.health_repo.get_applied_migrations().await {
+      Ok(applied) => applied.last().copied(),
+      Err(_) => None,
+    };
+
+    VersionResp {
+      version: env!("CARGO_PKG_VERSION").to_string(),
+      git_commit: env!("GIT_COMMIT").to_string(),
+      migration_version,
+    }
+  }
+
+  /// Every migration embedded into the binary, in ascending order.
+  #[tracing::instrument(name = "HealthService::get_migration_status", skip_all)]
+  pub async fn get_migration_status(&self) -> MigrationStatusFetchResult {
+    let applied = match self.health_repo.get_applied_migrations().await {
+      Ok(applied) => applied,
+      Err(e) => return MigrationStatusFetchResult::UnexpectedError(e),
+    };
+
+    let migrations = MIGRATOR.iter()
+      .filter(|m| !m.migration_type.is_down_migration())
+      .map(|m| MigrationStatus {
+        version: m.version,
+        description: m.description.to_string(),
+        applied: applied.contains(&m.version),
+      })
+      .collect();
+    MigrationStatusFetchResult::Ok(migrations)
+  }
+}
@@ -0,0 +1,127 @@
+use std::error::Error;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::adapters::repositories::book::BookRepository;
+use crate::adapters::repositories::copy::CopyRepository;
+use crate::adapters::repositories::hold::HoldRepository;
+use crate::application::dto::request::hold::PlaceHoldReq;
+use crate::application::dto::response::hold::{FullHoldResp, HoldListResp};
+use crate::application::entities::hold::Hold;
+
+
+pub struct HoldService
+{
+  hold_repo: Arc<HoldRepository>,
+  copy_repo: Arc<CopyRepository>,
+  book_repo: Arc<BookRepository>,
+}
+
+pub enum HoldListFetchResult {
+  Ok(HoldListResp),
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum HoldPlaceResult {
+  Ok(FullHoldResp),
+  BookNotFound,
+  AlreadyWaiting,
+  /// A copy can be borrowed right away.
+  CopyAvailable,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum HoldCancelResult {
+  Ok,
+  NotFound,
+  Forbidden,
+  UnexpectedError(Box<dyn Error>),
+}
+
+impl HoldService
+{
+  pub fn new(hold_repo: Arc<HoldRepository>, copy_repo: Arc<CopyRepository>, book_repo: Arc<BookRepository>) -> Self {
+    Self {
+      hold_repo,
+      copy_repo,
+      book_repo,
+    }
+  }
+
+  #[tracing::instrument(name = "HoldService::get_own_list", skip_all)]
+  pub async fn get_own_list(&self, user_id: &Uuid) -> HoldListFetchResult {
+    let holds = match self.hold_repo.get_waiting_list_by_user_id(user_id).await {
+      Ok(holds) => holds,
+      Err(e) => return HoldListFetchResult::UnexpectedError(e),
+    };
+
+    let mut res = Vec::with_capacity(holds.len());
+    for hold in holds {
+      match self.hold_repo.get_position(&hold).await {
+        Ok(position) => res.push(FullHoldResp::new(hold, Some(position))),
+        Err(e) => return HoldListFetchResult::UnexpectedError(e),
+      }
+    }
+
+    HoldListFetchResult::Ok(HoldListResp(res))
+  }
+
+  #[tracing::instrument(name = "HoldService::place", skip_all)]
+  pub async fn place(&self, user_id: &Uuid, data: PlaceHoldReq) -> HoldPlaceResult {
+    match self.book_repo.get_by_id(&data.book_id).await {
+      Ok(book) => if book.is_none() {
+        return HoldPlaceResult::BookNotFound
+      },
+      Err(e) => return HoldPlaceResult::UnexpectedError(e),
+    }
+
+    match self.hold_repo.get_waiting_by_book_id_and_user_id(&data.book_id, user_id).await {
+      Ok(hold) => if hold.is_some() {
+        return HoldPlaceResult::AlreadyWaiting
+      },
+      Err(e) => return HoldPlaceResult::UnexpectedError(e),
+    }
+
+    // holds only make sense when every copy on the shelf is reserved for somebody
+    let available = match self.copy_repo.count_available_by_book_id(&data.book_id).await {
+      Ok(count) => count,
+      Err(e) => return HoldPlaceResult::UnexpectedError(e),
+    };
+    let waiting = match self.hold_repo.count_waiting_by_book_id(&data.book_id).await {
+      Ok(count) => count,
+      Err(e) => return HoldPlaceResult::UnexpectedError(e),
+    };
+    if available > waiting {
+      return HoldPlaceResult::CopyAvailable
+    }
+
+    let hold = match self.hold_repo.add_one(Hold::new(data.book_id, *user_id)).await {
+      Ok(hold) => hold,
+      Err(e) => return HoldPlaceResult::UnexpectedError(e),
+    };
+
+    match self.hold_repo.get_position(&hold).await {
+      Ok(position) => HoldPlaceResult::Ok(FullHoldResp::new(hold, Some(position))),
+      Err(e) => HoldPlaceResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "HoldService::cancel", skip_all)]
+  pub async fn cancel(&self, id: &Uuid, user_id: &Uuid) -> HoldCancelResult {
+    match self.hold_repo.get_by_id(id).await {
+      Ok(hold) => match hold {
+        Some(hold) => if hold.user_id != *user_id {
+          return HoldCancelResult::Forbidden
+        },
+        None => return HoldCancelResult::NotFound,
+      },
+      Err(e) => return HoldCancelResult::UnexpectedError(e),
+    }
+
+    match self.hold_repo.cancel(id).await {
+      Ok(true) => HoldCancelResult::Ok,
+      Ok(false) => HoldCancelResult::NotFound,
+      Err(e) => HoldCancelResult::UnexpectedError(e),
+    }
+  }
+}
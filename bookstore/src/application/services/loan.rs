@@ -0,0 +1,251 @@
+use std::error::Error;
+use std::sync::Arc;
+use chrono::{Duration, Local};
+use uuid::Uuid;
+
+use crate::adapters::repositories::book::BookRepository;
+use crate::adapters::repositories::hold::HoldRepository;
+use crate::adapters::repositories::loan::LoanRepository;
+use crate::adapters::repositories::user::UserRepository;
+use crate::application::dto::request::loan::{CheckoutReq, GetLoanListReq, GetOverdueListReq};
+use crate::application::dto::response::loan::{FullLoanResp, LoanListResp};
+use crate::application::entities::user::UserRole;
+
+
+/// Lending rules of the library.
+#[derive(Debug, Clone)]
+pub struct LoanSettings {
+  /// How long a regular user may keep a copy.
+  pub user_loan_period: Duration,
+
+  /// How long an administrator may keep a copy.
+  pub admin_loan_period: Duration,
+
+  /// How many times a loan may be renewed.
+  pub max_renewals: i32,
+
+  /// Users with this many overdue loans cannot borrow.
+  pub overdue_block_threshold: i64,
+}
+
+impl LoanSettings {
+  pub fn loan_period(&self, role: &UserRole) -> Duration {
+    match role {
+      UserRole::Admin => self.admin_loan_period,
+      UserRole::User => self.user_loan_period,
+    }
+  }
+}
+
+pub struct LoanService
+{
+  loan_repo: Arc<LoanRepository>,
+  hold_repo: Arc<HoldRepository>,
+  book_repo: Arc<BookRepository>,
+  user_repo: Arc<UserRepository>,
+  settings: LoanSettings,
+}
+
+pub enum LoanFetchResult {
+  Ok(FullLoanResp),
+  NotFound,
+  Forbidden,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum LoanListFetchResult {
+  Ok(LoanListResp),
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum LoanCheckoutResult {
+  Ok(FullLoanResp),
+  BookNotFound,
+  UserNotFound,
+  /// The borrower is suspended or has too many overdue loans.
+  Blocked,
+  /// All copies are out or reserved for the users in the hold queue.
+  NoCopyAvailable,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum LoanRenewResult {
+  Ok(FullLoanResp),
+  NotFound,
+  Forbidden,
+  /// The borrower has too many overdue loans.
+  Blocked,
+  /// The loan is returned, renewed too many times, or somebody waits for the book.
+  Conflict,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum LoanReturnResult {
+  Ok(FullLoanResp),
+  NotFound,
+  AlreadyReturned,
+  UnexpectedError(Box<dyn Error>),
+}
+
+impl LoanService
+{
+  pub fn new(
+    loan_repo: Arc<LoanRepository>,
+    hold_repo: Arc<HoldRepository>,
+    book_repo: Arc<BookRepository>,
+    user_repo: Arc<UserRepository>,
+    settings: LoanSettings,
+  ) -> Self {
+    Self {
+      loan_repo,
+      hold_repo,
+      book_repo,
+      user_repo,
+      settings,
+    }
+  }
+
+  #[tracing::instrument(name = "LoanService::get_by_id", skip_all)]
+  pub async fn get_by_id(&self, id: &Uuid, user_id: &Uuid, is_admin: bool) -> LoanFetchResult {
+    match self.loan_repo.get_by_id(id).await {
+      Ok(loan) => match loan {
+        Some(loan) => if !is_admin && loan.user_id != *user_id {
+          LoanFetchResult::Forbidden
+        } else {
+          LoanFetchResult::Ok(FullLoanResp::new(loan))
+        },
+        None => LoanFetchResult::NotFound,
+      },
+      Err(e) => LoanFetchResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "LoanService::get_own_list", skip_all)]
+  pub async fn get_own_list(&self, user_id: &Uuid, data: GetLoanListReq) -> LoanListFetchResult {
+    match self.loan_repo.get_list_by_user_id(user_id, data.active_only, data.page, data.size).await {
+      Ok(loans) => LoanListFetchResult::Ok(LoanListResp::new(loans)),
+      Err(e) => LoanListFetchResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "LoanService::get_overdue_list", skip_all)]
+  pub async fn get_overdue_list(&self, data: GetOverdueListReq) -> LoanListFetchResult {
+    match self.loan_repo.get_overdue_list(data.page, data.size).await {
+      Ok(loans) => LoanListFetchResult::Ok(LoanListResp::new(loans)),
+      Err(e) => LoanListFetchResult::UnexpectedError(e),
+    }
+  }
+
+  async fn is_blocked(&self, user_id: &Uuid) -> Result<bool, Box<dyn Error>> {
+    let overdue = self.loan_repo.count_overdue_by_user_id(user_id).await?;
+    Ok(overdue >= self.settings.overdue_block_threshold)
+  }
+
+  #[tracing::instrument(name = "LoanService::checkout", skip_all)]
+  pub async fn checkout(&self, borrower_id: &Uuid, data: CheckoutReq) -> LoanCheckoutResult {
+    let borrower = match self.user_repo.get_by_id(borrower_id).await {
+      Ok(user) => match user {
+        Some(user) => user,
+        None => return LoanCheckoutResult::UserNotFound,
+      },
+      Err(e) => return LoanCheckoutResult::UnexpectedError(e),
+    };
+
+    if borrower.suspended {
+      return LoanCheckoutResult::Blocked
+    }
+
+    match self.is_blocked(borrower_id).await {
+      Ok(blocked) => if blocked {
+        return LoanCheckoutResult::Blocked
+      },
+      Err(e) => return LoanCheckoutResult::UnexpectedError(e),
+    }
+
+    match self.book_repo.get_by_id(&data.book_id).await {
+      Ok(book) => if book.is_none() {
+        return LoanCheckoutResult::BookNotFound
+      },
+      Err(e) => return LoanCheckoutResult::UnexpectedError(e),
+    }
+
+    let date_due = Local::now() + self.settings.loan_period(&borrower.role);
+    match self.loan_repo.checkout(&data.book_id, borrower_id, date_due).await {
+      Ok(loan) => match loan {
+        Some(loan) => LoanCheckoutResult::Ok(FullLoanResp::new(loan)),
+        None => LoanCheckoutResult::NoCopyAvailable,
+      },
+      Err(e) => LoanCheckoutResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "LoanService::renew", skip_all)]
+  pub async fn renew(&self, id: &Uuid, user_id: &Uuid, is_admin: bool) -> LoanRenewResult {
+    let loan = match self.loan_repo.get_by_id(id).await {
+      Ok(loan) => match loan {
+        Some(loan) => loan,
+        None => return LoanRenewResult::NotFound,
+      },
+      Err(e) => return LoanRenewResult::UnexpectedError(e),
+    };
+
+    if !is_admin && loan.user_id != *user_id {
+      return LoanRenewResult::Forbidden
+    }
+
+    if loan.date_returned.is_some() || loan.renewals >= self.settings.max_renewals {
+      return LoanRenewResult::Conflict
+    }
+
+    match self.is_blocked(&loan.user_id).await {
+      Ok(blocked) => if blocked {
+        return LoanRenewResult::Blocked
+      },
+      Err(e) => return LoanRenewResult::UnexpectedError(e),
+    }
+
+    // the copy goes to the next user in the queue instead
+    match self.hold_repo.count_waiting_by_book_id(&loan.book_id).await {
+      Ok(waiting) => if waiting > 0 {
+        return LoanRenewResult::Conflict
+      },
+      Err(e) => return LoanRenewResult::UnexpectedError(e),
+    }
+
+    let borrower = match self.user_repo.get_by_id(&loan.user_id).await {
+      Ok(user) => match user {
+        Some(user) => user,
+        None => return LoanRenewResult::NotFound,
+      },
+      Err(e) => return LoanRenewResult::UnexpectedError(e),
+    };
+
+    let date_due = loan.date_due + self.settings.loan_period(&borrower.role);
+    match self.loan_repo.renew(id, date_due, self.settings.max_renewals).await {
+      Ok(loan) => match loan {
+        Some(loan) => LoanRenewResult::Ok(FullLoanResp::new(loan)),
+        None => LoanRenewResult::Conflict,
+      },
+      Err(e) => LoanRenewResult::UnexpectedError(e),
+    }
+  }
+
+  /// Take the copy back; only the staff do it, when the copy is in their hands.
+  #[tracing::instrument(name = "LoanService::return_one", skip_all)]
+  pub async fn return_one(&self, id: &Uuid) -> LoanReturnResult {
+    match self.loan_repo.get_by_id(id).await {
+      Ok(loan) => if loan.is_none() {
+        return LoanReturnResult::NotFound
+      },
+      Err(e) => return LoanReturnResult::UnexpectedError(e),
+    }
+
+    match self.loan_repo.return_one(id).await {
+      Ok(loan) => match loan {
+        Some(loan) => LoanReturnResult::Ok(FullLoanResp::new(loan)),
+        None => LoanReturnResult::AlreadyReturned,
+      },
+      Err(e) => LoanReturnResult::UnexpectedError(e),
+    }
+  }
+}
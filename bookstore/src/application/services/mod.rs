@@ -2,3 +2,18 @@ pub mod user;
 pub mod auth;
 pub mod book;
 pub mod author;
+pub mod review;
+pub mod shelf;
+pub mod wishlist;
+pub mod notification;
+pub mod copy;
+pub mod loan;
+pub mod hold;
+pub mod branch;
+pub mod health;
+pub mod diagnostics;
+pub mod dump;
+pub mod seed;
+pub mod account;
+
+pub mod two_factor;
\ No newline at end of file
@@ -0,0 +1,65 @@
+use std::error::Error;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::adapters::repositories::notification::NotificationRepository;
+use crate::application::dto::request::notification::{GetNotificationListReq, UpdateReadReq};
+use crate::application::dto::response::notification::{FullNotificationResp, NotificationListResp};
+
+
+pub struct NotificationService
+{
+  notification_repo: Arc<NotificationRepository>,
+}
+
+pub enum NotificationListFetchResult {
+  Ok(NotificationListResp),
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum NotificationUpdateResult {
+  Ok(FullNotificationResp),
+  NotFound,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum NotificationMarkAllResult {
+  Ok,
+  UnexpectedError(Box<dyn Error>),
+}
+
+impl NotificationService
+{
+  pub fn new(notification_repo: Arc<NotificationRepository>) -> Self {
+    Self {
+      notification_repo,
+    }
+  }
+
+  #[tracing::instrument(name = "NotificationService::get_list", skip_all)]
+  pub async fn get_list(&self, user_id: &Uuid, params: GetNotificationListReq) -> NotificationListFetchResult {
+    match self.notification_repo.get_list_by_user_id(user_id, params.page, params.size, params.unread_only).await {
+      Ok(notifications) => NotificationListFetchResult::Ok(NotificationListResp::new(notifications)),
+      Err(e) => NotificationListFetchResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "NotificationService::update_read", skip_all)]
+  pub async fn update_read(&self, user_id: &Uuid, id: &Uuid, data: UpdateReadReq) -> NotificationUpdateResult {
+    match self.notification_repo.update_read(user_id, id, data.read).await {
+      Ok(notification) => match notification {
+        Some(notification) => NotificationUpdateResult::Ok(FullNotificationResp::new(notification)),
+        None => NotificationUpdateResult::NotFound,
+      },
+      Err(e) => NotificationUpdateResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "NotificationService::mark_all_read", skip_all)]
+  pub async fn mark_all_read(&self, user_id: &Uuid) -> NotificationMarkAllResult {
+    match self.notification_repo.mark_all_read(user_id).await {
+      Ok(_) => NotificationMarkAllResult::Ok,
+      Err(e) => NotificationMarkAllResult::UnexpectedError(e),
+    }
+  }
+}
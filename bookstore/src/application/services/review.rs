@@ -0,0 +1,185 @@
+use std::error::Error;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::adapters::repositories::book::BookRepository;
+use crate::adapters::repositories::is_unique_violation;
+use crate::adapters::repositories::review::ReviewRepository;
+use crate::adapters::repositories::user::UserRepository;
+use crate::application::dto::request::review::{AddReviewReq, GetReviewListReq, UpdateHiddenReq, UpdateReviewReq};
+use crate::application::dto::response::review::{FullReviewResp, ReviewListResp};
+use crate::application::entities::review::Review;
+
+const MAX_REVIEW_LENGTH: usize = 4096;
+
+
+pub struct ReviewService
+{
+  review_repo: Arc<ReviewRepository>,
+  book_repo: Arc<BookRepository>,
+  user_repo: Arc<UserRepository>,
+}
+
+pub enum ReviewAddResult {
+  Created(FullReviewResp),
+  BookNotFound,
+  AlreadyExists,
+  Suspended,
+  BadRequest,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum ReviewListFetchResult {
+  Ok(ReviewListResp),
+  BookNotFound,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum ReviewUpdateResult {
+  Ok(FullReviewResp),
+  NotFound,
+  BadRequest,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum ReviewDeleteResult {
+  Ok,
+  NotFound,
+  UnexpectedError(Box<dyn Error>),
+}
+
+impl ReviewService
+{
+  pub fn new(
+    review_repo: Arc<ReviewRepository>,
+    book_repo: Arc<BookRepository>,
+    user_repo: Arc<UserRepository>,
+  ) -> Self {
+    Self {
+      review_repo,
+      book_repo,
+      user_repo,
+    }
+  }
+
+  fn check_review(rating: i16, text: &str) -> bool {
+    (1..=5).contains(&rating) && text.chars().count() <= MAX_REVIEW_LENGTH
+  }
+
+  #[tracing::instrument(name = "ReviewService::get_list", skip_all)]
+  pub async fn get_list(&self, book_id: &Uuid, params: GetReviewListReq, include_hidden: bool) -> ReviewListFetchResult {
+    match self.book_repo.get_by_id(book_id).await {
+      Ok(book) => if book.is_none() {
+        return ReviewListFetchResult::BookNotFound
+      },
+      Err(e) => return ReviewListFetchResult::UnexpectedError(e),
+    }
+
+    match self.review_repo.get_list_by_book_id(book_id, params.page, params.size, include_hidden).await {
+      Ok(reviews) => ReviewListFetchResult::Ok(ReviewListResp::new(reviews)),
+      Err(e) => ReviewListFetchResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "ReviewService::add_one", skip_all)]
+  pub async fn add_one(&self, book_id: &Uuid, user_id: &Uuid, data: AddReviewReq) -> ReviewAddResult {
+    if !Self::check_review(data.rating, &data.text) {
+      return ReviewAddResult::BadRequest
+    }
+
+    // suspended users must not be able to post, whatever route leads here
+    match self.user_repo.get_by_id(user_id).await {
+      Ok(user) => match user {
+        Some(user) => if user.suspended {
+          return ReviewAddResult::Suspended
+        },
+        None => return ReviewAddResult::UnexpectedError("review author does not exist".into()),
+      },
+      Err(e) => return ReviewAddResult::UnexpectedError(e),
+    }
+
+    match self.book_repo.get_by_id(book_id).await {
+      Ok(book) => if book.is_none() {
+        return ReviewAddResult::BookNotFound
+      },
+      Err(e) => return ReviewAddResult::UnexpectedError(e),
+    }
+
+    match self.review_repo.get_by_book_and_user(book_id, user_id).await {
+      Ok(review) => if review.is_some() {
+        return ReviewAddResult::AlreadyExists
+      },
+      Err(e) => return ReviewAddResult::UnexpectedError(e),
+    }
+
+    let review = Review::new(*book_id, *user_id, data);
+    match self.review_repo.add_one(review.clone()).await {
+      Ok(_) => ReviewAddResult::Created(FullReviewResp::new(review)),
+      // another request of the user got in between the check and the insert
+      Err(e) if is_unique_violation(e.as_ref()) => ReviewAddResult::AlreadyExists,
+      Err(e) => ReviewAddResult::UnexpectedError(e),
+    }
+  }
+
+  /// Update the review left by `user_id` on `book_id`.
+  #[tracing::instrument(name = "ReviewService::update_own", skip_all)]
+  pub async fn update_own(&self, book_id: &Uuid, user_id: &Uuid, data: UpdateReviewReq) -> ReviewUpdateResult {
+    if !Self::check_review(data.rating, &data.text) {
+      return ReviewUpdateResult::BadRequest
+    }
+
+    let review = match self.review_repo.get_by_book_and_user(book_id, user_id).await {
+      Ok(review) => match review {
+        Some(review) => review,
+        None => return ReviewUpdateResult::NotFound,
+      },
+      Err(e) => return ReviewUpdateResult::UnexpectedError(e),
+    };
+
+    match self.review_repo.update_one(&review.id, data.rating, data.text).await {
+      Ok(review) => match review {
+        Some(review) => ReviewUpdateResult::Ok(FullReviewResp::new(review)),
+        None => ReviewUpdateResult::NotFound,
+      },
+      Err(e) => ReviewUpdateResult::UnexpectedError(e),
+    }
+  }
+
+  /// Delete the review left by `user_id` on `book_id`.
+  #[tracing::instrument(name = "ReviewService::delete_own", skip_all)]
+  pub async fn delete_own(&self, book_id: &Uuid, user_id: &Uuid) -> ReviewDeleteResult {
+    let review = match self.review_repo.get_by_book_and_user(book_id, user_id).await {
+      Ok(review) => match review {
+        Some(review) => review,
+        None => return ReviewDeleteResult::NotFound,
+      },
+      Err(e) => return ReviewDeleteResult::UnexpectedError(e),
+    };
+
+    match self.review_repo.delete_one(&review.id).await {
+      Ok(_) => ReviewDeleteResult::Ok,
+      Err(e) => ReviewDeleteResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "ReviewService::update_hidden", skip_all)]
+  pub async fn update_hidden(&self, book_id: &Uuid, review_id: &Uuid, data: UpdateHiddenReq) -> ReviewUpdateResult {
+    match self.review_repo.get_by_id(review_id).await {
+      Ok(review) => match review {
+        Some(review) => if review.book_id != *book_id {
+          return ReviewUpdateResult::NotFound
+        },
+        None => return ReviewUpdateResult::NotFound,
+      },
+      Err(e) => return ReviewUpdateResult::UnexpectedError(e),
+    }
+
+    match self.review_repo.update_hidden(review_id, data.hidden).await {
+      Ok(review) => match review {
+        Some(review) => ReviewUpdateResult::Ok(FullReviewResp::new(review)),
+        None => ReviewUpdateResult::NotFound,
+      },
+      Err(e) => ReviewUpdateResult::UnexpectedError(e),
+    }
+  }
+}
@@ -0,0 +1,296 @@
+use std::error::Error;
+use std::sync::Arc;
+use chrono::{Duration, Local, TimeZone};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+use crate::adapters::repositories::dump::DumpRepository;
+use crate::adapters::repositories::seed::SeedRepository;
+use crate::application::entities::author::Author;
+use crate::application::entities::book::Book;
+use crate::application::entities::user::{User, UserRole};
+use crate::application::util::password::PasswordHashing;
+
+/// Tables which must be empty unless the seeding is forced.
+const SEEDED_TABLES: [&str; 3] = ["authors", "books", "users"];
+
+// The names pass `NAME_RE`, some with `ё`, hyphens and apostrophes, as real ones do.
+
+/// Male and female forms.
+const RU_FIRST_NAMES: [(&str, &str); 13] = [
+  ("Александр", "Анна"), ("Алексей", "Екатерина"), ("Андрей", "Елена"), ("Борис", "Мария"),
+  ("Владимир", "Наталья"), ("Дмитрий", "Ольга"), ("Иван", "Татьяна"), ("Михаил", "Ирина"),
+  ("Николай", "Светлана"), ("Сергей", "Вера"), ("Павел", "Софья"), ("Юрий", "Дарья"),
+  ("Фёдор", "Алёна"),
+];
+const RU_LAST_NAMES: [(&str, &str); 16] = [
+  ("Иванов", "Иванова"), ("Петров", "Петрова"), ("Смирнов", "Смирнова"), ("Кузнецов", "Кузнецова"),
+  ("Соколов", "Соколова"), ("Попов", "Попова"), ("Лебедев", "Лебедева"), ("Козлов", "Козлова"),
+  ("Новиков", "Новикова"), ("Морозов", "Морозова"), ("Волков", "Волкова"), ("Орлов", "Орлова"),
+  ("Белов", "Белова"), ("Зайцев", "Зайцева"), ("Королёв", "Королёва"), ("Мамин-Сибиряк", "Мамина-Сибиряк"),
+];
+const RU_MIDDLE_NAMES: [(&str, &str); 11] = [
+  ("Александрович", "Александровна"), ("Алексеевич", "Алексеевна"), ("Андреевич", "Андреевна"),
+  ("Борисович", "Борисовна"), ("Владимирович", "Владимировна"), ("Дмитриевич", "Дмитриевна"),
+  ("Иванович", "Ивановна"), ("Михайлович", "Михайловна"), ("Николаевич", "Николаевна"),
+  ("Сергеевич", "Сергеевна"), ("Фёдорович", "Фёдоровна"),
+];
+const EN_FIRST_NAMES: [&str; 15] = [
+  "James", "Mary", "John", "Patricia", "Robert", "Jennifer", "Michael",
+  "Linda", "William", "Elizabeth", "David", "Susan", "Emily", "Thomas", "Mary-Jane",
+];
+const EN_LAST_NAMES: [&str; 15] = [
+  "Smith", "Johnson", "Williams", "Brown", "Jones", "Miller", "Davis",
+  "Wilson", "Anderson", "Taylor", "Moore", "Clarke", "Walker", "Hughes", "O'Brien",
+];
+
+#[derive(Clone, Copy)]
+enum Gender {
+  M,
+  F,
+  N,
+}
+
+/// Nominative, genitive and gender.
+const RU_NOUNS: [(&str, &str, Gender); 20] = [
+  ("Дом", "дома", Gender::M), ("Тень", "тени", Gender::F), ("Море", "моря", Gender::N),
+  ("Ночь", "ночи", Gender::F), ("Сад", "сада", Gender::M), ("Песня", "песни", Gender::F),
+  ("Город", "города", Gender::M), ("Река", "реки", Gender::F), ("Зеркало", "зеркала", Gender::N),
+  ("Ветер", "ветра", Gender::M), ("Звезда", "звезды", Gender::F), ("Дорога", "дороги", Gender::F),
+  ("Остров", "острова", Gender::M), ("Сердце", "сердца", Gender::N), ("Лес", "леса", Gender::M),
+  ("Память", "памяти", Gender::F), ("Письмо", "письма", Gender::N), ("Берег", "берега", Gender::M),
+  ("Зима", "зимы", Gender::F), ("Небо", "неба", Gender::N),
+];
+/// Masculine, feminine and neuter forms.
+const RU_ADJECTIVES: [(&str, &str, &str); 10] = [
+  ("Тихий", "Тихая", "Тихое"), ("Последний", "Последняя", "Последнее"), ("Белый", "Белая", "Белое"),
+  ("Старый", "Старая", "Старое"), ("Далёкий", "Далёкая", "Далёкое"), ("Красный", "Красная", "Красное"),
+  ("Забытый", "Забытая", "Забытое"), ("Северный", "Северная", "Северное"), ("Тайный", "Тайная", "Тайное"),
+  ("Летний", "Летняя", "Летнее"),
+];
+const EN_NOUNS: [&str; 20] = [
+  "House", "Shadow", "Sea", "Night", "Garden", "Song", "City", "River", "Mirror", "Wind",
+  "Star", "Road", "Island", "Heart", "Forest", "Memory", "Letter", "Shore", "Winter", "Sky",
+];
+const EN_ADJECTIVES: [&str; 10] = [
+  "Silent", "Last", "White", "Old", "Distant", "Red", "Forgotten", "Northern", "Secret", "Summer",
+];
+
+
+/// What to generate.
+#[derive(Debug, Clone)]
+pub struct SeedParams {
+  pub authors: usize,
+  pub books: usize,
+  pub users: usize,
+  /// The same seed always generates the same data.
+  pub seed: u64,
+  /// Password of every generated user.
+  pub user_password: String,
+  /// Add the data even if there already is some.
+  pub force: bool,
+}
+
+pub struct SeedService
+{
+  seed_repo: Arc<SeedRepository>,
+  dump_repo: Arc<DumpRepository>,
+  hashing: PasswordHashing,
+}
+
+pub enum SeedResult {
+  /// Numbers of rows added; rows generated by an earlier run with the same seed are skipped.
+  Ok {
+    authors: u64,
+    books: u64,
+    users: u64,
+  },
+  NotEmpty(Vec<String>),
+  UnexpectedError(Box<dyn Error>),
+}
+
+/// A person's name in one of the two languages.
+struct Name {
+  first: String,
+  last: String,
+  middle: Option<String>,
+  russian: bool,
+}
+
+/// Deterministic source of everything random in the generated data.
+struct Generator {
+  rng: ChaCha8Rng,
+}
+
+impl Generator {
+  fn new(seed: u64) -> Self {
+    Self {
+      rng: ChaCha8Rng::seed_from_u64(seed),
+    }
+  }
+
+  fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+    *items.choose(&mut self.rng).expect("the word lists are not empty")
+  }
+
+  fn uuid(&mut self) -> Uuid {
+    uuid::Builder::from_random_bytes(self.rng.gen()).into_uuid()
+  }
+
+  fn name(&mut self) -> Name {
+    if !self.rng.gen_bool(0.6) {
+      return Name {
+        first: self.pick(&EN_FIRST_NAMES).to_string(),
+        last: self.pick(&EN_LAST_NAMES).to_string(),
+        middle: None,
+        russian: false,
+      }
+    }
+
+    let male = self.rng.gen_bool(0.5);
+    let form = |(m, f): (&str, &str)| if male { m.to_string() } else { f.to_string() };
+    let first = form(self.pick(&RU_FIRST_NAMES));
+    let last = form(self.pick(&RU_LAST_NAMES));
+    let middle = if self.rng.gen_bool(0.8) { Some(form(self.pick(&RU_MIDDLE_NAMES))) } else { None };
+    Name {
+      first,
+      last,
+      middle,
+      russian: true,
+    }
+  }
+
+  fn russian_title(&mut self) -> String {
+    let (noun, _, gender) = self.pick(&RU_NOUNS);
+    let (_, genitive, _) = self.pick(&RU_NOUNS);
+    let (m, f, n) = self.pick(&RU_ADJECTIVES);
+    let adjective = match gender {
+      Gender::M => m,
+      Gender::F => f,
+      Gender::N => n,
+    };
+
+    match self.rng.gen_range(0..4) {
+      0 => format!("{} {}", adjective, noun.to_lowercase()),
+      1 => format!("{} и {}", noun, self.pick(&RU_NOUNS).0.to_lowercase()),
+      2 => format!("{} {}", noun, genitive),
+      _ => format!("{} {} {}", adjective, noun.to_lowercase(), genitive),
+    }
+  }
+
+  fn english_title(&mut self) -> String {
+    let noun = self.pick(&EN_NOUNS);
+    let other = self.pick(&EN_NOUNS);
+    let adjective = self.pick(&EN_ADJECTIVES);
+
+    match self.rng.gen_range(0..4) {
+      0 => format!("The {} {}", adjective, noun),
+      1 => format!("{} and {}", noun, other),
+      2 => format!("The {} of the {}", noun, other),
+      _ => format!("{} {}", adjective, noun),
+    }
+  }
+}
+
+impl SeedService
+{
+  pub fn new(seed_repo: Arc<SeedRepository>, dump_repo: Arc<DumpRepository>, hashing: PasswordHashing) -> Self {
+    Self {
+      seed_repo,
+      dump_repo,
+      hashing,
+    }
+  }
+
+  /// Generate authors, their books and readers, and save them in batches.
+  #[tracing::instrument(name = "SeedService::generate", skip_all)]
+  pub async fn generate(&self, params: SeedParams) -> SeedResult {
+    if !params.force {
+      match self.dump_repo.get_non_empty_tables(&SEEDED_TABLES).await {
+        Ok(tables) if tables.is_empty() => {},
+        Ok(tables) => return SeedResult::NotEmpty(tables),
+        Err(e) => return SeedResult::UnexpectedError(e),
+      }
+    }
+
+    // hashing is slow, so every user gets the same hash
+    let hashed_password = match self.hashing.hash(&params.user_password).await {
+      Ok(hashed_password) => hashed_password,
+      Err(e) => return SeedResult::UnexpectedError(e),
+    };
+
+    let mut gen = Generator::new(params.seed);
+
+    let mut authors = Vec::with_capacity(params.authors);
+    let mut languages = Vec::with_capacity(params.authors);
+    for _ in 0..params.authors {
+      let name = gen.name();
+      languages.push(name.russian);
+      authors.push(Author {
+        id: gen.uuid(),
+        first_name: name.first,
+        last_name: name.last,
+        middle_name: name.middle,
+      });
+    }
+
+    let mut books = Vec::with_capacity(params.books);
+    for _ in 0..params.books {
+      // a few books have no known author
+      let author = if !authors.is_empty() && gen.rng.gen_bool(0.95) {
+        Some(gen.rng.gen_range(0..authors.len()))
+      } else {
+        None
+      };
+      let russian = author.map_or_else(|| gen.rng.gen_bool(0.5), |i| languages[i]);
+      let title = if russian { gen.russian_title() } else { gen.english_title() };
+      let price = if gen.rng.gen_bool(0.9) { Some(gen.rng.gen_range(99..=2999) * 100) } else { None };
+
+      books.push(Book {
+        id: gen.uuid(),
+        title,
+        author_id: author.map(|i| authors[i].id),
+        rating_sum: 0,
+        rating_count: 0,
+        price,
+        stock: gen.rng.gen_range(0..=20),
+      });
+    }
+
+    let registered_from = Local.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap();
+    let mut users = Vec::with_capacity(params.users);
+    for _ in 0..params.users {
+      let name = gen.name();
+      let id = gen.uuid();
+      users.push(User {
+        id,
+        first_name: name.first,
+        last_name: name.last,
+        middle_name: name.middle,
+        // unique as long as the IDs are
+        nickname: format!("reader_{}", &id.simple().to_string()[..12]),
+        hashed_password: hashed_password.clone(),
+        date_registered: registered_from + Duration::minutes(gen.rng.gen_range(0..365 * 24 * 60)),
+        role: UserRole::User,
+        suspended: false,
+        session_version: 0,
+        email: None,
+        email_verified: false,
+        totp_secret: None,
+        totp_enabled: false,
+        totp_last_step: None,
+      });
+    }
+
+    match self.seed_repo.add_all(&authors, &books, &users).await {
+      Ok(inserted) => SeedResult::Ok {
+        authors: inserted.authors,
+        books: inserted.books,
+        users: inserted.users,
+      },
+      Err(e) => SeedResult::UnexpectedError(e),
+    }
+  }
+}
@@ -0,0 +1,342 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use chrono::Local;
+use uuid::Uuid;
+
+use crate::adapters::repositories::book::BookRepository;
+use crate::adapters::repositories::{is_check_violation, is_unique_violation};
+use crate::adapters::repositories::shelf::ShelfRepository;
+use crate::adapters::repositories::user::UserRepository;
+use crate::application::dto::request::shelf::{AddShelfReq, PutShelfEntryReq, UpdateShelfReq};
+use crate::application::dto::response::shelf::{FullShelfResp, ReadingStatsResp, ShelfEntryResp, ShelfListResp};
+use crate::application::entities::shelf::{Shelf, ShelfEntry, ShelfKind};
+
+const MAX_SHELF_NAME_LENGTH: usize = 64;
+
+
+pub struct ShelfService
+{
+  shelf_repo: Arc<ShelfRepository>,
+  book_repo: Arc<BookRepository>,
+  user_repo: Arc<UserRepository>,
+}
+
+pub enum ShelfListFetchResult {
+  Ok(ShelfListResp),
+  UserNotFound,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum ShelfAddResult {
+  Created(FullShelfResp),
+  AlreadyExists,
+  BadRequest,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum ShelfUpdateResult {
+  Ok(FullShelfResp),
+  NotFound,
+  AlreadyExists,
+  BadRequest,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum ShelfDeleteResult {
+  Ok,
+  NotFound,
+  BuiltIn,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum ShelfEntryPutResult {
+  Ok(ShelfEntryResp),
+  ShelfNotFound,
+  BookNotFound,
+  BadRequest,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum ShelfEntryDeleteResult {
+  Ok,
+  NotFound,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum ReadingStatsFetchResult {
+  Ok(ReadingStatsResp),
+  UnexpectedError(Box<dyn Error>),
+}
+
+impl ShelfService
+{
+  pub fn new(
+    shelf_repo: Arc<ShelfRepository>,
+    book_repo: Arc<BookRepository>,
+    user_repo: Arc<UserRepository>,
+  ) -> Self {
+    Self {
+      shelf_repo,
+      book_repo,
+      user_repo,
+    }
+  }
+
+  fn check_name(name: &str) -> bool {
+    !name.trim().is_empty() && name.chars().count() <= MAX_SHELF_NAME_LENGTH
+  }
+
+  fn check_entry(data: &PutShelfEntryReq) -> bool {
+    if let Some(percent) = data.progress_percent {
+      if !(0..=100).contains(&percent) {
+        return false
+      }
+    }
+    if let Some(page) = data.progress_page {
+      if page < 0 {
+        return false
+      }
+    }
+    if let (Some(started), Some(finished)) = (data.date_started, data.date_finished) {
+      if finished < started {
+        return false
+      }
+    }
+    true
+  }
+
+  /// Fetch the shelf only if it belongs to `user_id`.
+  async fn get_own_shelf(&self, user_id: &Uuid, shelf_id: &Uuid) -> Result<Option<Shelf>, Box<dyn Error>> {
+    match self.shelf_repo.get_by_id(shelf_id).await {
+      Ok(shelf) => Ok(shelf.filter(|s| s.user_id == *user_id)),
+      Err(e) => Err(e),
+    }
+  }
+
+  /// Attach the entries (with their books) to the shelves.
+  async fn with_entries(&self, shelves: Vec<Shelf>) -> Result<Vec<FullShelfResp>, Box<dyn Error>> {
+    let shelf_ids: Vec<Uuid> = shelves.iter().map(|s| s.id).collect();
+    let entries = self.shelf_repo.get_entries_by_shelf_ids(&shelf_ids).await?;
+
+    let book_ids: Vec<Uuid> = entries.iter().map(|e| e.book_id).collect();
+    let books: HashMap<Uuid, _> = self.book_repo.get_by_ids(&book_ids).await?
+      .into_iter()
+      .map(|b| (b.id, b))
+      .collect();
+
+    let mut by_shelf: HashMap<Uuid, Vec<ShelfEntryResp>> = HashMap::new();
+    for entry in entries {
+      // the same book may be on several shelves
+      if let Some(book) = books.get(&entry.book_id).cloned() {
+        by_shelf.entry(entry.shelf_id).or_default().push(ShelfEntryResp::new(entry, book));
+      }
+    }
+
+    Ok(
+      shelves.into_iter()
+        .map(|s| {
+          let entries = by_shelf.remove(&s.id).unwrap_or_default();
+          FullShelfResp::new(s, entries)
+        })
+        .collect()
+    )
+  }
+
+  #[tracing::instrument(name = "ShelfService::get_own_list", skip_all)]
+  pub async fn get_own_list(&self, user_id: &Uuid) -> ShelfListFetchResult {
+    if let Err(e) = self.shelf_repo.ensure_built_in(user_id).await {
+      return ShelfListFetchResult::UnexpectedError(e)
+    }
+
+    let shelves = match self.shelf_repo.get_list_by_user_id(user_id, false).await {
+      Ok(shelves) => shelves,
+      Err(e) => return ShelfListFetchResult::UnexpectedError(e),
+    };
+
+    match self.with_entries(shelves).await {
+      Ok(shelves) => ShelfListFetchResult::Ok(ShelfListResp(shelves)),
+      Err(e) => ShelfListFetchResult::UnexpectedError(e),
+    }
+  }
+
+  /// Fetch the public shelves of another user.
+  #[tracing::instrument(name = "ShelfService::get_public_list", skip_all)]
+  pub async fn get_public_list(&self, user_id: &Uuid) -> ShelfListFetchResult {
+    match self.user_repo.get_by_id(user_id).await {
+      Ok(user) => if user.is_none() {
+        return ShelfListFetchResult::UserNotFound
+      },
+      Err(e) => return ShelfListFetchResult::UnexpectedError(e),
+    }
+
+    let shelves = match self.shelf_repo.get_list_by_user_id(user_id, true).await {
+      Ok(shelves) => shelves,
+      Err(e) => return ShelfListFetchResult::UnexpectedError(e),
+    };
+
+    match self.with_entries(shelves).await {
+      Ok(shelves) => ShelfListFetchResult::Ok(ShelfListResp(shelves)),
+      Err(e) => ShelfListFetchResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "ShelfService::add_one", skip_all)]
+  pub async fn add_one(&self, user_id: &Uuid, data: AddShelfReq) -> ShelfAddResult {
+    if !Self::check_name(&data.name) {
+      return ShelfAddResult::BadRequest
+    }
+
+    // built-in shelves reserve their names, so create them first
+    if let Err(e) = self.shelf_repo.ensure_built_in(user_id).await {
+      return ShelfAddResult::UnexpectedError(e)
+    }
+
+    match self.shelf_repo.get_by_user_and_name(user_id, &data.name).await {
+      Ok(shelf) => if shelf.is_some() {
+        return ShelfAddResult::AlreadyExists
+      },
+      Err(e) => return ShelfAddResult::UnexpectedError(e),
+    }
+
+    let shelf = Shelf::new(*user_id, data);
+    match self.shelf_repo.add_one(shelf.clone()).await {
+      Ok(_) => ShelfAddResult::Created(FullShelfResp::new(shelf, vec![])),
+      // another request has taken the name since the check
+      Err(e) if is_unique_violation(e.as_ref()) => ShelfAddResult::AlreadyExists,
+      Err(e) => ShelfAddResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "ShelfService::update_one", skip_all)]
+  pub async fn update_one(&self, user_id: &Uuid, shelf_id: &Uuid, data: UpdateShelfReq) -> ShelfUpdateResult {
+    let shelf = match self.get_own_shelf(user_id, shelf_id).await {
+      Ok(shelf) => match shelf {
+        Some(shelf) => shelf,
+        None => return ShelfUpdateResult::NotFound,
+      },
+      Err(e) => return ShelfUpdateResult::UnexpectedError(e),
+    };
+
+    let name = match data.name {
+      Some(name) if name != shelf.name => {
+        if shelf.kind.is_built_in() || !Self::check_name(&name) {
+          return ShelfUpdateResult::BadRequest
+        }
+        match self.shelf_repo.get_by_user_and_name(user_id, &name).await {
+          Ok(other) => if other.is_some() {
+            return ShelfUpdateResult::AlreadyExists
+          },
+          Err(e) => return ShelfUpdateResult::UnexpectedError(e),
+        }
+        name
+      },
+      _ => shelf.name,
+    };
+    let public = data.public.unwrap_or(shelf.public);
+
+    let shelf = match self.shelf_repo.update_one(shelf_id, name, public).await {
+      Ok(shelf) => match shelf {
+        Some(shelf) => shelf,
+        None => return ShelfUpdateResult::NotFound,
+      },
+      Err(e) if is_unique_violation(e.as_ref()) => return ShelfUpdateResult::AlreadyExists,
+      Err(e) => return ShelfUpdateResult::UnexpectedError(e),
+    };
+
+    match self.with_entries(vec![shelf]).await {
+      Ok(mut shelves) => ShelfUpdateResult::Ok(shelves.remove(0)),
+      Err(e) => ShelfUpdateResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "ShelfService::delete_one", skip_all)]
+  pub async fn delete_one(&self, user_id: &Uuid, shelf_id: &Uuid) -> ShelfDeleteResult {
+    match self.get_own_shelf(user_id, shelf_id).await {
+      Ok(shelf) => match shelf {
+        Some(shelf) => if shelf.kind.is_built_in() {
+          return ShelfDeleteResult::BuiltIn
+        },
+        None => return ShelfDeleteResult::NotFound,
+      },
+      Err(e) => return ShelfDeleteResult::UnexpectedError(e),
+    }
+
+    match self.shelf_repo.delete_one(shelf_id).await {
+      Ok(_) => ShelfDeleteResult::Ok,
+      Err(e) => ShelfDeleteResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "ShelfService::put_entry", skip_all)]
+  pub async fn put_entry(
+    &self,
+    user_id: &Uuid,
+    shelf_id: &Uuid,
+    book_id: &Uuid,
+    mut data: PutShelfEntryReq,
+  ) -> ShelfEntryPutResult {
+    let shelf = match self.get_own_shelf(user_id, shelf_id).await {
+      Ok(shelf) => match shelf {
+        Some(shelf) => shelf,
+        None => return ShelfEntryPutResult::ShelfNotFound,
+      },
+      Err(e) => return ShelfEntryPutResult::UnexpectedError(e),
+    };
+
+    let book = match self.book_repo.get_by_id(book_id).await {
+      Ok(book) => match book {
+        Some(book) => book,
+        None => return ShelfEntryPutResult::BookNotFound,
+      },
+      Err(e) => return ShelfEntryPutResult::UnexpectedError(e),
+    };
+
+    let today = Local::now().date_naive();
+    match shelf.kind {
+      ShelfKind::Reading => {
+        data.date_started.get_or_insert(today);
+      },
+      ShelfKind::Read => {
+        data.date_finished.get_or_insert(today);
+        data.progress_percent = Some(100);
+      },
+      _ => {}
+    }
+
+    if !Self::check_entry(&data) {
+      return ShelfEntryPutResult::BadRequest
+    }
+
+    match self.shelf_repo.put_entry(&shelf, ShelfEntry::new(*shelf_id, *book_id, data)).await {
+      Ok(entry) => ShelfEntryPutResult::Ok(ShelfEntryResp::new(entry, book)),
+      // `check_entry` cannot see the start date carried over from another shelf
+      Err(e) if is_check_violation(e.as_ref()) => ShelfEntryPutResult::BadRequest,
+      Err(e) => ShelfEntryPutResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "ShelfService::delete_entry", skip_all)]
+  pub async fn delete_entry(&self, user_id: &Uuid, shelf_id: &Uuid, book_id: &Uuid) -> ShelfEntryDeleteResult {
+    match self.get_own_shelf(user_id, shelf_id).await {
+      Ok(shelf) => if shelf.is_none() {
+        return ShelfEntryDeleteResult::NotFound
+      },
+      Err(e) => return ShelfEntryDeleteResult::UnexpectedError(e),
+    }
+
+    match self.shelf_repo.delete_entry(shelf_id, book_id).await {
+      Ok(true) => ShelfEntryDeleteResult::Ok,
+      Ok(false) => ShelfEntryDeleteResult::NotFound,
+      Err(e) => ShelfEntryDeleteResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "ShelfService::get_reading_stats", skip_all)]
+  pub async fn get_reading_stats(&self, user_id: &Uuid) -> ReadingStatsFetchResult {
+    match self.shelf_repo.get_reading_stats(user_id).await {
+      Ok(stats) => ReadingStatsFetchResult::Ok(ReadingStatsResp::new(stats)),
+      Err(e) => ReadingStatsFetchResult::UnexpectedError(e),
+    }
+  }
+}
@@ -0,0 +1,272 @@
+use std::error::Error;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::adapters::middleware::jwt::JwtClaims;
+use crate::adapters::repositories::recovery_code::RecoveryCodeRepository;
+use crate::adapters::repositories::user::UserRepository;
+use crate::application::dto::request::two_factor::{DisableTwoFactorReq, StartTwoFactorReq, TwoFactorCodeReq};
+use crate::application::dto::response::two_factor::{RecoveryCodesResp, TwoFactorEnabledResp, TwoFactorSetupResp, TwoFactorStatusResp};
+use crate::application::entities::user::{User, UserRole};
+use crate::application::util::password::{PasswordHashing, Verification};
+use crate::application::util::totp;
+use crate::config::AuthConfig;
+
+
+pub enum TwoFactorSetupResult {
+  Ok(TwoFactorSetupResp),
+  NotFound,
+  WrongPassword,
+  AlreadyEnabled,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum TwoFactorConfirmResult {
+  Ok(TwoFactorEnabledResp),
+  NotFound,
+  /// There is no secret to confirm, or it has been confirmed already.
+  NotPending,
+  WrongCode,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum TwoFactorDisableResult {
+  Ok,
+  NotFound,
+  WrongPassword,
+  WrongCode,
+  NotEnabled,
+  /// Administrators may not go without it.
+  Required,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum RecoveryCodesResult {
+  Ok(RecoveryCodesResp),
+  NotFound,
+  WrongCode,
+  NotEnabled,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum PasswordCheckResult {
+  Ok(Box<User>),
+  NotFound,
+  WrongPassword,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum TwoFactorStatusResult {
+  Ok(TwoFactorStatusResp),
+  NotFound,
+  UnexpectedError(Box<dyn Error>),
+}
+
+
+/// RFC 6238 TOTP enrollment of the users and their recovery codes.
+pub struct TwoFactorService
+{
+  user_repo: Arc<UserRepository>,
+  code_repo: Arc<RecoveryCodeRepository>,
+  hashing: PasswordHashing,
+  jwt_secret: String,
+  issuer: String,
+  require_admin_2fa: bool,
+}
+
+impl TwoFactorService
+{
+  pub fn new(user_repo: Arc<UserRepository>, code_repo: Arc<RecoveryCodeRepository>, config: &AuthConfig) -> Self {
+    Self {
+      user_repo,
+      code_repo,
+      hashing: PasswordHashing::new(config),
+      jwt_secret: config.secret.clone(),
+      issuer: config.totp_issuer.clone(),
+      require_admin_2fa: config.require_admin_2fa,
+    }
+  }
+
+  /// Whether the user has two-factor authentication set up.
+  #[tracing::instrument(name = "TwoFactorService::get_status", skip_all)]
+  pub async fn get_status(&self, user_id: &Uuid) -> TwoFactorStatusResult {
+    let user = match self.user_repo.get_by_id(user_id).await {
+      Ok(Some(user)) => user,
+      Ok(None) => return TwoFactorStatusResult::NotFound,
+      Err(e) => return TwoFactorStatusResult::UnexpectedError(e),
+    };
+
+    let recovery_codes_left = if user.totp_enabled {
+      match self.code_repo.count_unused(&user.id).await {
+        Ok(count) => count,
+        Err(e) => return TwoFactorStatusResult::UnexpectedError(e),
+      }
+    } else {
+      0
+    };
+    TwoFactorStatusResult::Ok(TwoFactorStatusResp { enabled: user.totp_enabled, recovery_codes_left })
+  }
+
+  /// Give the user a new secret for the authenticator app. Nothing changes
+  /// for the logins until the user confirms it with a code.
+  #[tracing::instrument(name = "TwoFactorService::start", skip_all)]
+  pub async fn start(&self, user_id: &Uuid, data: StartTwoFactorReq) -> TwoFactorSetupResult {
+    let user = match self.verified_user(user_id, &data.password).await {
+      PasswordCheckResult::Ok(user) => *user,
+      PasswordCheckResult::WrongPassword => return TwoFactorSetupResult::WrongPassword,
+      PasswordCheckResult::NotFound => return TwoFactorSetupResult::NotFound,
+      PasswordCheckResult::UnexpectedError(e) => return TwoFactorSetupResult::UnexpectedError(e),
+    };
+    if user.totp_enabled {
+      return TwoFactorSetupResult::AlreadyEnabled
+    }
+
+    let secret = totp::generate_secret();
+    match self.user_repo.set_totp_secret(&user.id, &secret).await {
+      Ok(true) => {},
+      Ok(false) => return TwoFactorSetupResult::AlreadyEnabled,
+      Err(e) => return TwoFactorSetupResult::UnexpectedError(e),
+    };
+
+    let otpauth_uri = totp::otpauth_uri(&secret, &self.issuer, &user.nickname);
+    TwoFactorSetupResult::Ok(TwoFactorSetupResp { secret, otpauth_uri })
+  }
+
+  /// Turn two-factor authentication on with a code from the app. The sessions
+  /// started without it are ended, and the user gets a new one and the recovery codes.
+  #[tracing::instrument(name = "TwoFactorService::confirm", skip_all)]
+  pub async fn confirm(&self, user_id: &Uuid, data: TwoFactorCodeReq) -> TwoFactorConfirmResult {
+    let user = match self.user_repo.get_by_id(user_id).await {
+      Ok(Some(user)) => user,
+      Ok(None) => return TwoFactorConfirmResult::NotFound,
+      Err(e) => return TwoFactorConfirmResult::UnexpectedError(e),
+    };
+    let secret = match &user.totp_secret {
+      Some(secret) if !user.totp_enabled => secret,
+      _ => return TwoFactorConfirmResult::NotPending,
+    };
+
+    let step = match totp::matching_step(secret, &data.code, None) {
+      Some(step) => step,
+      None => return TwoFactorConfirmResult::WrongCode,
+    };
+    let session_version = match self.user_repo.enable_totp(&user.id, step).await {
+      Ok(Some(session_version)) => session_version,
+      Ok(None) => return TwoFactorConfirmResult::NotPending,
+      Err(e) => return TwoFactorConfirmResult::UnexpectedError(e),
+    };
+
+    let recovery_codes = match self.replace_recovery_codes(&user.id).await {
+      Ok(recovery_codes) => recovery_codes,
+      Err(e) => return TwoFactorConfirmResult::UnexpectedError(e),
+    };
+    let token = JwtClaims::new(user.id, user.role, session_version).to_token(&self.jwt_secret);
+    TwoFactorConfirmResult::Ok(TwoFactorEnabledResp { token, recovery_codes })
+  }
+
+  /// Turn two-factor authentication off, confirmed with the password and a code.
+  #[tracing::instrument(name = "TwoFactorService::disable", skip_all)]
+  pub async fn disable(&self, user_id: &Uuid, data: DisableTwoFactorReq) -> TwoFactorDisableResult {
+    let user = match self.verified_user(user_id, &data.password).await {
+      PasswordCheckResult::Ok(user) => *user,
+      PasswordCheckResult::WrongPassword => return TwoFactorDisableResult::WrongPassword,
+      PasswordCheckResult::NotFound => return TwoFactorDisableResult::NotFound,
+      PasswordCheckResult::UnexpectedError(e) => return TwoFactorDisableResult::UnexpectedError(e),
+    };
+    if !user.totp_enabled {
+      return TwoFactorDisableResult::NotEnabled
+    }
+    if self.is_required(&user) {
+      return TwoFactorDisableResult::Required
+    }
+
+    match self.check_code(&user, &data.code).await {
+      Ok(true) => {},
+      Ok(false) => return TwoFactorDisableResult::WrongCode,
+      Err(e) => return TwoFactorDisableResult::UnexpectedError(e),
+    };
+
+    if let Err(e) = self.user_repo.disable_totp(&user.id).await {
+      return TwoFactorDisableResult::UnexpectedError(e)
+    }
+    match self.code_repo.delete_all(&user.id).await {
+      Ok(_) => TwoFactorDisableResult::Ok,
+      Err(e) => TwoFactorDisableResult::UnexpectedError(e),
+    }
+  }
+
+  /// Replace the recovery codes of the user, confirmed with a code.
+  #[tracing::instrument(name = "TwoFactorService::regenerate_recovery_codes", skip_all)]
+  pub async fn regenerate_recovery_codes(&self, user_id: &Uuid, data: TwoFactorCodeReq) -> RecoveryCodesResult {
+    let user = match self.user_repo.get_by_id(user_id).await {
+      Ok(Some(user)) => user,
+      Ok(None) => return RecoveryCodesResult::NotFound,
+      Err(e) => return RecoveryCodesResult::UnexpectedError(e),
+    };
+    if !user.totp_enabled {
+      return RecoveryCodesResult::NotEnabled
+    }
+
+    match self.check_code(&user, &data.code).await {
+      Ok(true) => {},
+      Ok(false) => return RecoveryCodesResult::WrongCode,
+      Err(e) => return RecoveryCodesResult::UnexpectedError(e),
+    };
+
+    match self.replace_recovery_codes(&user.id).await {
+      Ok(recovery_codes) => RecoveryCodesResult::Ok(RecoveryCodesResp { recovery_codes }),
+      Err(e) => RecoveryCodesResult::UnexpectedError(e),
+    }
+  }
+
+  /// Whether the user must have two-factor authentication to use anything but `/api/me/2fa`.
+  pub fn is_required(&self, user: &User) -> bool {
+    self.require_admin_2fa && user.role == UserRole::Admin
+  }
+
+  /// Check a code from the app or a recovery code of the user and use it up.
+  pub async fn check_code(&self, user: &User, code: &str) -> Result<bool, Box<dyn Error>> {
+    let secret = match &user.totp_secret {
+      Some(secret) if user.totp_enabled => secret,
+      _ => return Ok(false),
+    };
+
+    if totp::is_code(code) {
+      return match totp::matching_step(secret, code, user.totp_last_step) {
+        // a concurrent request may have used the step meanwhile
+        Some(step) => self.user_repo.use_totp_step(&user.id, step).await,
+        None => Ok(false),
+      }
+    }
+
+    let used = self.code_repo.use_one(&user.id, &totp::hash_recovery_code(code)).await?;
+    if used {
+      log::info!("User {} has used a recovery code", user.id);
+    }
+    Ok(used)
+  }
+
+  /// New recovery codes in place of the old ones, as they are shown to the user.
+  async fn replace_recovery_codes(&self, user_id: &Uuid) -> Result<Vec<String>, Box<dyn Error>> {
+    let codes = totp::generate_recovery_codes();
+    let hashes: Vec<String> = codes.iter().map(|code| totp::hash_recovery_code(code)).collect();
+    self.code_repo.replace_all(user_id, &hashes).await?;
+    Ok(codes)
+  }
+
+  /// The user, if the password is theirs. Used by `AuthService` as well, for
+  /// the account changes confirmed with the password.
+  pub async fn verified_user(&self, user_id: &Uuid, password: &str) -> PasswordCheckResult {
+    let user = match self.user_repo.get_by_id(user_id).await {
+      Ok(Some(user)) => user,
+      Ok(None) => return PasswordCheckResult::NotFound,
+      Err(e) => return PasswordCheckResult::UnexpectedError(e),
+    };
+
+    match self.hashing.verify(password, &user.hashed_password).await {
+      Ok(Verification::Invalid) => PasswordCheckResult::WrongPassword,
+      Ok(_) => PasswordCheckResult::Ok(Box::new(user)),
+      Err(e) => PasswordCheckResult::UnexpectedError(e),
+    }
+  }
+}
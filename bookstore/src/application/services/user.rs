@@ -3,9 +3,10 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::adapters::repositories::user::UserRepository;
-use crate::application::dto::request::user::{GetUserListReq, RegisterReq, UpdateSuspendedReq};
+use crate::application::dto::request::user::{GetUserListReq, RegisterReq, UpdateMeReq, UpdateSuspendedReq};
 use crate::application::dto::response::user::{FullUserResp, UserListResp};
 use crate::application::entities::user::User;
+use crate::application::util::validation::normalize_name;
 
 
 pub struct UserService
@@ -29,6 +30,12 @@ pub enum UserAddResult {
   UnexpectedError(Box<dyn Error>),
 }
 
+pub enum UserUpdateResult {
+  Ok(FullUserResp),
+  NotFound,
+  UnexpectedError(Box<dyn Error>),
+}
+
 pub enum UserUpdateSuspendedResult {
   Ok(FullUserResp),
   NotFound,
@@ -43,6 +50,7 @@ impl UserService
     }
   }
 
+  #[tracing::instrument(name = "UserService::get_by_id", skip_all)]
   pub async fn get_by_id(&self, id: &Uuid) -> UserFetchResult {
     match self.user_repo.get_by_id(id).await {
       Ok(user) => match user {
@@ -53,6 +61,7 @@ impl UserService
     }
   }
 
+  #[tracing::instrument(name = "UserService::get_by_nickname", skip_all)]
   pub async fn get_by_nickname(&self, nickname: &String) -> UserFetchResult {
     match self.user_repo.get_by_nickname(nickname).await {
       Ok(user) => match user {
@@ -63,6 +72,7 @@ impl UserService
     }
   }
 
+  #[tracing::instrument(name = "UserService::add_one", skip_all)]
   pub async fn add_one(&self, user: RegisterReq) -> UserAddResult {
     match self.user_repo.add_one(User::new(user)).await {
       Ok(_) => UserAddResult::Created,
@@ -70,9 +80,11 @@ impl UserService
     }
   }
 
+  #[tracing::instrument(name = "UserService::get_list", skip_all)]
   pub async fn get_list(&self, params: GetUserListReq) -> UserListFetchResult {
     match self.user_repo.get_list(params.page, params.size).await {
       Ok(users) => {
+        #[allow(clippy::redundant_closure)]
         let res = users.into_iter().map(|user| FullUserResp::new(user)).collect();
         UserListFetchResult::Ok(UserListResp(res))
       }
@@ -80,6 +92,7 @@ impl UserService
     }
   }
 
+  #[tracing::instrument(name = "UserService::update_suspended", skip_all)]
   pub async fn update_suspended(&self, id: &Uuid, data: UpdateSuspendedReq) -> UserUpdateSuspendedResult {
     match self.user_repo.update_suspended(id, data).await {
       Ok(user) => match user {
@@ -89,4 +102,27 @@ impl UserService
       Err(e) => UserUpdateSuspendedResult::UnexpectedError(e),
     }
   }
-}
\ No newline at end of file
+
+  /// Change the names the user has sent, keeping the rest.
+  #[tracing::instrument(name = "UserService::update_names", skip_all)]
+  pub async fn update_names(&self, id: &Uuid, data: UpdateMeReq) -> UserUpdateResult {
+    let user = match self.user_repo.get_by_id(id).await {
+      Ok(Some(user)) => user,
+      Ok(None) => return UserUpdateResult::NotFound,
+      Err(e) => return UserUpdateResult::UnexpectedError(e),
+    };
+
+    let first_name = data.first_name.map(normalize_name).unwrap_or(user.first_name);
+    let last_name = data.last_name.map(normalize_name).unwrap_or(user.last_name);
+    let middle_name = match data.middle_name {
+      Some(middle_name) => middle_name.map(normalize_name),
+      None => user.middle_name,
+    };
+
+    match self.user_repo.update_names(id, first_name, last_name, middle_name).await {
+      Ok(Some(user)) => UserUpdateResult::Ok(FullUserResp::new(user)),
+      Ok(None) => UserUpdateResult::NotFound,
+      Err(e) => UserUpdateResult::UnexpectedError(e),
+    }
+  }
+}
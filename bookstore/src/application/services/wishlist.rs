@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::adapters::repositories::book::BookRepository;
+use crate::adapters::repositories::wishlist::WishlistRepository;
+use crate::application::dto::request::wishlist::PutWishlistItemReq;
+use crate::application::dto::response::wishlist::{WishlistItemResp, WishlistResp};
+use crate::application::entities::wishlist::WishlistItem;
+
+
+pub struct WishlistService
+{
+  wishlist_repo: Arc<WishlistRepository>,
+  book_repo: Arc<BookRepository>,
+}
+
+pub enum WishlistFetchResult {
+  Ok(WishlistResp),
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum WishlistPutResult {
+  Ok(WishlistItemResp),
+  BookNotFound,
+  BadRequest,
+  UnexpectedError(Box<dyn Error>),
+}
+
+pub enum WishlistDeleteResult {
+  Ok,
+  NotFound,
+  UnexpectedError(Box<dyn Error>),
+}
+
+impl WishlistService
+{
+  pub fn new(wishlist_repo: Arc<WishlistRepository>, book_repo: Arc<BookRepository>) -> Self {
+    Self {
+      wishlist_repo,
+      book_repo,
+    }
+  }
+
+  #[tracing::instrument(name = "WishlistService::get_list", skip_all)]
+  pub async fn get_list(&self, user_id: &Uuid) -> WishlistFetchResult {
+    let items = match self.wishlist_repo.get_list_by_user_id(user_id).await {
+      Ok(items) => items,
+      Err(e) => return WishlistFetchResult::UnexpectedError(e),
+    };
+
+    let book_ids: Vec<Uuid> = items.iter().map(|i| i.book_id).collect();
+    let mut books: HashMap<Uuid, _> = match self.book_repo.get_by_ids(&book_ids).await {
+      Ok(books) => books.into_iter().map(|b| (b.id, b)).collect(),
+      Err(e) => return WishlistFetchResult::UnexpectedError(e),
+    };
+
+    let res = items.into_iter()
+      .filter_map(|i| books.remove(&i.book_id).map(|b| WishlistItemResp::new(i, b)))
+      .collect();
+    WishlistFetchResult::Ok(WishlistResp(res))
+  }
+
+  #[tracing::instrument(name = "WishlistService::put_one", skip_all)]
+  pub async fn put_one(&self, user_id: &Uuid, book_id: &Uuid, data: PutWishlistItemReq) -> WishlistPutResult {
+    if data.price_threshold.is_some_and(|p| p < 0) {
+      return WishlistPutResult::BadRequest
+    }
+
+    let book = match self.book_repo.get_by_id(book_id).await {
+      Ok(book) => match book {
+        Some(book) => book,
+        None => return WishlistPutResult::BookNotFound,
+      },
+      Err(e) => return WishlistPutResult::UnexpectedError(e),
+    };
+
+    match self.wishlist_repo.put_one(WishlistItem::new(*user_id, *book_id, data)).await {
+      Ok(item) => WishlistPutResult::Ok(WishlistItemResp::new(item, book)),
+      Err(e) => WishlistPutResult::UnexpectedError(e),
+    }
+  }
+
+  #[tracing::instrument(name = "WishlistService::delete_one", skip_all)]
+  pub async fn delete_one(&self, user_id: &Uuid, book_id: &Uuid) -> WishlistDeleteResult {
+    match self.wishlist_repo.delete_one(user_id, book_id).await {
+      Ok(true) => WishlistDeleteResult::Ok,
+      Ok(false) => WishlistDeleteResult::NotFound,
+      Err(e) => WishlistDeleteResult::UnexpectedError(e),
+    }
+  }
+}
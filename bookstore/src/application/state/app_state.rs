@@ -1,15 +1,207 @@
 use std::sync::Arc;
+use chrono::Duration;
+use sqlx::PgPool;
 
+use crate::adapters::repositories::user::UserRepository;
+use crate::adapters::repositories::author::AuthorRepository;
+use crate::adapters::repositories::book::BookRepository;
+use crate::adapters::repositories::review::ReviewRepository;
+use crate::adapters::repositories::shelf::ShelfRepository;
+use crate::adapters::repositories::wishlist::WishlistRepository;
+use crate::adapters::repositories::notification::NotificationRepository;
+use crate::adapters::repositories::copy::CopyRepository;
+use crate::adapters::repositories::loan::LoanRepository;
+use crate::adapters::repositories::hold::HoldRepository;
+use crate::adapters::repositories::branch::BranchRepository;
+use crate::adapters::repositories::health::HealthRepository;
+use crate::adapters::repositories::dump::DumpRepository;
+use crate::adapters::repositories::seed::SeedRepository;
+use crate::adapters::repositories::account_token::AccountTokenRepository;
+use crate::adapters::repositories::outbox::OutboxRepository;
+use crate::adapters::repositories::login_failure::LoginFailureRepository;
+use crate::adapters::repositories::rate_limit::RateLimitRepository;
+use crate::adapters::repositories::recovery_code::RecoveryCodeRepository;
+use crate::adapters::mail::{Mailer, OutboxMailer};
+use crate::adapters::rate_limit::{MemoryStore, PostgresStore, RateLimitStore};
+use crate::adapters::metrics::Metrics;
 use crate::application::services::auth::AuthService;
-use crate::application::services::book::BookService;
 use crate::application::services::user::UserService;
 use crate::application::services::author::AuthorService;
+use crate::application::services::book::BookService;
+use crate::application::services::review::ReviewService;
+use crate::application::services::shelf::ShelfService;
+use crate::application::services::wishlist::WishlistService;
+use crate::application::services::notification::NotificationService;
+use crate::application::services::copy::CopyService;
+use crate::application::services::loan::{LoanService, LoanSettings};
+use crate::application::services::hold::HoldService;
+use crate::application::services::branch::BranchService;
+use crate::application::services::health::HealthService;
+use crate::application::services::diagnostics::DiagnosticsService;
+use crate::application::services::dump::DumpService;
+use crate::application::services::seed::SeedService;
+use crate::application::services::account::AccountService;
+use crate::application::services::two_factor::TwoFactorService;
+use crate::application::util::password::PasswordHashing;
+use crate::config::{AppConfig, RateLimitStoreKind};
+use crate::logging::LogControl;
 
 
 pub struct AppState
 {
+  pub config: Arc<AppConfig>,
+  pub metrics: Arc<Metrics>,
+  pub rate_limit_store: Arc<dyn RateLimitStore>,
   pub user_service: Arc<UserService>,
   pub auth_service: Arc<AuthService>,
   pub book_service: Arc<BookService>,
   pub author_service: Arc<AuthorService>,
-}
\ No newline at end of file
+  pub review_service: Arc<ReviewService>,
+  pub shelf_service: Arc<ShelfService>,
+  pub wishlist_service: Arc<WishlistService>,
+  pub notification_service: Arc<NotificationService>,
+  pub copy_service: Arc<CopyService>,
+  pub loan_service: Arc<LoanService>,
+  pub hold_service: Arc<HoldService>,
+  pub branch_service: Arc<BranchService>,
+  pub health_service: Arc<HealthService>,
+  pub diagnostics_service: Arc<DiagnosticsService>,
+  pub dump_service: Arc<DumpService>,
+  pub seed_service: Arc<SeedService>,
+  pub account_service: Arc<AccountService>,
+  pub two_factor_service: Arc<TwoFactorService>,
+}
+
+impl AppState {
+  /// Wire the repositories and services to an open pool.
+  pub fn new(config: AppConfig, conn_pool: PgPool, log_control: Arc<LogControl>) -> Self {
+    let loan_settings = LoanSettings {
+      user_loan_period: Duration::days(config.loan.period_user_days),
+      admin_loan_period: Duration::days(config.loan.period_admin_days),
+      max_renewals: config.loan.max_renewals,
+      overdue_block_threshold: config.loan.overdue_block_threshold,
+    };
+
+    let metrics = Arc::new(Metrics::new(conn_pool.clone()));
+
+    // Repositories
+    let user_repository = Arc::new(
+      UserRepository::new(conn_pool.clone())
+    );
+    let book_repository = Arc::new(
+      BookRepository::new(conn_pool.clone())
+    );
+    let author_repository = Arc::new(
+      AuthorRepository::new(conn_pool.clone())
+    );
+    let review_repository = Arc::new(
+      ReviewRepository::new(conn_pool.clone())
+    );
+    let shelf_repository = Arc::new(
+      ShelfRepository::new(conn_pool.clone())
+    );
+    let wishlist_repository = Arc::new(
+      WishlistRepository::new(conn_pool.clone())
+    );
+    let notification_repository = Arc::new(
+      NotificationRepository::new(conn_pool.clone())
+    );
+    let copy_repository = Arc::new(
+      CopyRepository::new(conn_pool.clone())
+    );
+    let loan_repository = Arc::new(
+      LoanRepository::new(conn_pool.clone())
+    );
+    let hold_repository = Arc::new(
+      HoldRepository::new(conn_pool.clone())
+    );
+    let branch_repository = Arc::new(
+      BranchRepository::new(conn_pool.clone())
+    );
+    let health_repository = Arc::new(
+      HealthRepository::new(conn_pool.clone())
+    );
+    let dump_repository = Arc::new(
+      DumpRepository::new(conn_pool.clone())
+    );
+    let seed_repository = Arc::new(
+      SeedRepository::new(conn_pool.clone())
+    );
+    let account_token_repository = Arc::new(
+      AccountTokenRepository::new(conn_pool.clone())
+    );
+    let outbox_repository = Arc::new(
+      OutboxRepository::new(conn_pool.clone())
+    );
+    let login_failure_repository = Arc::new(
+      LoginFailureRepository::new(conn_pool.clone())
+    );
+    let recovery_code_repository = Arc::new(
+      RecoveryCodeRepository::new(conn_pool.clone())
+    );
+
+    let rate_limit_store: Arc<dyn RateLimitStore> = match config.rate_limit.store {
+      RateLimitStoreKind::Memory => Arc::new(MemoryStore::new()),
+      RateLimitStoreKind::Postgres => Arc::new(PostgresStore::new(Arc::new(RateLimitRepository::new(conn_pool.clone())))),
+    };
+    let mailer: Arc<dyn Mailer> = Arc::new(OutboxMailer::new(outbox_repository, &config.mail));
+
+    // Services
+    let user_service = Arc::new(UserService::new(user_repository.clone()));
+    let two_factor_service = Arc::new(TwoFactorService::new(user_repository.clone(), recovery_code_repository, &config.auth));
+    let auth_service = Arc::new(AuthService::new(
+      user_repository.clone(),
+      login_failure_repository,
+      two_factor_service.clone(),
+      &config.auth,
+      &config.login,
+    ));
+    let account_service = Arc::new(AccountService::new(user_repository.clone(), account_token_repository, mailer, &config.auth));
+    let book_service = Arc::new(BookService::new(book_repository.clone(), author_repository.clone(), copy_repository.clone()));
+    let author_service = Arc::new(AuthorService::new(author_repository, book_repository.clone()));
+    let review_service = Arc::new(ReviewService::new(review_repository, book_repository.clone(), user_repository.clone()));
+    let shelf_service = Arc::new(ShelfService::new(shelf_repository, book_repository.clone(), user_repository.clone()));
+    let wishlist_service = Arc::new(WishlistService::new(wishlist_repository, book_repository.clone()));
+    let notification_service = Arc::new(NotificationService::new(notification_repository));
+    let copy_service = Arc::new(CopyService::new(copy_repository.clone(), book_repository.clone(), branch_repository.clone()));
+    let loan_service = Arc::new(LoanService::new(
+      loan_repository,
+      hold_repository.clone(),
+      book_repository.clone(),
+      user_repository,
+      loan_settings,
+    ));
+    let hold_service = Arc::new(HoldService::new(hold_repository, copy_repository.clone(), book_repository));
+    let branch_service = Arc::new(BranchService::new(branch_repository, copy_repository));
+    let health_service = Arc::new(HealthService::new(health_repository.clone()));
+
+    let config = Arc::new(config);
+    let diagnostics_service = Arc::new(DiagnosticsService::new(log_control, health_repository.clone(), config.clone()));
+    let dump_service = Arc::new(DumpService::new(dump_repository.clone(), health_repository));
+    let seed_service = Arc::new(SeedService::new(seed_repository, dump_repository, PasswordHashing::new(&config.auth)));
+
+    Self {
+      config,
+      metrics,
+      rate_limit_store,
+      user_service,
+      auth_service,
+      book_service,
+      author_service,
+      review_service,
+      shelf_service,
+      wishlist_service,
+      notification_service,
+      copy_service,
+      loan_service,
+      hold_service,
+      branch_service,
+      health_service,
+      diagnostics_service,
+      dump_service,
+      seed_service,
+      account_service,
+      two_factor_service,
+    }
+  }
+}
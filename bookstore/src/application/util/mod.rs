@@ -0,0 +1,4 @@
+pub mod validation;
+pub mod password;
+pub mod token;
+pub mod totp;
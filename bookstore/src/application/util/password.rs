@@ -0,0 +1,152 @@
+//! The password policy and the hashing of passwords.
+//!
+//! New hashes are Argon2id with the parameters from `[auth]`. The hashes of
+//! older versions (bcrypt, or Argon2id with other parameters) still verify,
+//! and the caller is told to replace them.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::error::Error;
+use std::sync::LazyLock;
+use actix_web::web;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::SaltString;
+use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordHasher as _, PasswordVerifier as _, Version};
+use validator::{ValidationError, ValidationErrors};
+
+use crate::application::util::validation::PASSWORD_MAX_LENGTH;
+use crate::config::AuthConfig;
+
+static COMMON_PASSWORDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
+  include_str!("common_passwords.txt")
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    .collect()
+});
+
+
+/// The rules a new password has to follow, besides the ones of the request DTOs.
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+  min_length: usize,
+  reject_common: bool,
+}
+
+impl PasswordPolicy {
+  pub fn new(config: &AuthConfig) -> Self {
+    Self {
+      min_length: config.password_min_length,
+      reject_common: config.reject_common_passwords,
+    }
+  }
+
+  /// Check the password of the user with the nickname; the errors are reported for `password`.
+  pub fn check(&self, password: &str, nickname: &str) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::new();
+    let length = password.chars().count();
+    let lowercase = password.to_lowercase();
+
+    if length < self.min_length || length > PASSWORD_MAX_LENGTH {
+      let mut error = ValidationError::new("length");
+      error.add_param(Cow::from("min"), &self.min_length);
+      error.add_param(Cow::from("max"), &PASSWORD_MAX_LENGTH);
+      errors.add("password", error);
+    }
+    if self.reject_common && COMMON_PASSWORDS.contains(lowercase.as_str()) {
+      errors.add("password", ValidationError::new("common_password"));
+    }
+    if !nickname.is_empty() && lowercase.contains(&nickname.to_lowercase()) {
+      errors.add("password", ValidationError::new("contains_nickname"));
+    }
+
+    if errors.is_empty() {
+      Ok(())
+    } else {
+      Err(errors)
+    }
+  }
+}
+
+
+/// How a password matched its stored hash.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verification {
+  Invalid,
+  Valid,
+  /// Valid, but the hash should be replaced with one made by `PasswordHashing::hash`.
+  Outdated,
+}
+
+/// Hashes passwords on the blocking thread pool, so that the workers are not stalled.
+#[derive(Debug, Clone)]
+pub struct PasswordHashing {
+  params: Params,
+  /// Matches no password; made with `params`, so checking it takes as long as a real hash.
+  dummy_hash: String,
+}
+
+impl PasswordHashing {
+  /// Panics on the parameters `AppConfig::load` rejects.
+  pub fn new(config: &AuthConfig) -> Self {
+    let params = Params::new(config.argon2_memory_kib, config.argon2_iterations, config.argon2_parallelism, None)
+      .expect("the Argon2 parameters are checked with the config");
+    // a fixed salt and an all-zero digest, which no password hashes to in practice
+    let dummy_hash = format!(
+      "$argon2id$v=19$m={},t={},p={}$c29tZXNhbHRzb21lc2FsdA${}",
+      params.m_cost(), params.t_cost(), params.p_cost(), "A".repeat(43),
+    );
+    Self { params, dummy_hash }
+  }
+
+  fn argon2(&self) -> Argon2<'static> {
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params.clone())
+  }
+
+  /// Hash a password the way it is stored in `users.hashed_password`.
+  pub async fn hash(&self, password: &str) -> Result<String, Box<dyn Error>> {
+    let argon2 = self.argon2();
+    let password = password.to_string();
+    let hash = web::block(move || {
+      let salt = SaltString::generate(&mut OsRng);
+      argon2.hash_password(password.as_bytes(), &salt).map(|hash| hash.to_string())
+    }).await?;
+    Ok(hash?)
+  }
+
+  /// Check a password against a hash made by this or an older version.
+  pub async fn verify(&self, password: &str, hash: &str) -> Result<Verification, Box<dyn Error>> {
+    let argon2 = self.argon2();
+    let params = self.params.clone();
+    let password = password.to_string();
+    let hash = hash.to_string();
+
+    let verification = web::block(move || -> Result<Verification, Box<dyn Error + Send + Sync>> {
+      // bcrypt was used before Argon2id
+      if hash.starts_with("$2") {
+        return match bcrypt::verify(&password, &hash)? {
+          true => Ok(Verification::Outdated),
+          false => Ok(Verification::Invalid),
+        }
+      }
+
+      let parsed = PasswordHash::new(&hash)?;
+      if argon2.verify_password(password.as_bytes(), &parsed).is_err() {
+        return Ok(Verification::Invalid)
+      }
+      let current = parsed.algorithm == Algorithm::Argon2id.ident()
+        && Params::try_from(&parsed).is_ok_and(|p| {
+          (p.m_cost(), p.t_cost(), p.p_cost()) == (params.m_cost(), params.t_cost(), params.p_cost())
+        });
+      Ok(if current { Verification::Valid } else { Verification::Outdated })
+    }).await?;
+
+    verification.map_err(|e| e as Box<dyn Error>)
+  }
+
+  /// Do the work of `verify` when there is no hash to check against,
+  /// so that the time it takes does not tell whether the user exists.
+  pub async fn verify_dummy(&self, password: &str) -> Result<(), Box<dyn Error>> {
+    self.verify(password, &self.dummy_hash).await.map(|_| ())
+  }
+}
@@ -0,0 +1,23 @@
+//! The one-time tokens sent by mail.
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+
+/// A new random token, 256 bits as hex.
+pub fn generate() -> String {
+  let mut bytes = [0u8; 32];
+  OsRng.fill_bytes(&mut bytes);
+  to_hex(&bytes)
+}
+
+/// The SHA-256 of the token as hex, which is what the database keeps.
+/// The tokens are random, so a plain hash is enough to make a leaked table useless.
+pub fn hash(token: &str) -> String {
+  to_hex(&Sha256::digest(token.as_bytes()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,100 @@
+//! RFC 6238 time-based one-time passwords, as the authenticator apps expect them
+//! by default: HMAC-SHA1, 6 digits, a new code every 30 seconds.
+
+use std::time::SystemTime;
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha1::Sha1;
+
+use crate::application::util::token;
+
+pub const DIGITS: usize = 6;
+pub const STEP_SECS: u64 = 30;
+/// How many steps a code may be behind or ahead, for the clocks that drift.
+const SKEW_STEPS: i64 = 1;
+const RECOVERY_CODE_COUNT: usize = 10;
+
+
+/// A new secret, 160 bits as RFC 4648 base32 without padding.
+pub fn generate_secret() -> String {
+  let mut bytes = [0u8; 20];
+  OsRng.fill_bytes(&mut bytes);
+  BASE32_NOPAD.encode(&bytes)
+}
+
+/// The `otpauth://` URI the authenticator apps read from a QR code.
+pub fn otpauth_uri(secret: &str, issuer: &str, account: &str) -> String {
+  format!(
+    "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+    encode(issuer), encode(account), secret, encode(issuer), DIGITS, STEP_SECS,
+  )
+}
+
+// `+` for a space is only understood in the query, so it is always `%20`
+fn encode(value: &str) -> String {
+  form_urlencoded::byte_serialize(value.as_bytes()).collect::<String>().replace('+', "%20")
+}
+
+/// The time step of now.
+pub fn current_step() -> i64 {
+  let secs = SystemTime::now()
+    .duration_since(SystemTime::UNIX_EPOCH)
+    .expect("going back in time, huh?")
+    .as_secs();
+  (secs / STEP_SECS) as i64
+}
+
+/// The code for the time step, `None` if the secret is not base32.
+pub fn code(secret: &str, step: i64) -> Option<String> {
+  let key = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+  // HMAC accepts keys of any length
+  let mut mac = Hmac::<Sha1>::new_from_slice(&key).unwrap();
+  mac.update(&step.to_be_bytes());
+  let digest = mac.finalize().into_bytes();
+
+  // RFC 4226 dynamic truncation
+  let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+  let value = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]]) & 0x7fff_ffff;
+  Some(format!("{:0width$}", value % 10u32.pow(DIGITS as u32), width = DIGITS))
+}
+
+/// The step of the code if it is valid now and newer than `last_step`,
+/// so that a code cannot be used twice.
+pub fn matching_step(secret: &str, code: &str, last_step: Option<i64>) -> Option<i64> {
+  let now = current_step();
+  (now - SKEW_STEPS..=now + SKEW_STEPS)
+    .filter(|step| last_step.is_none_or(|last| *step > last))
+    .find(|step| self::code(secret, *step).is_some_and(|expected| constant_time_eq(&expected, code)))
+}
+
+/// Whether the input looks like a TOTP code rather than a recovery code.
+pub fn is_code(value: &str) -> bool {
+  value.len() == DIGITS && value.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+  a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// New recovery codes, like `k3xq-7fpa`: 40 random bits each.
+pub fn generate_recovery_codes() -> Vec<String> {
+  (0..RECOVERY_CODE_COUNT)
+    .map(|_| {
+      let mut bytes = [0u8; 5];
+      OsRng.fill_bytes(&mut bytes);
+      let code = BASE32_NOPAD.encode(&bytes).to_lowercase();
+      format!("{}-{}", &code[..4], &code[4..])
+    })
+    .collect()
+}
+
+/// The SHA-256 of a recovery code as typed, whatever the case and the separators.
+pub fn hash_recovery_code(code: &str) -> String {
+  let normalized: String = code.chars()
+    .filter(|c| c.is_ascii_alphanumeric())
+    .map(|c| c.to_ascii_lowercase())
+    .collect();
+  token::hash(&normalized)
+}
@@ -0,0 +1,63 @@
+//! Rules shared by the request DTOs, which declare them with `#[validate(...)]`.
+//! The codes of the errors are part of the API: clients may rely on them, and
+//! the HTTP layer picks the message for each code.
+
+use std::borrow::Cow;
+use std::sync::LazyLock;
+use regex::Regex;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+use validator::ValidationError;
+
+/// Latin letters, digits and `.`, `-`, `_`.
+pub static NICKNAME_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[a-zA-Z0-9.\-_]+$").unwrap());
+
+/// Letters of any script, possibly with combining marks, in words joined by single
+/// spaces, hyphens or apostrophes: `Ёлкин`, `Салтыков-Щедрин`, `O'Brien`, `Mary Ann`.
+pub static NAME_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\p{L}\p{M}*(?:[ '’-]?\p{L}\p{M}*)*$").unwrap());
+
+/// A log target such as `bookstore::adapters` or `actix_web`.
+pub static LOG_MODULE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[a-zA-Z0-9_\-]+(::[a-zA-Z0-9_\-]+)*$").unwrap());
+
+/// A level of the `log` crate, in any case.
+pub static LOG_LEVEL_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?i)(off|error|warn|info|debug|trace)$").unwrap());
+
+pub const PASSWORD_MAX_LENGTH: usize = 128;
+
+/// The string has something besides whitespace.
+pub fn not_blank(value: &str) -> Result<(), ValidationError> {
+  if value.trim().is_empty() {
+    return Err(ValidationError::new("blank"))
+  }
+  Ok(())
+}
+
+/// Any non-empty password that fits into `PASSWORD_MAX_LENGTH` characters.
+pub fn check_password(password: &str) -> Result<(), ValidationError> {
+  let length = password.chars().count();
+  if length == 0 || length > PASSWORD_MAX_LENGTH {
+    let mut error = ValidationError::new("length");
+    error.add_param(Cow::from("min"), &1);
+    error.add_param(Cow::from("max"), &PASSWORD_MAX_LENGTH);
+    return Err(error)
+  }
+  Ok(())
+}
+
+/// The name in NFC, so that `Ё` typed as `Е` and a combining diaeresis is stored
+/// the same way as the precomposed letter.
+pub fn normalize_name(name: String) -> String {
+  if is_nfc(&name) {
+    return name
+  }
+  name.nfc().collect()
+}
+
+/// An error of a rule on several fields, reported for `field`.
+///
+/// Struct-level errors are collected under `__all__`; the `field` parameter
+/// tells which field the client should fix.
+pub fn cross_field_error(code: &'static str, field: &'static str) -> ValidationError {
+  let mut error = ValidationError::new(code);
+  error.add_param(Cow::from("field"), &field);
+  error
+}
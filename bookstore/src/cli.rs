@@ -0,0 +1,138 @@
+use std::path::PathBuf;
+use clap::{Args, Parser, Subcommand};
+
+
+/// Library API server and the tools to manage its deployment.
+#[derive(Debug, Parser)]
+#[command(name = "bookstore", version)]
+pub struct Cli {
+  /// TOML config file.
+  #[arg(long, global = true, env = "APP_CONFIG_FILE", value_name = "PATH")]
+  pub config: Option<PathBuf>,
+
+  /// Print the effective configuration, secrets redacted, and exit; the same as `check-config --print`.
+  #[arg(long)]
+  pub print_config: bool,
+
+  /// What to do; serves the API if omitted.
+  #[command(subcommand)]
+  pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+  /// Apply pending migrations and serve the API.
+  Serve,
+
+  /// Inspect or change the database schema.
+  Migrate {
+    #[command(subcommand)]
+    action: MigrateAction,
+  },
+
+  /// Create a user with the admin role.
+  CreateAdmin {
+    #[arg(long)]
+    nickname: String,
+
+    #[arg(long, default_value = "Admin")]
+    first_name: String,
+
+    #[arg(long, default_value = "Admin")]
+    last_name: String,
+
+    #[command(flatten)]
+    password: PasswordArgs,
+  },
+
+  /// Set a new password for a user.
+  ResetPassword {
+    nickname: String,
+
+    #[command(flatten)]
+    password: PasswordArgs,
+  },
+
+  /// Suspend a user, so that they cannot use the API.
+  Suspend {
+    nickname: String,
+
+    /// Lift the suspension instead.
+    #[arg(long)]
+    lift: bool,
+  },
+
+  /// Generate authors, books and users for demos and load tests.
+  Seed {
+    #[arg(long, default_value_t = 100)]
+    authors: usize,
+
+    #[arg(long, default_value_t = 1000)]
+    books: usize,
+
+    #[arg(long, default_value_t = 50)]
+    users: usize,
+
+    /// RNG seed; the same seed always generates the same data.
+    #[arg(long, default_value_t = 42)]
+    seed: u64,
+
+    /// Password of every generated user.
+    #[arg(long, default_value = "password")]
+    user_password: String,
+
+    /// Add the data even if the database already has some.
+    #[arg(long)]
+    force: bool,
+  },
+
+  /// Write all data to a JSON file.
+  Export {
+    #[arg(value_name = "PATH")]
+    output: PathBuf,
+  },
+
+  /// Load a file written by `export` into an empty database.
+  Import {
+    #[arg(value_name = "PATH")]
+    input: PathBuf,
+
+    /// Delete all existing data first.
+    #[arg(long)]
+    replace: bool,
+  },
+
+  /// Check the configuration without starting anything.
+  CheckConfig {
+    /// Print the effective configuration, secrets redacted.
+    #[arg(long)]
+    print: bool,
+  },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MigrateAction {
+  /// Apply pending migrations.
+  Up,
+
+  /// List the migrations and whether they are applied.
+  Status,
+
+  /// Revert the latest migration, or all migrations after `--to`.
+  Down {
+    /// Version to go back to; 0 reverts everything.
+    #[arg(long, value_name = "VERSION")]
+    to: Option<i64>,
+
+    /// Do revert; without it the migrations are only listed.
+    #[arg(long)]
+    yes: bool,
+  },
+}
+
+#[derive(Debug, Args)]
+pub struct PasswordArgs {
+  /// Read the password from the first line of stdin instead of prompting for it.
+  #[arg(long)]
+  pub password_stdin: bool,
+}
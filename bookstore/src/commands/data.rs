@@ -0,0 +1,70 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use bookstore::application::services::dump::{DataDump, DumpExportResult, DumpImportResult};
+use bookstore::application::services::seed::{SeedParams, SeedResult};
+use bookstore::application::state::app_state::AppState;
+
+use crate::error::CommandError;
+
+
+pub async fn seed(state: &AppState, params: SeedParams) -> Result<(), CommandError> {
+  match state.seed_service.generate(params).await {
+    SeedResult::Ok { authors, books, users } => {
+      println!("Added {} authors, {} books and {} users", authors, books, users);
+      Ok(())
+    },
+    SeedResult::NotEmpty(tables) => Err(CommandError::Refused(format!(
+      "the database already has data in {}; pass `--force` to add the generated data anyway",
+      tables.join(", "),
+    ))),
+    SeedResult::UnexpectedError(e) => Err(CommandError::Unexpected(e)),
+  }
+}
+
+pub async fn export(state: &AppState, output: &Path) -> Result<(), CommandError> {
+  let dump = match state.dump_service.export().await {
+    DumpExportResult::Ok(dump) => dump,
+    DumpExportResult::UnexpectedError(e) => return Err(CommandError::Unexpected(e)),
+  };
+
+  let io_error = |e| CommandError::Io { what: output.display().to_string(), source: e };
+  let mut writer = BufWriter::new(File::create(output).map_err(io_error)?);
+  serde_json::to_writer(&mut writer, &dump).map_err(|e| io_error(e.into()))?;
+  writer.flush().map_err(io_error)?;
+
+  println!("Exported {} tables to {}", dump.tables.len(), output.display());
+  Ok(())
+}
+
+pub async fn import(state: &AppState, input: &Path, replace: bool) -> Result<(), CommandError> {
+  let file = File::open(input)
+    .map_err(|e| CommandError::Io { what: input.display().to_string(), source: e })?;
+  let dump: DataDump = serde_json::from_reader(BufReader::new(file))
+    .map_err(|e| CommandError::InvalidDump { path: input.display().to_string(), source: e })?;
+  let exported_at = dump.exported_at;
+
+  match state.dump_service.import(dump, replace).await {
+    DumpImportResult::Ok => {
+      println!("Imported the data exported at {}", exported_at.to_rfc3339());
+      Ok(())
+    },
+    DumpImportResult::UnsupportedFormat(format) => Err(CommandError::Refused(
+      format!("the file has format version {}, which this version of bookstore cannot read", format)
+    )),
+    DumpImportResult::UnknownTable(table) => Err(CommandError::Refused(
+      format!("the file contains an unknown table `{}`", table)
+    )),
+    DumpImportResult::VersionMismatch { dump, database } => Err(CommandError::Refused(format!(
+      "the file was exported at schema version {}, but the database is at {}. Migrate the database to the same version first",
+      dump.map_or("none".to_string(), |v| v.to_string()),
+      database.map_or("none".to_string(), |v| v.to_string()),
+    ))),
+    DumpImportResult::NotEmpty(tables) => Err(CommandError::Refused(format!(
+      "the database already has data in {}; pass `--replace` to delete it first",
+      tables.join(", "),
+    ))),
+    DumpImportResult::UnexpectedError(e) => Err(CommandError::Unexpected(e)),
+  }
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use bookstore::application::entities::health::MigrationStatus;
+use bookstore::application::services::health::MigrationStatusFetchResult;
+use bookstore::config::AppConfig;
+use bookstore::logging::LogControl;
+use bookstore::MIGRATOR;
+
+use crate::cli::MigrateAction;
+use crate::db_conn::connect;
+use crate::error::CommandError;
+use crate::init::{wire, InitData};
+
+
+pub async fn run(config: AppConfig, log_control: Arc<LogControl>, action: MigrateAction) -> Result<(), CommandError> {
+  let conn_pool = connect(&config.database).await?;
+  let init_data = wire(config, conn_pool, log_control);
+
+  let result = match action {
+    MigrateAction::Up => up(&init_data).await,
+    MigrateAction::Status => status(&init_data).await,
+    MigrateAction::Down { to, yes } => down(&init_data, to, yes).await,
+  };
+
+  init_data.conn_pool.close().await;
+  result
+}
+
+async fn get_status(init_data: &InitData) -> Result<Vec<MigrationStatus>, CommandError> {
+  match init_data.app_state.health_service.get_migration_status().await {
+    MigrationStatusFetchResult::Ok(migrations) => Ok(migrations),
+    MigrationStatusFetchResult::UnexpectedError(e) => Err(CommandError::Unexpected(e)),
+  }
+}
+
+async fn up(init_data: &InitData) -> Result<(), CommandError> {
+  let pending: Vec<_> = get_status(init_data).await?.into_iter().filter(|m| !m.applied).collect();
+
+  MIGRATOR.run(&init_data.conn_pool).await.map_err(CommandError::Migration)?;
+
+  for m in &pending {
+    println!("applied {} {}", m.version, m.description);
+  }
+  println!("The schema is up to date ({} migrations applied now)", pending.len());
+  Ok(())
+}
+
+async fn status(init_data: &InitData) -> Result<(), CommandError> {
+  for m in get_status(init_data).await? {
+    println!("{} {:<8} {}", m.version, if m.applied { "applied" } else { "pending" }, m.description);
+  }
+  Ok(())
+}
+
+async fn down(init_data: &InitData, to: Option<i64>, yes: bool) -> Result<(), CommandError> {
+  let applied: Vec<_> = get_status(init_data).await?.into_iter().filter(|m| m.applied).collect();
+
+  // by default only the latest migration is reverted
+  let target = match to {
+    Some(version) => version,
+    None => applied.iter().rev().nth(1).map_or(0, |m| m.version),
+  };
+  let reverted: Vec<_> = applied.iter().rev().filter(|m| m.version > target).collect();
+
+  if reverted.is_empty() {
+    println!("Nothing to revert");
+    return Ok(())
+  }
+  if !yes {
+    for m in &reverted {
+      println!("would revert {} {}", m.version, m.description);
+    }
+    return Err(CommandError::Refused(
+      "reverting migrations may delete data; run again with `--yes` to revert them".to_string()
+    ))
+  }
+
+  MIGRATOR.undo(&init_data.conn_pool, target).await.map_err(CommandError::Migration)?;
+
+  for m in &reverted {
+    println!("reverted {} {}", m.version, m.description);
+  }
+  Ok(())
+}
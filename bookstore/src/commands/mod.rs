@@ -0,0 +1,88 @@
+//! Subcommands of the binary. All of them share the configuration, the
+//! logging and the service wiring with the server.
+
+mod serve;
+mod migrate;
+mod user;
+mod data;
+
+use std::path::Path;
+use std::sync::Arc;
+
+use bookstore::application::services::health::ReadinessCheckResult;
+use bookstore::application::services::seed::SeedParams;
+use bookstore::config::{AppConfig, ConfigError};
+use bookstore::logging::LogControl;
+
+use crate::cli::{Cli, Command};
+use crate::db_conn::connect;
+use crate::error::{CommandError, StartupError};
+use crate::init::{wire, InitData};
+
+
+pub async fn run(cli: Cli) -> Result<(), CommandError> {
+  if cli.print_config {
+    return check_config(cli.config.as_deref(), true)
+  }
+
+  let command = cli.command.unwrap_or(Command::Serve);
+  if let Command::CheckConfig { print } = command {
+    return check_config(cli.config.as_deref(), print)
+  }
+
+  let config = AppConfig::load(cli.config.as_deref()).map_err(StartupError::Config)?;
+  let log_control = LogControl::init(&config.logging).map_err(StartupError::Logging)?;
+
+  let tool = match command {
+    Command::Serve => return serve::run(config, log_control).await.map_err(CommandError::from),
+    Command::Migrate { action } => return migrate::run(config, log_control, action).await,
+    command => command,
+  };
+
+  let init_data = connect_current(config, log_control).await?;
+  let state = &init_data.app_state;
+  let result = match tool {
+    Command::CreateAdmin { nickname, first_name, last_name, password } =>
+      user::create_admin(state, nickname, first_name, last_name, &password).await,
+    Command::ResetPassword { nickname, password } => user::reset_password(state, nickname, &password).await,
+    Command::Suspend { nickname, lift } => user::suspend(state, nickname, !lift).await,
+    Command::Seed { authors, books, users, seed, user_password, force } => {
+      let params = SeedParams { authors, books, users, seed, user_password, force };
+      data::seed(state, params).await
+    },
+    Command::Export { output } => data::export(state, &output).await,
+    Command::Import { input, replace } => data::import(state, &input, replace).await,
+    Command::Serve | Command::Migrate { .. } | Command::CheckConfig { .. } => unreachable!("handled above"),
+  };
+
+  init_data.conn_pool.close().await;
+  result
+}
+
+/// Report every problem with the configuration, optionally printing it.
+fn check_config(file: Option<&Path>, print: bool) -> Result<(), CommandError> {
+  let (config, problems) = AppConfig::load_with_problems(file);
+  if print {
+    print!("{}", config.to_toml());
+  }
+  if !problems.is_empty() {
+    return Err(StartupError::Config(ConfigError { problems }).into())
+  }
+  if !print {
+    println!("The configuration is valid");
+  }
+  Ok(())
+}
+
+/// Connect and wire the services for a tool; unlike the server, tools do not
+/// migrate the database, so they refuse to work with an outdated schema.
+async fn connect_current(config: AppConfig, log_control: Arc<LogControl>) -> Result<InitData, CommandError> {
+  let conn_pool = connect(&config.database).await?;
+  let init_data = wire(config, conn_pool, log_control);
+
+  if let ReadinessCheckResult::NotReady(resp) = init_data.app_state.health_service.check_ready().await {
+    init_data.conn_pool.close().await;
+    return Err(CommandError::PendingMigrations(resp.pending_migrations))
+  }
+  Ok(init_data)
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use bookstore::config::AppConfig;
+use bookstore::logging::LogControl;
+
+use crate::error::StartupError;
+use crate::init::init;
+use crate::server::{create_metrics_server, create_server};
+use crate::telemetry;
+
+
+pub async fn run(config: AppConfig, log_control: Arc<LogControl>) -> Result<(), StartupError> {
+  telemetry::init(&config.tracing)?;
+
+  let init_data = init(config, log_control).await?;
+  let addr = format!("{}:{}", init_data.app_state.config.server.host, init_data.app_state.config.server.port);
+  let metrics_server = match create_metrics_server(init_data.app_state.clone()) {
+    Some(server) => {
+      let metrics_addr = format!("{}:{}", init_data.app_state.config.server.host, init_data.app_state.config.metrics.port);
+      let server = server.map_err(|e| StartupError::Bind { addr: metrics_addr.clone(), source: e })?;
+      log::info!("Metrics are served on {}", metrics_addr);
+      Some(server)
+    },
+    None => None,
+  };
+  let server = create_server(init_data.app_state)
+    .map_err(|e| StartupError::Bind { addr: addr.clone(), source: e })?;
+
+  log::info!("The server is listening on {}", addr);
+  let metrics_server = async {
+    if let Some(server) = metrics_server {
+      if let Err(e) = server.await {
+        log::error!(error:err = e; "The metrics server stopped with an error: {}", e);
+      }
+    }
+  };
+  let server = async {
+    if let Err(e) = server.await {
+      log::error!(error:err = e; "The server stopped with an error: {}", e);
+    }
+  };
+  // both servers stop on the same SIGINT / SIGTERM
+  futures::join!(server, metrics_server);
+
+  // the server has drained in-flight requests by now
+  init_data.conn_pool.close().await;
+  telemetry::shutdown();
+  log::info!("Shutdown complete");
+  Ok(())
+}
@@ -0,0 +1,104 @@
+use std::io::BufRead;
+use validator::ValidationErrors;
+
+use bookstore::application::dto::request::user::{RegisterReq, UpdateSuspendedReq};
+use bookstore::application::services::auth::{PasswordResetResult, RegistrationError};
+use bookstore::application::services::user::{UserFetchResult, UserUpdateSuspendedResult};
+use bookstore::application::state::app_state::AppState;
+
+use crate::cli::PasswordArgs;
+use crate::error::CommandError;
+
+
+/// The password from stdin, or typed twice at a prompt without echo.
+fn read_password(args: &PasswordArgs) -> Result<String, CommandError> {
+  if args.password_stdin {
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)
+      .map_err(|e| CommandError::Io { what: "stdin".to_string(), source: e })?;
+    return Ok(line.trim_end_matches(['\r', '\n']).to_string())
+  }
+
+  let prompt = |text: &str| rpassword::prompt_password(text)
+    .map_err(|e| CommandError::Io { what: "the terminal".to_string(), source: e });
+  let password = prompt("Password: ")?;
+  if prompt("Repeat the password: ")? != password {
+    return Err(CommandError::Refused("the passwords do not match".to_string()))
+  }
+  Ok(password)
+}
+
+/// The password policy rules the password breaks.
+fn weak_password(errors: ValidationErrors) -> CommandError {
+  let rules: Vec<_> = errors.field_errors().into_values().flatten()
+    .map(|e| match e.code.as_ref() {
+      "length" => format!("it must be {} to {} characters long", e.params["min"], e.params["max"]),
+      "common_password" => "it is too common".to_string(),
+      "contains_nickname" => "it contains the nickname".to_string(),
+      code => code.to_string(),
+    })
+    .collect();
+  CommandError::Refused(format!("the password does not follow the policy: {}", rules.join(", ")))
+}
+
+pub async fn create_admin(
+  state: &AppState,
+  nickname: String,
+  first_name: String,
+  last_name: String,
+  password: &PasswordArgs,
+) -> Result<(), CommandError> {
+  let data = RegisterReq {
+    first_name,
+    last_name,
+    middle_name: None,
+    nickname: nickname.clone(),
+    password: read_password(password)?,
+  };
+
+  match state.auth_service.register_admin(data).await {
+    Ok(user) => {
+      println!("Created admin {} ({})", user.nickname, user.id);
+      Ok(())
+    },
+    Err(RegistrationError::AlreadyExists) => Err(CommandError::Refused(
+      format!("user `{}` already exists", nickname)
+    )),
+    Err(RegistrationError::BadRequest) => Err(CommandError::Refused(
+      "the nickname, names or password are not valid".to_string()
+    )),
+    Err(RegistrationError::WeakPassword(errors)) => Err(weak_password(errors)),
+    Err(e) => Err(CommandError::Unexpected(Box::new(e))),
+  }
+}
+
+pub async fn reset_password(state: &AppState, nickname: String, password: &PasswordArgs) -> Result<(), CommandError> {
+  let password = read_password(password)?;
+
+  match state.auth_service.reset_password(&nickname, &password).await {
+    PasswordResetResult::Ok => {
+      println!("The password of {} is changed", nickname);
+      Ok(())
+    },
+    PasswordResetResult::UserNotFound => Err(CommandError::Refused(format!("user `{}` not found", nickname))),
+    PasswordResetResult::WeakPassword(errors) => Err(weak_password(errors)),
+    PasswordResetResult::UnexpectedError(e) => Err(CommandError::Unexpected(e)),
+  }
+}
+
+pub async fn suspend(state: &AppState, nickname: String, suspended: bool) -> Result<(), CommandError> {
+  let user = match state.user_service.get_by_nickname(&nickname).await {
+    UserFetchResult::Ok(user) => user,
+    UserFetchResult::NotFound => return Err(CommandError::Refused(format!("user `{}` not found", nickname))),
+    UserFetchResult::UnexpectedError(e) => return Err(CommandError::Unexpected(e)),
+  };
+
+  match state.user_service.update_suspended(&user.id, UpdateSuspendedReq { suspended }).await {
+    UserUpdateSuspendedResult::Ok(_) => {
+      println!("{} is {}", nickname, if suspended { "suspended" } else { "no longer suspended" });
+      Ok(())
+    },
+    UserUpdateSuspendedResult::NotFound => Err(CommandError::Refused(format!("user `{}` not found", nickname))),
+    UserUpdateSuspendedResult::UnexpectedError(e) => Err(CommandError::Unexpected(e)),
+  }
+}
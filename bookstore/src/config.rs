@@ -0,0 +1,659 @@
+//! Application configuration.
+//!
+//! The configuration is assembled once at startup from three layers, each
+//! overriding the previous one: built-in defaults, an optional TOML file
+//! and the `APP_*` environment variables.
+
+use std::fmt::{Display, Formatter};
+use std::net::IpAddr;
+use std::path::Path;
+use std::str::FromStr;
+use derive_more::Error;
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+
+use crate::application::util::validation::PASSWORD_MAX_LENGTH;
+
+
+const REDACTED: &str = "<redacted>";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+  pub server: ServerConfig,
+  pub database: DatabaseConfig,
+  pub auth: AuthConfig,
+  pub admin: AdminConfig,
+  pub loan: LoanConfig,
+  pub login: LoginConfig,
+  pub mail: MailConfig,
+  pub rate_limit: RateLimitConfig,
+  pub metrics: MetricsConfig,
+  pub tracing: TracingConfig,
+  pub logging: LoggingConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+  pub host: String,
+  pub port: u16,
+
+  /// Serve the interactive API docs at `/docs`.
+  pub docs_on: bool,
+
+  /// How long in-flight requests may take to finish after a shutdown signal.
+  pub shutdown_timeout_secs: u64,
+
+  /// Largest accepted JSON request body.
+  pub max_body_bytes: usize,
+
+  /// Reject request bodies and query strings with fields the endpoint does not know.
+  pub strict_requests: bool,
+
+  /// Addresses of the reverse proxies whose `X-Forwarded-For` is believed.
+  pub trusted_proxies: ProxyList,
+}
+
+impl Default for ServerConfig {
+  fn default() -> Self {
+    Self {
+      host: "0.0.0.0".to_string(),
+      port: 3000,
+      docs_on: false,
+      shutdown_timeout_secs: 30,
+      max_body_bytes: 256 * 1024,
+      strict_requests: false,
+      trusted_proxies: ProxyList::default(),
+    }
+  }
+}
+
+/// Addresses and networks, e.g. `10.0.0.1` or `172.16.0.0/12`; a comma-separated
+/// list in the environment.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<String>", into = "Vec<String>")]
+pub struct ProxyList(pub Vec<IpNet>);
+
+impl ProxyList {
+  pub fn contains(&self, ip: &IpAddr) -> bool {
+    self.0.iter().any(|net| net.contains(ip))
+  }
+}
+
+impl FromStr for ProxyList {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let items: Vec<String> = s.split(',')
+      .map(str::trim)
+      .filter(|item| !item.is_empty())
+      .map(str::to_string)
+      .collect();
+    Self::try_from(items)
+  }
+}
+
+impl TryFrom<Vec<String>> for ProxyList {
+  type Error = String;
+
+  fn try_from(items: Vec<String>) -> Result<Self, Self::Error> {
+    items.iter()
+      .map(|item| item.parse::<IpNet>()
+        .or_else(|_| item.parse::<IpAddr>().map(IpNet::from))
+        .map_err(|_| format!("`{}` is neither an address nor a network", item)))
+      .collect::<Result<Vec<_>, _>>()
+      .map(ProxyList)
+  }
+}
+
+impl From<ProxyList> for Vec<String> {
+  fn from(value: ProxyList) -> Self {
+    value.0.iter().map(IpNet::to_string).collect()
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+  pub user: String,
+  pub pass: String,
+  pub host: String,
+  pub port: u16,
+  pub name: String,
+
+  pub max_connections: u32,
+  pub min_connections: u32,
+
+  /// How long a request may wait for a free connection.
+  pub acquire_timeout_secs: u64,
+
+  /// Server-side limit for a single statement; 0 disables it.
+  pub statement_timeout_ms: u64,
+
+  /// How long to keep retrying the initial connection at startup.
+  pub connect_deadline_secs: u64,
+}
+
+impl Default for DatabaseConfig {
+  fn default() -> Self {
+    Self {
+      user: "postgres".to_string(),
+      pass: "postgres".to_string(),
+      host: "localhost".to_string(),
+      port: 5432,
+      name: "postgres".to_string(),
+      max_connections: 10,
+      min_connections: 0,
+      acquire_timeout_secs: 30,
+      statement_timeout_ms: 30_000,
+      connect_deadline_secs: 60,
+    }
+  }
+}
+
+impl DatabaseConfig {
+  /// Connection URL without the password, for messages.
+  pub fn display_url(&self) -> String {
+    format!("postgres://{}@{}:{}/{}", self.user, self.host, self.port, self.name)
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+  /// Key the JWTs are signed with. Required.
+  pub secret: String,
+  /// Shortest accepted new password, in characters.
+  pub password_min_length: usize,
+  /// Reject the new passwords found in the bundled list of common ones.
+  pub reject_common_passwords: bool,
+  /// Argon2id parameters of the new hashes; the older ones are replaced on login.
+  pub argon2_memory_kib: u32,
+  pub argon2_iterations: u32,
+  pub argon2_parallelism: u32,
+  /// How long a mailed password reset token stays valid.
+  pub reset_token_ttl_mins: i64,
+  /// How long a mailed email verification token stays valid.
+  pub email_token_ttl_hours: i64,
+  /// Issuer the authenticator apps show the TOTP codes under.
+  pub totp_issuer: String,
+  /// Administrators without two-factor authentication may only use `/api/me/2fa` to set it up.
+  pub require_admin_2fa: bool,
+  /// How long the second step of a login may wait for the code.
+  pub login_challenge_ttl_mins: u64,
+}
+
+impl Default for AuthConfig {
+  fn default() -> Self {
+    // the minimum recommended by OWASP for Argon2id
+    Self {
+      secret: "".to_string(),
+      password_min_length: 8,
+      reject_common_passwords: true,
+      argon2_memory_kib: 19 * 1024,
+      argon2_iterations: 2,
+      argon2_parallelism: 1,
+      reset_token_ttl_mins: 30,
+      email_token_ttl_hours: 48,
+      totp_issuer: "Bookstore".to_string(),
+      require_admin_2fa: false,
+      login_challenge_ttl_mins: 5,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminConfig {
+  pub user: String,
+  pub pass: String,
+}
+
+impl Default for AdminConfig {
+  fn default() -> Self {
+    Self {
+      user: "admin".to_string(),
+      pass: "1234".to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoanConfig {
+  pub period_user_days: i64,
+  pub period_admin_days: i64,
+  pub max_renewals: i32,
+  pub overdue_block_threshold: i64,
+}
+
+impl Default for LoanConfig {
+  fn default() -> Self {
+    Self {
+      period_user_days: 14,
+      period_admin_days: 28,
+      max_renewals: 2,
+      overdue_block_threshold: 1,
+    }
+  }
+}
+
+/// Throttling of the failed logins, counted separately per nickname and per client address.
+/// After the free attempts every failure doubles the delay before the next attempt,
+/// from `backoff_base_secs` up to a lockout at the threshold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoginConfig {
+  pub free_attempts: i32,
+  pub lockout_threshold: i32,
+  /// Same for a client address, which several users may share.
+  pub ip_free_attempts: i32,
+  pub ip_lockout_threshold: i32,
+  pub backoff_base_secs: i64,
+  /// How long a lockout lasts; the failures are forgotten after as long without new ones.
+  pub lockout_mins: i64,
+}
+
+impl Default for LoginConfig {
+  fn default() -> Self {
+    Self {
+      free_attempts: 3,
+      lockout_threshold: 10,
+      ip_free_attempts: 20,
+      ip_lockout_threshold: 100,
+      backoff_base_secs: 1,
+      lockout_mins: 15,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MailConfig {
+  /// Copy the messages queued in the outbox to `stdout` or to the file at this
+  /// path, for development; empty copies them nowhere.
+  pub copy_to: String,
+}
+
+/// Token buckets of the API scopes, per user or, for anonymous requests, per client
+/// address: up to `capacity` requests at once, refilled at `per_minute` a minute.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+  pub enabled: bool,
+  /// `memory` keeps the buckets in the process; `postgres` shares them between the instances.
+  pub store: RateLimitStoreKind,
+  /// `/api/auth`: logins, registrations and the mailed tokens.
+  pub auth: BucketConfig,
+  /// `/api/book` and `/api/author`.
+  pub catalogue: BucketConfig,
+  /// The rest of the API.
+  pub default: BucketConfig,
+}
+
+impl Default for RateLimitConfig {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      store: RateLimitStoreKind::Memory,
+      auth: BucketConfig { capacity: 10, per_minute: 10 },
+      catalogue: BucketConfig { capacity: 300, per_minute: 600 },
+      default: BucketConfig { capacity: 100, per_minute: 300 },
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BucketConfig {
+  pub capacity: u32,
+  pub per_minute: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitStoreKind {
+  Memory,
+  Postgres,
+}
+
+impl FromStr for RateLimitStoreKind {
+  type Err = ();
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "memory" => Ok(RateLimitStoreKind::Memory),
+      "postgres" => Ok(RateLimitStoreKind::Postgres),
+      _ => Err(()),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+  /// Serve the Prometheus metrics at `/metrics`.
+  pub enabled: bool,
+
+  /// Serve the metrics on a separate port (on `server.host`); 0 means the main port.
+  pub port: u16,
+}
+
+impl Default for MetricsConfig {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      // off the main port, which the reverse proxy publishes
+      port: 9464,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TracingConfig {
+  /// Base URL of an OTLP/HTTP collector, e.g. `http://localhost:4318`;
+  /// empty disables exporting spans.
+  pub otlp_endpoint: String,
+
+  /// `service.name` the spans are reported under.
+  pub service_name: String,
+}
+
+impl Default for TracingConfig {
+  fn default() -> Self {
+    Self {
+      otlp_endpoint: String::new(),
+      service_name: "bookstore".to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+  Text,
+  Json,
+}
+
+impl FromStr for LogFormat {
+  type Err = ();
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "text" => Ok(LogFormat::Text),
+      "json" => Ok(LogFormat::Json),
+      _ => Err(()),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+  /// `text` uses the log4rs config file, if there is one; `json` always writes
+  /// JSON lines to stdout, for log shippers.
+  pub format: LogFormat,
+
+  /// Level of the built-in configuration.
+  pub level: String,
+
+  /// log4rs config file; the built-in configuration is used if it does not exist.
+  pub file: String,
+}
+
+impl Default for LoggingConfig {
+  fn default() -> Self {
+    Self {
+      format: LogFormat::Text,
+      level: "info".to_string(),
+      file: "log_config.yml".to_string(),
+    }
+  }
+}
+
+/// Everything wrong with the configuration, so that it can be fixed in one go.
+#[derive(Debug, Clone, Error)]
+pub struct ConfigError {
+  pub problems: Vec<String>,
+}
+
+impl Display for ConfigError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "invalid configuration:")?;
+    for problem in self.problems.iter() {
+      write!(f, "\n  - {}", problem)?;
+    }
+    Ok(())
+  }
+}
+
+impl AppConfig {
+  /// Read and validate the configuration.
+  pub fn load(file: Option<&Path>) -> Result<Self, ConfigError> {
+    let (config, problems) = Self::load_with_problems(file);
+    if problems.is_empty() {
+      Ok(config)
+    } else {
+      Err(ConfigError { problems })
+    }
+  }
+
+  /// Merge the defaults, the file and the environment, and return the result
+  /// along with everything wrong with it. Values that fail to parse keep their defaults.
+  pub fn load_with_problems(file: Option<&Path>) -> (Self, Vec<String>) {
+    let mut problems = vec![];
+
+    let mut config = match file {
+      Some(path) => match std::fs::read_to_string(path) {
+        Ok(text) => match toml::from_str::<AppConfig>(&text) {
+          Ok(config) => config,
+          Err(e) => {
+            problems.push(format!("{}: {}", path.display(), e.message()));
+            AppConfig::default()
+          }
+        },
+        Err(e) => {
+          problems.push(format!("{}: {}", path.display(), e));
+          AppConfig::default()
+        }
+      },
+      None => AppConfig::default(),
+    };
+
+    config.apply_env(&mut problems);
+    config.validate(&mut problems);
+
+    (config, problems)
+  }
+
+  fn apply_env(&mut self, problems: &mut Vec<String>) {
+    env_override("APP_HOST", &mut self.server.host, problems);
+    env_override("APP_PORT", &mut self.server.port, problems);
+    env_override("APP_DOCS_ON", &mut self.server.docs_on, problems);
+    env_override("APP_SHUTDOWN_TIMEOUT_SECS", &mut self.server.shutdown_timeout_secs, problems);
+    env_override("APP_MAX_BODY_BYTES", &mut self.server.max_body_bytes, problems);
+    env_override("APP_STRICT_REQUESTS", &mut self.server.strict_requests, problems);
+    env_override("APP_TRUSTED_PROXIES", &mut self.server.trusted_proxies, problems);
+
+    env_override("APP_DATABASE_USER", &mut self.database.user, problems);
+    env_override("APP_DATABASE_PASS", &mut self.database.pass, problems);
+    env_override("APP_DATABASE_HOST", &mut self.database.host, problems);
+    env_override("APP_DATABASE_PORT", &mut self.database.port, problems);
+    env_override("APP_DATABASE_NAME", &mut self.database.name, problems);
+    env_override("APP_DATABASE_MAX_CONNECTIONS", &mut self.database.max_connections, problems);
+    env_override("APP_DATABASE_MIN_CONNECTIONS", &mut self.database.min_connections, problems);
+    env_override("APP_DATABASE_ACQUIRE_TIMEOUT_SECS", &mut self.database.acquire_timeout_secs, problems);
+    env_override("APP_DATABASE_STATEMENT_TIMEOUT_MS", &mut self.database.statement_timeout_ms, problems);
+    env_override("APP_DATABASE_CONNECT_DEADLINE_SECS", &mut self.database.connect_deadline_secs, problems);
+
+    env_override("APP_SECRET", &mut self.auth.secret, problems);
+    env_override("APP_PASSWORD_MIN_LENGTH", &mut self.auth.password_min_length, problems);
+    env_override("APP_REJECT_COMMON_PASSWORDS", &mut self.auth.reject_common_passwords, problems);
+    env_override("APP_ARGON2_MEMORY_KIB", &mut self.auth.argon2_memory_kib, problems);
+    env_override("APP_ARGON2_ITERATIONS", &mut self.auth.argon2_iterations, problems);
+    env_override("APP_ARGON2_PARALLELISM", &mut self.auth.argon2_parallelism, problems);
+    env_override("APP_RESET_TOKEN_TTL_MINS", &mut self.auth.reset_token_ttl_mins, problems);
+    env_override("APP_EMAIL_TOKEN_TTL_HOURS", &mut self.auth.email_token_ttl_hours, problems);
+    env_override("APP_TOTP_ISSUER", &mut self.auth.totp_issuer, problems);
+    env_override("APP_REQUIRE_ADMIN_2FA", &mut self.auth.require_admin_2fa, problems);
+    env_override("APP_LOGIN_CHALLENGE_TTL_MINS", &mut self.auth.login_challenge_ttl_mins, problems);
+
+    env_override("APP_ADMIN_USER", &mut self.admin.user, problems);
+    env_override("APP_ADMIN_PASS", &mut self.admin.pass, problems);
+
+    env_override("APP_LOAN_PERIOD_USER_DAYS", &mut self.loan.period_user_days, problems);
+    env_override("APP_LOAN_PERIOD_ADMIN_DAYS", &mut self.loan.period_admin_days, problems);
+    env_override("APP_LOAN_MAX_RENEWALS", &mut self.loan.max_renewals, problems);
+    env_override("APP_LOAN_OVERDUE_BLOCK_THRESHOLD", &mut self.loan.overdue_block_threshold, problems);
+
+    env_override("APP_LOGIN_FREE_ATTEMPTS", &mut self.login.free_attempts, problems);
+    env_override("APP_LOGIN_LOCKOUT_THRESHOLD", &mut self.login.lockout_threshold, problems);
+    env_override("APP_LOGIN_IP_FREE_ATTEMPTS", &mut self.login.ip_free_attempts, problems);
+    env_override("APP_LOGIN_IP_LOCKOUT_THRESHOLD", &mut self.login.ip_lockout_threshold, problems);
+    env_override("APP_LOGIN_BACKOFF_BASE_SECS", &mut self.login.backoff_base_secs, problems);
+    env_override("APP_LOGIN_LOCKOUT_MINS", &mut self.login.lockout_mins, problems);
+
+    env_override("APP_MAIL_COPY_TO", &mut self.mail.copy_to, problems);
+
+    env_override("APP_RATE_LIMIT_ON", &mut self.rate_limit.enabled, problems);
+    env_override("APP_RATE_LIMIT_STORE", &mut self.rate_limit.store, problems);
+    env_override("APP_RATE_LIMIT_AUTH_CAPACITY", &mut self.rate_limit.auth.capacity, problems);
+    env_override("APP_RATE_LIMIT_AUTH_PER_MINUTE", &mut self.rate_limit.auth.per_minute, problems);
+    env_override("APP_RATE_LIMIT_CATALOGUE_CAPACITY", &mut self.rate_limit.catalogue.capacity, problems);
+    env_override("APP_RATE_LIMIT_CATALOGUE_PER_MINUTE", &mut self.rate_limit.catalogue.per_minute, problems);
+    env_override("APP_RATE_LIMIT_DEFAULT_CAPACITY", &mut self.rate_limit.default.capacity, problems);
+    env_override("APP_RATE_LIMIT_DEFAULT_PER_MINUTE", &mut self.rate_limit.default.per_minute, problems);
+
+    env_override("APP_METRICS_ON", &mut self.metrics.enabled, problems);
+    env_override("APP_METRICS_PORT", &mut self.metrics.port, problems);
+
+    env_override("APP_OTLP_ENDPOINT", &mut self.tracing.otlp_endpoint, problems);
+    env_override("APP_OTLP_SERVICE_NAME", &mut self.tracing.service_name, problems);
+
+    env_override("APP_LOG_FORMAT", &mut self.logging.format, problems);
+    env_override("APP_LOG_LEVEL", &mut self.logging.level, problems);
+    env_override("APP_LOG_CONFIG", &mut self.logging.file, problems);
+  }
+
+  /// Check the values which parse fine but make no sense.
+  fn validate(&self, problems: &mut Vec<String>) {
+    if self.server.host.trim().is_empty() {
+      problems.push("server.host (APP_HOST) must not be empty".to_string());
+    }
+    if self.server.max_body_bytes < 1024 {
+      problems.push("server.max_body_bytes (APP_MAX_BODY_BYTES) must be at least 1024".to_string());
+    }
+    if self.auth.secret.is_empty() {
+      problems.push("auth.secret (APP_SECRET) is required".to_string());
+    }
+    if !(1..=PASSWORD_MAX_LENGTH).contains(&self.auth.password_min_length) {
+      problems.push(format!("auth.password_min_length (APP_PASSWORD_MIN_LENGTH) must be from 1 to {}", PASSWORD_MAX_LENGTH));
+    }
+    if let Err(e) = argon2::Params::new(self.auth.argon2_memory_kib, self.auth.argon2_iterations, self.auth.argon2_parallelism, None) {
+      problems.push(format!("auth.argon2_* (APP_ARGON2_*) are not valid Argon2 parameters: {}", e));
+    }
+    if self.auth.reset_token_ttl_mins < 1 {
+      problems.push("auth.reset_token_ttl_mins (APP_RESET_TOKEN_TTL_MINS) must be at least 1".to_string());
+    }
+    if self.auth.email_token_ttl_hours < 1 {
+      problems.push("auth.email_token_ttl_hours (APP_EMAIL_TOKEN_TTL_HOURS) must be at least 1".to_string());
+    }
+    if self.auth.totp_issuer.trim().is_empty() || self.auth.totp_issuer.contains(':') {
+      problems.push("auth.totp_issuer (APP_TOTP_ISSUER) must not be empty or contain `:`".to_string());
+    }
+    if self.auth.login_challenge_ttl_mins < 1 {
+      problems.push("auth.login_challenge_ttl_mins (APP_LOGIN_CHALLENGE_TTL_MINS) must be at least 1".to_string());
+    }
+    if self.database.host.trim().is_empty() {
+      problems.push("database.host (APP_DATABASE_HOST) must not be empty".to_string());
+    }
+    if self.database.name.trim().is_empty() {
+      problems.push("database.name (APP_DATABASE_NAME) must not be empty".to_string());
+    }
+    if self.database.max_connections < 1 {
+      problems.push("database.max_connections (APP_DATABASE_MAX_CONNECTIONS) must be at least 1".to_string());
+    }
+    if self.database.min_connections > self.database.max_connections {
+      problems.push(
+        "database.min_connections (APP_DATABASE_MIN_CONNECTIONS) must not exceed database.max_connections".to_string()
+      );
+    }
+    if self.database.acquire_timeout_secs < 1 {
+      problems.push("database.acquire_timeout_secs (APP_DATABASE_ACQUIRE_TIMEOUT_SECS) must be at least 1".to_string());
+    }
+    if self.loan.period_user_days < 1 {
+      problems.push("loan.period_user_days (APP_LOAN_PERIOD_USER_DAYS) must be at least 1".to_string());
+    }
+    if self.loan.period_admin_days < 1 {
+      problems.push("loan.period_admin_days (APP_LOAN_PERIOD_ADMIN_DAYS) must be at least 1".to_string());
+    }
+    if self.loan.max_renewals < 0 {
+      problems.push("loan.max_renewals (APP_LOAN_MAX_RENEWALS) must not be negative".to_string());
+    }
+    if self.loan.overdue_block_threshold < 1 {
+      problems.push("loan.overdue_block_threshold (APP_LOAN_OVERDUE_BLOCK_THRESHOLD) must be at least 1".to_string());
+    }
+    if self.login.free_attempts < 0 || self.login.free_attempts >= self.login.lockout_threshold {
+      problems.push("login.free_attempts (APP_LOGIN_FREE_ATTEMPTS) must be from 0 to login.lockout_threshold - 1".to_string());
+    }
+    if self.login.ip_free_attempts < 0 || self.login.ip_free_attempts >= self.login.ip_lockout_threshold {
+      problems.push("login.ip_free_attempts (APP_LOGIN_IP_FREE_ATTEMPTS) must be from 0 to login.ip_lockout_threshold - 1".to_string());
+    }
+    if self.login.backoff_base_secs < 1 {
+      problems.push("login.backoff_base_secs (APP_LOGIN_BACKOFF_BASE_SECS) must be at least 1".to_string());
+    }
+    if self.login.lockout_mins < 1 {
+      problems.push("login.lockout_mins (APP_LOGIN_LOCKOUT_MINS) must be at least 1".to_string());
+    }
+    let buckets = [
+      ("auth", "AUTH", &self.rate_limit.auth),
+      ("catalogue", "CATALOGUE", &self.rate_limit.catalogue),
+      ("default", "DEFAULT", &self.rate_limit.default),
+    ];
+    for (name, env, bucket) in buckets {
+      if bucket.capacity < 1 || bucket.per_minute < 1 {
+        problems.push(format!(
+          "rate_limit.{}.capacity and per_minute (APP_RATE_LIMIT_{}_*) must be at least 1", name, env
+        ));
+      }
+    }
+    if self.metrics.port != 0 && self.metrics.port == self.server.port {
+      problems.push("metrics.port (APP_METRICS_PORT) must differ from server.port; use 0 to serve on the main port".to_string());
+    }
+    let endpoint = &self.tracing.otlp_endpoint;
+    if !(endpoint.is_empty() || endpoint.starts_with("http://") || endpoint.starts_with("https://")) {
+      problems.push("tracing.otlp_endpoint (APP_OTLP_ENDPOINT) must be an http:// or https:// URL".to_string());
+    }
+    if log::LevelFilter::from_str(&self.logging.level).is_err() {
+      problems.push("logging.level (APP_LOG_LEVEL) must be one of off, error, warn, info, debug, trace".to_string());
+    }
+  }
+
+  /// A copy that is safe to print or log.
+  pub fn redacted(&self) -> Self {
+    let mut config = self.clone();
+    for secret in [&mut config.auth.secret, &mut config.database.pass, &mut config.admin.pass] {
+      if !secret.is_empty() {
+        *secret = REDACTED.to_string();
+      }
+    }
+    config
+  }
+
+  /// Effective configuration in the config file format, secrets redacted.
+  pub fn to_toml(&self) -> String {
+    toml::to_string_pretty(&self.redacted()).unwrap_or_default()
+  }
+}
+
+/// Replace `target` with the value of the environment variable `name`, if it is set.
+fn env_override<T: FromStr>(name: &str, target: &mut T, problems: &mut Vec<String>) {
+  if let Ok(value) = std::env::var(name) {
+    match value.parse() {
+      Ok(value) => *target = value,
+      Err(_) => problems.push(format!("{}: cannot parse `{}`", name, value)),
+    }
+  }
+}
@@ -1,12 +1,88 @@
-pub fn get_db_url() -> String {
-  format!(
-    "postgres://{}:{}@{}:{}/{}",
-    std::env::var("APP_DATABASE_USER").unwrap_or("postgres".to_string()),
-    std::env::var("APP_DATABASE_PASS").unwrap_or("postgres".to_string()),
-    std::env::var("APP_DATABASE_HOST").unwrap_or("localhost".to_string()),
-    std::env::var("APP_DATABASE_PORT").unwrap_or("5432".to_string())
-      .parse::<u16>()
-      .unwrap(),
-    std::env::var("APP_DATABASE_NAME").unwrap_or("postgres".to_string()),
-  )
-}
\ No newline at end of file
+use std::cmp;
+use std::time::{Duration, Instant};
+use sqlx::{ConnectOptions, Connection};
+use sqlx::postgres::{PgConnectOptions, PgPool, PgPoolOptions};
+
+use bookstore::config::DatabaseConfig;
+
+use crate::error::StartupError;
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(8);
+
+pub fn get_connect_options(config: &DatabaseConfig) -> PgConnectOptions {
+  let options = PgConnectOptions::new()
+    .host(&config.host)
+    .port(config.port)
+    .username(&config.user)
+    .password(&config.pass)
+    .database(&config.name);
+
+  if config.statement_timeout_ms > 0 {
+    options.options([("statement_timeout", format!("{}ms", config.statement_timeout_ms))])
+  } else {
+    options
+  }
+}
+
+fn get_pool_options(config: &DatabaseConfig) -> PgPoolOptions {
+  PgPoolOptions::new()
+    .max_connections(config.max_connections)
+    .min_connections(config.min_connections)
+    .acquire_timeout(Duration::from_secs(config.acquire_timeout_secs))
+}
+
+/// Errors worth waiting out: the server is not up yet or is still starting.
+fn is_transient(e: &sqlx::Error) -> bool {
+  match e {
+    sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+    sqlx::Error::Database(e) => e.code().is_some_and(|code| code == "57P03"),
+    _ => false,
+  }
+}
+
+/// Connect to the database, retrying with exponential backoff until
+/// `connect_deadline_secs` runs out, and open the connection pool.
+pub async fn connect(config: &DatabaseConfig) -> Result<PgPool, StartupError> {
+  let options = get_connect_options(config);
+  let started = Instant::now();
+  let deadline = started + Duration::from_secs(config.connect_deadline_secs);
+  let mut backoff = INITIAL_BACKOFF;
+  let mut attempt = 1;
+
+  // probe with a single connection first: the pool retries internally and would hide the attempts
+  loop {
+    match options.connect().await {
+      Ok(conn) => {
+        let _ = conn.close().await;
+        break
+      },
+      Err(e) if is_transient(&e) && Instant::now() + backoff < deadline => {
+        log::warn!(
+          "Database at {} is not available yet (attempt {}): {}. Retrying in {} ms",
+          config.display_url(), attempt, e, backoff.as_millis(),
+        );
+        actix_web::rt::time::sleep(backoff).await;
+        backoff = cmp::min(backoff * 2, MAX_BACKOFF);
+        attempt += 1;
+      },
+      Err(e) if is_transient(&e) => return Err(StartupError::DatabaseUnavailable {
+        url: config.display_url(),
+        waited: started.elapsed(),
+        source: e,
+      }),
+      Err(e) => return Err(StartupError::DatabaseRejected {
+        url: config.display_url(),
+        source: e,
+      }),
+    }
+  }
+
+  get_pool_options(config)
+    .connect_with(options)
+    .await
+    .map_err(|e| StartupError::DatabaseRejected {
+      url: config.display_url(),
+      source: e,
+    })
+}
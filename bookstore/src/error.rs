@@ -0,0 +1,150 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use bookstore::config::ConfigError;
+
+
+/// Everything that can stop the application from starting, with a hint on how to fix it.
+#[derive(Debug)]
+pub enum StartupError {
+  Config(ConfigError),
+  Logging(String),
+  Tracing {
+    endpoint: String,
+    source: opentelemetry::trace::TraceError,
+  },
+  DatabaseUnavailable {
+    url: String,
+    waited: Duration,
+    source: sqlx::Error,
+  },
+  DatabaseRejected {
+    url: String,
+    source: sqlx::Error,
+  },
+  Migration(sqlx::migrate::MigrateError),
+  Bind {
+    addr: String,
+    source: std::io::Error,
+  },
+}
+
+impl Display for StartupError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      StartupError::Config(e) => write!(f, "{}", e),
+      StartupError::Logging(e) => write!(
+        f,
+        "could not set up logging: {}. Fix the log config file, point APP_LOG_CONFIG to another one \
+        or remove it to use the built-in configuration",
+        e,
+      ),
+      StartupError::Tracing { endpoint, source } => write!(
+        f,
+        "could not set up the OTLP exporter for {}: {}. Check APP_OTLP_ENDPOINT, or leave it empty \
+        to disable exporting spans",
+        endpoint, source,
+      ),
+      StartupError::DatabaseUnavailable { url, waited, source } => write!(
+        f,
+        "could not connect to the database at {} within {}s: {}. Check that PostgreSQL is running \
+        and APP_DATABASE_HOST / APP_DATABASE_PORT point to it, or raise APP_DATABASE_CONNECT_DEADLINE_SECS",
+        url, waited.as_secs(), source,
+      ),
+      StartupError::DatabaseRejected { url, source } => write!(
+        f,
+        "the database at {} refused the connection: {}. Check APP_DATABASE_USER, APP_DATABASE_PASS \
+        and APP_DATABASE_NAME",
+        url, source,
+      ),
+      StartupError::Migration(e) => write!(
+        f,
+        "could not apply database migrations: {}. The schema may have been changed by hand \
+        or by a newer version of the application",
+        e,
+      ),
+      StartupError::Bind { addr, source } => write!(
+        f,
+        "could not listen on {}: {}. Make sure the port is free or change APP_HOST / APP_PORT",
+        addr, source,
+      ),
+    }
+  }
+}
+
+impl Error for StartupError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      StartupError::Config(e) => Some(e),
+      StartupError::Logging(_) => None,
+      StartupError::Tracing { source, .. } => Some(source),
+      StartupError::DatabaseUnavailable { source, .. } => Some(source),
+      StartupError::DatabaseRejected { source, .. } => Some(source),
+      StartupError::Migration(e) => Some(e),
+      StartupError::Bind { source, .. } => Some(source),
+    }
+  }
+}
+
+
+/// Everything that can make a command fail.
+#[derive(Debug)]
+pub enum CommandError {
+  Startup(StartupError),
+  Migration(sqlx::migrate::MigrateError),
+  PendingMigrations(Vec<i64>),
+  /// The command refused to do what it was asked; the message says why.
+  Refused(String),
+  Io {
+    what: String,
+    source: std::io::Error,
+  },
+  InvalidDump {
+    path: String,
+    source: serde_json::Error,
+  },
+  Unexpected(Box<dyn Error>),
+}
+
+impl Display for CommandError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      CommandError::Startup(e) => write!(f, "{}", e),
+      CommandError::Migration(e) => write!(f, "could not change the database schema: {}", e),
+      CommandError::PendingMigrations(versions) => write!(
+        f,
+        "the database schema is out of date ({} migrations pending). Run `bookstore migrate up` first",
+        versions.len(),
+      ),
+      CommandError::Refused(message) => write!(f, "{}", message),
+      CommandError::Io { what, source } => write!(f, "could not access {}: {}", what, source),
+      CommandError::InvalidDump { path, source } => write!(
+        f,
+        "{} is not a file written by `bookstore export`: {}",
+        path, source,
+      ),
+      CommandError::Unexpected(e) => write!(f, "unexpected error: {}. See the log for details", e),
+    }
+  }
+}
+
+impl Error for CommandError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      CommandError::Startup(e) => Some(e),
+      CommandError::Migration(e) => Some(e),
+      CommandError::PendingMigrations(_) => None,
+      CommandError::Refused(_) => None,
+      CommandError::Io { source, .. } => Some(source),
+      CommandError::InvalidDump { source, .. } => Some(source),
+      CommandError::Unexpected(e) => Some(e.as_ref()),
+    }
+  }
+}
+
+impl From<StartupError> for CommandError {
+  fn from(value: StartupError) -> Self {
+    CommandError::Startup(value)
+  }
+}
@@ -1,14 +1,12 @@
 #[macro_use]
 extern crate actix_web;
 
-use std::sync::Arc;
-
-use crate::application::services::user::UserService;
+use sqlx::migrate::Migrator;
 
 pub mod application;
 pub mod adapters;
+pub mod config;
+pub mod logging;
 
-pub async fn add_admin_user(_user_service: Arc<UserService>, _nickname: String, _password: String)
-{
-  todo!();
-}
\ No newline at end of file
+/// Migrations embedded into the binary.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
@@ -0,0 +1,494 @@
+//! Logging setup: a log4rs config file if there is one, or a built-in
+//! console configuration writing either plain text or JSON lines.
+//!
+//! The configuration stays under the control of [`LogControl`], which lets
+//! admins raise or lower the level of single modules for a while and keeps
+//! a summary of the errors logged since the start.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use chrono::{DateTime, Local};
+use log::kv::Key;
+use log::{Level, LevelFilter, Record};
+use log4rs::append::Append;
+use log4rs::append::console::ConsoleAppender;
+use log4rs::config::{Appender, Config, Deserializers, Logger, RawConfig, Root};
+use log4rs::encode::pattern::PatternEncoder;
+use log4rs::encode::{Encode, Write};
+use log4rs::Handle;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::config::{LogFormat, LoggingConfig};
+
+/// Same as the pattern in the shipped `log_config*.yml` files.
+const TEXT_PATTERN: &str = "{d} {l} {t} [{X(request_id)(-)} {X(user_id)(-)}] - {m}{n}";
+
+/// Name of the appender which collects the error summaries.
+const ERROR_LOG_APPENDER: &str = "error_log";
+
+/// How many targets the error summary keeps track of.
+const MAX_ERROR_TARGETS: usize = 200;
+
+/// How often expired overrides and config file changes are checked for.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+
+/// One JSON object per line with the timestamp, level, target, message,
+/// request and user IDs (from the MDC) and the chain of the error attached
+/// to the record as the `error` key, if any.
+#[derive(Debug, Default)]
+pub struct JsonLogEncoder;
+
+impl JsonLogEncoder {
+  fn error_chain(record: &Record) -> Option<Value> {
+    let value = record.key_values().get(Key::from_str("error"))?;
+
+    let chain = match value.to_borrowed_error() {
+      Some(e) => {
+        let mut chain = vec![];
+        let mut source: Option<&dyn Error> = Some(e);
+        while let Some(e) = source {
+          chain.push(Value::String(e.to_string()));
+          source = e.source();
+        }
+        chain
+      },
+      None => vec![Value::String(value.to_string())],
+    };
+    Some(Value::Array(chain))
+  }
+}
+
+impl Encode for JsonLogEncoder {
+  fn encode(&self, w: &mut dyn Write, record: &Record) -> anyhow::Result<()> {
+    let mut entry = Map::new();
+    entry.insert("timestamp".to_string(), json!(chrono::Local::now().to_rfc3339()));
+    entry.insert("level".to_string(), json!(record.level().as_str()));
+    entry.insert("target".to_string(), json!(record.target()));
+    entry.insert("message".to_string(), json!(record.args().to_string()));
+
+    for key in ["request_id", "user_id"] {
+      if let Some(value) = log_mdc::get(key, |value| value.map(str::to_string)) {
+        entry.insert(key.to_string(), json!(value));
+      }
+    }
+    if let Some(chain) = Self::error_chain(record) {
+      entry.insert("error".to_string(), chain);
+    }
+
+    serde_json::to_writer(&mut *w, &entry)?;
+    w.write_all(b"\n")?;
+    Ok(())
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonLogEncoderConfig {}
+
+/// Makes the encoder available in config files as `kind: json_log`.
+struct JsonLogEncoderDeserializer;
+
+impl log4rs::config::Deserialize for JsonLogEncoderDeserializer {
+  type Trait = dyn Encode;
+  type Config = JsonLogEncoderConfig;
+
+  fn deserialize(&self, _config: JsonLogEncoderConfig, _: &Deserializers) -> anyhow::Result<Box<dyn Encode>> {
+    Ok(Box::new(JsonLogEncoder))
+  }
+}
+
+
+/// Errors logged by one target since the start.
+#[derive(Debug, Clone)]
+pub struct ErrorSummary {
+  pub target: String,
+  pub count: u64,
+  pub last_seen: DateTime<Local>,
+  pub last_message: String,
+  pub last_request_id: Option<String>,
+}
+
+/// Collects error records into per-target summaries; attached to the root
+/// and to every non-additive logger.
+#[derive(Debug, Default)]
+struct ErrorLog {
+  summaries: Mutex<HashMap<String, ErrorSummary>>,
+}
+
+impl ErrorLog {
+  fn get_summaries(&self) -> Vec<ErrorSummary> {
+    let summaries = self.summaries.lock().unwrap();
+    let mut list: Vec<_> = summaries.values().cloned().collect();
+    list.sort_by_key(|s| std::cmp::Reverse(s.last_seen));
+    list
+  }
+}
+
+#[derive(Debug)]
+struct ErrorLogAppender(Arc<ErrorLog>);
+
+impl Append for ErrorLogAppender {
+  fn append(&self, record: &Record) -> anyhow::Result<()> {
+    if record.level() != Level::Error {
+      return Ok(())
+    }
+
+    let now = Local::now();
+    let message = record.args().to_string();
+    let request_id = log_mdc::get("request_id", |value| value.map(str::to_string));
+
+    let mut summaries = self.0.summaries.lock().unwrap();
+    if let Some(summary) = summaries.get_mut(record.target()) {
+      summary.count += 1;
+      summary.last_seen = now;
+      summary.last_message = message;
+      summary.last_request_id = request_id;
+      return Ok(())
+    }
+
+    // make room by forgetting the target which has been quiet for the longest
+    if summaries.len() >= MAX_ERROR_TARGETS {
+      let oldest = summaries.values()
+        .min_by_key(|s| s.last_seen)
+        .map(|s| s.target.clone());
+      if let Some(target) = oldest {
+        summaries.remove(&target);
+      }
+    }
+    summaries.insert(record.target().to_string(), ErrorSummary {
+      target: record.target().to_string(),
+      count: 1,
+      last_seen: now,
+      last_message: message,
+      last_request_id: request_id,
+    });
+    Ok(())
+  }
+
+  fn flush(&self) {}
+}
+
+
+/// A level set at runtime for a module and its submodules.
+#[derive(Debug, Clone)]
+pub struct LevelOverride {
+  pub module: String,
+  pub level: LevelFilter,
+  pub expires_at: DateTime<Local>,
+}
+
+/// Levels currently in effect.
+#[derive(Debug, Clone)]
+pub struct LogLevels {
+  pub root: LevelFilter,
+  /// Loggers of the configuration, before the overrides are applied.
+  pub configured: Vec<(String, LevelFilter)>,
+  pub overrides: Vec<LevelOverride>,
+}
+
+/// Where the base configuration comes from.
+enum Source {
+  File {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+  },
+  Builtin {
+    format: LogFormat,
+    level: LevelFilter,
+  },
+}
+
+struct State {
+  source: Source,
+  /// Root level and loggers of the last applied base configuration.
+  root: LevelFilter,
+  configured: Vec<(String, LevelFilter)>,
+  /// Reload period requested by the config file.
+  refresh_rate: Option<Duration>,
+  last_refresh: SystemTime,
+  overrides: Vec<LevelOverride>,
+}
+
+/// The installed logger configuration, which can be changed at runtime.
+pub struct LogControl {
+  handle: Handle,
+  error_log: Arc<ErrorLog>,
+  state: Mutex<State>,
+}
+
+impl LogControl {
+  /// Install the logger and start watching for expired overrides and
+  /// changes of the config file.
+  pub fn init(config: &LoggingConfig) -> Result<Arc<Self>, String> {
+    // validated along with the rest of the configuration
+    let level = LevelFilter::from_str(&config.level).unwrap_or(LevelFilter::Info);
+
+    let file = match config.format {
+      LogFormat::Text => find_config_file(&config.file),
+      LogFormat::Json => None,
+    };
+    let source = match &file {
+      Some(path) => Source::File { path: path.clone(), modified: modified_at(path) },
+      None => Source::Builtin { format: config.format, level },
+    };
+
+    let error_log = Arc::new(ErrorLog::default());
+    let mut state = State {
+      source,
+      root: level,
+      configured: vec![],
+      refresh_rate: None,
+      last_refresh: SystemTime::now(),
+      overrides: vec![],
+    };
+    let log_config = build_config(&mut state, &error_log)?;
+    let handle = log4rs::init_config(log_config).map_err(|e| e.to_string())?;
+
+    match file {
+      Some(path) => log::info!("Logging is configured by {}", path.display()),
+      None => log::info!("Using the built-in logging configuration ({:?}, level {})", config.format, level),
+    }
+
+    let control = Arc::new(Self {
+      handle,
+      error_log,
+      state: Mutex::new(state),
+    });
+
+    let watched = Arc::downgrade(&control);
+    std::thread::Builder::new()
+      .name("log-control".to_string())
+      .spawn(move || {
+        while let Some(control) = watched.upgrade() {
+          control.tick();
+          drop(control);
+          std::thread::sleep(WATCH_INTERVAL);
+        }
+      })
+      .map_err(|e| e.to_string())?;
+
+    Ok(control)
+  }
+
+  pub fn get_levels(&self) -> LogLevels {
+    let state = self.state.lock().unwrap();
+    LogLevels {
+      root: state.root,
+      configured: state.configured.clone(),
+      overrides: state.overrides.clone(),
+    }
+  }
+
+  /// Set the level of `module` until `ttl` runs out, replacing an earlier override.
+  pub fn set_override(&self, module: &str, level: LevelFilter, ttl: Duration) -> Result<LevelOverride, String> {
+    let ttl = chrono::Duration::from_std(ttl).map_err(|e| e.to_string())?;
+    let level_override = LevelOverride {
+      module: module.to_string(),
+      level,
+      expires_at: Local::now() + ttl,
+    };
+
+    let mut state = self.state.lock().unwrap();
+    let previous = std::mem::take(&mut state.overrides);
+    state.overrides = previous.iter()
+      .filter(|o| o.module != module)
+      .cloned()
+      .chain(std::iter::once(level_override.clone()))
+      .collect();
+
+    if let Err(e) = self.apply(&mut state) {
+      state.overrides = previous;
+      return Err(e)
+    }
+    drop(state);
+
+    log::warn!("Log level of {} is set to {} until {}", module, level, level_override.expires_at.to_rfc3339());
+    Ok(level_override)
+  }
+
+  /// Drop the override of `module`; `false` if there is none.
+  pub fn remove_override(&self, module: &str) -> Result<bool, String> {
+    let mut state = self.state.lock().unwrap();
+    let Some(index) = state.overrides.iter().position(|o| o.module == module) else {
+      return Ok(false)
+    };
+
+    let removed = state.overrides.remove(index);
+    if let Err(e) = self.apply(&mut state) {
+      state.overrides.insert(index, removed);
+      return Err(e)
+    }
+    drop(state);
+
+    log::warn!("Log level override of {} is removed", module);
+    Ok(true)
+  }
+
+  /// Error summaries by target, most recent first.
+  pub fn get_error_summaries(&self) -> Vec<ErrorSummary> {
+    self.error_log.get_summaries()
+  }
+
+  fn apply(&self, state: &mut State) -> Result<(), String> {
+    let config = build_config(state, &self.error_log)?;
+    self.handle.set_config(config);
+    Ok(())
+  }
+
+  /// Revert expired overrides and reload the config file if it has changed.
+  fn tick(&self) {
+    let mut state = self.state.lock().unwrap();
+    let now = Local::now();
+    let mut changed = false;
+
+    let (expired, active): (Vec<_>, Vec<_>) = std::mem::take(&mut state.overrides)
+      .into_iter()
+      .partition(|o| o.expires_at <= now);
+    state.overrides = active;
+    if !expired.is_empty() {
+      changed = true;
+    }
+
+    let refresh_due = state.refresh_rate
+      .is_some_and(|rate| state.last_refresh.elapsed().map_or(true, |elapsed| elapsed >= rate));
+    if refresh_due {
+      state.last_refresh = SystemTime::now();
+      if let Source::File { path, modified } = &mut state.source {
+        let current = modified_at(path);
+        if current != *modified {
+          *modified = current;
+          changed = true;
+        }
+      }
+    }
+
+    if !changed {
+      return
+    }
+    // on a broken config file the previous configuration stays in effect
+    let result = self.apply(&mut state);
+    drop(state);
+
+    for o in expired {
+      log::warn!("Log level override of {} ({}) has expired", o.module, o.level);
+    }
+    if let Err(e) = result {
+      log::error!("Error reloading the logging configuration: {}", e);
+    }
+  }
+}
+
+/// The config file, looked up in the working directory and its parent.
+fn find_config_file(file: &str) -> Option<PathBuf> {
+  let path = Path::new(file);
+  if path.exists() {
+    return Some(path.to_path_buf())
+  }
+
+  let parent = Path::new("..").join(path);
+  if path.is_relative() && parent.exists() {
+    Some(parent)
+  } else {
+    None
+  }
+}
+
+fn modified_at(path: &Path) -> Option<SystemTime> {
+  std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Configuration of the source, before the overrides are applied.
+struct BaseConfig {
+  appenders: Vec<Appender>,
+  root: Root,
+  loggers: Vec<Logger>,
+  refresh_rate: Option<Duration>,
+}
+
+/// Parse the config file the same way `log4rs::init_file` does.
+fn read_config_file(path: &Path) -> Result<BaseConfig, String> {
+  let text = std::fs::read_to_string(path)
+    .map_err(|e| format!("{}: {}", path.display(), e))?;
+  let raw: RawConfig = serde_yaml::from_str(&text)
+    .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+  let mut deserializers = Deserializers::default();
+  deserializers.insert("json_log", JsonLogEncoderDeserializer);
+
+  // like log4rs, skip broken appenders and report them on stderr
+  let (appenders, mut errors) = raw.appenders_lossy(&deserializers);
+  errors.handle();
+
+  Ok(BaseConfig {
+    appenders,
+    root: raw.root(),
+    loggers: raw.loggers(),
+    refresh_rate: raw.refresh_rate(),
+  })
+}
+
+fn builtin_appender(format: LogFormat) -> Appender {
+  let encoder: Box<dyn Encode> = match format {
+    LogFormat::Text => Box::new(PatternEncoder::new(TEXT_PATTERN)),
+    LogFormat::Json => Box::new(JsonLogEncoder),
+  };
+  let appender = ConsoleAppender::builder().encoder(encoder).build();
+  Appender::builder().build("main", Box::new(appender))
+}
+
+/// Base configuration of the source with the overrides and the error log on top.
+fn build_config(state: &mut State, error_log: &Arc<ErrorLog>) -> Result<Config, String> {
+  let BaseConfig { mut appenders, root, loggers, refresh_rate } = match &state.source {
+    Source::File { path, .. } => read_config_file(path)?,
+    Source::Builtin { format, level } => BaseConfig {
+      appenders: vec![builtin_appender(*format)],
+      root: Root::builder().appender("main").build(*level),
+      loggers: vec![],
+      refresh_rate: None,
+    },
+  };
+
+  let root_level = root.level();
+  let configured = loggers.iter().map(|l| (l.name().to_string(), l.level())).collect();
+
+  appenders.push(Appender::builder().build(ERROR_LOG_APPENDER, Box::new(ErrorLogAppender(error_log.clone()))));
+
+  let with_error_log = |names: &[String]| -> Vec<String> {
+    names.iter().cloned().chain(std::iter::once(ERROR_LOG_APPENDER.to_string())).collect()
+  };
+
+  // an override keeps the appenders of the logger it replaces
+  let mut loggers: Vec<Logger> = loggers.into_iter()
+    .map(|l| {
+      let level = state.overrides.iter()
+        .find(|o| o.module == l.name())
+        .map_or(l.level(), |o| o.level);
+      let appenders = if l.additive() { l.appenders().to_vec() } else { with_error_log(l.appenders()) };
+      Logger::builder().appenders(appenders).additive(l.additive()).build(l.name(), level)
+    })
+    .collect();
+  for o in &state.overrides {
+    if !loggers.iter().any(|l| l.name() == o.module) {
+      loggers.push(Logger::builder().build(o.module.clone(), o.level));
+    }
+  }
+
+  let root = Root::builder().appenders(with_error_log(root.appenders())).build(root.level());
+
+  let config = Config::builder()
+    .appenders(appenders)
+    .loggers(loggers)
+    .build(root)
+    .map_err(|e| e.to_string())?;
+
+  state.root = root_level;
+  state.configured = configured;
+  state.refresh_rate = refresh_rate;
+  Ok(config)
+}
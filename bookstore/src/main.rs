@@ -2,25 +2,30 @@ mod server;
 mod db_conn;
 mod api_docs;
 mod init;
+mod error;
+mod telemetry;
+mod cli;
+mod commands;
 
-use actix_web;
+use std::process::ExitCode;
+use clap::Parser;
 use dotenv::dotenv;
-use log4rs;
 
-use crate::init::init;
-use crate::server::create_server;
+use crate::cli::Cli;
 
 #[actix_web::main]
-async fn main() -> std::io::Result<()> {
+async fn main() -> ExitCode {
   dotenv().ok();
 
-  log4rs::init_file("log_config.yml", Default::default())
-    .or(log4rs::init_file("../log_config.yml", Default::default()))
-    .unwrap();
+  // exits with a usage message on bad arguments
+  let cli = Cli::parse();
 
-  let init_data = init().await;
-  let server = create_server(&init_data.host, init_data.port, init_data.app_state);
-
-  log::info!("The server is listening on {}:{}", init_data.host, init_data.port);
-  server.await
+  match commands::run(cli).await {
+    Ok(_) => ExitCode::SUCCESS,
+    Err(e) => {
+      log::error!(error:err = e; "{}", e);
+      eprintln!("error: {}", e);
+      ExitCode::FAILURE
+    }
+  }
 }
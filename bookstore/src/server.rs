@@ -4,18 +4,21 @@ use actix_web::middleware::Logger;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
-use bookstore::application::entities::user::UserRole;
 use bookstore::application::state::app_state::AppState;
-use bookstore::adapters::middleware::jwt::JwtAuth;
-use bookstore::adapters::routes::{ping, user, auth, book, author};
+use bookstore::adapters::middleware::metrics::RequestMetrics;
+use bookstore::adapters::middleware::problem::ProblemDetails;
+use bookstore::adapters::middleware::request_id::RequestTracing;
+use bookstore::adapters::routes;
+use bookstore::adapters::routes::metrics;
 
 use crate::api_docs::ApiDoc;
 
-pub fn create_server(host: &str, port: u16, app_state: web::Data<AppState>) -> Server {
-  let enable_docs: bool = std::env::var("APP_DOCS_ON")
-    .unwrap_or("false".to_string())
-    .parse()
-    .expect("boolean expected");
+pub fn create_server(app_state: web::Data<AppState>) -> std::io::Result<Server> {
+  let enable_docs = app_state.config.server.docs_on;
+  let host = app_state.config.server.host.clone();
+  let port = app_state.config.server.port;
+  let shutdown_timeout = app_state.config.server.shutdown_timeout_secs;
+  let enable_metrics = app_state.config.metrics.enabled && app_state.config.metrics.port == 0;
 
   // this move-block is executed once per worker thread
   HttpServer::new(move || {
@@ -31,47 +34,44 @@ pub fn create_server(host: &str, port: u16, app_state: web::Data<AppState>) -> S
       app_builder
     };
 
+    let app_builder = if enable_metrics {
+      app_builder.service(metrics::export)
+    } else {
+      app_builder
+    };
+
     app_builder
-      .service(
-        web::scope("/api")
-          .service(
-            web::scope("/ping")
-              .service(ping::say_pong)
-          )
-          .service(
-            web::scope("/auth")
-              .service(auth::login)
-              .service(auth::register)
-          )
-          .service(
-            web::scope("/user")
-              .service(user::get_by_id)
-              .service(user::get_list)
-              .service(user::update_suspended)
-              .wrap(JwtAuth::new(vec![UserRole::Admin, UserRole::User], app_state.clone()))
-              // scope-wide middleware: protect the scope with JwtAuth
-          )
-          .service(
-            web::scope("/book")
-              .service(book::get_list)
-              .service(book::get_by_id)
-              .service(book::add_one)
-              .service(book::delete_one)
-              .wrap(JwtAuth::new(vec![UserRole::Admin, UserRole::User], app_state.clone()))
-          )
-          .service(
-            web::scope("/author")
-              .service(author::get_list)
-              .service(author::get_by_id)
-              .service(author::add_one)
-              .service(author::delete_one)
-              .wrap(JwtAuth::new(vec![UserRole::Admin, UserRole::User], app_state.clone()))
-          )
-          // log requests and responses
-      )
-      .wrap(Logger::default())
+      .configure(|cfg| routes::configure(cfg, &app_state))
+      .wrap(ProblemDetails)
+      .wrap(RequestMetrics::new(app_state.metrics.clone()))
+      .wrap(RequestTracing)
+      // the access log is written once the body is sent, outside of the request span,
+      // so the request ID is taken from the response header
+      .wrap(Logger::new(r#"%a "%r" %s %b "%{Referer}i" "%{User-Agent}i" %T request_id=%{x-request-id}o"#))
   })
+    // on SIGINT / SIGTERM stop accepting connections and let in-flight requests finish
+    .shutdown_timeout(shutdown_timeout)
     .bind((host, port))
-    .unwrap()
-    .run()
+    .map(|server| server.run())
+}
+
+/// Server for `/metrics` alone, if it is configured to listen on its own port.
+pub fn create_metrics_server(app_state: web::Data<AppState>) -> Option<std::io::Result<Server>> {
+  let config = &app_state.config.metrics;
+  if !config.enabled || config.port == 0 {
+    return None
+  }
+  let host = app_state.config.server.host.clone();
+  let port = config.port;
+
+  let server = HttpServer::new(move || {
+    App::new()
+      .app_data(app_state.clone())
+      .service(metrics::export)
+  })
+    .workers(1)
+    .bind((host, port))
+    .map(|server| server.run());
+
+  Some(server)
 }
\ No newline at end of file
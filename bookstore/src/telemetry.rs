@@ -0,0 +1,197 @@
+//! Glue between `tracing` spans and the `log`-based logging.
+//!
+//! Request context lives in spans; log4rs only knows about the MDC, so the
+//! fields of the innermost entered span are copied into the MDC whenever a
+//! span is entered or exited. This way every `log::` call made while handling
+//! a request carries its ID and user, without threading them through the code.
+
+use std::fmt::Write;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{runtime, trace, Resource};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{Layer, Registry};
+
+use bookstore::config::TracingConfig;
+
+use crate::error::StartupError;
+
+/// Span fields which are copied into the MDC.
+const MDC_FIELDS: [&str; 2] = ["request_id", "user_id"];
+
+
+#[derive(Debug, Clone, Default)]
+struct MdcFields {
+  values: Vec<(&'static str, String)>,
+}
+
+impl MdcFields {
+  fn apply(&self) {
+    for key in MDC_FIELDS {
+      match self.values.iter().find(|(k, _)| *k == key) {
+        Some((_, value)) => log_mdc::insert(key, value),
+        None => log_mdc::remove(key),
+      };
+    }
+  }
+
+  fn clear() {
+    for key in MDC_FIELDS {
+      log_mdc::remove(key);
+    }
+  }
+}
+
+impl Visit for MdcFields {
+  fn record_str(&mut self, field: &Field, value: &str) {
+    self.record_debug(field, &value)
+  }
+
+  fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+    if let Some(key) = MDC_FIELDS.into_iter().find(|k| *k == field.name()) {
+      let value = format!("{:?}", value);
+      self.values.retain(|(k, _)| *k != key);
+      self.values.push((key, value.trim_matches('"').to_string()));
+    }
+  }
+}
+
+/// Text of a `tracing` event: the message followed by the other fields.
+#[derive(Default)]
+struct EventText(String);
+
+impl Visit for EventText {
+  fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+    if field.name() == "message" {
+      let _ = write!(self.0, "{:?}", value);
+    } else {
+      let _ = write!(self.0, " {}={:?}", field.name(), value);
+    }
+  }
+}
+
+/// Keeps the MDC in sync with the entered span and hands `tracing` events
+/// (e.g. sqlx's slow query warnings) over to `log`.
+pub struct MdcLayer;
+
+impl<S> Layer<S> for MdcLayer
+  where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+  fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+    let Some(span) = ctx.span(id) else { return };
+
+    // child spans inherit the request context
+    let mut fields = span.parent()
+      .and_then(|parent| parent.extensions().get::<MdcFields>().cloned())
+      .unwrap_or_default();
+    attrs.record(&mut fields);
+    span.extensions_mut().insert(fields);
+  }
+
+  fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+    let Some(span) = ctx.span(id) else { return };
+
+    let mut extensions = span.extensions_mut();
+    if let Some(fields) = extensions.get_mut::<MdcFields>() {
+      values.record(fields);
+      // a span may be recorded to after it has been exited
+      if ctx.current_span().id() == Some(id) {
+        fields.apply();
+      }
+    }
+  }
+
+  fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+    if let Some(fields) = ctx.span(id).and_then(|span| span.extensions().get::<MdcFields>().cloned()) {
+      fields.apply();
+    }
+  }
+
+  fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+    let parent = ctx.span(id)
+      .and_then(|span| span.parent())
+      .and_then(|parent| parent.extensions().get::<MdcFields>().cloned());
+
+    match parent {
+      Some(fields) => fields.apply(),
+      None => MdcFields::clear(),
+    }
+  }
+
+  fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+    let meta = event.metadata();
+    let level = match *meta.level() {
+      tracing::Level::ERROR => log::Level::Error,
+      tracing::Level::WARN => log::Level::Warn,
+      tracing::Level::INFO => log::Level::Info,
+      tracing::Level::DEBUG => log::Level::Debug,
+      tracing::Level::TRACE => log::Level::Trace,
+    };
+
+    let log_meta = log::Metadata::builder().level(level).target(meta.target()).build();
+    if level > log::max_level() || !log::logger().enabled(&log_meta) {
+      return
+    }
+
+    let mut text = EventText::default();
+    event.record(&mut text);
+    log::logger().log(
+      &log::Record::builder()
+        .metadata(log_meta)
+        .args(format_args!("{}", text.0))
+        .module_path(meta.module_path())
+        .file(meta.file())
+        .line(meta.line())
+        .build()
+    );
+  }
+}
+
+/// Install the global `tracing` subscriber; spans are also exported over OTLP
+/// if an endpoint is configured. Must be called after log4rs is initialized.
+pub fn init(config: &TracingConfig) -> Result<(), StartupError> {
+  let otlp_layer = if config.otlp_endpoint.is_empty() {
+    None
+  } else {
+    let tracer = opentelemetry_otlp::new_pipeline()
+      .tracing()
+      .with_exporter(
+        opentelemetry_otlp::new_exporter()
+          .http()
+          .with_endpoint(&config.otlp_endpoint)
+      )
+      .with_trace_config(
+        trace::config().with_resource(Resource::new(vec![
+          KeyValue::new("service.name", config.service_name.clone()),
+        ]))
+      )
+      .install_batch(runtime::TokioCurrentThread)
+      .map_err(|e| StartupError::Tracing { endpoint: config.otlp_endpoint.clone(), source: e })?;
+
+    // export failures would otherwise go straight to stderr
+    let _ = opentelemetry::global::set_error_handler(|e| log::warn!("Could not export spans: {}", e));
+
+    log::info!("Exporting spans to {}", config.otlp_endpoint);
+    Some(tracing_opentelemetry::layer().with_tracer(tracer))
+  };
+
+  let subscriber = Registry::default()
+    .with(MdcLayer)
+    .with(otlp_layer);
+
+  // only fails if a subscriber has already been installed
+  if tracing::subscriber::set_global_default(subscriber).is_err() {
+    log::warn!("A tracing subscriber is already installed");
+  }
+  Ok(())
+}
+
+/// Flush the spans which have not been exported yet.
+pub fn shutdown() {
+  opentelemetry::global::shutdown_tracer_provider();
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+use sqlx::PgPool;
+
+use common::{admin_token, bearer, init_app, register, send, user_id};
+
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn protected_routes_need_a_valid_token(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+
+  let req = TestRequest::get().uri("/api/book?page=0&size=20");
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+  assert_eq!(body["status"], 401);
+
+  let req = TestRequest::get()
+    .uri("/api/book?page=0&size=20")
+    .insert_header(("Authorization", "Token abc"));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+  let req = TestRequest::get()
+    .uri("/api/book?page=0&size=20")
+    .insert_header(bearer("not.a.token"));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn admin_scopes_turn_users_away(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let user = register(&app, "reader").await;
+  let admin = admin_token(&state).await;
+
+  let req = TestRequest::get().uri("/api/admin/diagnostics/pool").insert_header(bearer(&user));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+
+  let req = TestRequest::get().uri("/api/admin/diagnostics/pool").insert_header(bearer(&admin));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn only_admins_suspend_users(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  let user = register(&app, "reader").await;
+  let other = register(&app, "other").await;
+
+  let req = TestRequest::put()
+    .uri(&format!("/api/user/{}/suspend", user_id(&other)))
+    .insert_header(bearer(&user))
+    .set_json(json!({ "suspended": true }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn suspended_users_are_blocked_until_lifted(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let user = register(&app, "reader").await;
+  let admin = admin_token(&state).await;
+  let suspend_uri = format!("/api/user/{}/suspend", user_id(&user));
+
+  let req = TestRequest::put()
+    .uri(&suspend_uri)
+    .insert_header(bearer(&admin))
+    .set_json(json!({ "suspended": true }));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body["suspended"], true);
+
+  // the token is still valid, but the account is not
+  let req = TestRequest::get().uri("/api/book?page=0&size=20").insert_header(bearer(&user));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+
+  let req = TestRequest::put()
+    .uri(&suspend_uri)
+    .insert_header(bearer(&admin))
+    .set_json(json!({ "suspended": false }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+
+  let req = TestRequest::get().uri("/api/book?page=0&size=20").insert_header(bearer(&user));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+use sqlx::PgPool;
+
+use bookstore::MIGRATOR;
+use common::{bearer, init_app, login_req, register, send, PASSWORD};
+
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn register_returns_a_working_token(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  let token = register(&app, "reader").await;
+
+  let req = TestRequest::get().uri("/api/book?page=0&size=20").insert_header(bearer(&token));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn register_rejects_a_taken_nickname(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  register(&app, "reader").await;
+
+  let req = TestRequest::post()
+    .uri("/api/auth/register")
+    .set_json(json!({
+      "first_name": "Anna",
+      "last_name": "Smith",
+      "nickname": "reader",
+      "password": PASSWORD,
+    }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn nicknames_ignore_the_case(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  register(&app, "Reader").await;
+
+  let req = TestRequest::post()
+    .uri("/api/auth/register")
+    .set_json(json!({ "first_name": "Anna", "last_name": "Smith", "nickname": "rEADER", "password": PASSWORD }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::CONFLICT);
+
+  let req = login_req("READER", PASSWORD);
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert!(body["token"].is_string());
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn concurrent_registrations_get_one_nickname(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+
+  let request = |nickname: &str| TestRequest::post()
+    .uri("/api/auth/register")
+    .set_json(json!({ "first_name": "Anna", "last_name": "Smith", "nickname": nickname, "password": PASSWORD }));
+  let (first, second) = futures::join!(send(&app, request("reader")), send(&app, request("READER")));
+
+  let mut statuses = [first.0, second.0];
+  statuses.sort();
+  assert_eq!(statuses, [StatusCode::CREATED, StatusCode::CONFLICT]);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn migration_names_colliding_nicknames(pool: PgPool) {
+  // back to the schema without the unique index
+  MIGRATOR.undo(&pool, 20240212120000).await.unwrap();
+  for nickname in ["reader", "Reader"] {
+    sqlx::query("INSERT INTO users (id, first_name, last_name, nickname, hashed_password, role) VALUES ($1, 'Anna', 'Smith', $2, '', 'user')")
+      .bind(uuid::Uuid::new_v4())
+      .bind(nickname)
+      .execute(&pool)
+      .await
+      .unwrap();
+  }
+
+  let error = MIGRATOR.run(&pool).await.unwrap_err().to_string();
+  assert!(error.contains("reader (reader ") && error.contains("Reader "), "{}", error);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn register_rejects_malformed_fields(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+
+  for (first_name, nickname, field) in [("Иван1", "reader", "first_name"), ("Иван", "re ader", "nickname"), ("Иван", "", "nickname")] {
+    let req = TestRequest::post()
+      .uri("/api/auth/register")
+      .set_json(json!({
+        "first_name": first_name,
+        "last_name": "Петров",
+        "nickname": nickname,
+        "password": PASSWORD,
+      }));
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{} / {}", first_name, nickname);
+    assert_eq!(body["errors"][0]["field"], field, "{}", body);
+  }
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn login_checks_the_password(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  register(&app, "reader").await;
+
+  let req = login_req("reader", PASSWORD);
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert!(body["token"].is_string());
+
+  let req = login_req("reader", "wrong");
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+  let req = login_req("nobody", PASSWORD);
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+use sqlx::PgPool;
+
+use common::{add_book, add_copy, admin_token, bearer, init_app, register, send};
+
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn copies_move_between_branches(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let admin = admin_token(&state).await;
+  let reader = register(&app, "reader").await;
+  let book_id = add_book(&app, &admin, "Бесы").await;
+
+  let mut branches = vec![];
+  for name in ["Центральная", "Северная"] {
+    let req = TestRequest::post()
+      .uri("/api/branch")
+      .insert_header(bearer(&admin))
+      .set_json(json!({ "name": name, "address": "ул. Ленина, 1" }));
+    let (status, branch) = send(&app, req).await;
+    assert_eq!(status, StatusCode::CREATED, "{}", branch);
+    branches.push(branch["id"].as_str().unwrap().to_string());
+  }
+  let copy_id = add_copy(&app, &admin, &book_id, "0001", Some(&branches[0])).await;
+
+  let transfer = |token: &str, branch_id: &str| TestRequest::post()
+    .uri(&format!("/api/copy/{}/transfer", copy_id))
+    .insert_header(bearer(token))
+    .set_json(json!({ "branch_id": branch_id }));
+
+  let (status, _) = send(&app, transfer(&reader, &branches[1])).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+
+  let (status, copy) = send(&app, transfer(&admin, &branches[1])).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(copy["branch_id"], branches[1].as_str());
+  let (status, _) = send(&app, transfer(&admin, &branches[1])).await;
+  assert_eq!(status, StatusCode::CONFLICT);
+
+  let req = TestRequest::get().uri(&format!("/api/copy/{}/transfers", copy_id)).insert_header(bearer(&admin));
+  let (status, transfers) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(transfers.as_array().unwrap().len(), 1);
+  assert_eq!(transfers[0]["from_branch_id"], branches[0].as_str());
+  assert_eq!(transfers[0]["to_branch_id"], branches[1].as_str());
+
+  // a copy on loan stays where it was lent from
+  let req = TestRequest::post().uri("/api/loan").insert_header(bearer(&reader)).set_json(json!({ "book_id": book_id }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::CREATED);
+  let (status, _) = send(&app, transfer(&admin, &branches[0])).await;
+  assert_eq!(status, StatusCode::CONFLICT);
+
+  // the branch with the copy cannot go, the empty one can
+  let delete = |branch_id: &str| TestRequest::delete()
+    .uri(&format!("/api/branch/{}", branch_id))
+    .insert_header(bearer(&admin));
+  let (status, _) = send(&app, delete(&branches[1])).await;
+  assert_eq!(status, StatusCode::CONFLICT);
+  let (status, _) = send(&app, delete(&branches[0])).await;
+  assert_eq!(status, StatusCode::OK);
+}
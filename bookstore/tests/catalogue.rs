@@ -0,0 +1,147 @@
+mod common;
+
+use actix_http::Request;
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+use common::{admin_token, bearer, find, init_app, register, send};
+
+
+/// Add an author as the admin and return their ID; the API does not return it.
+async fn add_author<S, B>(app: &S, admin: &str, last_name: &str) -> String
+  where
+    S: Service<Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+  let req = TestRequest::post()
+    .uri("/api/author")
+    .insert_header(bearer(admin))
+    .set_json(json!({ "first_name": "Лев", "last_name": last_name }));
+  let (status, _) = send(app, req).await;
+  assert_eq!(status, StatusCode::CREATED);
+
+  let req = TestRequest::get().uri("/api/author?page=0&size=100").insert_header(bearer(admin));
+  let (_, body) = send(app, req).await;
+  find(&body, "last_name", last_name)["id"].as_str().unwrap().to_string()
+}
+
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn admin_manages_authors_and_books(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let admin = admin_token(&state).await;
+  let author_id = add_author(&app, &admin, "Толстой").await;
+
+  let req = TestRequest::post()
+    .uri("/api/book")
+    .insert_header(bearer(&admin))
+    .set_json(json!({ "title": "Война и мир", "author_id": author_id, "price": 99900, "stock": 3 }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::CREATED);
+
+  let req = TestRequest::get().uri(&format!("/api/author/{}", author_id)).insert_header(bearer(&admin));
+  let (status, author) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  let book_id = find(&author["books"], "title", "Война и мир")["id"].as_str().unwrap().to_string();
+
+  let req = TestRequest::get().uri(&format!("/api/book/{}", book_id)).insert_header(bearer(&admin));
+  let (status, book) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(book["author"]["last_name"], "Толстой");
+  assert_eq!(book["price"], 99900);
+  assert_eq!(book["stock"], 3);
+
+  let req = TestRequest::put()
+    .uri(&format!("/api/book/{}/inventory", book_id))
+    .insert_header(bearer(&admin))
+    .set_json(json!({ "stock": 5 }));
+  let (status, book) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(book["stock"], 5);
+  assert_eq!(book["price"], 99900);
+
+  let req = TestRequest::delete().uri(&format!("/api/book/{}", book_id)).insert_header(bearer(&admin));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+
+  let req = TestRequest::get().uri(&format!("/api/book/{}", book_id)).insert_header(bearer(&admin));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn book_needs_an_existing_author(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let admin = admin_token(&state).await;
+
+  let req = TestRequest::post()
+    .uri("/api/book")
+    .insert_header(bearer(&admin))
+    .set_json(json!({ "title": "Сирота", "author_id": uuid::Uuid::new_v4() }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn users_only_read_the_catalogue(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let admin = admin_token(&state).await;
+  let user = register(&app, "reader").await;
+  let author_id = add_author(&app, &admin, "Пушкин").await;
+
+  let req = TestRequest::post()
+    .uri("/api/author")
+    .insert_header(bearer(&user))
+    .set_json(json!({ "first_name": "Лев", "last_name": "Толстой" }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+
+  let req = TestRequest::post()
+    .uri("/api/book")
+    .insert_header(bearer(&user))
+    .set_json(json!({ "title": "Онегин", "author_id": author_id }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+
+  let req = TestRequest::delete().uri(&format!("/api/author/{}", author_id)).insert_header(bearer(&user));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+
+  let req = TestRequest::get().uri(&format!("/api/author/{}", author_id)).insert_header(bearer(&user));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn deleting_an_author_keeps_their_books(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let admin = admin_token(&state).await;
+  let author_id = add_author(&app, &admin, "Гоголь").await;
+
+  let req = TestRequest::post()
+    .uri("/api/book")
+    .insert_header(bearer(&admin))
+    .set_json(json!({ "title": "Шинель", "author_id": author_id }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::CREATED);
+
+  let req = TestRequest::delete().uri(&format!("/api/author/{}", author_id)).insert_header(bearer(&admin));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+
+  let req = TestRequest::get().uri(&format!("/api/author/{}", author_id)).insert_header(bearer(&admin));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::NOT_FOUND);
+
+  // ON DELETE SET NULL: the book stays, without an author
+  let req = TestRequest::get().uri("/api/book?page=0&size=100").insert_header(bearer(&admin));
+  let (status, books) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  let book = find(&books, "title", "Шинель");
+  assert_eq!(book["author_id"], Value::Null);
+  assert_eq!(book["author"], Value::Null);
+}
@@ -0,0 +1,187 @@
+//! Shared setup of the integration tests. Each test gets its own database from
+//! `#[sqlx::test]`, migrated by `bookstore::MIGRATOR`; the server found by
+//! `DATABASE_URL` must let the user create databases.
+
+// every test crate uses only some of the helpers
+#![allow(dead_code)]
+
+use std::sync::{Arc, OnceLock};
+use actix_http::Request;
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use bookstore::adapters::middleware::jwt::JwtClaims;
+use bookstore::adapters::middleware::problem::ProblemDetails;
+use bookstore::adapters::middleware::request_id::RequestTracing;
+use bookstore::adapters::routes;
+use bookstore::application::dto::request::user::{LoginReq, RegisterReq};
+use bookstore::application::services::auth::LoginResult;
+use bookstore::application::state::app_state::AppState;
+use bookstore::config::{AppConfig, LogFormat};
+use bookstore::logging::LogControl;
+
+pub const SECRET: &str = "test-secret";
+pub const PASSWORD: &str = "correct horse battery";
+
+/// The logger can only be installed once per process, so all tests share it.
+fn log_control(config: &AppConfig) -> Arc<LogControl> {
+  static LOG_CONTROL: OnceLock<Arc<LogControl>> = OnceLock::new();
+  LOG_CONTROL
+    .get_or_init(|| LogControl::init(&config.logging).expect("failed to install the logger"))
+    .clone()
+}
+
+pub fn config() -> AppConfig {
+  let mut config = AppConfig::default();
+  config.auth.secret = SECRET.to_string();
+  // hashing as in production would slow the tests down
+  config.auth.argon2_memory_kib = 1024;
+  config.auth.argon2_iterations = 1;
+  config.logging.format = LogFormat::Json;
+  config.logging.level = "off".to_string();
+  // the tests make requests in bursts; `rate_limit.rs` turns the limiter back on
+  config.rate_limit.enabled = false;
+  config
+}
+
+/// The API as the server mounts it, on top of the test database.
+pub async fn init_app(pool: PgPool) -> (
+  web::Data<AppState>,
+  impl Service<Request, Response = ServiceResponse<impl MessageBody>, Error = actix_web::Error>,
+) {
+  init_app_with(pool, config()).await
+}
+
+/// Same as `init_app`, but with a tweaked `config()`.
+pub async fn init_app_with(pool: PgPool, config: AppConfig) -> (
+  web::Data<AppState>,
+  impl Service<Request, Response = ServiceResponse<impl MessageBody>, Error = actix_web::Error>,
+) {
+  let log_control = log_control(&config);
+  let state = web::Data::new(AppState::new(config, pool, log_control));
+
+  let app = test::init_service(
+    App::new()
+      .app_data(state.clone())
+      .configure(|cfg| routes::configure(cfg, &state))
+      .wrap(ProblemDetails)
+      .wrap(RequestTracing)
+  ).await;
+
+  (state, app)
+}
+
+/// Send the request and read the JSON body, `Value::Null` if there is none.
+pub async fn send<S, B>(app: &S, req: test::TestRequest) -> (StatusCode, Value)
+  where
+    S: Service<Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+  let res = test::call_service(app, req.to_request()).await;
+  let status = res.status();
+  let body = test::read_body(res).await;
+  let value = if body.is_empty() {
+    Value::Null
+  } else {
+    serde_json::from_slice(&body).expect("the body is not JSON")
+  };
+  (status, value)
+}
+
+pub fn bearer(token: &str) -> (&'static str, String) {
+  ("Authorization", format!("Bearer {}", token))
+}
+
+pub fn user_id(token: &str) -> Uuid {
+  JwtClaims::from_token(token.to_string(), SECRET).expect("the token is invalid").user_id()
+}
+
+/// Register a user through the API and return their token.
+pub async fn register<S, B>(app: &S, nickname: &str) -> String
+  where
+    S: Service<Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+  let req = test::TestRequest::post()
+    .uri("/api/auth/register")
+    .set_json(json!({
+      "first_name": "Иван",
+      "last_name": "Петров",
+      "nickname": nickname,
+      "password": PASSWORD,
+    }));
+  let (status, body) = send(app, req).await;
+  assert_eq!(status, StatusCode::CREATED, "{}", body);
+  body["token"].as_str().expect("no token in the response").to_string()
+}
+
+/// The item of a JSON list with the field set to the value.
+pub fn find<'a>(list: &'a Value, field: &str, value: &str) -> &'a Value {
+  list.as_array()
+    .expect("not a list")
+    .iter()
+    .find(|item| item[field] == value)
+    .unwrap_or_else(|| panic!("no item with {} = {}", field, value))
+}
+
+/// Add a book without an author as the admin and return its ID; the API does not return it.
+pub async fn add_book<S, B>(app: &S, admin: &str, title: &str) -> String
+  where
+    S: Service<Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+  let req = test::TestRequest::post()
+    .uri("/api/book")
+    .insert_header(bearer(admin))
+    .set_json(json!({ "title": title }));
+  let (status, body) = send(app, req).await;
+  assert_eq!(status, StatusCode::CREATED, "{}", body);
+
+  let req = test::TestRequest::get().uri("/api/book?page=0&size=100").insert_header(bearer(admin));
+  let (_, books) = send(app, req).await;
+  find(&books, "title", title)["id"].as_str().unwrap().to_string()
+}
+
+/// Add a copy of the book as the admin, in the branch if one is given, and return its ID.
+pub async fn add_copy<S, B>(app: &S, admin: &str, book_id: &str, barcode: &str, branch_id: Option<&str>) -> String
+  where
+    S: Service<Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+  let req = test::TestRequest::post()
+    .uri("/api/copy")
+    .insert_header(bearer(admin))
+    .set_json(json!({ "book_id": book_id, "barcode": barcode, "condition": "good", "branch_id": branch_id }));
+  let (status, body) = send(app, req).await;
+  assert_eq!(status, StatusCode::CREATED, "{}", body);
+  body["id"].as_str().unwrap().to_string()
+}
+
+/// A login request with the given credentials.
+pub fn login_req(nickname: &str, password: &str) -> test::TestRequest {
+  test::TestRequest::post()
+    .uri("/api/auth/login")
+    .set_json(json!({ "nickname": nickname, "password": password }))
+}
+
+/// Admins cannot register through the API, so the admin is added directly.
+pub async fn admin_token(state: &AppState) -> String {
+  let nickname = "admin".to_string();
+  state.auth_service.register_admin(RegisterReq {
+    first_name: "Admin".to_string(),
+    last_name: "Admin".to_string(),
+    middle_name: None,
+    nickname: nickname.clone(),
+    password: PASSWORD.to_string(),
+  }).await.expect("failed to add the admin");
+
+  match state.auth_service.login(LoginReq { nickname, password: PASSWORD.to_string() }, None).await {
+    LoginResult::Ok(token) => token,
+    _ => panic!("the admin cannot log in"),
+  }
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+use sqlx::PgPool;
+
+use common::{bearer, config, init_app, init_app_with, register, send, PASSWORD};
+
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn malformed_body_is_a_problem(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+
+  let req = TestRequest::post()
+    .uri("/api/auth/login")
+    .insert_header(("Content-Type", "application/json"))
+    .set_payload("{\"nickname\": ");
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::BAD_REQUEST);
+  assert_eq!(body["status"], 400);
+  assert!(body["request_id"].is_string());
+
+  let req = TestRequest::post()
+    .uri("/api/auth/login")
+    .set_json(json!({ "nickname": 42, "password": "password" }));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::BAD_REQUEST);
+  assert!(body["detail"].as_str().unwrap().contains("invalid type"), "{}", body);
+
+  let req = TestRequest::post()
+    .uri("/api/auth/login")
+    .insert_header(("Content-Type", "text/plain"))
+    .set_payload("{}");
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+  assert_eq!(body["status"], 415);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn body_size_is_limited(pool: PgPool) {
+  let mut config = config();
+  config.server.max_body_bytes = 1024;
+  let (_, app) = init_app_with(pool, config).await;
+
+  let req = TestRequest::post()
+    .uri("/api/auth/login")
+    .set_json(json!({ "nickname": "reader", "password": "x".repeat(2048) }));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+  assert_eq!(body["status"], 413);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn malformed_query_and_path_are_problems(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  let user = register(&app, "reader").await;
+
+  let req = TestRequest::get().uri("/api/author?page=abc").insert_header(bearer(&user));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::BAD_REQUEST);
+  assert!(body["detail"].as_str().unwrap().contains("query"), "{}", body);
+
+  let req = TestRequest::get().uri("/api/book/not-a-uuid").insert_header(bearer(&user));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::BAD_REQUEST);
+  assert!(body["detail"].as_str().unwrap().contains("path"), "{}", body);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn unknown_fields_are_rejected_in_strict_mode(pool: PgPool) {
+  let mut config = config();
+  config.server.strict_requests = true;
+  let (_, app) = init_app_with(pool, config).await;
+
+  let body = json!({
+    "first_name": "Иван",
+    "last_name": "Петров",
+    "nickname": "reader",
+    "password": "password",
+    "role": "admin",
+  });
+  let req = TestRequest::post().uri("/api/auth/register").set_json(&body);
+  let (status, res) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+  assert_eq!(res["errors"], json!([{ "field": "role", "code": "unknown_field", "message": "Неизвестное поле." }]));
+
+  let user = register(&app, "reader").await;
+  let req = TestRequest::get()
+    .uri("/api/author?page=0&size=10&sort=name")
+    .insert_header(bearer(&user))
+    .insert_header(("Accept-Language", "en"));
+  let (status, res) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+  assert_eq!(res["errors"], json!([{ "field": "sort", "code": "unknown_field", "message": "Unknown field." }]));
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn unknown_fields_are_ignored_by_default(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+
+  let req = TestRequest::post()
+    .uri("/api/auth/register")
+    .set_json(json!({
+      "first_name": "Иван",
+      "last_name": "Петров",
+      "nickname": "reader",
+      "password": PASSWORD,
+      "role": "admin",
+    }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::CREATED);
+}
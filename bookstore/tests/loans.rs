@@ -0,0 +1,103 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+use sqlx::PgPool;
+
+use common::{add_book, add_copy, admin_token, bearer, init_app, register, send};
+
+
+fn checkout(token: &str, book_id: &str) -> TestRequest {
+  TestRequest::post()
+    .uri("/api/loan")
+    .insert_header(bearer(token))
+    .set_json(json!({ "book_id": book_id }))
+}
+
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn only_the_staff_take_copies_back(pool: PgPool) {
+  let (state, app) = init_app(pool.clone()).await;
+  let admin = admin_token(&state).await;
+  let reader = register(&app, "reader").await;
+  let book_id = add_book(&app, &admin, "Отцы и дети").await;
+  add_copy(&app, &admin, &book_id, "0001", None).await;
+  add_copy(&app, &admin, &book_id, "0002", None).await;
+
+  let (status, loan) = send(&app, checkout(&reader, &book_id)).await;
+  assert_eq!(status, StatusCode::CREATED, "{}", loan);
+  let loan_id = loan["id"].as_str().unwrap().to_string();
+
+  // the loan is overdue, so the reader may not borrow more
+  sqlx::query("UPDATE loans SET date_due = now() - interval '1 day'").execute(&pool).await.unwrap();
+  let (status, _) = send(&app, checkout(&reader, &book_id)).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+
+  // nor lift the block by returning the copy on paper
+  let return_req = |token: &str| TestRequest::post()
+    .uri(&format!("/api/loan/{}/return", loan_id))
+    .insert_header(bearer(token));
+  let (status, _) = send(&app, return_req(&reader)).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+  let (status, _) = send(&app, checkout(&reader, &book_id)).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+
+  let (status, loan) = send(&app, return_req(&admin)).await;
+  assert_eq!(status, StatusCode::OK);
+  assert!(loan["date_returned"].is_string());
+  let (status, _) = send(&app, return_req(&admin)).await;
+  assert_eq!(status, StatusCode::CONFLICT);
+
+  let (status, _) = send(&app, checkout(&reader, &book_id)).await;
+  assert_eq!(status, StatusCode::CREATED);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn concurrent_checkouts_get_the_last_copy_once(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let admin = admin_token(&state).await;
+  let first = register(&app, "first").await;
+  let second = register(&app, "second").await;
+  let book_id = add_book(&app, &admin, "Ревизор").await;
+  add_copy(&app, &admin, &book_id, "0001", None).await;
+
+  let (a, b) = futures::join!(send(&app, checkout(&first, &book_id)), send(&app, checkout(&second, &book_id)));
+  let mut statuses = [a.0, b.0];
+  statuses.sort();
+  assert_eq!(statuses, [StatusCode::CREATED, StatusCode::CONFLICT]);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn returned_copy_is_kept_for_the_hold_queue(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let admin = admin_token(&state).await;
+  let borrower = register(&app, "borrower").await;
+  let waiting = register(&app, "waiting").await;
+  let passerby = register(&app, "passerby").await;
+  let book_id = add_book(&app, &admin, "Мёртвые души").await;
+  add_copy(&app, &admin, &book_id, "0001", None).await;
+
+  let (status, loan) = send(&app, checkout(&borrower, &book_id)).await;
+  assert_eq!(status, StatusCode::CREATED);
+  let req = TestRequest::post().uri("/api/hold").insert_header(bearer(&waiting)).set_json(json!({ "book_id": book_id }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::CREATED);
+
+  let req = TestRequest::post()
+    .uri(&format!("/api/loan/{}/return", loan["id"].as_str().unwrap()))
+    .insert_header(bearer(&admin));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+
+  // the copy is back on the shelf, but it is reserved
+  let (status, _) = send(&app, checkout(&passerby, &book_id)).await;
+  assert_eq!(status, StatusCode::CONFLICT);
+  let (status, _) = send(&app, checkout(&waiting, &book_id)).await;
+  assert_eq!(status, StatusCode::CREATED);
+
+  // and the hold is fulfilled, so it leaves the list
+  let req = TestRequest::get().uri("/api/hold").insert_header(bearer(&waiting));
+  let (_, holds) = send(&app, req).await;
+  assert_eq!(holds.as_array().unwrap().len(), 0, "{}", holds);
+}
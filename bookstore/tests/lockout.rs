@@ -0,0 +1,155 @@
+mod common;
+
+use std::net::SocketAddr;
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+use sqlx::PgPool;
+
+use common::{admin_token, bearer, config, init_app_with, login_req, register, send, PASSWORD};
+
+
+/// The backoff delays are lifted, as if the time has passed.
+async fn wait_out_delays(pool: &PgPool) {
+  sqlx::query("UPDATE login_failures SET locked_until = NULL").execute(pool).await.unwrap();
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn nickname_is_delayed_then_locked_out(pool: PgPool) {
+  let mut config = config();
+  config.login.free_attempts = 2;
+  config.login.lockout_threshold = 4;
+  config.login.lockout_mins = 15;
+  let (state, app) = init_app_with(pool.clone(), config).await;
+  register(&app, "reader").await;
+
+  for _ in 0..2 {
+    let (status, _) = send(&app, login_req("reader", "wrong password")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+  }
+
+  // the third failure is not free, the next attempt has to wait a second
+  let (status, _) = send(&app, login_req("Reader", "wrong password")).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+  let res = actix_web::test::call_service(&app, login_req("reader", PASSWORD).to_request()).await;
+  assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+  assert_eq!(res.headers().get("retry-after").unwrap(), "1");
+
+  wait_out_delays(&pool).await;
+  let (status, _) = send(&app, login_req("reader", "wrong password")).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+  // locked out: even the right password is not checked
+  let res = actix_web::test::call_service(&app, login_req("reader", PASSWORD).to_request()).await;
+  assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+  let retry_after: i64 = res.headers().get("retry-after").unwrap().to_str().unwrap().parse().unwrap();
+  assert!((899..=900).contains(&retry_after), "{}", retry_after);
+
+  let admin = admin_token(&state).await;
+  let req = TestRequest::get().uri("/api/admin/lockouts?page=0&size=10&locked_only=true").insert_header(bearer(&admin));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body, json!([{
+    "kind": "nickname",
+    "key": "reader",
+    "failures": 4,
+    "date_last_failure": body[0]["date_last_failure"],
+    "locked_until": body[0]["locked_until"],
+  }]));
+
+  let reader = register(&app, "other").await;
+  let req = TestRequest::delete().uri("/api/admin/lockouts/nickname/Reader").insert_header(bearer(&reader));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+
+  let req = TestRequest::delete().uri("/api/admin/lockouts/nickname/Reader").insert_header(bearer(&admin));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+
+  let (status, _) = send(&app, login_req("reader", PASSWORD)).await;
+  assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn successful_login_forgets_the_failures(pool: PgPool) {
+  let mut config = config();
+  config.login.free_attempts = 2;
+  config.login.lockout_threshold = 3;
+  let (_, app) = init_app_with(pool.clone(), config).await;
+  register(&app, "reader").await;
+
+  for _ in 0..2 {
+    send(&app, login_req("reader", "wrong password")).await;
+  }
+  let (status, _) = send(&app, login_req("reader", PASSWORD)).await;
+  assert_eq!(status, StatusCode::OK);
+
+  for _ in 0..2 {
+    let (status, _) = send(&app, login_req("reader", "wrong password")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+  }
+  let (status, _) = send(&app, login_req("reader", PASSWORD)).await;
+  assert_eq!(status, StatusCode::OK);
+
+  // nicknames nobody has are throttled all the same
+  for _ in 0..3 {
+    send(&app, login_req("nobody", "wrong password")).await;
+  }
+  let (status, _) = send(&app, login_req("nobody", "wrong password")).await;
+  assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn client_address_is_taken_from_trusted_proxies_only(pool: PgPool) {
+  let mut config = config();
+  config.server.trusted_proxies = "10.0.0.0/8, 192.168.1.1".parse().unwrap();
+  config.login.ip_free_attempts = 1;
+  config.login.ip_lockout_threshold = 2;
+  let (state, app) = init_app_with(pool.clone(), config).await;
+  register(&app, "reader").await;
+
+  let from = |peer: &str, forwarded: &str, nickname: &str, password: &str| {
+    let req = login_req(nickname, password).peer_addr(SocketAddr::new(peer.parse().unwrap(), 40000));
+    match forwarded {
+      "" => req,
+      forwarded => req.insert_header(("X-Forwarded-For", forwarded.to_string())),
+    }
+  };
+
+  // the client made up the first entry, the proxies appended the rest
+  for nickname in ["first", "second"] {
+    let (status, _) = send(&app, from("10.0.0.2", "6.6.6.6, 1.2.3.4, 192.168.1.1", nickname, "wrong password")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+  }
+  let (status, _) = send(&app, from("10.0.0.2", "1.2.3.4", "reader", PASSWORD)).await;
+  assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+  let (status, _) = send(&app, from("10.0.0.2", "5.6.7.8", "reader", PASSWORD)).await;
+  assert_eq!(status, StatusCode::OK);
+
+  // an untrusted peer is the client, whatever it says
+  let (status, _) = send(&app, from("8.8.8.8", "1.2.3.4", "reader", PASSWORD)).await;
+  assert_eq!(status, StatusCode::OK);
+
+  let admin = admin_token(&state).await;
+  let req = TestRequest::get().uri("/api/admin/lockouts?page=0&size=10&locked_only=true").insert_header(bearer(&admin));
+  let (_, body) = send(&app, req).await;
+  assert_eq!(body.as_array().unwrap().len(), 1);
+  assert_eq!(body[0]["kind"], "ip");
+  assert_eq!(body[0]["key"], "1.2.3.4");
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn lowercased_nickname_may_grow_longer(pool: PgPool) {
+  let (_, app) = init_app_with(pool.clone(), config()).await;
+
+  // 'İ' lowercases into two characters
+  let (status, _) = send(&app, login_req(&"İ".repeat(64), "wrong password")).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+  let key: String = sqlx::query_scalar("SELECT key FROM login_failures WHERE kind = 'nickname'")
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+  assert_eq!(key.chars().count(), 128);
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use common::{admin_token, bearer, init_app, login_req, register, send, user_id, PASSWORD};
+
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn profile_is_read_and_patched(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  let user = register(&app, "reader").await;
+
+  let req = TestRequest::get().uri("/api/me").insert_header(bearer(&user));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body["id"], user_id(&user).to_string());
+  assert_eq!(body["nickname"], "reader");
+  assert!(body.get("hashed_password").is_none());
+
+  let req = TestRequest::patch()
+    .uri("/api/me")
+    .insert_header(bearer(&user))
+    .set_json(json!({ "last_name": "Салтыков-Щедрин", "middle_name": "Евграфович" }));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body["first_name"], "Иван");
+  assert_eq!(body["last_name"], "Салтыков-Щедрин");
+  assert_eq!(body["middle_name"], "Евграфович");
+
+  // `null` clears the middle name, a missing field keeps it
+  let req = TestRequest::patch().uri("/api/me").insert_header(bearer(&user)).set_json(json!({ "middle_name": null }));
+  let (_, body) = send(&app, req).await;
+  assert!(body["middle_name"].is_null());
+  assert_eq!(body["last_name"], "Салтыков-Щедрин");
+
+  let req = TestRequest::patch().uri("/api/me").insert_header(bearer(&user)).set_json(json!({ "first_name": "" }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn password_change_ends_the_other_sessions(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  let user = register(&app, "reader").await;
+  let (_, body) = send(&app, login_req("reader", PASSWORD)).await;
+  let other_session = body["token"].as_str().unwrap().to_string();
+
+  let change = |current: &str, new: &str| TestRequest::post()
+    .uri("/api/me/password")
+    .insert_header(bearer(&user))
+    .set_json(json!({ "current_password": current, "new_password": new }));
+
+  let (status, _) = send(&app, change("wrong password", "brand new secret")).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+
+  let (status, body) = send(&app, change(PASSWORD, "qwerty123")).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+  assert_eq!(body["errors"][0]["code"], "common_password");
+
+  let (status, body) = send(&app, change(PASSWORD, "brand new secret")).await;
+  assert_eq!(status, StatusCode::OK);
+  let new_session = body["token"].as_str().unwrap().to_string();
+
+  for token in [&user, &other_session] {
+    let req = TestRequest::get().uri("/api/me").insert_header(bearer(token));
+    let (status, _) = send(&app, req).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+  }
+  let req = TestRequest::get().uri("/api/me").insert_header(bearer(&new_session));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+
+  let (status, _) = send(&app, login_req("reader", "brand new secret")).await;
+  assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn account_is_deleted_with_the_password(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  let user = register(&app, "reader").await;
+
+  let delete = |password: &str| TestRequest::delete()
+    .uri("/api/me")
+    .insert_header(bearer(&user))
+    .set_json(json!({ "password": password }));
+
+  let (status, _) = send(&app, delete("wrong password")).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+
+  let (status, _) = send(&app, delete(PASSWORD)).await;
+  assert_eq!(status, StatusCode::OK);
+
+  let req = TestRequest::get().uri("/api/me").insert_header(bearer(&user));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::NOT_FOUND);
+
+  // the nickname is free again
+  register(&app, "reader").await;
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn account_with_books_on_loan_is_kept(pool: PgPool) {
+  let (_, app) = init_app(pool.clone()).await;
+  let user = register(&app, "reader").await;
+
+  let (book_id, copy_id) = (Uuid::new_v4(), Uuid::new_v4());
+  sqlx::query("INSERT INTO books (id, title) VALUES ($1, 'Война и мир')").bind(book_id).execute(&pool).await.unwrap();
+  sqlx::query("INSERT INTO copies (id, book_id, barcode, status) VALUES ($1, $2, 'b1', 'on_loan')")
+    .bind(copy_id)
+    .bind(book_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+  sqlx::query("INSERT INTO loans (id, copy_id, book_id, user_id, date_due) VALUES ($1, $2, $3, $4, now() + interval '14 days')")
+    .bind(Uuid::new_v4())
+    .bind(copy_id)
+    .bind(book_id)
+    .bind(user_id(&user))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+  let req = TestRequest::delete().uri("/api/me").insert_header(bearer(&user)).set_json(json!({ "password": PASSWORD }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn only_admins_read_other_users(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let user = register(&app, "reader").await;
+  let other = register(&app, "other").await;
+  let admin = admin_token(&state).await;
+
+  for uri in [format!("/api/user/{}", user_id(&other)), "/api/user?page=0&size=10".to_string()] {
+    let req = TestRequest::get().uri(&uri).insert_header(bearer(&user));
+    let (status, _) = send(&app, req).await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{}", uri);
+
+    let req = TestRequest::get().uri(&uri).insert_header(bearer(&admin));
+    let (status, _) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK, "{}", uri);
+  }
+}
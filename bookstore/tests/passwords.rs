@@ -0,0 +1,102 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+use common::{config, init_app, init_app_with, login_req, register, send, PASSWORD};
+
+
+fn register_req(nickname: &str, password: &str) -> TestRequest {
+  TestRequest::post()
+    .uri("/api/auth/register")
+    .set_json(json!({ "first_name": "Anna", "last_name": "Smith", "nickname": nickname, "password": password }))
+}
+
+fn codes(body: &Value) -> Vec<&str> {
+  body["errors"].as_array().unwrap().iter().map(|e| e["code"].as_str().unwrap()).collect()
+}
+
+async fn stored_hash(pool: &PgPool, nickname: &str) -> String {
+  sqlx::query_scalar("SELECT hashed_password FROM users WHERE nickname = $1")
+    .bind(nickname)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn weak_passwords_are_rejected(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+
+  let (status, body) = send(&app, register_req("reader", "x1y2z3")).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+  assert_eq!(codes(&body), ["length"]);
+  assert_eq!(body["errors"][0]["field"], "password");
+  assert_eq!(body["errors"][0]["message"], "Длина должна быть от 8 до 128 символов.");
+
+  let (status, body) = send(&app, register_req("reader", "Qwerty123")).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+  assert_eq!(codes(&body), ["common_password"]);
+
+  let (status, body) = send(&app, register_req("reader", "my-READER-secret")).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+  assert_eq!(codes(&body), ["contains_nickname"]);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn policy_follows_the_config(pool: PgPool) {
+  let mut config = config();
+  config.auth.password_min_length = 4;
+  config.auth.reject_common_passwords = false;
+  let (_, app) = init_app_with(pool, config).await;
+
+  let (status, _) = send(&app, register_req("reader", "1234")).await;
+  assert_eq!(status, StatusCode::CREATED);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn new_passwords_are_hashed_with_argon2id(pool: PgPool) {
+  let (_, app) = init_app(pool.clone()).await;
+  register(&app, "reader").await;
+
+  assert!(stored_hash(&pool, "reader").await.starts_with("$argon2id$v=19$m=1024,t=1,p=1$"));
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn bcrypt_hashes_are_upgraded_on_login(pool: PgPool) {
+  let (_, app) = init_app(pool.clone()).await;
+  sqlx::query("INSERT INTO users (id, first_name, last_name, nickname, hashed_password, role) VALUES ($1, 'Anna', 'Smith', 'reader', $2, 'user')")
+    .bind(uuid::Uuid::new_v4())
+    .bind(bcrypt::hash(PASSWORD, 4).unwrap())
+    .execute(&pool)
+    .await
+    .unwrap();
+
+  // a wrong password leaves the hash alone
+  let (status, _) = send(&app, login_req("reader", "wrong password")).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+  assert!(stored_hash(&pool, "reader").await.starts_with("$2"));
+
+  let (status, _) = send(&app, login_req("reader", PASSWORD)).await;
+  assert_eq!(status, StatusCode::OK);
+  assert!(stored_hash(&pool, "reader").await.starts_with("$argon2id$"));
+
+  let (status, _) = send(&app, login_req("reader", PASSWORD)).await;
+  assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn hashes_with_old_parameters_are_upgraded_on_login(pool: PgPool) {
+  let mut old = config();
+  old.auth.argon2_memory_kib = 2048;
+  let (_, app) = init_app_with(pool.clone(), old).await;
+  register(&app, "reader").await;
+  assert!(stored_hash(&pool, "reader").await.starts_with("$argon2id$v=19$m=2048,"));
+
+  let (_, app) = init_app(pool.clone()).await;
+  let (status, _) = send(&app, login_req("reader", PASSWORD)).await;
+  assert_eq!(status, StatusCode::OK);
+  assert!(stored_hash(&pool, "reader").await.starts_with("$argon2id$v=19$m=1024,"));
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use std::net::SocketAddr;
+use actix_web::http::StatusCode;
+use actix_web::test::{self, TestRequest};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+use bookstore::config::{AppConfig, BucketConfig, RateLimitStoreKind};
+use common::{bearer, config, init_app_with, login_req, register, send, PASSWORD};
+
+
+fn limited(store: RateLimitStoreKind) -> AppConfig {
+  let mut config = config();
+  config.rate_limit.enabled = true;
+  config.rate_limit.store = store;
+  config.rate_limit.auth = BucketConfig { capacity: 2, per_minute: 1 };
+  config
+}
+
+fn login_from(peer: &str) -> TestRequest {
+  login_req("nobody", "wrong password").peer_addr(SocketAddr::new(peer.parse().unwrap(), 40000))
+}
+
+fn header(headers: &actix_web::http::header::HeaderMap, name: &str) -> String {
+  headers.get(name).unwrap_or_else(|| panic!("no {} header", name)).to_str().unwrap().to_string()
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn anonymous_requests_are_limited_per_address(pool: PgPool) {
+  let (_, app) = init_app_with(pool, limited(RateLimitStoreKind::Memory)).await;
+
+  for remaining in ["1", "0"] {
+    let res = test::call_service(&app, login_from("1.2.3.4").to_request()).await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(header(res.headers(), "ratelimit-limit"), "2");
+    assert_eq!(header(res.headers(), "ratelimit-remaining"), remaining);
+    assert_eq!(header(res.headers(), "ratelimit-policy"), "2;w=120");
+  }
+
+  let res = test::call_service(&app, login_from("1.2.3.4").to_request()).await;
+  assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+  assert_eq!(header(res.headers(), "content-type"), "application/problem+json");
+  assert_eq!(header(res.headers(), "retry-after"), "60");
+  assert_eq!(header(res.headers(), "ratelimit-remaining"), "0");
+  let body: Value = test::read_body_json(res).await;
+  assert_eq!(body["status"], 429);
+
+  // another client has a bucket of its own
+  let (status, _) = send(&app, login_from("5.6.7.8")).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn users_are_limited_by_their_tokens(pool: PgPool) {
+  let mut config = limited(RateLimitStoreKind::Memory);
+  config.rate_limit.auth = BucketConfig { capacity: 10, per_minute: 10 };
+  config.rate_limit.catalogue = BucketConfig { capacity: 1, per_minute: 1 };
+  let (_, app) = init_app_with(pool, config).await;
+  let first = register(&app, "first").await;
+  let second = register(&app, "second").await;
+
+  let books = |token: &str| TestRequest::get()
+    .uri("/api/book?page=0&size=10")
+    .peer_addr("1.2.3.4:40000".parse().unwrap())
+    .insert_header(bearer(token));
+
+  // the same address, but different users
+  let (status, _) = send(&app, books(&first)).await;
+  assert_eq!(status, StatusCode::OK);
+  let (status, _) = send(&app, books(&second)).await;
+  assert_eq!(status, StatusCode::OK);
+  let (status, _) = send(&app, books(&first)).await;
+  assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+  // a forged token is turned away by JwtAuth before it reaches a bucket
+  for _ in 0..2 {
+    let (status, _) = send(&app, books(&format!("{}x", first))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+  }
+
+  // the other scopes have buckets of their own
+  let req = TestRequest::get().uri("/api/me").insert_header(bearer(&first));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn tokens_do_not_reset_the_auth_limit(pool: PgPool) {
+  let (_, app) = init_app_with(pool, limited(RateLimitStoreKind::Memory)).await;
+
+  let register_from = |nickname: &str| TestRequest::post()
+    .uri("/api/auth/register")
+    .peer_addr("1.2.3.4:40000".parse().unwrap())
+    .set_json(json!({ "first_name": "Иван", "last_name": "Петров", "nickname": nickname, "password": PASSWORD }));
+
+  let (status, body) = send(&app, register_from("first")).await;
+  assert_eq!(status, StatusCode::CREATED);
+  let token = body["token"].as_str().unwrap().to_string();
+  let (status, _) = send(&app, register_from("second")).await;
+  assert_eq!(status, StatusCode::CREATED);
+
+  // the token of the new account does not open a bucket of its own
+  let (status, _) = send(&app, register_from("third").insert_header(bearer(&token))).await;
+  assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn postgres_store_refills_the_buckets(pool: PgPool) {
+  let (_, app) = init_app_with(pool.clone(), limited(RateLimitStoreKind::Postgres)).await;
+
+  for _ in 0..2 {
+    let (status, _) = send(&app, login_from("1.2.3.4")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+  }
+  let (status, _) = send(&app, login_from("1.2.3.4")).await;
+  assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+  let (key, allowed): (String, bool) = sqlx::query_as("SELECT key, allowed FROM rate_limit_buckets")
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+  assert_eq!((key.as_str(), allowed), ("auth:ip:1.2.3.4", false));
+
+  // a minute later there is a token again
+  sqlx::query("UPDATE rate_limit_buckets SET date_updated = date_updated - interval '1 minute'")
+    .execute(&pool)
+    .await
+    .unwrap();
+  let res = test::call_service(&app, login_from("1.2.3.4").to_request()).await;
+  assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+  assert_eq!(header(res.headers(), "ratelimit-remaining"), "0");
+  let (status, _) = send(&app, login_from("1.2.3.4")).await;
+  assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+}
@@ -0,0 +1,209 @@
+mod common;
+
+use std::time::Duration;
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+use sqlx::PgPool;
+
+use common::{bearer, init_app, login_req, register, send, PASSWORD};
+
+
+/// The messages queued for the address, oldest first.
+async fn outbox(pool: &PgPool, recipient: &str) -> Vec<String> {
+  sqlx::query_scalar("SELECT body FROM outbox WHERE recipient = $1 ORDER BY date_created")
+    .bind(recipient)
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+/// The messages queued for the address, once there are `count` of them:
+/// `/api/auth/forgot` queues its message after it has answered.
+async fn wait_for_mail(pool: &PgPool, recipient: &str, count: usize) -> Vec<String> {
+  for _ in 0..500 {
+    let mail = outbox(pool, recipient).await;
+    if mail.len() >= count {
+      return mail
+    }
+    tokio::time::sleep(Duration::from_millis(10)).await;
+  }
+  panic!("no mail for {} in time", recipient)
+}
+
+/// The token in the message, the only line of 64 hex digits.
+fn token(body: &str) -> String {
+  body.lines()
+    .map(str::trim)
+    .find(|line| line.len() == 64 && line.chars().all(|c| c.is_ascii_hexdigit()))
+    .expect("no token in the mail")
+    .to_string()
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn email_is_verified_with_the_mailed_token(pool: PgPool) {
+  let (_, app) = init_app(pool.clone()).await;
+  let user = register(&app, "reader").await;
+
+  let req = TestRequest::put().uri("/api/me/email").insert_header(bearer(&user)).set_json(json!({ "email": "not an address" }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+  let req = TestRequest::put().uri("/api/me/email").insert_header(bearer(&user)).set_json(json!({ "email": "reader@example.com" }));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body["email"], "reader@example.com");
+  assert_eq!(body["email_verified"], false);
+
+  let mail = outbox(&pool, "reader@example.com").await;
+  assert_eq!(mail.len(), 1);
+  let token = token(&mail[0]);
+  let stored: String = sqlx::query_scalar("SELECT token_hash FROM account_tokens").fetch_one(&pool).await.unwrap();
+  assert_ne!(stored, token, "the token is stored as is");
+
+  let verify = || TestRequest::post().uri("/api/auth/verify-email").set_json(json!({ "token": token }));
+  let (status, _) = send(&app, verify()).await;
+  assert_eq!(status, StatusCode::OK);
+
+  let req = TestRequest::get().uri("/api/me").insert_header(bearer(&user));
+  let (_, body) = send(&app, req).await;
+  assert_eq!(body["email_verified"], true);
+
+  // single use
+  let (status, _) = send(&app, verify()).await;
+  assert_eq!(status, StatusCode::BAD_REQUEST);
+
+  let req = TestRequest::delete().uri("/api/me/email").insert_header(bearer(&user));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert!(body["email"].is_null());
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn verified_address_belongs_to_one_user(pool: PgPool) {
+  let (_, app) = init_app(pool.clone()).await;
+  let first = register(&app, "first").await;
+  let second = register(&app, "second").await;
+
+  // both may claim it until one of them verifies it
+  for user in [&first, &second] {
+    let req = TestRequest::put().uri("/api/me/email").insert_header(bearer(user)).set_json(json!({ "email": "shared@example.com" }));
+    let (status, _) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+  }
+  let mail = outbox(&pool, "shared@example.com").await;
+
+  let req = TestRequest::post().uri("/api/auth/verify-email").set_json(json!({ "token": token(&mail[0]) }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  let req = TestRequest::post().uri("/api/auth/verify-email").set_json(json!({ "token": token(&mail[1]) }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::CONFLICT);
+
+  let req = TestRequest::put().uri("/api/me/email").insert_header(bearer(&second)).set_json(json!({ "email": "SHARED@example.com" }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn forgot_does_not_tell_whether_the_account_exists(pool: PgPool) {
+  let (_, app) = init_app(pool.clone()).await;
+  let user = register(&app, "reader").await;
+  let req = TestRequest::put().uri("/api/me/email").insert_header(bearer(&user)).set_json(json!({ "email": "reader@example.com" }));
+  send(&app, req).await;
+
+  let forgot = |email: &str| TestRequest::post().uri("/api/auth/forgot").set_json(json!({ "email": email }));
+
+  // neither an unknown nor an unverified address gets a reset token
+  let (unknown_status, unknown_body) = send(&app, forgot("nobody@example.com")).await;
+  let (unverified_status, unverified_body) = send(&app, forgot("reader@example.com")).await;
+  assert_eq!(unknown_status, StatusCode::ACCEPTED);
+  assert_eq!((unknown_status, unknown_body.clone()), (unverified_status, unverified_body));
+
+  let mail = outbox(&pool, "reader@example.com").await;
+  let req = TestRequest::post().uri("/api/auth/verify-email").set_json(json!({ "token": token(&mail[0]) }));
+  send(&app, req).await;
+
+  let (status, body) = send(&app, forgot("Reader@Example.com")).await;
+  assert_eq!((status, body), (unknown_status, unknown_body));
+
+  let mail = wait_for_mail(&pool, "reader@example.com", 2).await;
+  assert_eq!(mail.len(), 2);
+  assert!(mail[1].contains("/api/auth/reset"));
+  assert!(outbox(&pool, "nobody@example.com").await.is_empty());
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn reset_sets_the_password_and_ends_the_sessions(pool: PgPool) {
+  let (_, app) = init_app(pool.clone()).await;
+  let user = register(&app, "reader").await;
+  let req = TestRequest::put().uri("/api/me/email").insert_header(bearer(&user)).set_json(json!({ "email": "reader@example.com" }));
+  send(&app, req).await;
+  let mail = outbox(&pool, "reader@example.com").await;
+  let req = TestRequest::post().uri("/api/auth/verify-email").set_json(json!({ "token": token(&mail[0]) }));
+  send(&app, req).await;
+
+  let req = TestRequest::post().uri("/api/auth/forgot").set_json(json!({ "email": "reader@example.com" }));
+  send(&app, req).await;
+  let token = token(&wait_for_mail(&pool, "reader@example.com", 2).await[1]);
+
+  let reset = |token: &str, password: &str| TestRequest::post()
+    .uri("/api/auth/reset")
+    .set_json(json!({ "token": token, "new_password": password }));
+
+  let (status, _) = send(&app, reset(&"0".repeat(64), "brand new secret")).await;
+  assert_eq!(status, StatusCode::BAD_REQUEST);
+
+  // a weak password does not use the token up
+  let (status, body) = send(&app, reset(&token, "password")).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+  assert_eq!(body["errors"][0]["code"], "common_password");
+
+  let (status, _) = send(&app, reset(&token, "brand new secret")).await;
+  assert_eq!(status, StatusCode::OK);
+
+  let (status, _) = send(&app, reset(&token, "another new secret")).await;
+  assert_eq!(status, StatusCode::BAD_REQUEST);
+
+  let req = TestRequest::get().uri("/api/me").insert_header(bearer(&user));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+  let (status, _) = send(&app, login_req("reader", PASSWORD)).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+  let (status, _) = send(&app, login_req("reader", "brand new secret")).await;
+  assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn stale_reset_tokens_are_rejected(pool: PgPool) {
+  let (_, app) = init_app(pool.clone()).await;
+  let user = register(&app, "reader").await;
+  let req = TestRequest::put().uri("/api/me/email").insert_header(bearer(&user)).set_json(json!({ "email": "reader@example.com" }));
+  send(&app, req).await;
+  let mail = outbox(&pool, "reader@example.com").await;
+  let req = TestRequest::post().uri("/api/auth/verify-email").set_json(json!({ "token": token(&mail[0]) }));
+  send(&app, req).await;
+
+  let forgot = || TestRequest::post().uri("/api/auth/forgot").set_json(json!({ "email": "reader@example.com" }));
+  let reset = |token: &str| TestRequest::post()
+    .uri("/api/auth/reset")
+    .set_json(json!({ "token": token, "new_password": "brand new secret" }));
+
+  send(&app, forgot()).await;
+  let expired = token(&wait_for_mail(&pool, "reader@example.com", 2).await[1]);
+  sqlx::query("UPDATE account_tokens SET date_expires = now() - interval '1 minute' WHERE purpose = 'reset_password'")
+    .execute(&pool)
+    .await
+    .unwrap();
+  let (status, _) = send(&app, reset(&expired)).await;
+  assert_eq!(status, StatusCode::BAD_REQUEST);
+
+  // changing the address revokes the tokens mailed to the old one
+  send(&app, forgot()).await;
+  let revoked = token(&wait_for_mail(&pool, "reader@example.com", 3).await[2]);
+  let req = TestRequest::put().uri("/api/me/email").insert_header(bearer(&user)).set_json(json!({ "email": "new@example.com" }));
+  send(&app, req).await;
+  let (status, _) = send(&app, reset(&revoked)).await;
+  assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use std::collections::HashSet;
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+use sqlx::PgPool;
+
+use common::{add_book, admin_token, bearer, init_app, register, send};
+
+
+fn review(token: &str, book_id: &str, rating: i16) -> TestRequest {
+  TestRequest::post()
+    .uri(&format!("/api/book/{}/review", book_id))
+    .insert_header(bearer(token))
+    .set_json(json!({ "rating": rating, "text": "Перечитаю." }))
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn concurrent_reviews_leave_one(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let admin = admin_token(&state).await;
+  let reader = register(&app, "reader").await;
+  let book_id = add_book(&app, &admin, "Идиот").await;
+
+  let (first, second) = futures::join!(send(&app, review(&reader, &book_id, 5)), send(&app, review(&reader, &book_id, 4)));
+  let mut statuses = [first.0, second.0];
+  statuses.sort();
+  assert_eq!(statuses, [StatusCode::CREATED, StatusCode::CONFLICT]);
+
+  let req = TestRequest::get().uri(&format!("/api/book/{}", book_id)).insert_header(bearer(&reader));
+  let (_, book) = send(&app, req).await;
+  assert_eq!(book["review_count"], 1);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn rating_pages_neither_repeat_nor_skip(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let admin = admin_token(&state).await;
+  let reader = register(&app, "reader").await;
+
+  // two pairs of equal ratings and a few unrated books
+  let mut titles = vec![];
+  for (i, rating) in [Some(5), Some(5), Some(3), Some(3), None, None, None].into_iter().enumerate() {
+    let title = format!("Книга {}", i);
+    let book_id = add_book(&app, &admin, &title).await;
+    if let Some(rating) = rating {
+      let (status, _) = send(&app, review(&reader, &book_id, rating)).await;
+      assert_eq!(status, StatusCode::CREATED);
+    }
+    titles.push(title);
+  }
+
+  let mut seen = vec![];
+  for page in 0..4 {
+    let req = TestRequest::get()
+      .uri(&format!("/api/book?page={}&size=2&sort=rating", page))
+      .insert_header(bearer(&reader));
+    let (status, books) = send(&app, req).await;
+    assert_eq!(status, StatusCode::OK, "{}", books);
+    seen.extend(books.as_array().unwrap().iter().map(|b| b["title"].as_str().unwrap().to_string()));
+  }
+  assert_eq!(seen.len(), titles.len());
+  assert_eq!(seen.iter().collect::<HashSet<_>>(), titles.iter().collect::<HashSet<_>>());
+  assert!(seen[..2].iter().all(|t| t == "Книга 0" || t == "Книга 1"), "{:?}", seen);
+}
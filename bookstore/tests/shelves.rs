@@ -0,0 +1,59 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+use sqlx::PgPool;
+
+use common::{add_book, admin_token, bearer, find, init_app, register, send};
+
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn start_date_is_carried_over_to_the_read_shelf(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let admin = admin_token(&state).await;
+  let reader = register(&app, "reader").await;
+  let book_id = add_book(&app, &admin, "Обломов").await;
+
+  let req = TestRequest::get().uri("/api/me/shelves").insert_header(bearer(&reader));
+  let (status, shelves) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  let reading = find(&shelves, "kind", "reading")["id"].as_str().unwrap().to_string();
+  let read = find(&shelves, "kind", "read")["id"].as_str().unwrap().to_string();
+
+  let put = |shelf_id: &str, body: serde_json::Value| TestRequest::put()
+    .uri(&format!("/api/me/shelves/{}/book/{}", shelf_id, book_id))
+    .insert_header(bearer(&reader))
+    .set_json(body);
+
+  let (status, _) = send(&app, put(&reading, json!({ "date_started": "2024-03-10" }))).await;
+  assert_eq!(status, StatusCode::OK);
+
+  // finished before the carried over start: refused, and the book stays where it was
+  let (status, _) = send(&app, put(&read, json!({ "date_finished": "2024-03-01" }))).await;
+  assert_eq!(status, StatusCode::BAD_REQUEST);
+  let req = TestRequest::get().uri("/api/me/shelves").insert_header(bearer(&reader));
+  let (_, shelves) = send(&app, req).await;
+  assert_eq!(find(&shelves, "kind", "reading")["entries"].as_array().unwrap().len(), 1);
+
+  let (status, entry) = send(&app, put(&read, json!({ "date_finished": "2024-03-20" }))).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(entry["date_started"], "2024-03-10");
+  assert_eq!(entry["date_finished"], "2024-03-20");
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn concurrent_shelves_get_one_name(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  let reader = register(&app, "reader").await;
+
+  let request = || TestRequest::post()
+    .uri("/api/me/shelves")
+    .insert_header(bearer(&reader))
+    .set_json(json!({ "name": "Классика", "public": false }));
+  let (first, second) = futures::join!(send(&app, request()), send(&app, request()));
+
+  let mut statuses = [first.0, second.0];
+  statuses.sort();
+  assert_eq!(statuses, [StatusCode::CREATED, StatusCode::CONFLICT]);
+}
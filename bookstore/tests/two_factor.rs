@@ -0,0 +1,231 @@
+mod common;
+
+use actix_http::Request;
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::json;
+use sqlx::PgPool;
+
+use bookstore::application::util::totp;
+use common::{admin_token, bearer, config, init_app, init_app_with, login_req, register, send, PASSWORD};
+
+
+fn login_2fa(challenge: &str, code: &str) -> TestRequest {
+  TestRequest::post()
+    .uri("/api/auth/login/2fa")
+    .set_json(json!({ "challenge": challenge, "code": code }))
+}
+
+/// The code of the app `ahead` steps from now.
+fn code(secret: &str, ahead: i64) -> String {
+  totp::code(secret, totp::current_step() + ahead).unwrap()
+}
+
+/// A code that is surely not the right one.
+fn wrong(code: &str) -> String {
+  code.chars().map(|c| char::from_digit((c.to_digit(10).unwrap() + 5) % 10, 10).unwrap()).collect()
+}
+
+/// Set up 2FA for the user: the secret, the token of the new session and the recovery codes.
+async fn enable<S, B>(app: &S, token: &str) -> (String, String, Vec<String>)
+  where
+    S: Service<Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+  let req = TestRequest::post().uri("/api/me/2fa").insert_header(bearer(token)).set_json(json!({ "password": PASSWORD }));
+  let (status, body) = send(app, req).await;
+  assert_eq!(status, StatusCode::OK, "{}", body);
+  let secret = body["secret"].as_str().unwrap().to_string();
+
+  let req = TestRequest::post().uri("/api/me/2fa/confirm").insert_header(bearer(token)).set_json(json!({ "code": code(&secret, 0) }));
+  let (status, body) = send(app, req).await;
+  assert_eq!(status, StatusCode::OK, "{}", body);
+  let codes = body["recovery_codes"].as_array().unwrap().iter().map(|code| code.as_str().unwrap().to_string()).collect();
+  (secret, body["token"].as_str().unwrap().to_string(), codes)
+}
+
+async fn challenge<S, B>(app: &S, nickname: &str) -> String
+  where
+    S: Service<Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody,
+{
+  let (status, body) = send(app, login_req(nickname, PASSWORD)).await;
+  assert_eq!(status, StatusCode::ACCEPTED, "{}", body);
+  body["challenge"].as_str().unwrap().to_string()
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn enrollment_turns_on_the_two_step_login(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  let token = register(&app, "reader").await;
+
+  let req = TestRequest::get().uri("/api/me/2fa").insert_header(bearer(&token));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body, json!({ "enabled": false, "recovery_codes_left": 0 }));
+
+  let req = TestRequest::post().uri("/api/me/2fa").insert_header(bearer(&token)).set_json(json!({ "password": "wrong password" }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+
+  let req = TestRequest::post().uri("/api/me/2fa").insert_header(bearer(&token)).set_json(json!({ "password": PASSWORD }));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  let secret = body["secret"].as_str().unwrap();
+  assert_eq!(
+    body["otpauth_uri"],
+    format!("otpauth://totp/Bookstore:reader?secret={}&issuer=Bookstore&algorithm=SHA1&digits=6&period=30", secret),
+  );
+
+  // nothing changes until the secret is confirmed
+  let (status, _) = send(&app, login_req("reader", PASSWORD)).await;
+  assert_eq!(status, StatusCode::OK);
+  let req = TestRequest::post().uri("/api/me/2fa/confirm").insert_header(bearer(&token)).set_json(json!({ "code": wrong(&code(secret, 0)) }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+
+  let confirmed = code(secret, 0);
+  let req = TestRequest::post().uri("/api/me/2fa/confirm").insert_header(bearer(&token)).set_json(json!({ "code": confirmed }));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body["recovery_codes"].as_array().unwrap().len(), 10);
+  let new_token = body["token"].as_str().unwrap();
+
+  // the sessions started with the password alone are over
+  let (status, _) = send(&app, TestRequest::get().uri("/api/me").insert_header(bearer(&token))).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+  let req = TestRequest::get().uri("/api/me/2fa").insert_header(bearer(new_token));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body, json!({ "enabled": true, "recovery_codes_left": 10 }));
+  let req = TestRequest::post().uri("/api/me/2fa").insert_header(bearer(new_token)).set_json(json!({ "password": PASSWORD }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::CONFLICT);
+
+  let (status, body) = send(&app, login_req("reader", PASSWORD)).await;
+  assert_eq!(status, StatusCode::ACCEPTED);
+  assert_eq!(body["expires_in"], 300);
+  assert!(body.get("token").is_none());
+  let challenge = body["challenge"].as_str().unwrap();
+
+  // neither the challenge nor a used code lets anyone in
+  let (status, _) = send(&app, TestRequest::get().uri("/api/me").insert_header(bearer(challenge))).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+  let (status, _) = send(&app, login_2fa(&format!("{}x", challenge), &code(secret, 1))).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+  let (status, _) = send(&app, login_2fa(challenge, &confirmed)).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+  let (status, body) = send(&app, login_2fa(challenge, &code(secret, 1))).await;
+  assert_eq!(status, StatusCode::OK);
+  let req = TestRequest::get().uri("/api/me").insert_header(bearer(body["token"].as_str().unwrap()));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body["two_factor_enabled"], true);
+
+  // the code of the step is used up now
+  let (status, _) = send(&app, login_2fa(challenge, &code(secret, 1))).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn recovery_codes_work_once(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  let token = register(&app, "reader").await;
+  let (secret, token, codes) = enable(&app, &token).await;
+
+  let challenge = challenge(&app, "reader").await;
+  let (status, _) = send(&app, login_2fa(&challenge, &codes[0].to_uppercase())).await;
+  assert_eq!(status, StatusCode::OK);
+  let (status, _) = send(&app, login_2fa(&challenge, &codes[0])).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+  let req = TestRequest::get().uri("/api/me/2fa").insert_header(bearer(&token));
+  let (_, body) = send(&app, req).await;
+  assert_eq!(body["recovery_codes_left"], 9);
+
+  let req = TestRequest::post().uri("/api/me/2fa/recovery-codes").insert_header(bearer(&token)).set_json(json!({ "code": codes[0] }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+  let req = TestRequest::post().uri("/api/me/2fa/recovery-codes").insert_header(bearer(&token)).set_json(json!({ "code": codes[1] }));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  let new_codes = body["recovery_codes"].as_array().unwrap();
+  assert_eq!(new_codes.len(), 10);
+
+  // the old codes are gone
+  let (status, _) = send(&app, login_2fa(&challenge, &codes[2])).await;
+  assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+  let disable = |password: &str, code: &str| TestRequest::delete()
+    .uri("/api/me/2fa")
+    .insert_header(bearer(&token))
+    .set_json(json!({ "password": password, "code": code }));
+  let (status, _) = send(&app, disable("wrong password", new_codes[0].as_str().unwrap())).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+  let (status, _) = send(&app, disable(PASSWORD, &wrong(&code(&secret, 1)))).await;
+  assert_eq!(status, StatusCode::FORBIDDEN);
+  let (status, _) = send(&app, disable(PASSWORD, new_codes[0].as_str().unwrap())).await;
+  assert_eq!(status, StatusCode::OK);
+  let (status, _) = send(&app, disable(PASSWORD, new_codes[1].as_str().unwrap())).await;
+  assert_eq!(status, StatusCode::CONFLICT);
+
+  let (status, _) = send(&app, login_req("reader", PASSWORD)).await;
+  assert_eq!(status, StatusCode::OK);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn wrong_codes_count_as_failed_logins(pool: PgPool) {
+  let mut config = config();
+  config.login.free_attempts = 1;
+  config.login.lockout_threshold = 2;
+  let (_, app) = init_app_with(pool, config).await;
+  let token = register(&app, "reader").await;
+  let (secret, _, _) = enable(&app, &token).await;
+
+  let challenge = challenge(&app, "reader").await;
+  for _ in 0..2 {
+    let (status, _) = send(&app, login_2fa(&challenge, &wrong(&code(&secret, 1)))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+  }
+
+  // locked out: even the right code is not checked
+  let (status, _) = send(&app, login_2fa(&challenge, &code(&secret, 1))).await;
+  assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn admins_must_enroll_when_required(pool: PgPool) {
+  let mut config = config();
+  config.auth.require_admin_2fa = true;
+  let (state, app) = init_app_with(pool, config).await;
+  let admin = admin_token(&state).await;
+  let reader = register(&app, "reader").await;
+
+  let books = |token: &str| TestRequest::get().uri("/api/book?page=0&size=10").insert_header(bearer(token));
+  let (status, body) = send(&app, books(&admin)).await;
+  assert_eq!(status, StatusCode::FORBIDDEN, "{}", body);
+  // the users are free to go without it
+  let (status, _) = send(&app, books(&reader)).await;
+  assert_eq!(status, StatusCode::OK);
+
+  // nor is the rest of the profile theirs until they set it up
+  for uri in ["/api/me", "/api/me/shelves"] {
+    let (status, _) = send(&app, TestRequest::get().uri(uri).insert_header(bearer(&admin))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{}", uri);
+  }
+  let (status, _) = send(&app, TestRequest::get().uri("/api/me/2fa").insert_header(bearer(&admin))).await;
+  assert_eq!(status, StatusCode::OK);
+  let (secret, admin, _) = enable(&app, &admin).await;
+  let (status, _) = send(&app, books(&admin)).await;
+  assert_eq!(status, StatusCode::OK);
+
+  let req = TestRequest::delete()
+    .uri("/api/me/2fa")
+    .insert_header(bearer(&admin))
+    .set_json(json!({ "password": PASSWORD, "code": code(&secret, 1) }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::CONFLICT);
+}
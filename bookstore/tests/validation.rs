@@ -0,0 +1,162 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+use common::{admin_token, bearer, init_app, register, send};
+
+
+/// `(field, code)` of every error in a 422 response.
+fn failures(body: &Value) -> Vec<(String, String)> {
+  body["errors"].as_array()
+    .expect("no errors in the response")
+    .iter()
+    .map(|e| (e["field"].as_str().unwrap().to_string(), e["code"].as_str().unwrap().to_string()))
+    .collect()
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn every_failing_field_is_reported(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+
+  let req = TestRequest::post()
+    .uri("/api/auth/register")
+    .set_json(json!({
+      "first_name": "",
+      "last_name": "Petrov2",
+      "middle_name": "x".repeat(65),
+      "nickname": "ab",
+      "password": "",
+    }));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+  assert_eq!(body["status"], 422);
+  assert!(body["request_id"].is_string());
+  assert_eq!(failures(&body), [
+    ("first_name", "length"),
+    ("first_name", "name"),
+    ("last_name", "name"),
+    ("middle_name", "length"),
+    ("nickname", "length"),
+    ("password", "length"),
+  ].map(|(f, c)| (f.to_string(), c.to_string())));
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn messages_follow_accept_language(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  let body = json!({ "first_name": "Иван", "last_name": "Петров", "nickname": "ab", "password": "password" });
+
+  let req = TestRequest::post().uri("/api/auth/register").set_json(&body);
+  let (_, res) = send(&app, req).await;
+  assert_eq!(res["errors"][0]["message"], "Длина должна быть от 3 до 64 символов.");
+
+  let req = TestRequest::post()
+    .uri("/api/auth/register")
+    .insert_header(("Accept-Language", "de-DE, en;q=0.8, ru;q=0.5"))
+    .set_json(&body);
+  let (_, res) = send(&app, req).await;
+  assert_eq!(res["errors"][0]["message"], "Must be 3 to 64 characters long.");
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn lengths_follow_the_schema(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let admin = admin_token(&state).await;
+
+  let req = TestRequest::post()
+    .uri("/api/book")
+    .insert_header(bearer(&admin))
+    .set_json(json!({ "title": "Я".repeat(257), "price": -1 }));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+  assert_eq!(failures(&body), [("price", "range"), ("title", "length")].map(|(f, c)| (f.to_string(), c.to_string())));
+
+  let req = TestRequest::post()
+    .uri("/api/book")
+    .insert_header(bearer(&admin))
+    .set_json(json!({ "title": "   " }));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+  assert_eq!(failures(&body), [("title".to_string(), "blank".to_string())]);
+
+  let req = TestRequest::post()
+    .uri("/api/book")
+    .insert_header(bearer(&admin))
+    .set_json(json!({ "title": "x".repeat(256) }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::CREATED);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn query_parameters_are_validated(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  let user = register(&app, "reader").await;
+
+  let req = TestRequest::get().uri("/api/author?page=0&size=0").insert_header(bearer(&user));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+  assert_eq!(failures(&body), [("size".to_string(), "range".to_string())]);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn cross_field_rules_name_the_field(pool: PgPool) {
+  let (_, app) = init_app(pool).await;
+  let user = register(&app, "reader").await;
+
+  // the rule is checked before the shelf and the book are looked up
+  let uri = format!("/api/me/shelves/{}/book/{}", uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+  let req = TestRequest::put()
+    .uri(&uri)
+    .insert_header(bearer(&user))
+    .set_json(json!({ "date_started": "2024-02-01", "date_finished": "2024-01-01" }));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+  assert_eq!(failures(&body), [("date_finished".to_string(), "date_order".to_string())]);
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn names_take_letters_of_any_script(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let admin = admin_token(&state).await;
+
+  for name in ["Ёлкин", "Салтыков-Щедрин", "O'Brien", "D’Artagnan", "Mary Ann", "Łukasz", "Ἀριστοτέλης", "李"] {
+    let req = TestRequest::post()
+      .uri("/api/author")
+      .insert_header(bearer(&admin))
+      .set_json(json!({ "first_name": name, "last_name": name, "middle_name": name }));
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::CREATED, "{}: {}", name, body);
+  }
+
+  for name in ["Иван1", "-Иван", "Иван-", "Иван--Петров", "Иван  Петров", " Иван", "Иван_Петров", "\u{301}Иван"] {
+    let req = TestRequest::post()
+      .uri("/api/author")
+      .insert_header(bearer(&admin))
+      .set_json(json!({ "first_name": "Иван", "last_name": "Петров", "middle_name": name }));
+    let (status, body) = send(&app, req).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{:?}", name);
+    assert_eq!(failures(&body), [("middle_name".to_string(), "name".to_string())]);
+  }
+}
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn names_are_stored_in_nfc(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let admin = admin_token(&state).await;
+
+  // `Е` with a combining diaeresis
+  let req = TestRequest::post()
+    .uri("/api/author")
+    .insert_header(bearer(&admin))
+    .set_json(json!({ "first_name": "Е\u{308}лкин", "last_name": "Jose\u{301}" }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::CREATED);
+
+  let req = TestRequest::get().uri("/api/author?page=0&size=10").insert_header(bearer(&admin));
+  let (_, body) = send(&app, req).await;
+  assert_eq!(body[0]["first_name"], "Ёлкин");
+  assert_eq!(body[0]["last_name"], "José");
+}
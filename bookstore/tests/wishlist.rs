@@ -0,0 +1,40 @@
+mod common;
+
+use actix_web::http::StatusCode;
+use actix_web::test::TestRequest;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+
+use common::{add_book, admin_token, bearer, init_app, register, send};
+
+
+#[sqlx::test(migrator = "bookstore::MIGRATOR")]
+async fn first_price_under_the_threshold_is_notified(pool: PgPool) {
+  let (state, app) = init_app(pool).await;
+  let admin = admin_token(&state).await;
+  let reader = register(&app, "reader").await;
+  let book_id = add_book(&app, &admin, "Нос").await;
+
+  let req = TestRequest::put()
+    .uri(&format!("/api/me/wishlist/{}", book_id))
+    .insert_header(bearer(&reader))
+    .set_json(json!({ "price_threshold": 50000, "notify_restock": false }));
+  let (status, _) = send(&app, req).await;
+  assert!(status.is_success(), "{}", status);
+
+  // the book had no price, so any price under the threshold is news
+  let req = TestRequest::put()
+    .uri(&format!("/api/book/{}/inventory", book_id))
+    .insert_header(bearer(&admin))
+    .set_json(json!({ "price": 39900 }));
+  let (status, _) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+
+  let req = TestRequest::get().uri("/api/me/notifications?page=0&size=10").insert_header(bearer(&reader));
+  let (status, body) = send(&app, req).await;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(body.as_array().unwrap().len(), 1, "{}", body);
+  assert_eq!(body[0]["kind"], "price_drop");
+  assert_eq!(body[0]["old_price"], Value::Null);
+  assert_eq!(body[0]["new_price"], 39900);
+}